@@ -0,0 +1,326 @@
+//! Benchmarks key generation, proof generation, and verification of the pkcs1v15 signature verification with SHA256.
+//!
+//! The circuit reproduces the configuration of `TestRSASignatureWithHashCircuit1` at `k = 15` for several key sizes,
+//! and prints the advice cells, fixed rows, and lookup cells it uses before the measurements.
+//!
+//! For a 2048 bits key, it is also measured exposing the commitment of [`RSASignatureVerifier::commit_public_key`] instead of the modulus limbs,
+//! which trades 30 instance values for the SHA256 blocks of the modulus bytes.
+//!
+//! Run with `cargo bench --bench rsa_signature_with_hash`.
+
+use std::sync::{Arc, Mutex};
+
+use anon_aadhaar_halo2::{
+    halo2_dynamic_sha256::Sha256DynamicConfig, public_key_commitment_native, sha256_padded_len,
+    BigUintConfig, RSAConfig, RSAInstructions, RSAPubE, RSAPublicKey, RSASignature,
+    RSASignatureVerifier,
+};
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use halo2_base::halo2_proofs::{
+    circuit::{Cell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Column, ConstraintSystem, Error,
+        Instance,
+    },
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::{ProverSHPLONK, VerifierSHPLONK},
+        strategy::SingleStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use halo2_base::{
+    gates::{
+        range::{RangeConfig, RangeStrategy::Vertical},
+        GateInstructions,
+    },
+    utils::PrimeField,
+    SKIP_FIRST_PASS,
+};
+use num_bigint::BigUint;
+use rand::{rngs::OsRng, thread_rng, Rng};
+use rsa::{
+    pkcs1v15::SigningKey,
+    sha2::{Digest, Sha256},
+    signature::{SignatureEncoding, Signer},
+    traits::PublicKeyParts,
+    RsaPrivateKey, RsaPublicKey,
+};
+
+const K: u32 = 15;
+const MSG_LEN: usize = 1024;
+const EXP_LIMB_BITS: usize = 5;
+const DEFAULT_E: u64 = 65537;
+const NUM_FIXED: usize = 1;
+const NUM_LOOKUP_ADVICE: usize = 16;
+const LOOKUP_BITS: usize = 12;
+const SHA256_LOOKUP_BITS: usize = 8;
+const SHA256_LOOKUP_ADVICE: usize = 8;
+
+/// The cells used by a synthesis, as printed by `TestRSASignatureWithHashCircuit1`.
+#[derive(Debug, Clone, Copy, Default)]
+struct Metrics {
+    total_advice: usize,
+    fixed_rows: usize,
+    lookup_cells: usize,
+}
+
+#[derive(Debug, Clone)]
+struct BenchConfig<F: PrimeField> {
+    rsa_config: RSAConfig<F>,
+    sha256_config: Sha256DynamicConfig<F>,
+    n_instance: Column<Instance>,
+    hash_instance: Column<Instance>,
+}
+
+/// Verifies a pkcs1v15 signature of `BITS_LEN` bits for a message hashed in a single SHA256 slot of [`MSG_LEN`] bytes.
+/// The modular power of a larger key takes more cells, so `NUM_ADVICE` is chosen per key size to fit in `2^K` rows.
+/// If `COMMIT_KEY` is true, the modulus is hashed in a second slot and its commitment is exposed instead of its limbs.
+#[derive(Debug, Clone)]
+struct BenchCircuit<
+    F: PrimeField,
+    const BITS_LEN: usize,
+    const NUM_ADVICE: usize,
+    const COMMIT_KEY: bool,
+> {
+    signature: Value<BigUint>,
+    n: Value<BigUint>,
+    msg: Vec<u8>,
+    metrics: Arc<Mutex<Metrics>>,
+    _f: std::marker::PhantomData<F>,
+}
+
+impl<F: PrimeField, const BITS_LEN: usize, const NUM_ADVICE: usize, const COMMIT_KEY: bool>
+    Circuit<F> for BenchCircuit<F, BITS_LEN, NUM_ADVICE, COMMIT_KEY>
+{
+    type Config = BenchConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            signature: Value::unknown(),
+            n: Value::unknown(),
+            msg: vec![0; self.msg.len()],
+            metrics: Arc::new(Mutex::new(Metrics::default())),
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let range_config = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[NUM_ADVICE],
+            &[NUM_LOOKUP_ADVICE],
+            NUM_FIXED,
+            LOOKUP_BITS,
+            0,
+            K as usize,
+        );
+        let bigint_config = BigUintConfig::construct(range_config.clone(), 64);
+        let rsa_config = RSAConfig::construct(bigint_config, BITS_LEN, EXP_LIMB_BITS);
+        let mut sha256_max_byte_sizes = vec![MSG_LEN];
+        if COMMIT_KEY {
+            sha256_max_byte_sizes.push(sha256_padded_len(BITS_LEN / 8));
+        }
+        let sha256_config = Sha256DynamicConfig::configure(
+            meta,
+            sha256_max_byte_sizes,
+            range_config,
+            SHA256_LOOKUP_BITS,
+            SHA256_LOOKUP_ADVICE,
+            true,
+        );
+        let n_instance = meta.instance_column();
+        let hash_instance = meta.instance_column();
+        meta.enable_equality(n_instance);
+        meta.enable_equality(hash_instance);
+        BenchConfig {
+            rsa_config,
+            sha256_config,
+            n_instance,
+            hash_instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let biguint_config = config.rsa_config.biguint_config();
+        config.sha256_config.load(&mut layouter)?;
+        biguint_config.range().load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let (key_cells, hashed_msg_cells) = layouter.assign_region(
+            || "rsa signature with hash benchmark",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok((vec![], vec![]));
+                }
+                let mut aux = biguint_config.new_context(region);
+                let ctx = &mut aux;
+                let sign = config
+                    .rsa_config
+                    .assign_signature(ctx, RSASignature::new(self.signature.clone()))?;
+                let public_key = config.rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(self.n.clone(), RSAPubE::Fix(BigUint::from(DEFAULT_E))),
+                )?;
+                let mut verifier = RSASignatureVerifier::new(
+                    config.rsa_config.clone(),
+                    config.sha256_config.clone(),
+                );
+                let (is_valid, hashed_msg) =
+                    verifier.verify_pkcs1v15_signature(ctx, &public_key, &self.msg, &sign)?;
+                biguint_config
+                    .gate()
+                    .assert_is_const(ctx, &is_valid, F::one());
+                let key_cells = if COMMIT_KEY {
+                    verifier
+                        .commit_public_key(ctx, &public_key)?
+                        .iter()
+                        .map(|v| v.cell())
+                        .collect()
+                } else {
+                    public_key.cells().n
+                };
+                biguint_config.range().finalize(ctx);
+                *self.metrics.lock().unwrap() = Metrics {
+                    total_advice: ctx.total_advice,
+                    fixed_rows: ctx.total_fixed + 1,
+                    lookup_cells: ctx.cells_to_lookup.len(),
+                };
+                let hashed_msg_cells = hashed_msg
+                    .into_iter()
+                    .map(|v| v.cell())
+                    .collect::<Vec<Cell>>();
+                Ok((key_cells, hashed_msg_cells))
+            },
+        )?;
+        for (i, cell) in key_cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.n_instance, i)?;
+        }
+        for (i, cell) in hashed_msg_cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.hash_instance, i)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a circuit for a random key and message together with its instances.
+fn random_circuit<const BITS_LEN: usize, const NUM_ADVICE: usize, const COMMIT_KEY: bool>() -> (
+    BenchCircuit<Fr, BITS_LEN, NUM_ADVICE, COMMIT_KEY>,
+    Vec<Vec<Fr>>,
+) {
+    let mut rng = thread_rng();
+    let private_key = RsaPrivateKey::new(&mut rng, BITS_LEN).expect("failed to generate a key");
+    let public_key = RsaPublicKey::from(&private_key);
+    let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+    let msg = (0..MSG_LEN / 2).map(|_| rng.gen()).collect::<Vec<u8>>();
+    let signing_key = SigningKey::<Sha256>::new(private_key);
+    let signature = BigUint::from_bytes_be(&signing_key.sign(&msg).to_vec());
+
+    let key_instances = if COMMIT_KEY {
+        public_key_commitment_native::<Fr>(&public_key)
+    } else {
+        anon_aadhaar_halo2::decompose_biguint::<Fr>(&n, BITS_LEN / 64, 64)
+    };
+    let hash = Sha256::digest(&msg)
+        .iter()
+        .map(|byte| Fr::from(*byte as u64))
+        .collect::<Vec<_>>();
+    let circuit = BenchCircuit {
+        signature: Value::known(signature),
+        n: Value::known(n),
+        msg,
+        metrics: Arc::new(Mutex::new(Metrics::default())),
+        _f: std::marker::PhantomData,
+    };
+    (circuit, vec![key_instances, hash])
+}
+
+fn bench_key_size<const BITS_LEN: usize, const NUM_ADVICE: usize, const COMMIT_KEY: bool>(
+    c: &mut Criterion,
+    params: &ParamsKZG<Bn256>,
+) {
+    let (circuit, instances) = random_circuit::<BITS_LEN, NUM_ADVICE, COMMIT_KEY>();
+    let instances = instances.iter().map(|v| v.as_slice()).collect::<Vec<_>>();
+    let group_name = if COMMIT_KEY {
+        "rsa_signature_with_hash_key_commitment"
+    } else {
+        "rsa_signature_with_hash"
+    };
+    let mut group = c.benchmark_group(group_name);
+    group.sample_size(10);
+
+    group.bench_with_input(
+        BenchmarkId::new("keygen", BITS_LEN),
+        &circuit,
+        |b, circuit| {
+            b.iter(|| {
+                let vk = keygen_vk(params, circuit).unwrap();
+                keygen_pk(params, vk, circuit).unwrap()
+            })
+        },
+    );
+    let vk = keygen_vk(params, &circuit).unwrap();
+    let pk = keygen_pk(params, vk.clone(), &circuit).unwrap();
+
+    let prove = || {
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+            params,
+            &pk,
+            &[circuit.clone()],
+            &[instances.as_slice()],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        transcript.finalize()
+    };
+    let proof = prove();
+    let metrics = *circuit.metrics.lock().unwrap();
+    println!("{group_name}, {BITS_LEN} bits key with {NUM_ADVICE} advice columns:");
+    println!(
+        "instance values: {}",
+        instances.iter().map(|v| v.len()).sum::<usize>()
+    );
+    println!("total advice cells: {}", metrics.total_advice);
+    println!(
+        "maximum rows used by a fixed column: {}",
+        metrics.fixed_rows
+    );
+    println!("lookup cells used: {}", metrics.lookup_cells);
+    group.bench_function(BenchmarkId::new("prove", BITS_LEN), |b| b.iter(&prove));
+
+    group.bench_function(BenchmarkId::new("verify", BITS_LEN), |b| {
+        b.iter(|| {
+            let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+            verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+                params,
+                &vk,
+                SingleStrategy::new(params),
+                &[instances.as_slice()],
+                &mut transcript,
+            )
+            .unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn bench_rsa_signature_with_hash(c: &mut Criterion) {
+    let params = ParamsKZG::<Bn256>::setup(K, OsRng);
+    bench_key_size::<2048, 80, false>(c, &params);
+    bench_key_size::<2048, 80, true>(c, &params);
+    bench_key_size::<3072, 180, false>(c, &params);
+}
+
+criterion_group!(benches, bench_rsa_signature_with_hash);
+criterion_main!(benches);
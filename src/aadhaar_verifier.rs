@@ -0,0 +1,531 @@
+//! Verification of a signed QR payload together with the extraction of the attributes of the holder.
+//!
+//! The extractors read the very bytes hashed by the SHA256 chip, i.e. the input bytes of the hash whose signature is verified,
+//! so a prover cannot extract attributes from bytes other than the signed ones.
+//!
+//! The bytes decoded from a secure QR code are DEFLATE-compressed, see [`inflate`].
+//! Depending on the version of the QR code, the signature covers either the inflated payload or the compressed bytes:
+//! the secure QR code v2 signs the inflated payload, whose trailing 256 bytes are the signature itself,
+//! while some other versions sign the compressed bytes and carry the signature next to them.
+//! [`AadhaarVerifier::verify_payload`] accepts either form of the payload and hashes the bytes chosen by [`HashedBytes`].
+//! Since the inflation is not constrained in the circuit, [`AadhaarVerifier::verify_and_extract_payload`] only extracts the attributes of a payload whose inflated form is signed.
+
+use crate::extractors::age_extractor::extract_age_years;
+use crate::extractors::gender_extractor::{extract_gender, ExtractedGender};
+use crate::extractors::photo_extractor::extract_photo_commitment;
+use crate::extractors::pincode_extractor::extract_pincode;
+use crate::extractors::qrdata_extractor::{extract_last4, inflate, split_fields};
+use crate::poseidon::StandardPoseidonChip;
+use crate::qr_spec::QrField;
+use crate::utils::assigned_bytes_to_vec;
+use crate::{
+    AssignedBigUint, AssignedRSAPublicKey, AssignedRSASignature, Fresh, RSAConfig, RSAInstructions,
+    RsaCircuitError, RsaHashAlgo,
+};
+use halo2_base::{gates::GateInstructions, utils::PrimeField, AssignedValue, Context, QuantumCell};
+use halo2_dynamic_sha256::{AssignedHashResult, Sha256DynamicConfig};
+
+/// The outputs of [`AadhaarVerifier::verify_and_extract`].
+#[derive(Debug, Clone)]
+pub struct AadhaarOutputs<'v, F: PrimeField> {
+    /// The bit equivalent to one if the signature is valid for the payload.
+    pub is_valid: AssignedValue<'v, F>,
+    /// The age in years on the signing date.
+    pub age: AssignedValue<'v, F>,
+    /// The gender and its validity bit.
+    pub gender: ExtractedGender<'v, F>,
+    /// The pincode.
+    pub pincode: AssignedBigUint<'v, F, Fresh>,
+    /// The last 4 digits of the Aadhaar number.
+    pub last4: AssignedBigUint<'v, F, Fresh>,
+    /// The commitment to the photo, see [`extract_photo_commitment`].
+    pub photo_commitment: AssignedValue<'v, F>,
+}
+
+/// The signed payload passed to [`AadhaarVerifier::verify_payload`] and [`AadhaarVerifier::verify_and_extract_payload`], without its signature.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrPayload<'a> {
+    /// The DEFLATE-compressed bytes, e.g. as decoded by [`crate::extractors::qrdata_extractor::decode_qr_bignum`].
+    Compressed(&'a [u8]),
+    /// The already inflated bytes.
+    Inflated(&'a [u8]),
+}
+
+/// The bytes of a [`QrPayload`] covered by the signature, and thus hashed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum HashedBytes {
+    /// The inflated payload is signed, as in the secure QR code v2.
+    #[default]
+    Inflated,
+    /// The compressed bytes are signed, so the attributes cannot be extracted in the circuit.
+    Compressed,
+}
+
+/// A circuit implementation to verify a signed QR payload and extract the attributes of its holder.
+#[derive(Clone, Debug)]
+pub struct AadhaarVerifier<F: PrimeField> {
+    rsa_config: RSAConfig<F>,
+    sha256_config: Sha256DynamicConfig<F>,
+    poseidon: StandardPoseidonChip<F>,
+}
+
+impl<F: PrimeField> AadhaarVerifier<F> {
+    /// Creates new [`AadhaarVerifier`].
+    ///
+    /// # Arguments
+    /// * rsa_config - a [`RSAConfig`].
+    /// * sha256_config - a [`Sha256DynamicConfig`].
+    /// * poseidon - a [`StandardPoseidonChip`] used for the photo commitment.
+    ///
+    /// # Return values
+    /// Returns new [`AadhaarVerifier`].
+    pub fn new(
+        rsa_config: RSAConfig<F>,
+        sha256_config: Sha256DynamicConfig<F>,
+        poseidon: StandardPoseidonChip<F>,
+    ) -> Self {
+        Self {
+            rsa_config,
+            sha256_config,
+            poseidon,
+        }
+    }
+
+    /// Given a RSA public key, a signed QR payload, and its pkcs1v15 signature, verifies the signature with SHA256 hash function and extracts the attributes of the holder.
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * public_key - an assigned public key used for the verification.
+    /// * signed_data - the signed payload, i.e. without its signature, hashed in the next slot of the SHA256 config.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the [`AadhaarOutputs`], extracted from the input bytes of the SHA256 hash.
+    /// If `signature` is valid for `public_key` and `signed_data`, its `is_valid` bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// The caller should constrain the bit to one unless it exposes it.
+    /// Returns the [`RsaCircuitError`] of the first failing extractor, and [`RsaCircuitError::Halo2`] if the hash or the verification fails.
+    pub fn verify_and_extract<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        signed_data: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<AadhaarOutputs<'b, F>, RsaCircuitError> {
+        let hashed = self.sha256_config.digest(ctx, signed_data, None)?;
+        self.extract_hashed(ctx, public_key, &hashed, signature)
+    }
+
+    /// Same as [`AadhaarVerifier::verify_and_extract`] but for a payload given either compressed or inflated.
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * public_key - an assigned public key used for the verification.
+    /// * payload - the signed payload, i.e. without its signature.
+    /// * hashed_bytes - which bytes of `payload` the signature covers.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the [`AadhaarOutputs`] as [`AadhaarVerifier::verify_and_extract`], the inflated payload being hashed in the next slot of the SHA256 config.
+    /// Returns [`RsaCircuitError::Inflate`] if the compressed bytes cannot be inflated, or with [`HashedBytes::Compressed`].
+    /// The inflation is not constrained in the circuit, so the attributes of a payload signed in its compressed form would not be bound to the signature;
+    /// use [`AadhaarVerifier::verify_payload`] to only verify such a signature.
+    pub fn verify_and_extract_payload<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        payload: QrPayload<'a>,
+        hashed_bytes: HashedBytes,
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<AadhaarOutputs<'b, F>, RsaCircuitError> {
+        match (payload, hashed_bytes) {
+            (QrPayload::Inflated(inflated), HashedBytes::Inflated) => {
+                self.verify_and_extract(ctx, public_key, inflated, signature)
+            }
+            (QrPayload::Compressed(compressed), HashedBytes::Inflated) => {
+                let inflated = inflate(compressed).ok_or(RsaCircuitError::Inflate)?;
+                self.verify_and_extract(ctx, public_key, &inflated, signature)
+            }
+            (_, HashedBytes::Compressed) => Err(RsaCircuitError::Inflate),
+        }
+    }
+
+    /// Given a RSA public key, a signed QR payload, and its pkcs1v15 signature, verifies the signature with SHA256 hash function without extracting any attribute.
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * public_key - an assigned public key used for the verification.
+    /// * payload - the signed payload, i.e. without its signature.
+    /// * hashed_bytes - which bytes of `payload` the signature covers, hashed in the next slot of the SHA256 config.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit equivalent to one if `signature` is valid for `public_key` and the hashed bytes, or zero otherwise.
+    /// Returns [`RsaCircuitError::Inflate`] if the compressed bytes cannot be inflated, or if they are to be hashed but `payload` is inflated.
+    pub fn verify_payload<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        payload: QrPayload<'a>,
+        hashed_bytes: HashedBytes,
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<AssignedValue<'b, F>, RsaCircuitError> {
+        let hashed = match (payload, hashed_bytes) {
+            (QrPayload::Inflated(inflated), HashedBytes::Inflated) => {
+                self.sha256_config.digest(ctx, inflated, None)?
+            }
+            (QrPayload::Compressed(compressed), HashedBytes::Inflated) => {
+                let inflated = inflate(compressed).ok_or(RsaCircuitError::Inflate)?;
+                self.sha256_config.digest(ctx, &inflated, None)?
+            }
+            (QrPayload::Compressed(compressed), HashedBytes::Compressed) => {
+                self.sha256_config.digest(ctx, compressed, None)?
+            }
+            (QrPayload::Inflated(_), HashedBytes::Compressed) => {
+                return Err(RsaCircuitError::Inflate)
+            }
+        };
+        Ok(self.rsa_config.verify_pkcs1v15_signature_with_hash(
+            ctx,
+            public_key,
+            RsaHashAlgo::Sha256,
+            &hashed.output_bytes,
+            signature,
+        )?)
+    }
+
+    /// Same as [`AadhaarVerifier::verify_and_extract`] but for a payload already assigned by the caller, e.g. to extract other fields from the same cells.
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * public_key - an assigned public key used for the verification.
+    /// * signed_data - the assigned bytes of the signed payload, i.e. without its signature, hashed in the next slot of the SHA256 config.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the [`AadhaarOutputs`] as [`AadhaarVerifier::verify_and_extract`].
+    /// Every byte of `signed_data` is constrained to equal the input byte of the SHA256 hash at the same index, and the hashed length to the length of `signed_data`,
+    /// so the attributes are extracted from the signed bytes.
+    pub fn verify_and_extract_assigned<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        signed_data: &[AssignedValue<'b, F>],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<AadhaarOutputs<'b, F>, RsaCircuitError> {
+        let hashed = self
+            .sha256_config
+            .digest(ctx, &assigned_bytes_to_vec(signed_data), None)?;
+        let gate = self.rsa_config.gate();
+        gate.assert_is_const(ctx, &hashed.input_len, F::from(signed_data.len() as u64));
+        for (hashed, byte) in hashed.input_bytes.iter().zip(signed_data.iter()) {
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(hashed),
+                QuantumCell::Existing(byte),
+            );
+        }
+        self.extract_hashed(ctx, public_key, &hashed, signature)
+    }
+
+    /// Verifies the signature of the `hashed` payload and extracts the attributes from the very input cells of the hash.
+    fn extract_hashed<'b>(
+        &self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        hashed: &AssignedHashResult<'b, F>,
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<AadhaarOutputs<'b, F>, RsaCircuitError> {
+        let is_valid = self.rsa_config.verify_pkcs1v15_signature_with_hash(
+            ctx,
+            public_key,
+            RsaHashAlgo::Sha256,
+            &hashed.output_bytes,
+            signature,
+        )?;
+        let range = self.rsa_config.range();
+        let qr_bytes = &hashed.input_bytes;
+        let fields = split_fields(range, ctx, qr_bytes, QrField::ReferenceId.position() + 1);
+        Ok(AadhaarOutputs {
+            is_valid,
+            age: extract_age_years(range, ctx, qr_bytes)?,
+            gender: extract_gender(range, ctx, qr_bytes)?,
+            pincode: extract_pincode(range, ctx, qr_bytes)?,
+            last4: extract_last4(range, ctx, qr_bytes, &fields)?,
+            photo_commitment: extract_photo_commitment(
+                range,
+                &self.poseidon,
+                ctx,
+                qr_bytes,
+                &hashed.input_len,
+            )?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::extractors::gender_extractor::Gender;
+    use crate::extractors::photo_extractor::photo_commitment_native;
+    use crate::poseidon::{R_F, R_P};
+    use crate::qr_spec::NUM_DELIMITERS;
+    use crate::test_utils::{
+        load_bytes, mock_prove_with_sha256, sample_qr_payload, test_private_key,
+        SHA256_MAX_BYTE_SIZES,
+    };
+    use crate::{BigUintConfig, RSAPubE, RSAPublicKey, RSASignature};
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+    use num_bigint::BigUint;
+    use rsa::{
+        pkcs1v15::SigningKey,
+        signature::{SignatureEncoding, Signer},
+        traits::PublicKeyParts,
+        RsaPublicKey,
+    };
+
+    /// A payload without an address, which fits in a single test slot.
+    fn sample_signed_data() -> Vec<u8> {
+        let fields: [&[u8]; NUM_DELIMITERS] = [
+            b"V2",
+            b"0",
+            b"269720240718124553876",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+            b"",
+            b"",
+            b"",
+            b"",
+            b"",
+            b"110051",
+            b"",
+            b"",
+            b"",
+            b"",
+            b"",
+            b"1234",
+        ];
+        sample_qr_payload(&fields, 16)
+    }
+
+    #[test]
+    fn test_verify_and_extract() {
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let signing_key = SigningKey::<rsa::sha2::Sha256>::new(private_key);
+        let signed_data = sample_signed_data();
+        let photo_commitment =
+            photo_commitment_native::<Fr>(&signed_data, SHA256_MAX_BYTE_SIZES[0]).unwrap();
+        let mut tampered_data = signed_data.clone();
+        tampered_data[3] = b'1';
+
+        for (signed_by_key, is_valid) in [(&signed_data, Fr::one()), (&tampered_data, Fr::zero())] {
+            let sign = BigUint::from_bytes_be(&signing_key.sign(signed_by_key).to_vec());
+            let n = n.clone();
+            let signed_data = signed_data.clone();
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let mut verifier = AadhaarVerifier::new(
+                        rsa_config,
+                        sha256.clone(),
+                        StandardPoseidonChip::new(R_F, R_P),
+                    );
+                    let outputs =
+                        verifier.verify_and_extract(ctx, &public_key, &signed_data, &sign)?;
+                    Ok(vec![
+                        outputs.is_valid,
+                        outputs.age,
+                        outputs.gender.value,
+                        outputs.pincode.limbs()[0].clone(),
+                        outputs.last4.limbs()[0].clone(),
+                        outputs.photo_commitment,
+                    ])
+                },
+                vec![
+                    is_valid,
+                    // Born on 1984-01-01 and signed on 2024-07-18.
+                    Fr::from(40u64),
+                    Fr::from(Gender::Male.code()),
+                    Fr::from(110051u64),
+                    Fr::from(2697u64),
+                    photo_commitment,
+                ],
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_verify_and_extract_assigned_binds_extracted_bytes() {
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let signed_data = sample_signed_data();
+        let sign = BigUint::from_bytes_be(
+            &SigningKey::<rsa::sha2::Sha256>::new(private_key)
+                .sign(&signed_data)
+                .to_vec(),
+        );
+        // The extractors are given the cells of another pincode than the signed one.
+        let pincode_start = signed_data
+            .windows(6)
+            .position(|window| window == b"110051")
+            .unwrap();
+        let mut swapped_data = signed_data.clone();
+        swapped_data[pincode_start..pincode_start + 6].copy_from_slice(b"560001");
+
+        for (extracted_data, pincode, is_ok) in [
+            (signed_data, 110051u64, true),
+            (swapped_data, 560001u64, false),
+        ] {
+            let n = n.clone();
+            let sign = sign.clone();
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let qr_bytes = load_bytes(ctx, range, &extracted_data, extracted_data.len());
+                    let mut verifier = AadhaarVerifier::new(
+                        rsa_config,
+                        sha256.clone(),
+                        StandardPoseidonChip::new(R_F, R_P),
+                    );
+                    let outputs =
+                        verifier.verify_and_extract_assigned(ctx, &public_key, &qr_bytes, &sign)?;
+                    range
+                        .gate
+                        .assert_is_const(ctx, &outputs.is_valid, Fr::one());
+                    Ok(vec![outputs.pincode.limbs()[0].clone()])
+                },
+                vec![Fr::from(pincode)],
+            );
+            assert_eq!(prover.verify().is_ok(), is_ok);
+        }
+    }
+
+    #[test]
+    fn test_verify_and_extract_payload() {
+        use flate2::{write::ZlibEncoder, Compression};
+        use std::io::Write;
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let signing_key = SigningKey::<rsa::sha2::Sha256>::new(private_key);
+        let inflated = sample_signed_data();
+        let mut encoder = ZlibEncoder::new(vec![], Compression::best());
+        encoder.write_all(&inflated).unwrap();
+        let compressed = encoder.finish().unwrap();
+        let photo_commitment =
+            photo_commitment_native::<Fr>(&inflated, SHA256_MAX_BYTE_SIZES[0]).unwrap();
+
+        for (is_compressed, hashed_bytes) in [
+            (false, HashedBytes::Inflated),
+            (true, HashedBytes::Inflated),
+            (true, HashedBytes::Compressed),
+        ] {
+            let signed_by_key = match hashed_bytes {
+                HashedBytes::Inflated => &inflated,
+                HashedBytes::Compressed => &compressed,
+            };
+            let sign = BigUint::from_bytes_be(&signing_key.sign(signed_by_key).to_vec());
+            let n = n.clone();
+            let (inflated, compressed) = (inflated.clone(), compressed.clone());
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let payload = if is_compressed {
+                        QrPayload::Compressed(&compressed)
+                    } else {
+                        QrPayload::Inflated(&inflated)
+                    };
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let mut verifier = AadhaarVerifier::new(
+                        rsa_config,
+                        sha256.clone(),
+                        StandardPoseidonChip::new(R_F, R_P),
+                    );
+                    // The compressed bytes cannot be recovered from the inflated payload.
+                    assert!(matches!(
+                        verifier.verify_payload(
+                            ctx,
+                            &public_key,
+                            QrPayload::Inflated(&inflated),
+                            HashedBytes::Compressed,
+                            &sign
+                        ),
+                        Err(RsaCircuitError::Inflate)
+                    ));
+                    if hashed_bytes == HashedBytes::Compressed {
+                        // The attributes would not be bound to the signed bytes.
+                        assert!(matches!(
+                            verifier.verify_and_extract_payload(
+                                ctx,
+                                &public_key,
+                                payload,
+                                hashed_bytes,
+                                &sign
+                            ),
+                            Err(RsaCircuitError::Inflate)
+                        ));
+                        let is_valid = verifier.verify_payload(
+                            ctx,
+                            &public_key,
+                            payload,
+                            hashed_bytes,
+                            &sign,
+                        )?;
+                        return Ok(vec![is_valid]);
+                    }
+                    let outputs = verifier.verify_and_extract_payload(
+                        ctx,
+                        &public_key,
+                        payload,
+                        hashed_bytes,
+                        &sign,
+                    )?;
+                    Ok(vec![
+                        outputs.is_valid,
+                        outputs.pincode.limbs()[0].clone(),
+                        outputs.photo_commitment,
+                    ])
+                },
+                match hashed_bytes {
+                    HashedBytes::Inflated => vec![Fr::one(), Fr::from(110051u64), photo_commitment],
+                    HashedBytes::Compressed => vec![Fr::one()],
+                },
+            );
+            prover.assert_satisfied();
+        }
+    }
+}
@@ -7,7 +7,7 @@ use halo2_base::halo2_proofs::{
 use halo2_base::utils::PrimeField;
 use num_bigint::BigUint;
 
-use crate::timestamp::{TimestampCircuit, TimestampConfig};
+use crate::timestamp::{TimeZone, TimestampCircuit, TimestampConfig};
 use crate::conditional_secrets::{IdentityCircuit, IdentityConfig};
 use crate::signal::{SquareCircuit, SquareConfig};
 //use std::sync::Arc;
@@ -41,7 +41,7 @@ impl<F: PrimeField> AadhaarQRVerifierCircuit<F> {
 impl<F:PrimeField> Circuit<F> for AadhaarQRVerifierCircuit<F> {
     type Config = (TestRSASignatureWithHashConfig1<F>, 
                     IdentityConfig,
-                    TimestampConfig,
+                    TimestampConfig<F>,
                     SquareConfig);
     type FloorPlanner = SimpleFloorPlanner;
 
@@ -137,7 +137,8 @@ mod tests {
                 Some(F::from(8u64)),
                 Some(F::from(12u64)),
                 Some(F::from(34u64)),
-                Some(F::from(56u64)));
+                Some(F::from(56u64)),
+                TimeZone::Utc);
 
             // Signal Hash Subcircuit
             let signal_hash = 5;
@@ -0,0 +1,411 @@
+//! Extraction of the holder's full address from the QR payload.
+//!
+//! The address layout depends on the [`QrFormat`]: format v1 packs the whole address into the location field,
+//! while format v2 splits it into the delimited fields from care-of to VTC.
+//! In both cases, the address is a contiguous region of the payload, see [`QrFormat::address_fields`].
+
+use crate::poseidon::{poseidon_hash, StandardPoseidonChip};
+use crate::qr_spec::{QrFormat, DELIMITER};
+use crate::utils::{pack_bytes, pack_bytes_native};
+use halo2_base::halo2_proofs::{circuit::Value, plonk::Error};
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
+};
+
+/// A variable-length region of the payload extracted together with a commitment to it, see [`commit_region`].
+#[derive(Debug, Clone)]
+pub struct ExtractedRegion<'v, F: PrimeField> {
+    /// The region bytes, zero-padded to the maximum length.
+    pub bytes: Vec<AssignedValue<'v, F>>,
+    /// The length of the region.
+    pub len: AssignedValue<'v, F>,
+    /// A commitment to the region, i.e. `Poseidon(pack_bytes(bytes)..., len)`.
+    pub commitment: AssignedValue<'v, F>,
+}
+
+/// An address extracted by [`extract_address`].
+pub type ExtractedAddress<'v, F> = ExtractedRegion<'v, F>;
+
+/// Extracts the full address from the QR payload and commits to it.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * ctx - a region context.
+/// * format - the format of the payload.
+/// * qr_bytes - assigned bytes of the payload. They are assumed to be already constrained to 8 bits, e.g. as SHA256 inputs.
+/// * max_len - the maximum byte length of the address.
+///
+/// # Return values
+/// Returns the [`ExtractedAddress`].
+/// Returns [`Error::Synthesis`] if `format` has no address fields.
+pub fn extract_address<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    poseidon: &StandardPoseidonChip<F>,
+    ctx: &mut Context<'v, F>,
+    format: QrFormat,
+    qr_bytes: &[AssignedValue<'v, F>],
+    max_len: usize,
+) -> Result<ExtractedAddress<'v, F>, Error> {
+    let (first, last) = format.address_fields();
+    let first = format.spec(first).ok_or(Error::Synthesis)?.position;
+    let last = format.spec(last).ok_or(Error::Synthesis)?.position;
+    let (bytes, len) = extract_delimited_region(range, ctx, qr_bytes, first, last, max_len)?;
+    Ok(commit_region(range.gate(), poseidon, ctx, bytes, len))
+}
+
+/// Commits to a region, e.g. to prove that it equals an expected value without revealing it.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * ctx - a region context.
+/// * bytes - the assigned region bytes zero-padded to the maximum length.
+/// * len - the assigned length of the region.
+///
+/// # Return values
+/// Returns the [`ExtractedRegion`], whose commitment equals [`region_commitment_native`] for the same region.
+pub fn commit_region<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    poseidon: &StandardPoseidonChip<F>,
+    ctx: &mut Context<'v, F>,
+    bytes: Vec<AssignedValue<'v, F>>,
+    len: AssignedValue<'v, F>,
+) -> ExtractedRegion<'v, F> {
+    let mut inputs = pack_bytes(gate, ctx, &bytes);
+    inputs.push(len.clone());
+    let commitment = poseidon.hash(gate, ctx, &inputs);
+    ExtractedRegion {
+        bytes,
+        len,
+        commitment,
+    }
+}
+
+/// Computes the commitment of [`ExtractedAddress`] off-circuit.
+///
+/// # Arguments
+/// * address - the address bytes.
+/// * max_len - the maximum byte length of the address passed to [`extract_address`].
+///
+/// # Return values
+/// Returns the commitment, or an error under the conditions of [`poseidon_hash`].
+pub fn address_commitment_native<F: PrimeField>(
+    address: &[u8],
+    max_len: usize,
+) -> Result<F, Error> {
+    region_commitment_native(address, max_len)
+}
+
+/// Computes the commitment of [`ExtractedRegion`] off-circuit.
+///
+/// # Arguments
+/// * region - the region bytes.
+/// * max_len - the maximum byte length of the region.
+///
+/// # Return values
+/// Returns the commitment, or an error under the conditions of [`poseidon_hash`].
+pub fn region_commitment_native<F: PrimeField>(region: &[u8], max_len: usize) -> Result<F, Error> {
+    let mut padded = region.to_vec();
+    padded.resize(max_len, 0);
+    let mut inputs = pack_bytes_native::<F>(&padded);
+    inputs.push(F::from(region.len() as u64));
+    poseidon_hash(&inputs)
+}
+
+/// Extracts the region spanning the delimited fields from `first_position` to `last_position`, both inclusive.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * first_position - the position of the first field, i.e. the number of delimiters preceding it.
+/// * last_position - the position of the last field.
+/// * max_len - the maximum byte length of the region.
+///
+/// # Return values
+/// Returns the region bytes zero-padded to `max_len` and the assigned length of the region.
+/// The region is constrained to start right after the `first_position`-th delimiter (or at zero) and to end right before the next delimiter following the `last_position`-th one.
+/// Returns [`Error::Synthesis`] if `first_position > last_position`.
+pub fn extract_delimited_region<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    first_position: usize,
+    last_position: usize,
+    max_len: usize,
+) -> Result<(Vec<AssignedValue<'v, F>>, AssignedValue<'v, F>), Error> {
+    if first_position > last_position {
+        return Err(Error::Synthesis);
+    }
+    let gate = range.gate();
+    // `end` is the delimiter terminating the last field.
+    let end = delimiter_index(range, ctx, qr_bytes, last_position);
+    // `start` follows the delimiter preceding the first field.
+    let start = match first_position {
+        0 => gate.load_zero(ctx),
+        position => {
+            let prev = delimiter_index(range, ctx, qr_bytes, position - 1);
+            gate.add(
+                ctx,
+                QuantumCell::Existing(&prev),
+                QuantumCell::Constant(F::one()),
+            )
+        }
+    };
+
+    Ok(extract_region(range, ctx, qr_bytes, &start, &end, max_len))
+}
+
+/// Extracts the bytes in `start..end`.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * start - the assigned index of the first byte of the region.
+/// * end - the assigned index following the last byte of the region.
+/// * max_len - the maximum byte length of the region.
+///
+/// # Return values
+/// Returns the region bytes zero-padded to `max_len` and the assigned length of the region, which is constrained to be at most `max_len`.
+pub fn extract_region<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    start: &AssignedValue<'v, F>,
+    end: &AssignedValue<'v, F>,
+    max_len: usize,
+) -> (Vec<AssignedValue<'v, F>>, AssignedValue<'v, F>) {
+    let gate = range.gate();
+    let len = gate.sub(
+        ctx,
+        QuantumCell::Existing(end),
+        QuantumCell::Existing(start),
+    );
+    range.check_less_than_safe(ctx, &len, max_len as u64 + 1);
+    let len_bits = (usize::BITS - max_len.leading_zeros()) as usize;
+    let bytes = (0..max_len)
+        .map(|i| {
+            let idx = gate.add(
+                ctx,
+                QuantumCell::Existing(start),
+                QuantumCell::Constant(F::from(i as u64)),
+            );
+            let byte = gate.select_from_idx(
+                ctx,
+                qr_bytes
+                    .iter()
+                    .map(QuantumCell::Existing)
+                    .collect::<Vec<_>>(),
+                QuantumCell::Existing(&idx),
+            );
+            let is_in_region = range.is_less_than(
+                ctx,
+                QuantumCell::Constant(F::from(i as u64)),
+                QuantumCell::Existing(&len),
+                len_bits,
+            );
+            gate.mul(
+                ctx,
+                QuantumCell::Existing(&byte),
+                QuantumCell::Existing(&is_in_region),
+            )
+        })
+        .collect();
+    (bytes, len)
+}
+
+/// Locates the delimiter terminating the field at `position`.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * position - the position of the field, i.e. the number of delimiters preceding the located delimiter.
+///
+/// # Return values
+/// Returns the assigned index of the delimiter.
+/// The byte at the index is constrained to be [`DELIMITER`] and to be preceded by exactly `position` delimiters.
+pub fn delimiter_index<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    position: usize,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let mut delimiters = vec![];
+    for (idx, byte) in qr_bytes.iter().enumerate() {
+        byte.value().map(|v| {
+            if v.get_lower_32() == DELIMITER as u32 {
+                delimiters.push(idx);
+            }
+        });
+    }
+    let idx = delimiters.get(position).copied().unwrap_or(0);
+    let idx = gate.load_witness(ctx, Value::known(F::from(idx as u64)));
+    let idx_bits = (usize::BITS - qr_bytes.len().leading_zeros()) as usize;
+    assert_delimiter_at(gate, ctx, qr_bytes, &idx);
+    let num_before = count_delimiters_before(range, ctx, qr_bytes, &idx, idx_bits);
+    gate.assert_is_const(ctx, &num_before, F::from(position as u64));
+    idx
+}
+
+/// Constrains the byte at `idx` to be [`DELIMITER`]. An out-of-range `idx` fails the constraint.
+fn assert_delimiter_at<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    idx: &AssignedValue<'v, F>,
+) {
+    let byte = gate.select_from_idx(
+        ctx,
+        qr_bytes
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+        QuantumCell::Existing(idx),
+    );
+    gate.assert_is_const(ctx, &byte, F::from(DELIMITER as u64));
+}
+
+/// Counts the delimiters in `qr_bytes[..idx]`, where `idx` is less than `2^idx_bits`.
+fn count_delimiters_before<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    idx: &AssignedValue<'v, F>,
+    idx_bits: usize,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let mut is_delimiters = vec![];
+    let mut is_befores = vec![];
+    for (i, byte) in qr_bytes.iter().enumerate() {
+        is_delimiters.push(gate.is_equal(
+            ctx,
+            QuantumCell::Existing(byte),
+            QuantumCell::Constant(F::from(DELIMITER as u64)),
+        ));
+        is_befores.push(range.is_less_than(
+            ctx,
+            QuantumCell::Constant(F::from(i as u64)),
+            QuantumCell::Existing(idx),
+            idx_bits,
+        ));
+    }
+    gate.inner_product(
+        ctx,
+        is_delimiters
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+        is_befores
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poseidon::{R_F, R_P};
+    use crate::qr_spec::{field_range, QrField, CONTACT_HASH_LEN, SIGNATURE_LEN};
+    use crate::test_utils::mock_prove;
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    const ADDRESS_MAX_LEN: usize = 128;
+    const V1_LOCATION: &[u8] =
+        b"B-31, 3rd Floor, Radhey Shyam Park Extension, Gandhi Nagar, East Delhi, Delhi 110051";
+
+    fn qr_with_fields(fields: &[&[u8]]) -> Vec<u8> {
+        let mut data = vec![];
+        for field in fields {
+            data.extend_from_slice(field);
+            data.push(DELIMITER);
+        }
+        data.extend_from_slice(&[255, 79, 255, 81, 0, 47, 0, 0]);
+        data.extend_from_slice(&[0x0b; CONTACT_HASH_LEN]);
+        data.extend_from_slice(&[0x51; SIGNATURE_LEN]);
+        data
+    }
+
+    fn address_is_extracted(format: QrFormat, data: Vec<u8>, expected_address: Vec<u8>) {
+        // The signature is not part of the signed payload.
+        let signed = data[..data.len() - SIGNATURE_LEN].to_vec();
+        let mut public_inputs = expected_address
+            .iter()
+            .map(|byte| Fr::from(*byte as u64))
+            .collect::<Vec<_>>();
+        public_inputs.resize(ADDRESS_MAX_LEN, Fr::from(0u64));
+        public_inputs.push(Fr::from(expected_address.len() as u64));
+        public_inputs.push(address_commitment_native(&expected_address, ADDRESS_MAX_LEN).unwrap());
+        let prover = mock_prove(
+            move |ctx, range| {
+                let poseidon = StandardPoseidonChip::new(R_F, R_P);
+                let qr_bytes = signed
+                    .iter()
+                    .map(|byte| {
+                        range
+                            .gate
+                            .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                    })
+                    .collect::<Vec<_>>();
+                let address =
+                    extract_address(range, &poseidon, ctx, format, &qr_bytes, ADDRESS_MAX_LEN)?;
+                let mut public_values = address.bytes;
+                public_values.extend([address.len, address.commitment]);
+                Ok(public_values)
+            },
+            public_inputs,
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_extract_v1_location() {
+        let data = qr_with_fields(&[
+            b"1",
+            b"269720190612104534087",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+            b"C/O Ishwar Chand",
+            V1_LOCATION,
+            b"1234",
+        ]);
+        assert_eq!(QrFormat::detect(&data), QrFormat::V1);
+        address_is_extracted(QrFormat::V1, data, V1_LOCATION.to_vec());
+    }
+
+    #[test]
+    fn test_extract_v2_address() {
+        let data = qr_with_fields(&[
+            b"V2",
+            b"1",
+            b"269720240718124553876",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+            b"C/O Ishwar Chand",
+            b"East Delhi",
+            b"",
+            b"B-31, 3rd Floor",
+            b"",
+            b"110051",
+            b"Krishna Nagar",
+            b"Delhi",
+            b"Radhey Shyam Park Extension",
+            b"Gandhi Nagar",
+            b"Krishna Nagar",
+            b"1234",
+        ]);
+        assert_eq!(QrFormat::detect(&data), QrFormat::V2);
+        let start = field_range(&data, QrField::CareOf).unwrap().start;
+        let end = field_range(&data, QrField::Vtc).unwrap().end;
+        let expected_address = data[start..end].to_vec();
+        address_is_extracted(QrFormat::V2, data, expected_address);
+    }
+}
@@ -1,16 +1,27 @@
 use super::utils::decompose_biguint;
-use crate::{AssignedBigUint, BigUintInstructions, Fresh, Muled, RangeType, RefreshAux};
-use halo2_base::halo2_proofs::{circuit::Region, circuit::Value, plonk::Error};
+use crate::utils::bits_to_num;
+use crate::{
+    AssignedBigUint, BigUintInstructions, Endianness, Fresh, Muled, RangeType, RefreshAux,
+};
+use halo2_base::halo2_proofs::{
+    circuit::Region,
+    circuit::Value,
+    plonk::{ConstraintSystem, Error},
+};
 use halo2_base::utils::fe_to_bigint;
 use halo2_base::ContextParams;
 use halo2_base::QuantumCell;
 use halo2_base::{
-    gates::{flex_gate::FlexGateConfig, range::RangeConfig, GateInstructions, RangeInstructions},
+    gates::{
+        flex_gate::FlexGateConfig,
+        range::{RangeConfig, RangeStrategy::Vertical},
+        GateInstructions, RangeInstructions,
+    },
     utils::{bigint_to_fe, biguint_to_fe, fe_to_biguint, PrimeField},
     AssignedValue, Context,
 };
 use halo2_ecc::bigint::{
-    big_is_equal, big_is_zero, mul_no_carry, select, sub, FixedOverflowInteger, OverflowInteger,
+    big_is_equal, mul_no_carry, select, sub, FixedOverflowInteger, OverflowInteger,
 };
 use num_bigint::{BigInt, BigUint, Sign};
 use num_traits::{One, Zero};
@@ -44,6 +55,9 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
         bit_len: usize,
     ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
         assert_eq!(bit_len % self.limb_bits, 0);
+        // Values shorter than `bit_len` (e.g. parsed from big-endian bytes whose leading zeros were stripped) are padded with zero limbs,
+        // whereas longer values cannot be represented.
+        value.error_if_known_and(|v| v.bits() as usize > bit_len)?;
         let num_limbs = bit_len / self.limb_bits;
         let gate = self.gate();
         let range = self.range();
@@ -144,7 +158,17 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
         Ok(new_assigned_int)
     }
 
-    /// Given a bit value `sel`, return `a` if `a`=1 and `b` otherwise.
+    /// Given a bit value `sel`, return `a` if `sel`=1 and `b` otherwise.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an integer returned if `sel`=1.
+    /// * `b` - an integer returned if `sel`=0.
+    /// * `sel` - an assigned value, which is constrained to be a bit.
+    ///
+    /// # Return values
+    /// Returns the selected integer, whose number of limbs is the larger one of `a` and `b`.
+    /// The operand with fewer limbs is padded with zero limbs.
     fn select<'v, T: RangeType>(
         &self,
         ctx: &mut Context<'v, F>,
@@ -152,7 +176,19 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
         b: &AssignedBigUint<'v, F, T>,
         sel: &AssignedValue<'v, F>,
     ) -> Result<AssignedBigUint<'v, F, T>, Error> {
-        let int = select::assign(self.gate(), ctx, &a.int, &b.int, sel);
+        let gate = self.gate();
+        // `sel * sel = sel` iff `sel` is a bit.
+        let sel_square = gate.mul(ctx, QuantumCell::Existing(sel), QuantumCell::Existing(sel));
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(&sel_square),
+            QuantumCell::Existing(sel),
+        );
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let zero = gate.load_zero(ctx);
+        let a = a.extend_limbs(num_limbs - a.num_limbs(), zero.clone());
+        let b = b.extend_limbs(num_limbs - b.num_limbs(), zero);
+        let int = select::assign(gate, ctx, &a.int, &b.int, sel);
         let value = a
             .value
             .as_ref()
@@ -168,6 +204,52 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
         Ok(AssignedBigUint::new(int, value))
     }
 
+    /// Given an assigned index `index`, returns `values[index]`.
+    /// The selection uses a one-hot indicator of `index`, which is constrained to have exactly one non-zero entry.
+    fn select_by_index<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        values: &[AssignedBigUint<'v, F, Fresh>],
+        index: &AssignedValue<'v, F>,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        let gate = self.gate();
+        if values.is_empty() {
+            return Err(Error::Synthesis);
+        }
+        let num_limbs = values[0].num_limbs();
+        if values.iter().any(|v| v.num_limbs() != num_limbs) {
+            return Err(Error::Synthesis);
+        }
+        let indicator = gate.idx_to_indicator(ctx, QuantumCell::Existing(index), values.len());
+        // The indicator is all zeros if `index` is out of range.
+        let indicator_sum = gate.sum(
+            ctx,
+            indicator
+                .iter()
+                .map(QuantumCell::Existing)
+                .collect::<Vec<QuantumCell<F>>>(),
+        );
+        gate.assert_is_const(ctx, &indicator_sum, F::one());
+        let limbs = (0..num_limbs)
+            .map(|i| {
+                let candidates = values
+                    .iter()
+                    .map(|v| QuantumCell::Existing(v.limb(i)))
+                    .collect::<Vec<QuantumCell<F>>>();
+                gate.select_by_indicator(ctx, candidates, &indicator)
+            })
+            .collect::<Vec<AssignedValue<F>>>();
+        let max_limb_bits = values.iter().map(|v| v.int.max_limb_bits).max().unwrap();
+        let int = OverflowInteger::construct(limbs, max_limb_bits);
+        let value = index.value().and_then(|idx| {
+            values
+                .get(idx.get_lower_32() as usize)
+                .map(|v| v.value())
+                .unwrap_or(Value::unknown())
+        });
+        Ok(AssignedBigUint::new(int, value))
+    }
+
     /// Given two inputs `a,b`, performs the addition `a + b`.
     fn add<'v>(
         &self,
@@ -376,12 +458,12 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
             .unzip();
 
         // 3. Assign the quotient and remainder after checking the range of each limb.
+        // `n` is already assigned with range-checked limbs, so it is reused rather than assigned again.
         let assign_q = self.assign_integer(ctx, q_big, n2 * limb_bits)?;
-        let assign_n = self.assign_integer(ctx, n_big, n1 * limb_bits)?;
         let assign_prod = self.assign_integer(ctx, prod_big, n1 * limb_bits)?;
         // 4. Assert `a * b = quotient_int * n + prod_int`, i.e., `prod_int = (a * b) mod n`.
         let ab = self.mul(ctx, a, b)?;
-        let qn = self.mul(ctx, &assign_q, &assign_n)?;
+        let qn = self.mul(ctx, &assign_q, n)?;
         let gate = self.gate();
         let n_sum = n1 + n2;
         let qn_prod = {
@@ -460,42 +542,73 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
     ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
         let num_limbs = a.num_limbs();
         assert_eq!(num_limbs, n.num_limbs());
-        let num_e_bits = Self::bits_size(&BigInt::from_biguint(Sign::Plus, e.clone()));
         // Decompose `e` into bits.
-        let e_bits = e
-            .to_bytes_le()
-            .into_iter()
-            .flat_map(|v| {
-                (0..8)
-                    .map(|i: u8| (v >> i) & 1u8 == 1u8)
-                    .collect::<Vec<bool>>()
-            })
-            .collect::<Vec<bool>>();
-        let e_bits = e_bits[0..num_e_bits].to_vec();
-        let mut acc = self.assign_constant(ctx, BigUint::from(1usize))?;
-        let zero = self.gate().load_zero(ctx);
-        acc = acc.extend_limbs(num_limbs - acc.num_limbs(), zero);
-        let mut squared: AssignedBigUint<'v, F, Fresh> = a.clone();
-        for e_bit in e_bits.into_iter() {
-            let cur_sq = squared;
-            // Square `squared`.
-            squared = self.square_mod(ctx, &cur_sq, n)?;
-            if !e_bit {
-                continue;
+        let e_bits = Self::fixed_exp_bits(e);
+        // The bits are scanned from the most significant one, which sets `acc` to `a`.
+        // Each following bit squares `acc` and multiplies it by `a` if set, e.g. `e = 65537 = 2^16 + 1` takes 16 squarings and one multiplication.
+        let lower_bits = match e_bits.split_last() {
+            Some((_, lower_bits)) => lower_bits,
+            None => return self.assign_one(ctx, num_limbs),
+        };
+        if lower_bits.is_empty() {
+            // `e = 1` has no squaring to reduce `a`, so it is multiplied by one instead.
+            let one = self.assign_one(ctx, num_limbs)?;
+            return self.mul_mod(ctx, a, &one, n);
+        }
+        let mut acc = a.clone();
+        for e_bit in lower_bits.iter().rev() {
+            acc = self.square_mod(ctx, &acc, n)?;
+            if *e_bit {
+                acc = self.mul_mod(ctx, &acc, a, n)?;
             }
-            // If `e_bit = 1`, update `acc` to `acc * cur_sq`.
-            acc = self.mul_mod(ctx, &acc, &cur_sq, n)?;
         }
         Ok(acc)
     }
 
-    /// Returns an assigned bit representing whether `a` is zero or not.
+    /// Given an input `a` and a modulus `n`, performs the modular inverse `a^{-1} mod n`.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - input of the inverse.
+    /// * `n` - a modulus.
+    ///
+    /// # Return values
+    /// Returns the modular inverse `a^{-1} mod n` as [`AssignedBigUint<F, Fresh>`] and an assigned bit as [`AssignedValue<F>`].
+    /// If `a` is invertible modulo `n`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero and the returned integer is zero.
+    /// # Requirements
+    /// Before calling this function, you must assert that `a<n` and `n>1`. `a` and `n` must have the same number of limbs.
+    fn mod_inverse<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(AssignedBigUint<'v, F, Fresh>, AssignedValue<'v, F>), Error> {
+        // Compute the inverse if it exists.
+        // Otherwise, compute a common divisor `g>1` of `a` and `n` such that `a = g * x` and `n = g * y`, which proves that `a` is not invertible.
+        let witness = a
+            .value()
+            .zip(n.value())
+            .map(|(a, n)| Self::inverse_witness(&a, &n));
+        self.mod_inverse_with_witness(ctx, a, n, witness)
+    }
+
+    /// Returns an assigned bit representing whether `a` is zero or not, i.e., whether all of its limbs are zero.
     fn is_zero<'v>(
         &self,
         ctx: &mut Context<'v, F>,
-        a: &'v AssignedBigUint<'v, F, Fresh>,
+        a: &AssignedBigUint<'v, F, Fresh>,
     ) -> Result<AssignedValue<'v, F>, Error> {
-        let out = big_is_zero::assign(self.gate(), ctx, a.int_ref());
+        let gate = self.gate();
+        let mut out = gate.load_constant(ctx, F::one());
+        for limb in a.limbs() {
+            let is_limb_zero = gate.is_zero(ctx, limb);
+            out = gate.and(
+                ctx,
+                QuantumCell::Existing(&out),
+                QuantumCell::Existing(&is_limb_zero),
+            );
+        }
         Ok(out)
     }
 
@@ -518,98 +631,26 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
         num_limbs_l: usize,
         num_limbs_r: usize,
     ) -> Result<AssignedValue<'v, F>, Error> {
-        // The following constraints are designed with reference to EqualWhenCarried template in https://github.com/jacksoom/circom-bigint/blob/master/circuits/mult.circom.
-        // We use lookup tables to optimize range checks.
         let min_n = if num_limbs_r >= num_limbs_l {
             num_limbs_l
         } else {
             num_limbs_r
         };
-        // Each limb of `a` and `b` is less than `min_n * (1^(limb_bits) - 1)^2  + (1^(limb_bits) - 1)`.
-        let muled_limb_max = Self::compute_muled_limb_max(self.limb_bits, min_n);
-        let muled_limb_max_fe = bigint_to_fe::<F>(&muled_limb_max);
-        let num_limbs = num_limbs_l + num_limbs_r - 1;
-        let muled_limb_max_bits = Self::bits_size(&(&muled_limb_max * 2u32));
-        let carry_bits = muled_limb_max_bits - self.limb_bits;
-        let gate = self.gate();
-        let range = self.range();
-
-        // The naive approach is to subtract the two integers limb by limb and:
-        //  a. Verify that they sum to zero along the way while
-        //  b. Propagating carries
-        // but this doesn't work because early sums might be negative.
-        // So instead we verify that `a - b + word_max = word_max`.
-        let limb_max = BigInt::from(1) << self.limb_bits;
-        let zero = gate.load_constant(ctx, F::zero());
-        let mut accumulated_extra = zero.clone();
-        let mut carry = Vec::with_capacity(num_limbs);
-        let mut cs = Vec::with_capacity(num_limbs);
-        carry.push(zero.clone());
-        let mut eq_bit = gate.load_constant(ctx, F::one());
-        let a_limbs = a.limbs();
-        let b_limbs = b.limbs();
-        for i in 0..num_limbs {
-            // `sum = a - b + word_max`
-            let a_b_sub = gate.sub(
-                ctx,
-                QuantumCell::Existing(&a_limbs[i]),
-                QuantumCell::Existing(&b_limbs[i]),
-            );
-            let sum = gate.sum(
-                ctx,
-                vec![
-                    QuantumCell::Existing(&a_b_sub),
-                    QuantumCell::Existing(&carry[i]),
-                    QuantumCell::Constant(muled_limb_max_fe),
-                ],
-            );
-            // `c` is lower `self.limb_width` bits of `sum`.
-            // `new_carry` is any other upper bits.
-            let (new_carry, c) = self.div_mod_unsafe(ctx, &sum, &limb_max);
-            carry.push(new_carry);
-            cs.push(c);
-
-            // `accumulated_extra` is the sum of `word_max`.
-            accumulated_extra = gate.add(
-                ctx,
-                QuantumCell::Existing(&accumulated_extra),
-                QuantumCell::Constant(muled_limb_max_fe),
-            );
-            let (q_acc, mod_acc) = self.div_mod_unsafe(ctx, &accumulated_extra, &limb_max);
-            // If and only if `a` is equal to `b`, lower `self.limb_width` bits of `sum` and `accumulated_extra` are the same.
-            let cs_acc_eq = gate.is_equal(
-                ctx,
-                QuantumCell::Existing(&cs[i]),
-                QuantumCell::Existing(&mod_acc),
-            );
-            eq_bit = gate.and(
-                ctx,
-                QuantumCell::Existing(&eq_bit),
-                QuantumCell::Existing(&cs_acc_eq),
-            );
-            accumulated_extra = q_acc;
-
-            if i < num_limbs - 1 {
-                // Assert that each carry fits in `carry_bits` bits.
-                range.range_check(ctx, &carry[i + 1], carry_bits);
-            } else {
-                // The final carry should match the `accumulated_extra`.
-                let final_carry_eq = gate.is_equal(
-                    ctx,
-                    QuantumCell::Existing(&carry[i + 1]),
-                    QuantumCell::Existing(&accumulated_extra),
-                );
-                eq_bit = gate.and(
-                    ctx,
-                    QuantumCell::Existing(&eq_bit),
-                    QuantumCell::Existing(&final_carry_eq),
-                );
-            }
-        }
-        Ok(eq_bit)
+        self.is_equal_muled_with_min_n(ctx, a, b, num_limbs_l + num_limbs_r - 1, min_n)
     }
 
     /// Returns an assigned bit representing whether `a` is less than `b` (`a<b`).
+    ///
+    /// The bit is the borrow out of the most significant limb when `a - b` is computed limb by limb with borrows.
+    /// If `a` and `b` have different numbers of limbs, the shorter one is padded with zero limbs, so the comparison is between the integer values.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer.
+    /// * `b` - an assigned integer.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as [`AssignedValue<F>`], which is one iff `a<b`. In particular, it is zero if `a` and `b` are equivalent.
     fn is_less_than<'v>(
         &self,
         ctx: &mut Context<'v, F>,
@@ -620,6 +661,19 @@ impl<F: PrimeField> BigUintInstructions<F> for BigUintConfig<F> {
         Ok(is_overfloe)
     }
 
+    /// Assert that `a` is less than `b` (`a<b`).
+    /// If `a` and `b` have different numbers of limbs, the shorter one is padded with zero limbs as [`BigUintInstructions::is_less_than`].
+    fn assert_less_than<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(), Error> {
+        let result = self.is_less_than(ctx, a, b)?;
+        self.gate().assert_is_const(ctx, &result, F::one());
+        Ok(())
+    }
+
     /// Returns an assigned bit representing whether `a` is less than or equal to `b` (`a<=b`).
     fn is_less_than_or_equal<'v>(
         &self,
@@ -722,65 +776,897 @@ impl<F: PrimeField> BigUintConfig<F> {
         Self { range, limb_bits }
     }
 
-    pub fn new_context<'a, 'b>(&'b self, region: Region<'a, F>) -> Context<'a, F> {
-        Context::new(
-            region,
-            ContextParams {
-                max_rows: self.range.gate.max_rows,
-                num_context_ids: 1,
-                fixed_columns: self.range.gate.constants.clone(),
-            },
-        )
+    /// Configures a [`RangeConfig`] with the given column counts and constructs a new [`BigIntChip`] on top of it.
+    ///
+    /// # Arguments
+    /// * meta - a constraint system.
+    /// * num_advice - the number of advice columns.
+    /// * num_lookup_advice - the number of advice columns copied into the lookup table of range checks.
+    /// * num_fixed - the number of fixed columns.
+    /// * lookup_bits - the bit length of the lookup table of range checks, at most `k - 1`.
+    /// * k - the degree of the circuit, i.e. it has `2^k` rows.
+    /// * limb_bits - the bit length of limbs.
+    ///
+    /// # Columns and rows
+    /// The cells of the chip fill the advice columns vertically, so a circuit fits if `num_advice * 2^k` exceeds its advice cells,
+    /// and likewise for the lookup cells and `num_lookup_advice`.
+    /// Halving the columns at `k + 1` keeps the capacity: the proof and the verification cost shrink with the columns, while the prover handles polynomials of twice the degree.
+    /// See [`crate::RSAConfigBuilder::suggest_num_advice`] for the column counts of a RSA verification.
+    ///
+    /// # Return values
+    /// Returns a new [`BigIntChip`].
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        num_advice: usize,
+        num_lookup_advice: usize,
+        num_fixed: usize,
+        lookup_bits: usize,
+        k: usize,
+        limb_bits: usize,
+    ) -> Self {
+        let range = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[num_advice],
+            &[num_lookup_advice],
+            num_fixed,
+            lookup_bits,
+            0,
+            k,
+        );
+        Self::construct(range, limb_bits)
     }
 
-    /// Returns the fewest bits necessary to express the [`BigUint`].
-    fn bits_size(val: &BigInt) -> usize {
-        val.bits() as usize
+    /// Given two inputs `a,b`, performs the addition `a + b` and returns the final carry separately.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - input of addition.
+    /// * `b` - input of addition.
+    ///
+    /// # Return values
+    /// Returns the sum modulo `2^(limb_bits * max(a.num_limbs(), b.num_limbs()))` as [`AssignedBigUint<F, Fresh>`] and the assigned carry bit out of its most significant limb.
+    /// Each limb of the sum is range-checked to `limb_bits` bits as in [`BigUintInstructions::add`].
+    pub fn add_with_carry<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(AssignedBigUint<'v, F, Fresh>, AssignedValue<'v, F>), Error> {
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let added = self.add(ctx, a, b)?;
+        // The last limb of `added` is the final carry, which is zero or one since both inputs fit in `num_limbs` limbs.
+        let carry = added.limb(num_limbs).clone();
+        let bits = self.limb_bits * num_limbs;
+        let value = added.value().map(|v| v % (BigUint::one() << bits));
+        let sum = AssignedBigUint::new(
+            OverflowInteger::construct(added.limbs()[..num_limbs].to_vec(), self.limb_bits),
+            value,
+        );
+        Ok((sum, carry))
     }
 
-    fn num_limbs(&self, val: &BigInt) -> usize {
-        let bits = Self::bits_size(&val);
-        let num_limbs = if bits % self.limb_bits == 0 {
-            bits / self.limb_bits
-        } else {
-            bits / self.limb_bits + 1
-        };
-        num_limbs
+    /// Given two inputs `a,b`, performs the subtraction `a - b` with a borrow bit.
+    ///
+    /// The limbs are subtracted from the least significant one with borrow propagation, and each limb of the difference is range-checked.
+    /// Therefore, the difference is well-formed even if `a<b`, in which case it is the two's complement `a - b + 2^(limb_bits * num_limbs)`.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - input of subtraction.
+    /// * `b` - input of subtraction.
+    ///
+    /// # Return values
+    /// Returns the difference with `num_limbs = max(a.num_limbs(), b.num_limbs())` limbs as [`AssignedBigUint<F, Fresh>`] and the assigned borrow bit, which is one iff `a<b`.
+    pub fn sub<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(AssignedBigUint<'v, F, Fresh>, AssignedValue<'v, F>), Error> {
+        let bits = self.limb_bits * a.num_limbs().max(b.num_limbs());
+        let (subed, borrow) = self.sub_unsafe(ctx, a, b)?;
+        // `sub_unsafe` sets the value to zero on underflow, while the limbs hold the two's complement.
+        let value = a.value().zip(b.value()).map(|(a, b)| {
+            if a >= b {
+                a - b
+            } else {
+                (BigUint::one() << bits) + a - b
+            }
+        });
+        Ok((AssignedBigUint::new(subed.int, value), borrow))
     }
 
-    /// Returns the maximum limb size of [`Muled`] type integers.
-    fn compute_muled_limb_max(limb_width: usize, min_n: usize) -> BigInt {
-        let one = BigInt::from(1usize);
-        let out_base = BigInt::from(1usize) << limb_width;
-        BigInt::from(min_n) * (&out_base - &one) * (&out_base - &one) + (&out_base - &one)
+    /// Returns an assigned bit representing whether `a` and `b` are equivalent, whose [`RangeType`] is [`Fresh`].
+    ///
+    /// Unlike [`BigUintInstructions::is_equal_fresh`], `a` and `b` may have different numbers of limbs, and the shorter one is padded with zero limbs.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer.
+    /// * `b` - an assigned integer, e.g. an expected constant returned by [`BigUintInstructions::assign_constant`].
+    ///
+    /// # Return values
+    /// Returns the assigned bit as [`AssignedValue<F>`], which is one iff `a` and `b` are equivalent.
+    pub fn is_equal<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+    ) -> AssignedValue<'v, F> {
+        let num_limbs = a.num_limbs().max(b.num_limbs());
+        let zero = self.gate().load_zero(ctx);
+        let a = a.extend_limbs(num_limbs - a.num_limbs(), zero.clone());
+        let b = b.extend_limbs(num_limbs - b.num_limbs(), zero);
+        big_is_equal::assign(self.gate(), ctx, &a.int, &b.int)
     }
 
-    /// Given a integer `a` and a divisor `n`, performs `a/n` and `a mod n`.
-    /// # Panics
-    /// Panics if `n=0`.
-    fn div_mod_unsafe<'v>(
+    /// Decomposes an integer into bytes.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer whose limb bits are a multiple of 8.
+    /// * `endianness` - the byte order of the returned bytes.
+    ///
+    /// # Return values
+    /// Returns `limb_bits / 8` assigned bytes per limb of `a` in the order of `endianness`, each of which is constrained to 8 bits.
+    pub fn to_assigned_bytes<'v>(
         &self,
         ctx: &mut Context<'v, F>,
-        a: &AssignedValue<'v, F>,
-        b: &BigInt,
-    ) -> (AssignedValue<'v, F>, AssignedValue<'v, F>) {
+        a: &AssignedBigUint<'v, F, Fresh>,
+        endianness: Endianness,
+    ) -> Vec<AssignedValue<'v, F>> {
+        assert_eq!(self.limb_bits % 8, 0);
         let gate = self.gate();
-        let (q_val, n_val) = a
-            .value
-            .map(|a| {
-                let a = fe_to_bigint(&a);
-                let (q_val, n_val) = (&a / b, &a % b);
-                (bigint_to_fe(&q_val), bigint_to_fe(&n_val))
-            })
-            .unzip();
-        let q = gate.load_witness(ctx, q_val);
-        let n = gate.load_witness(ctx, n_val);
-        let prod = gate.mul(
-            ctx,
-            QuantumCell::Existing(&q),
-            QuantumCell::Constant(bigint_to_fe(b)),
-        );
-        let a_prod_sub = gate.sub(ctx, QuantumCell::Existing(&a), QuantumCell::Existing(&prod));
+        let range = self.range();
+        let limb_bytes = self.limb_bits / 8;
+        let bases = (0..limb_bytes)
+            .map(|i| QuantumCell::Constant(gate.pow_of_two()[8 * i]))
+            .collect::<Vec<QuantumCell<F>>>();
+        let mut bytes = vec![];
+        for limb in a.limbs().iter() {
+            let limb_bytes = (0..limb_bytes)
+                .map(|i| {
+                    let byte = limb.value().map(|v| {
+                        let byte = (fe_to_biguint(v) >> (8 * i)) % BigUint::from(256u64);
+                        biguint_to_fe::<F>(&byte)
+                    });
+                    let byte = gate.load_witness(ctx, byte);
+                    range.range_check(ctx, &byte, 8);
+                    byte
+                })
+                .collect::<Vec<_>>();
+            let sum = gate.inner_product(
+                ctx,
+                limb_bytes
+                    .iter()
+                    .map(QuantumCell::Existing)
+                    .collect::<Vec<_>>(),
+                bases.clone(),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(limb),
+                QuantumCell::Existing(&sum),
+            );
+            bytes.extend(limb_bytes);
+        }
+        if endianness == Endianness::Big {
+            bytes.reverse();
+        }
+        bytes
+    }
+
+    /// Decomposes an integer into bits.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer.
+    /// * `num_bits` - the number of returned bits, at most `limb_bits * a.num_limbs()`. If it is smaller, the bits of `a` above `num_bits` are constrained to be zero, i.e. `a < 2^num_bits`.
+    ///
+    /// # Return values
+    /// Returns `num_bits` assigned bits of `a` in little-endian order, each of which is constrained to be boolean.
+    /// Their recomposition is constrained to equal the limbs of `a`.
+    pub fn to_bits<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        num_bits: usize,
+    ) -> Vec<AssignedValue<'v, F>> {
+        assert!(num_bits <= self.limb_bits * a.num_limbs());
+        let gate = self.gate();
+        let mut bits = vec![];
+        for (i, limb) in a.limbs().iter().enumerate() {
+            let limb_num_bits = num_bits
+                .saturating_sub(self.limb_bits * i)
+                .min(self.limb_bits);
+            if limb_num_bits == 0 {
+                gate.assert_is_const(ctx, limb, F::zero());
+            } else {
+                bits.extend(gate.num_to_bits(ctx, limb, limb_num_bits));
+            }
+        }
+        bits
+    }
+
+    /// Recomposes an integer from bytes.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `bytes` - assigned bytes in the order of `endianness`.
+    /// * `endianness` - the byte order of `bytes`.
+    ///
+    /// # Return values
+    /// Returns the integer as [`AssignedBigUint<F, Fresh>`] with `ceil(bytes.len() * 8 / limb_bits)` limbs.
+    /// Every byte is range-checked to 8 bits with the lookup table, which also bounds each limb to `limb_bits` bits.
+    /// Otherwise, a byte of `256` would pack to the same limb as a carry into the next byte.
+    pub fn from_assigned_bytes<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        bytes: &[AssignedValue<'v, F>],
+        endianness: Endianness,
+    ) -> AssignedBigUint<'v, F, Fresh> {
+        assert_eq!(self.limb_bits % 8, 0);
+        let gate = self.gate();
+        let range = self.range();
+        let limb_bytes = self.limb_bits / 8;
+        let mut le_bytes = bytes.to_vec();
+        if endianness == Endianness::Big {
+            le_bytes.reverse();
+        }
+        let limbs = le_bytes
+            .chunks(limb_bytes)
+            .map(|chunk| {
+                let bases = (0..chunk.len())
+                    .map(|i| QuantumCell::Constant(gate.pow_of_two()[8 * i]))
+                    .collect::<Vec<QuantumCell<F>>>();
+                for byte in chunk.iter() {
+                    range.range_check(ctx, byte, 8);
+                }
+                gate.inner_product(
+                    ctx,
+                    chunk.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                    bases,
+                )
+            })
+            .collect::<Vec<_>>();
+        let value = limbs
+            .iter()
+            .rev()
+            .fold(Value::known(BigUint::zero()), |acc, limb| {
+                acc.zip(limb.value())
+                    .map(|(acc, v)| (acc << self.limb_bits) + fe_to_biguint(v))
+            });
+        AssignedBigUint::new(OverflowInteger::construct(limbs, self.limb_bits), value)
+    }
+
+    /// Asserts that `a` and `b` are equivalent, whose [`RangeType`] is [`Fresh`].
+    /// `a` and `b` may have different numbers of limbs as [`BigUintConfig::is_equal`].
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer.
+    /// * `b` - an assigned integer.
+    pub fn assert_equal<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+    ) {
+        let is_eq = self.is_equal(ctx, a, b);
+        self.gate().assert_is_const(ctx, &is_eq, F::one());
+    }
+
+    /// Asserts that `a`, whose [`RangeType`] is [`Fresh`], equals a constant, e.g. an expected encoded message.
+    /// Each limb of `a` is constrained to equal a fixed cell, so the constant is not assigned with [`BigUintInstructions::assign_constant`] first.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer.
+    /// * `c` - a constant.
+    ///
+    /// # Return values
+    /// Returns [`Error::Synthesis`] if `c` does not fit in the limbs of `a`.
+    pub fn assert_equal_const<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        c: &BigUint,
+    ) -> Result<(), Error> {
+        let num_limbs = a.num_limbs();
+        if c.bits() as usize > self.limb_bits * num_limbs {
+            return Err(Error::Synthesis);
+        }
+        let gate = self.gate();
+        for (limb, c_limb) in
+            a.limbs()
+                .iter()
+                .zip(decompose_biguint::<F>(c, num_limbs, self.limb_bits))
+        {
+            gate.assert_is_const(ctx, limb, c_limb);
+        }
+        Ok(())
+    }
+
+    /// Given a base `a`, a variable exponent `e`, and a modulus `n`, performs the modular power `a^e mod n` with a fixed window of `window_bits` bits.
+    ///
+    /// The powers `a^0, ..., a^(2^window_bits - 1)` are precomputed, and the exponent is consumed from its most significant window:
+    /// each window squares the accumulator `window_bits` times and multiplies it by the power selected by the window.
+    /// It takes `exp_bits` squarings, `ceil(exp_bits / window_bits)` multiplications and `2^window_bits - 2` precomputations,
+    /// against `exp_bits` squarings and `exp_bits` multiplications of [`BigUintInstructions::pow_mod`], so it pays off for long exponents only.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - a base.
+    /// * `e` - a variable exponent, constrained to `exp_bits` bits.
+    /// * `n` - a modulus.
+    /// * `exp_bits` - the bit length of the exponent.
+    /// * `window_bits` - the bit length of a window, e.g. 4.
+    ///
+    /// # Return values
+    /// Returns `a^e mod n` as [`AssignedBigUint<F, Fresh>`].
+    /// Returns [`Error::Synthesis`] if `window_bits` is zero.
+    /// # Requirements
+    /// Before calling this function, you must assert that `a<n`.
+    pub fn pow_mod_windowed<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        e: &AssignedValue<'v, F>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+        exp_bits: usize,
+        window_bits: usize,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        if window_bits == 0 {
+            return Err(Error::Synthesis);
+        }
+        let gate = self.gate();
+        let e_bits = gate.num_to_bits(ctx, e, exp_bits);
+        let num_limbs = a.num_limbs();
+        assert_eq!(num_limbs, n.num_limbs());
+        // The windows in little-endian order, the most significant one being shorter if `window_bits` does not divide `exp_bits`.
+        let windows = e_bits
+            .chunks(window_bits)
+            .map(|bits| bits_to_num(gate, ctx, bits))
+            .collect::<Vec<_>>();
+        let one = self.assign_one(ctx, num_limbs)?;
+        let mut table = vec![one.clone(), a.clone()];
+        for _ in 2..1 << window_bits {
+            let next = self.mul_mod(ctx, table.last().unwrap(), a, n)?;
+            table.push(next);
+        }
+        let mut acc = one;
+        for (i, window) in windows.iter().rev().enumerate() {
+            if i > 0 {
+                for _ in 0..window_bits {
+                    acc = self.square_mod(ctx, &acc, n)?;
+                }
+            }
+            let power = self.select_by_index(ctx, &table, window)?;
+            acc = if i == 0 {
+                power
+            } else {
+                self.mul_mod(ctx, &acc, &power, n)?
+            };
+        }
+        Ok(acc)
+    }
+
+    /// Given two integers `a` and `n`, asserts that they are coprime, i.e. `gcd(a, n) = 1`.
+    ///
+    /// The Bézout identity `s * a + t * n = 1` is constrained over the integers.
+    /// The coefficients are normalized to `0 <= s < n` and `t <= 0`, so the identity is checked as `s * a = |t| * n + 1` between non-negative integers.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an integer, e.g. a blinding factor.
+    /// * `n` - a modulus greater than one.
+    ///
+    /// # Return values
+    /// Returns `Ok(())` after adding the constraints, which are unsatisfiable if `gcd(a, n) != 1`.
+    pub fn assert_coprime<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(), Error> {
+        let limb_bits = self.limb_bits;
+        let (num_limbs_a, num_limbs_n) = (a.num_limbs(), n.num_limbs());
+        // `s = a^{-1} mod n` and `|t| = (s * a - 1) / n < a`. Both are zero if `a` is not invertible.
+        let (s_big, t_big) = a
+            .value()
+            .zip(n.value())
+            .map(|(a, n)| {
+                let s = a.modinv(&n).unwrap_or_default();
+                let sa = &s * &a;
+                let t = if sa.is_zero() {
+                    BigUint::zero()
+                } else {
+                    (sa - BigUint::one()) / &n
+                };
+                (s, t)
+            })
+            .unzip();
+        let s = self.assign_integer(ctx, s_big, num_limbs_n * limb_bits)?;
+        let t = self.assign_integer(ctx, t_big, num_limbs_a * limb_bits)?;
+        let sa = self.mul(ctx, &s, a)?;
+        let tn = self.mul(ctx, &t, n)?;
+        let gate = self.gate();
+        let mut limbs = tn.limbs().to_vec();
+        limbs[0] = gate.add(
+            ctx,
+            QuantumCell::Existing(&limbs[0]),
+            QuantumCell::Constant(F::one()),
+        );
+        let tn_one = AssignedBigUint::<F, Muled>::new(
+            OverflowInteger::construct(limbs, limb_bits),
+            tn.value().map(|v| v + BigUint::one()),
+        );
+        let is_eq = self.is_equal_muled(ctx, &sa, &tn_one, num_limbs_n, num_limbs_a)?;
+        gate.assert_is_const(ctx, &is_eq, F::one());
+        Ok(())
+    }
+
+    /// Carries a [`Muled`] integer `a` into a [`Fresh`] integer with `num_limbs` limbs.
+    /// Each limb of `a` must be less than `min_n * (1^(limb_bits) - 1)^2  + (1^(limb_bits) - 1)`.
+    pub(crate) fn carry_muled<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Muled>,
+        num_limbs: usize,
+        min_n: usize,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        let fresh = self.assign_integer(ctx, a.value(), num_limbs * self.limb_bits)?;
+        let zero = self.gate().load_zero(ctx);
+        let a = a.extend_limbs(num_limbs - a.num_limbs(), zero);
+        let is_eq =
+            self.is_equal_muled_with_min_n(ctx, &a, &fresh.clone().to_muled(), num_limbs, min_n)?;
+        self.gate().assert_is_const(ctx, &is_eq, F::one());
+        Ok(fresh)
+    }
+
+    /// Shifts the limbs of `a` to the left by `num_shift` positions, i.e. computes `a * 2^(limb_bits * num_shift)`.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - an assigned integer.
+    /// * `num_shift` - the number of zero limbs inserted as the least significant limbs.
+    ///
+    /// # Return values
+    /// Returns the shifted integer with `a.num_limbs() + num_shift` limbs, i.e. `limb_bits * num_shift` bits longer than `a`.
+    /// No constraint is added except for a zero limb.
+    pub fn shift_limbs_left<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        num_shift: usize,
+    ) -> AssignedBigUint<'v, F, Fresh> {
+        let zero = self.gate().load_zero(ctx);
+        let mut limbs = vec![zero; num_shift];
+        limbs.extend_from_slice(a.limbs());
+        let shift = self.limb_bits * num_shift;
+        AssignedBigUint::new(
+            OverflowInteger::construct(limbs, self.limb_bits),
+            a.value().map(|v| v << shift),
+        )
+    }
+
+    /// Shifts the limbs of `a` to the right by `num_shift` positions, i.e. computes `floor(a / 2^(limb_bits * num_shift))`.
+    ///
+    /// # Arguments
+    /// * `a` - an assigned integer.
+    /// * `num_shift` - the number of the least significant limbs dropped.
+    ///
+    /// # Return values
+    /// Returns the shifted integer with `a.num_limbs() - num_shift` limbs, i.e. `limb_bits * num_shift` bits shorter than `a`, or no limbs if `num_shift` exceeds them.
+    /// No constraint is added.
+    pub fn shift_limbs_right<'v>(
+        &self,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        num_shift: usize,
+    ) -> AssignedBigUint<'v, F, Fresh> {
+        let num_shift = num_shift.min(a.num_limbs());
+        let shift = self.limb_bits * num_shift;
+        AssignedBigUint::new(
+            OverflowInteger::construct(a.limbs()[num_shift..].to_vec(), self.limb_bits),
+            a.value().map(|v| v >> shift),
+        )
+    }
+
+    /// Given two inputs `a,b` and a power-of-two modulus `2^k`, performs the modular multiplication `a * b mod 2^k`.
+    ///
+    /// The reduction is a truncation: only the limb products contributing to the lowest `ceil(k / limb_bits)` limbs are computed,
+    /// their carries are propagated with witnessed carry cells, and the boundary limb is masked to its low bits.
+    /// Hence, no quotient is witnessed and no product with a modulus is computed unlike [`BigUintInstructions::mul_mod`].
+    /// Use it only where the modulus is known to be a power of two.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `a` - input of multiplication.
+    /// * `b` - input of multiplication.
+    /// * `k` - the exponent of the modulus, which must be positive.
+    ///
+    /// # Return values
+    /// Returns the modular multiplication result `a * b mod 2^k` with `ceil(k / limb_bits)` limbs as [`AssignedBigUint<F, Fresh>`].
+    /// Returns [`Error::Synthesis`] if `k` is zero.
+    pub fn mul_mod_pow2<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+        k: usize,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        if k == 0 {
+            return Err(Error::Synthesis);
+        }
+        let gate = self.gate();
+        let range = self.range();
+        let num_limbs = (k + self.limb_bits - 1) / self.limb_bits;
+        // Each carry is less than `(i + 2) * 2^limb_bits` for the `i`-th limb.
+        let carry_bits = self.limb_bits + (usize::BITS - (num_limbs + 1).leading_zeros()) as usize;
+        let base = BigUint::one() << self.limb_bits;
+        let mut limbs = vec![];
+        let mut carry: Option<AssignedValue<'v, F>> = None;
+        for i in 0..num_limbs {
+            let mut lhs = vec![];
+            let mut rhs = vec![];
+            for j in 0..=i {
+                if j < a.num_limbs() && i - j < b.num_limbs() {
+                    lhs.push(QuantumCell::Existing(a.limb(j)));
+                    rhs.push(QuantumCell::Existing(b.limb(i - j)));
+                }
+            }
+            if let Some(carry) = carry.as_ref() {
+                lhs.push(QuantumCell::Existing(carry));
+                rhs.push(QuantumCell::Constant(F::one()));
+            }
+            if lhs.is_empty() {
+                limbs.push(gate.load_zero(ctx));
+                continue;
+            }
+            let sum = gate.inner_product(ctx, lhs, rhs);
+            let (limb, next_carry) = sum
+                .value()
+                .map(|v| {
+                    let v = fe_to_biguint(v);
+                    (
+                        biguint_to_fe::<F>(&(&v % &base)),
+                        biguint_to_fe::<F>(&(&v / &base)),
+                    )
+                })
+                .unzip();
+            let limb = gate.load_witness(ctx, limb);
+            range.range_check(ctx, &limb, self.limb_bits);
+            let next_carry = gate.load_witness(ctx, next_carry);
+            range.range_check(ctx, &next_carry, carry_bits);
+            let recomposed = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(&next_carry),
+                QuantumCell::Constant(biguint_to_fe(&base)),
+                QuantumCell::Existing(&limb),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(&sum),
+                QuantumCell::Existing(&recomposed),
+            );
+            limbs.push(limb);
+            carry = Some(next_carry);
+        }
+
+        // Mask the boundary limb to its low `k - limb_bits * (num_limbs - 1)` bits.
+        let top_bits = k - self.limb_bits * (num_limbs - 1);
+        if top_bits < self.limb_bits {
+            let top_base = BigUint::one() << top_bits;
+            let top = limbs.pop().unwrap();
+            let (low, high) = top
+                .value()
+                .map(|v| {
+                    let v = fe_to_biguint(v);
+                    (
+                        biguint_to_fe::<F>(&(&v % &top_base)),
+                        biguint_to_fe::<F>(&(&v / &top_base)),
+                    )
+                })
+                .unzip();
+            let low = gate.load_witness(ctx, low);
+            range.range_check(ctx, &low, top_bits);
+            let high = gate.load_witness(ctx, high);
+            range.range_check(ctx, &high, self.limb_bits - top_bits);
+            let recomposed = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(&high),
+                QuantumCell::Constant(biguint_to_fe(&top_base)),
+                QuantumCell::Existing(&low),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(&top),
+                QuantumCell::Existing(&recomposed),
+            );
+            limbs.push(low);
+        }
+        let value = a
+            .value()
+            .zip(b.value())
+            .map(|(a, b)| (a * b) % (BigUint::one() << k));
+        Ok(AssignedBigUint::new(
+            OverflowInteger::construct(limbs, self.limb_bits),
+            value,
+        ))
+    }
+
+    /// Returns the constant one with `num_limbs` limbs.
+    pub(crate) fn assign_one<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        num_limbs: usize,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        let one = self.assign_constant(ctx, BigUint::one())?;
+        let zero = self.gate().load_zero(ctx);
+        Ok(one.extend_limbs(num_limbs - one.num_limbs(), zero))
+    }
+
+    /// Returns the bits of a fixed exponent `e` in little-endian order.
+    fn fixed_exp_bits(e: &BigUint) -> Vec<bool> {
+        let num_e_bits = Self::bits_size(&BigInt::from_biguint(Sign::Plus, e.clone()));
+        let e_bits = e
+            .to_bytes_le()
+            .into_iter()
+            .flat_map(|v| {
+                (0..8)
+                    .map(|i: u8| (v >> i) & 1u8 == 1u8)
+                    .collect::<Vec<bool>>()
+            })
+            .collect::<Vec<bool>>();
+        e_bits[0..num_e_bits].to_vec()
+    }
+
+    /// Returns an assigned bit representing whether `a` and `b` with `num_limbs` limbs are equivalent, whose [`RangeType`] is [`Muled`].
+    /// Each limb of `a` and `b` must be less than `min_n * (1^(limb_bits) - 1)^2  + (1^(limb_bits) - 1)`.
+    fn is_equal_muled_with_min_n<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Muled>,
+        b: &AssignedBigUint<'v, F, Muled>,
+        num_limbs: usize,
+        min_n: usize,
+    ) -> Result<AssignedValue<'v, F>, Error> {
+        // The following constraints are designed with reference to EqualWhenCarried template in https://github.com/jacksoom/circom-bigint/blob/master/circuits/mult.circom.
+        // We use lookup tables to optimize range checks.
+        let muled_limb_max = Self::compute_muled_limb_max(self.limb_bits, min_n);
+        let muled_limb_max_fe = bigint_to_fe::<F>(&muled_limb_max);
+        let muled_limb_max_bits = Self::bits_size(&(&muled_limb_max * 2u32));
+        let carry_bits = muled_limb_max_bits - self.limb_bits;
+        let gate = self.gate();
+        let range = self.range();
+
+        // The naive approach is to subtract the two integers limb by limb and:
+        //  a. Verify that they sum to zero along the way while
+        //  b. Propagating carries
+        // but this doesn't work because early sums might be negative.
+        // So instead we verify that `a - b + word_max = word_max`.
+        let limb_max = BigInt::from(1) << self.limb_bits;
+        let zero = gate.load_constant(ctx, F::zero());
+        let mut accumulated_extra = zero.clone();
+        let mut carry = Vec::with_capacity(num_limbs);
+        let mut cs = Vec::with_capacity(num_limbs);
+        carry.push(zero.clone());
+        let mut eq_bit = gate.load_constant(ctx, F::one());
+        let a_limbs = a.limbs();
+        let b_limbs = b.limbs();
+        for i in 0..num_limbs {
+            // `sum = a - b + word_max`
+            let a_b_sub = gate.sub(
+                ctx,
+                QuantumCell::Existing(&a_limbs[i]),
+                QuantumCell::Existing(&b_limbs[i]),
+            );
+            let sum = gate.sum(
+                ctx,
+                vec![
+                    QuantumCell::Existing(&a_b_sub),
+                    QuantumCell::Existing(&carry[i]),
+                    QuantumCell::Constant(muled_limb_max_fe),
+                ],
+            );
+            // `c` is lower `self.limb_width` bits of `sum`.
+            // `new_carry` is any other upper bits.
+            let (new_carry, c) = self.div_mod_unsafe(ctx, &sum, &limb_max);
+            carry.push(new_carry);
+            cs.push(c);
+
+            // `accumulated_extra` is the sum of `word_max`.
+            accumulated_extra = gate.add(
+                ctx,
+                QuantumCell::Existing(&accumulated_extra),
+                QuantumCell::Constant(muled_limb_max_fe),
+            );
+            let (q_acc, mod_acc) = self.div_mod_unsafe(ctx, &accumulated_extra, &limb_max);
+            // If and only if `a` is equal to `b`, lower `self.limb_width` bits of `sum` and `accumulated_extra` are the same.
+            let cs_acc_eq = gate.is_equal(
+                ctx,
+                QuantumCell::Existing(&cs[i]),
+                QuantumCell::Existing(&mod_acc),
+            );
+            eq_bit = gate.and(
+                ctx,
+                QuantumCell::Existing(&eq_bit),
+                QuantumCell::Existing(&cs_acc_eq),
+            );
+            accumulated_extra = q_acc;
+
+            if i < num_limbs - 1 {
+                // Assert that each carry fits in `carry_bits` bits.
+                range.range_check(ctx, &carry[i + 1], carry_bits);
+            } else {
+                // The final carry should match the `accumulated_extra`.
+                let final_carry_eq = gate.is_equal(
+                    ctx,
+                    QuantumCell::Existing(&carry[i + 1]),
+                    QuantumCell::Existing(&accumulated_extra),
+                );
+                eq_bit = gate.and(
+                    ctx,
+                    QuantumCell::Existing(&eq_bit),
+                    QuantumCell::Existing(&final_carry_eq),
+                );
+            }
+        }
+        Ok(eq_bit)
+    }
+
+    pub fn new_context<'a, 'b>(&'b self, region: Region<'a, F>) -> Context<'a, F> {
+        Context::new(
+            region,
+            ContextParams {
+                max_rows: self.range.gate.max_rows,
+                num_context_ids: 1,
+                fixed_columns: self.range.gate.constants.clone(),
+            },
+        )
+    }
+
+    /// Returns the fewest bits necessary to express the [`BigUint`].
+    fn bits_size(val: &BigInt) -> usize {
+        val.bits() as usize
+    }
+
+    fn num_limbs(&self, val: &BigInt) -> usize {
+        let bits = Self::bits_size(&val);
+        let num_limbs = if bits % self.limb_bits == 0 {
+            bits / self.limb_bits
+        } else {
+            bits / self.limb_bits + 1
+        };
+        num_limbs
+    }
+
+    /// Returns the maximum limb size of [`Muled`] type integers.
+    fn compute_muled_limb_max(limb_width: usize, min_n: usize) -> BigInt {
+        let one = BigInt::from(1usize);
+        let out_base = BigInt::from(1usize) << limb_width;
+        BigInt::from(min_n) * (&out_base - &one) * (&out_base - &one) + (&out_base - &one)
+    }
+
+    /// Performs [`BigUintInstructions::mod_inverse`] with a given witness, i.e. whether `a` is invertible modulo `n`, the inverse, and `(g, a/g, n/g)` of [`Self::inverse_witness`].
+    /// Every part of the witness is constrained, so a witness inconsistent with `a` and `n` leaves the constraints unsatisfied.
+    fn mod_inverse_with_witness<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+        witness: Value<(bool, BigUint, BigUint, BigUint, BigUint)>,
+    ) -> Result<(AssignedBigUint<'v, F, Fresh>, AssignedValue<'v, F>), Error> {
+        // 1. Assign the witness.
+        let num_limbs = n.num_limbs();
+        assert_eq!(a.num_limbs(), num_limbs);
+        let bit_len = num_limbs * self.limb_bits;
+        let gate = self.gate();
+        let is_invertible = gate.load_witness(ctx, witness.as_ref().map(|w| F::from(w.0 as u64)));
+        self.range().range_check(ctx, &is_invertible, 1);
+        let assign_inv =
+            self.assign_integer(ctx, witness.as_ref().map(|w| w.1.clone()), bit_len)?;
+        let assign_g = self.assign_integer(ctx, witness.as_ref().map(|w| w.2.clone()), bit_len)?;
+        let assign_x = self.assign_integer(ctx, witness.as_ref().map(|w| w.3.clone()), bit_len)?;
+        let assign_y = self.assign_integer(ctx, witness.as_ref().map(|w| w.4.clone()), bit_len)?;
+
+        // 2. If `a` is invertible, assert `a * inv = 1 mod n`.
+        self.assert_in_field(ctx, &assign_inv, n)?;
+        let prod = self.mul_mod(ctx, a, &assign_inv, n)?;
+        let zero = gate.load_zero(ctx);
+        let one = self.assign_constant(ctx, BigUint::one())?;
+        let one = one.extend_limbs(num_limbs - one.num_limbs(), zero.clone());
+        let is_one = self.is_equal_fresh(ctx, &prod, &one)?;
+        let is_not_one = gate.not(ctx, QuantumCell::Existing(&is_one));
+        let is_wrong_inv = gate.mul(
+            ctx,
+            QuantumCell::Existing(&is_invertible),
+            QuantumCell::Existing(&is_not_one),
+        );
+        gate.assert_is_const(ctx, &is_wrong_inv, F::zero());
+
+        // 3. Otherwise, assert `g>1`, `a = g * x` and `n = g * y`.
+        let is_g_gt_one = self.is_greater_than(ctx, &assign_g, &one)?;
+        let gx = self.mul(ctx, &assign_g, &assign_x)?;
+        let gy = self.mul(ctx, &assign_g, &assign_y)?;
+        let a_muled = a.extend_limbs(num_limbs - 1, zero.clone()).to_muled();
+        let n_muled = n.extend_limbs(num_limbs - 1, zero).to_muled();
+        let is_a_eq = self.is_equal_muled(ctx, &gx, &a_muled, num_limbs, num_limbs)?;
+        let is_n_eq = self.is_equal_muled(ctx, &gy, &n_muled, num_limbs, num_limbs)?;
+        let is_divisor = gate.and(
+            ctx,
+            QuantumCell::Existing(&is_a_eq),
+            QuantumCell::Existing(&is_n_eq),
+        );
+        let is_divisor = gate.and(
+            ctx,
+            QuantumCell::Existing(&is_divisor),
+            QuantumCell::Existing(&is_g_gt_one),
+        );
+        let is_proved = gate.or(
+            ctx,
+            QuantumCell::Existing(&is_invertible),
+            QuantumCell::Existing(&is_divisor),
+        );
+        gate.assert_is_const(ctx, &is_proved, F::one());
+
+        // 4. If `a` is not invertible, assert that the returned integer is zero.
+        let is_not_invertible = gate.not(ctx, QuantumCell::Existing(&is_invertible));
+        for limb in assign_inv.limbs() {
+            let masked = gate.mul(
+                ctx,
+                QuantumCell::Existing(limb),
+                QuantumCell::Existing(&is_not_invertible),
+            );
+            gate.assert_is_const(ctx, &masked, F::zero());
+        }
+        Ok((assign_inv, is_invertible))
+    }
+
+    /// Returns the witness of [`BigUintInstructions::mod_inverse`], i.e. whether `a` is invertible modulo `n`, the inverse, and `(g, a/g, n/g)` for `g = gcd(a, n)`.
+    /// If `a` is not invertible, the inverse is zero. Otherwise, `g` is one.
+    fn inverse_witness(a: &BigUint, n: &BigUint) -> (bool, BigUint, BigUint, BigUint, BigUint) {
+        match a.modinv(n) {
+            Some(inv) => (true, inv, BigUint::one(), a.clone(), n.clone()),
+            None => {
+                let (mut g, mut r) = (n.clone(), a.clone());
+                while !r.is_zero() {
+                    let next = &g % &r;
+                    g = r;
+                    r = next;
+                }
+                (false, BigUint::zero(), g.clone(), a / &g, n / &g)
+            }
+        }
+    }
+
+    /// Given a integer `a` and a divisor `n`, performs `a/n` and `a mod n`.
+    /// # Panics
+    /// Panics if `n=0`.
+    fn div_mod_unsafe<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedValue<'v, F>,
+        b: &BigInt,
+    ) -> (AssignedValue<'v, F>, AssignedValue<'v, F>) {
+        let gate = self.gate();
+        let (q_val, n_val) = a
+            .value
+            .map(|a| {
+                let a = fe_to_bigint(&a);
+                let (q_val, n_val) = (&a / b, &a % b);
+                (bigint_to_fe(&q_val), bigint_to_fe(&n_val))
+            })
+            .unzip();
+        let q = gate.load_witness(ctx, q_val);
+        let n = gate.load_witness(ctx, n_val);
+        let prod = gate.mul(
+            ctx,
+            QuantumCell::Existing(&q),
+            QuantumCell::Constant(bigint_to_fe(b)),
+        );
+        let a_prod_sub = gate.sub(ctx, QuantumCell::Existing(&a), QuantumCell::Existing(&prod));
         let is_eq = gate.is_equal(
             ctx,
             QuantumCell::Existing(&n),
@@ -790,3 +1676,608 @@ impl<F: PrimeField> BigUintConfig<F> {
         (q, n)
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::mock_prove;
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    #[test]
+    fn test_select_by_index() {
+        let values = [
+            BigUint::from(3u64),
+            BigUint::from(u64::MAX) * BigUint::from(7u64),
+            BigUint::one() << 100,
+        ];
+        for index in 0..values.len() {
+            let values = values.clone();
+            let public_inputs = decompose_biguint::<Fr>(&values[index], 2, 64);
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let assigned = values
+                        .iter()
+                        .map(|v| config.assign_integer(ctx, Value::known(v.clone()), 128))
+                        .collect::<Result<Vec<_>, Error>>()?;
+                    let index = config
+                        .gate()
+                        .load_witness(ctx, Value::known(Fr::from(index as u64)));
+                    let selected = config.select_by_index(ctx, &assigned, &index)?;
+                    Ok(selected.limbs().to_vec())
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_limb_accessors() {
+        for (bits_len, limb_bits) in [(2048, 64), (128, 64), (255, 17)] {
+            let value = (BigUint::one() << (bits_len - 1)) + BigUint::from(5u64);
+            let public_inputs = decompose_biguint::<Fr>(&value, bits_len / limb_bits, limb_bits);
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), limb_bits);
+                    let assigned =
+                        config.assign_integer(ctx, Value::known(value.clone()), bits_len)?;
+                    assert_eq!(assigned.num_limbs(), bits_len / limb_bits);
+                    assert_eq!(assigned.limb_bits(), limb_bits);
+                    assert_eq!(assigned.bit_len(), bits_len);
+                    assert_eq!(assigned.limbs().len(), assigned.num_limbs());
+                    // The first limb is the least significant one.
+                    assigned
+                        .limb(0)
+                        .value()
+                        .map(|v| assert_eq!(*v, Fr::from(5u64)));
+                    Ok(assigned.limbs().to_vec())
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        // 2^127 - 1 is prime, so the inverse is `a^(p-2) mod p`.
+        let p = (BigUint::one() << 127) - BigUint::one();
+        let a = BigUint::from(0x1234_5678_9abc_def0u64);
+        let expected = a.modpow(&(&p - BigUint::from(2u64)), &p);
+        assert_eq!((&a * &expected) % &p, BigUint::one());
+        // A composite modulus (2^61 - 1) * (2^89 - 1).
+        let q = (BigUint::one() << 61) - BigUint::one();
+        let n = &q * ((BigUint::one() << 89) - BigUint::one());
+        let b = BigUint::from(12345u64);
+        let b_inv = b.modinv(&n).unwrap();
+        for (a, n, inv, is_invertible) in [
+            (a, p.clone(), expected, true),
+            (b, n.clone(), b_inv, true),
+            (&q * BigUint::from(3u64), n.clone(), BigUint::zero(), false),
+            (BigUint::zero(), p, BigUint::zero(), false),
+        ] {
+            let mut public_inputs = decompose_biguint::<Fr>(&inv, 3, 64);
+            public_inputs.push(Fr::from(is_invertible as u64));
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 192)?;
+                    let n = config.assign_integer(ctx, Value::known(n.clone()), 192)?;
+                    let (inv, is_invertible) = config.mod_inverse(ctx, &a, &n)?;
+                    let mut public_values = inv.limbs().to_vec();
+                    public_values.push(is_invertible);
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse_rejects_tampered_witness() {
+        // `3q` shares the factor `q = 2^61 - 1` with `n`, so it is not invertible.
+        let q = (BigUint::one() << 61) - BigUint::one();
+        let n = &q * ((BigUint::one() << 89) - BigUint::one());
+        let a = &q * BigUint::from(3u64);
+        let run = |inv: BigUint| {
+            let (a, n) = (a.clone(), n.clone());
+            let (_, _, g, x, y) = BigUintConfig::<Fr>::inverse_witness(&a, &n);
+            let witness = (false, inv.clone(), g, x, y);
+            let mut public_inputs = decompose_biguint::<Fr>(&inv, 3, 64);
+            public_inputs.push(Fr::zero());
+            mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 192)?;
+                    let n = config.assign_integer(ctx, Value::known(n.clone()), 192)?;
+                    let (inv, is_invertible) = config.mod_inverse_with_witness(
+                        ctx,
+                        &a,
+                        &n,
+                        Value::known(witness.clone()),
+                    )?;
+                    let mut public_values = inv.limbs().to_vec();
+                    public_values.push(is_invertible);
+                    Ok(public_values)
+                },
+                public_inputs,
+            )
+        };
+        run(BigUint::zero()).assert_satisfied();
+        // A non-zero integer next to the proof of non-invertibility must be rejected.
+        assert!(run(BigUint::from(5u64)).verify().is_err());
+    }
+
+    #[test]
+    fn test_mul_mod_binds_modulus() {
+        // 2^127 - 1 spans two 64-bit limbs.
+        let n = (BigUint::one() << 127) - BigUint::one();
+        let a = (BigUint::one() << 126) + BigUint::from(12345u64);
+        let b = (BigUint::one() << 125) + BigUint::from(67890u64);
+        let run = |witness_n: BigUint| {
+            let (a, b, n) = (a.clone(), b.clone(), n.clone());
+            let prod = (&a * &b) % &witness_n;
+            mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 128)?;
+                    let b = config.assign_integer(ctx, Value::known(b.clone()), 128)?;
+                    let n = config.assign_integer(ctx, Value::known(n.clone()), 128)?;
+                    // The limbs of `n` are kept, but the witnesses are computed for `witness_n`.
+                    let n = AssignedBigUint::new(n.int.clone(), Value::known(witness_n.clone()));
+                    let prod = config.mul_mod(ctx, &a, &b, &n)?;
+                    Ok(prod.limbs().to_vec())
+                },
+                decompose_biguint::<Fr>(&prod, 2, 64),
+            )
+        };
+        run(n.clone()).assert_satisfied();
+        // A reduction by another modulus must not satisfy the constraints on the assigned `n`.
+        assert!(run(&n - BigUint::from(2u64)).verify().is_err());
+    }
+
+    #[test]
+    fn test_pow_mod_fixed_exp_matches_variable_exp() {
+        use num_bigint::RandBigInt;
+        use std::{cell::RefCell, rc::Rc};
+        let mut rng = rand::thread_rng();
+        let n = rng.gen_biguint(2048) | (BigUint::one() << 2047) | BigUint::one();
+        let a = rng.gen_biguint_below(&n);
+        let e = BigUint::from(65537u64);
+        let expected = decompose_biguint::<Fr>(&a.modpow(&e, &n), 32, 64);
+        let cells = Rc::new(RefCell::new((0, 0)));
+        let recorded = cells.clone();
+        let prover = mock_prove(
+            move |ctx, range| {
+                let config = BigUintConfig::construct(range.clone(), 64);
+                let a = config.assign_integer(ctx, Value::known(a.clone()), 2048)?;
+                let n = config.assign_integer(ctx, Value::known(n.clone()), 2048)?;
+                let start = ctx.total_advice;
+                let fixed = config.pow_mod_fixed_exp(ctx, &a, &e, &n)?;
+                let fixed_cells = ctx.total_advice - start;
+                let var_e = config
+                    .gate()
+                    .load_witness(ctx, Value::known(Fr::from(65537u64)));
+                let start = ctx.total_advice;
+                let variable = config.pow_mod(ctx, &a, &var_e, &n, 17)?;
+                *recorded.borrow_mut() = (fixed_cells, ctx.total_advice - start);
+                let mut public_values = fixed.limbs().to_vec();
+                public_values.extend(variable.limbs().to_vec());
+                Ok(public_values)
+            },
+            [expected.clone(), expected].concat(),
+        );
+        prover.assert_satisfied();
+        let (fixed_cells, variable_cells) = *cells.borrow();
+        // 16 squarings and one multiplication, against 17 of each and the selections.
+        assert!(fixed_cells * 17 < variable_cells * 10);
+    }
+
+    #[test]
+    fn test_pow_mod_fixed_exp_reduces_small_exp() {
+        use num_bigint::RandBigInt;
+        let mut rng = rand::thread_rng();
+        let n = rng.gen_biguint(2047) | (BigUint::one() << 2046) | BigUint::one();
+        // `a` is not reduced modulo `n`.
+        let a = &n + rng.gen_biguint_below(&n);
+        for e in [0u64, 1] {
+            let e = BigUint::from(e);
+            let expected = decompose_biguint::<Fr>(&a.modpow(&e, &n), 32, 64);
+            let (a, n) = (a.clone(), n.clone());
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 2048)?;
+                    let n = config.assign_integer(ctx, Value::known(n.clone()), 2048)?;
+                    let powed = config.pow_mod_fixed_exp(ctx, &a, &e, &n)?;
+                    Ok(powed.limbs().to_vec())
+                },
+                expected,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_pow_mod_windowed() {
+        use num_bigint::RandBigInt;
+        use std::{cell::RefCell, rc::Rc};
+        let mut rng = rand::thread_rng();
+        let n = rng.gen_biguint(512) | (BigUint::one() << 511) | BigUint::one();
+        let a = rng.gen_biguint_below(&n);
+        // `e` is shorter than the 64 exponent bits, so its most significant window is zero.
+        let e = rng.gen_biguint(62);
+        let expected = decompose_biguint::<Fr>(&a.modpow(&e, &n), 8, 64);
+        for window_bits in [1, 3, 4] {
+            let (a, e, n) = (a.clone(), e.clone(), n.clone());
+            let cells = Rc::new(RefCell::new((0, 0)));
+            let recorded = cells.clone();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 512)?;
+                    let n = config.assign_integer(ctx, Value::known(n.clone()), 512)?;
+                    let var_e = config
+                        .gate()
+                        .load_witness(ctx, Value::known(biguint_to_fe(&e)));
+                    let start = ctx.total_advice;
+                    let windowed = config.pow_mod_windowed(ctx, &a, &var_e, &n, 64, window_bits)?;
+                    let windowed_cells = ctx.total_advice - start;
+                    let start = ctx.total_advice;
+                    config.pow_mod(ctx, &a, &var_e, &n, 64)?;
+                    *recorded.borrow_mut() = (windowed_cells, ctx.total_advice - start);
+                    Ok(windowed.limbs().to_vec())
+                },
+                expected.clone(),
+            );
+            prover.assert_satisfied();
+            let (windowed_cells, bitwise_cells) = *cells.borrow();
+            if window_bits == 4 {
+                assert!(windowed_cells < bitwise_cells);
+            }
+        }
+    }
+
+    #[test]
+    fn test_assert_coprime() {
+        // n = 3 * 5 * (2^61 - 1).
+        let p = (BigUint::one() << 61) - BigUint::one();
+        let n = BigUint::from(15u64) * &p;
+        for (a, is_coprime) in [
+            (BigUint::from(7u64) << 64, true),
+            (BigUint::from(1u64), true),
+            (BigUint::from(9u64), false),
+            (&p * BigUint::from(2u64), false),
+            (BigUint::zero(), false),
+        ] {
+            let n = n.clone();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 128)?;
+                    let n = config.assign_integer(ctx, Value::known(n.clone()), 128)?;
+                    config.assert_coprime(ctx, &a, &n)?;
+                    Ok(vec![])
+                },
+                vec![],
+            );
+            assert_eq!(prover.verify().is_ok(), is_coprime);
+        }
+    }
+
+    #[test]
+    fn test_is_equal_with_different_num_limbs() {
+        let value = BigUint::from(u64::MAX) * BigUint::from(3u64);
+        let other = &value + BigUint::one();
+        let prover = mock_prove(
+            move |ctx, range| {
+                let config = BigUintConfig::construct(range.clone(), 64);
+                // The constant has two limbs, while the assigned integers have four.
+                let expected = config.assign_constant(ctx, value.clone())?;
+                let a = config.assign_integer(ctx, Value::known(value.clone()), 256)?;
+                let b = config.assign_integer(ctx, Value::known(other.clone()), 256)?;
+                config.assert_equal(ctx, &a, &expected);
+                config.assert_equal(ctx, &expected, &a);
+                Ok(vec![
+                    config.is_equal(ctx, &a, &expected),
+                    config.is_equal(ctx, &b, &expected),
+                ])
+            },
+            vec![Fr::one(), Fr::zero()],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_is_less_than_boundary() {
+        let b = (BigUint::one() << 128) + BigUint::from(5u64);
+        for (a, expected) in [
+            (b.clone() - BigUint::one(), true),
+            (b.clone(), false),
+            (b.clone() + BigUint::one(), false),
+            (BigUint::from(5u64), true),
+        ] {
+            let b = b.clone();
+            let is_greater = a > b;
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    // `a` has four limbs, while `b` has three.
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 256)?;
+                    let b = config.assign_integer(ctx, Value::known(b.clone()), 192)?;
+                    if expected {
+                        config.assert_less_than(ctx, &a, &b)?;
+                    }
+                    Ok(vec![
+                        config.is_less_than(ctx, &a, &b)?,
+                        config.is_less_than(ctx, &b, &a)?,
+                    ])
+                },
+                vec![Fr::from(expected as u64), Fr::from(is_greater as u64)],
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_to_assigned_bytes() {
+        let value = BigUint::from_bytes_be(&(1..=24u8).collect::<Vec<u8>>());
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let bytes = match endianness {
+                Endianness::Little => value.to_bytes_le(),
+                Endianness::Big => value.to_bytes_be(),
+            };
+            assert_eq!(bytes.len(), 24);
+            let value = value.clone();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(value.clone()), 192)?;
+                    Ok(config.to_assigned_bytes(ctx, &a, endianness))
+                },
+                bytes.iter().map(|byte| Fr::from(*byte as u64)).collect(),
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_from_assigned_bytes_round_trip() {
+        let bytes = (1..=20u8).collect::<Vec<u8>>();
+        for endianness in [Endianness::Little, Endianness::Big] {
+            let value = match endianness {
+                Endianness::Little => BigUint::from_bytes_le(&bytes),
+                Endianness::Big => BigUint::from_bytes_be(&bytes),
+            };
+            let bytes = bytes.clone();
+            // The 20 bytes are padded to 3 limbs of 8 bytes.
+            let mut public_inputs = decompose_biguint::<Fr>(&value, 3, 64);
+            let padded = match endianness {
+                Endianness::Little => [bytes.clone(), vec![0; 4]].concat(),
+                Endianness::Big => [vec![0; 4], bytes.clone()].concat(),
+            };
+            public_inputs.extend(padded.iter().map(|byte| Fr::from(*byte as u64)));
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let assigned_bytes = bytes
+                        .iter()
+                        .map(|byte| {
+                            config
+                                .gate()
+                                .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                        })
+                        .collect::<Vec<_>>();
+                    let a = config.from_assigned_bytes(ctx, &assigned_bytes, endianness);
+                    let mut public_values = a.limbs().to_vec();
+                    public_values.extend(config.to_assigned_bytes(ctx, &a, endianness));
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_from_assigned_bytes_rejects_non_byte() {
+        // `[0x100, 0x00]` packs to the same limb as `[0x00, 0x01]`.
+        for (bytes, expected) in [([0x00u64, 0x01], true), ([0x100, 0x00], false)] {
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let assigned_bytes = bytes
+                        .iter()
+                        .map(|byte| {
+                            config
+                                .gate()
+                                .load_witness(ctx, Value::known(Fr::from(*byte)))
+                        })
+                        .collect::<Vec<_>>();
+                    let a = config.from_assigned_bytes(ctx, &assigned_bytes, Endianness::Little);
+                    Ok(a.limbs().to_vec())
+                },
+                vec![Fr::from(0x100u64)],
+            );
+            assert_eq!(prover.verify().is_ok(), expected);
+        }
+    }
+
+    #[test]
+    fn test_select() {
+        let a = BigUint::from(u64::MAX) << 64;
+        let b = BigUint::from(42u64);
+        for (sel, expected) in [(1u64, Some(&a)), (0, Some(&b)), (2, None)] {
+            let (a, b) = (a.clone(), b.clone());
+            let public_inputs = decompose_biguint::<Fr>(expected.unwrap_or(&b), 2, 64);
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    // `b` has fewer limbs than `a`.
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 128)?;
+                    let b = config.assign_integer(ctx, Value::known(b.clone()), 64)?;
+                    let sel = config.gate().load_witness(ctx, Value::known(Fr::from(sel)));
+                    let selected = config.select(ctx, &a, &b, &sel)?;
+                    Ok(selected.limbs().to_vec())
+                },
+                public_inputs,
+            );
+            // A non-boolean `sel` is rejected.
+            assert_eq!(prover.verify().is_ok(), expected.is_some());
+        }
+    }
+
+    #[test]
+    fn test_add_with_carry() {
+        let max = (BigUint::one() << 128) - BigUint::one();
+        for (a, b) in [
+            (max.clone(), BigUint::one()),
+            (max.clone(), max.clone()),
+            (BigUint::from(u64::MAX), BigUint::from(u64::MAX)),
+        ] {
+            let expected = &a + &b;
+            let mut public_inputs =
+                decompose_biguint::<Fr>(&(&expected % (BigUint::one() << 128)), 2, 64);
+            public_inputs.push(Fr::from((expected >> 128u32 == BigUint::one()) as u64));
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 128)?;
+                    let b = config.assign_integer(ctx, Value::known(b.clone()), 128)?;
+                    let (sum, carry) = config.add_with_carry(ctx, &a, &b)?;
+                    let mut public_values = sum.limbs().to_vec();
+                    public_values.push(carry);
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_sub_with_borrow() {
+        let b = (BigUint::one() << 100) + BigUint::from(7u64);
+        for a in [
+            b.clone(),
+            &b - BigUint::one(),
+            &b + BigUint::one(),
+            BigUint::zero(),
+        ] {
+            let (difference, borrow) = if a >= b {
+                (&a - &b, false)
+            } else {
+                ((BigUint::one() << 128) + &a - &b, true)
+            };
+            let mut public_inputs = decompose_biguint::<Fr>(&difference, 2, 64);
+            public_inputs.push(Fr::from(borrow as u64));
+            let b = b.clone();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 128)?;
+                    let b = config.assign_integer(ctx, Value::known(b.clone()), 128)?;
+                    let (difference, borrow) = config.sub(ctx, &a, &b)?;
+                    let mut public_values = difference.limbs().to_vec();
+                    public_values.push(borrow);
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_shift_limbs() {
+        let value = (BigUint::from(0xdead_beefu64) << 64) + BigUint::from(0x1234u64);
+        let mut public_inputs = decompose_biguint::<Fr>(&(&value << 64u32), 3, 64);
+        public_inputs.extend(decompose_biguint::<Fr>(&(&value >> 64u32), 1, 64));
+        let prover = mock_prove(
+            move |ctx, range| {
+                let config = BigUintConfig::construct(range.clone(), 64);
+                let a = config.assign_integer(ctx, Value::known(value.clone()), 128)?;
+                let shifted = config.shift_limbs_left(ctx, &a, 1);
+                assert_eq!(shifted.num_limbs(), 3);
+                // `shift_limbs_left(a, 1)` equals `a * 2^64`.
+                let base = config.assign_constant(ctx, BigUint::one() << 64)?;
+                let muled = config.mul(ctx, &a, &base)?;
+                config.assert_equal_muled(ctx, &shifted.clone().to_muled(), &muled, 2, 2)?;
+                let mut public_values = shifted.limbs().to_vec();
+                public_values.extend_from_slice(config.shift_limbs_right(&a, 1).limbs());
+                Ok(public_values)
+            },
+            public_inputs,
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_to_bits() {
+        let value = BigUint::from(0b1011_0110_1001u64 | (1 << 19));
+        for (num_bits, fits) in [(128, true), (20, true), (19, false), (70, true)] {
+            let value = value.clone();
+            let public_inputs = (0..num_bits)
+                .map(|i| Fr::from(value.bit(i as u64) as u64))
+                .collect::<Vec<_>>();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(value.clone()), 128)?;
+                    Ok(config.to_bits(ctx, &a, num_bits))
+                },
+                public_inputs,
+            );
+            // The value has 20 bits, so it does not fit in 19 bits.
+            assert_eq!(prover.verify().is_ok(), fits);
+        }
+    }
+
+    #[test]
+    fn test_is_zero() {
+        let top_limb_only = BigUint::from(1u64) << 192;
+        for (value, expected) in [
+            (BigUint::from(0u64), Fr::one()),
+            (top_limb_only, Fr::zero()),
+        ] {
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(value.clone()), 256)?;
+                    assert_eq!(a.num_limbs(), 4);
+                    Ok(vec![config.is_zero(ctx, &a)?])
+                },
+                vec![expected],
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_mul_mod_pow2() {
+        use num_bigint::RandBigInt;
+        let mut rng = rand::thread_rng();
+        let a = rng.gen_biguint(256);
+        let b = rng.gen_biguint(200);
+        for k in [1, 20, 64, 100, 256, 500] {
+            let expected = (&a * &b) % (BigUint::one() << k);
+            let num_limbs = (k + 63) / 64;
+            let public_inputs = decompose_biguint::<Fr>(&expected, num_limbs, 64);
+            let (a, b) = (a.clone(), b.clone());
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let config = BigUintConfig::construct(range.clone(), 64);
+                    let a = config.assign_integer(ctx, Value::known(a.clone()), 256)?;
+                    let b = config.assign_integer(ctx, Value::known(b.clone()), 256)?;
+                    let c = config.mul_mod_pow2(ctx, &a, &b, k)?;
+                    c.value().map(|v| assert_eq!(v, expected));
+                    Ok(c.limbs().to_vec())
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+    }
+}
@@ -45,7 +45,8 @@ pub trait BigUintInstructions<F: PrimeField> {
         aux: &RefreshAux,
     ) -> Result<AssignedBigUint<'v, F, Fresh>, Error>;
 
-    /// Given a bit value `sel`, return `a` if `a`=1 and `b` otherwise.
+    /// Given a bit value `sel`, return `a` if `sel`=1 and `b` otherwise.
+    /// `sel` is constrained to be a bit, and the operand with fewer limbs is padded with zero limbs.
     fn select<'v, T: RangeType>(
         &self,
         ctx: &mut Context<'v, F>,
@@ -54,6 +55,14 @@ pub trait BigUintInstructions<F: PrimeField> {
         sel: &AssignedValue<'v, F>,
     ) -> Result<AssignedBigUint<'v, F, T>, Error>;
 
+    /// Given an assigned index `index`, returns `values[index]`.
+    fn select_by_index<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        values: &[AssignedBigUint<'v, F, Fresh>],
+        index: &AssignedValue<'v, F>,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error>;
+
     /// Given two inputs `a,b`, performs the addition `a + b`.
     fn add<'v>(
         &self,
@@ -140,11 +149,20 @@ pub trait BigUintInstructions<F: PrimeField> {
         n: &AssignedBigUint<'v, F, Fresh>,
     ) -> Result<AssignedBigUint<'v, F, Fresh>, Error>;
 
-    /// Returns an assigned bit representing whether `a` is zero or not.
+    /// Given an input `a` and a modulus `n`, performs the modular inverse `a^{-1} mod n`.
+    /// Returns the inverse, which is zero if it does not exist, and an assigned bit representing whether `a` is invertible modulo `n`.
+    fn mod_inverse<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(AssignedBigUint<'v, F, Fresh>, AssignedValue<'v, F>), Error>;
+
+    /// Returns an assigned bit representing whether `a` is zero or not, i.e., whether all of its limbs are zero.
     fn is_zero<'v>(
         &self,
         ctx: &mut Context<'v, F>,
-        a: &'v AssignedBigUint<'v, F, Fresh>,
+        a: &AssignedBigUint<'v, F, Fresh>,
     ) -> Result<AssignedValue<'v, F>, Error>;
 
     /// Returns an assigned bit representing whether `a` and `b` are equivalent, whose [`RangeType`] is [`Fresh`].
@@ -166,6 +184,7 @@ pub trait BigUintInstructions<F: PrimeField> {
     ) -> Result<AssignedValue<'v, F>, Error>;
 
     /// Returns an assigned bit representing whether `a` is less than `b` (`a<b`).
+    /// The operands may have different numbers of limbs.
     fn is_less_than<'v>(
         &self,
         ctx: &mut Context<'v, F>,
@@ -173,6 +192,15 @@ pub trait BigUintInstructions<F: PrimeField> {
         b: &AssignedBigUint<'v, F, Fresh>,
     ) -> Result<AssignedValue<'v, F>, Error>;
 
+    /// Assert that `a` is less than `b` (`a<b`).
+    /// The operands may have different numbers of limbs.
+    fn assert_less_than<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        a: &AssignedBigUint<'v, F, Fresh>,
+        b: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<(), Error>;
+
     /// Returns an assigned bit representing whether `a` is less than or equal to `b` (`a<=b`).
     fn is_less_than_or_equal<'v>(
         &self,
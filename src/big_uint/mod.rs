@@ -26,18 +26,36 @@ impl<'v, F: PrimeField, T: RangeType> AssignedBigUint<'v, F, T> {
         }
     }
 
-    pub fn limb(&self, i: usize) -> &AssignedValue<F> {
+    /// Returns the `i`-th limb, the least significant one being the `0`-th. Panics if `i >= self.num_limbs()`.
+    pub fn limb(&self, i: usize) -> &AssignedValue<'v, F> {
         &self.int.limbs[i]
     }
 
+    /// Returns the number of limbs, e.g. `bit_len / limb_bits` for an integer assigned by [`BigUintInstructions::assign_integer`].
     pub fn num_limbs(&self) -> usize {
         self.int.limbs.len()
     }
 
+    /// Returns the limbs in little-endian order, i.e. the integer is `sum(limbs[i] * 2^(i * limb_bits))`.
+    ///
+    /// The order matches [`decompose_biguint`], so the limbs can be bound to instances computed by it.
     pub fn limbs(&self) -> &[AssignedValue<'v, F>] {
         &self.int.limbs
     }
 
+    /// Returns the maximum bit length of a limb.
+    /// It equals the `limb_bits` of the [`BigUintConfig`] for a [`Fresh`] integer and may be larger for a [`Muled`] one.
+    pub fn limb_bits(&self) -> usize {
+        self.int.max_limb_bits
+    }
+
+    /// Returns the declared bit length of the integer, i.e. `self.num_limbs() * self.limb_bits()`.
+    ///
+    /// For a [`Fresh`] integer, every limb is range checked, so the value is less than `2^bit_len`.
+    pub fn bit_len(&self) -> usize {
+        self.num_limbs() * self.limb_bits()
+    }
+
     pub fn value(&self) -> Value<BigUint> {
         self.value.clone()
     }
@@ -92,6 +110,15 @@ impl RangeType for Fresh {}
 pub struct Muled {}
 impl RangeType for Muled {}
 
+/// The byte order of assigned bytes converted from or to [`AssignedBigUint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Endianness {
+    /// The least significant byte first.
+    Little,
+    /// The most significant byte first.
+    Big,
+}
+
 /// Auxiliary data for refreshing a [`Muled`] type integer to a [`Fresh`] type integer.
 #[derive(Debug, Clone)]
 pub struct RefreshAux {
@@ -1,4 +1,4 @@
-use halo2_base::utils::{decompose_biguint as _decompose_biguint, PrimeField};
+use halo2_base::utils::{biguint_to_fe, PrimeField};
 use num_bigint::{BigInt, BigUint};
 use num_traits::Signed;
 
@@ -17,19 +17,26 @@ pub fn decompose_bigint<F: PrimeField>(
     }
 }
 
+/// Decomposes `e` into `number_of_limbs` little-endian limbs of `limb_bits_len` bits.
+///
+/// The maximum supported `limb_bits_len` is 128. Bits of `e` above `number_of_limbs * limb_bits_len` are dropped.
 pub fn decompose_biguint<F: PrimeField>(
     e: &BigUint,
     number_of_limbs: usize,
     limb_bits_len: usize,
 ) -> Vec<F> {
-    assert!(limb_bits_len < 128);
+    assert!(limb_bits_len > 0 && limb_bits_len <= 128);
     if limb_bits_len <= 64 {
         decompose_u64_digits_to_limbs(e.to_u64_digits(), number_of_limbs, limb_bits_len)
             .into_iter()
             .map(|v| F::from(v))
             .collect()
     } else {
-        _decompose_biguint(e, number_of_limbs, limb_bits_len)
+        // The upstream `decompose_biguint` overflows a shift at exactly 128 bits, so the limbs are sliced from `e` directly.
+        let mask = (BigUint::from(1u64) << limb_bits_len) - 1u64;
+        (0..number_of_limbs)
+            .map(|i| biguint_to_fe(&((e >> (i * limb_bits_len)) & &mask)))
+            .collect()
     }
 }
 
@@ -67,3 +74,57 @@ pub(crate) fn decompose_u64_digits_to_limbs(
         })
         .collect()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+    use halo2_base::utils::fe_to_biguint;
+    use num_bigint::RandBigInt;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_decompose_biguint_128_bit_limbs() {
+        let e = (BigUint::from(0xdead_beefu64) << 268) + (BigUint::from(7u64) << 128) + 42u64;
+        assert_eq!(e.bits(), 300);
+        let limbs = decompose_biguint::<Fr>(&e, 3, 128);
+        assert_eq!(fe_to_biguint(&limbs[0]), BigUint::from(42u64));
+        assert_eq!(fe_to_biguint(&limbs[1]), BigUint::from(7u64));
+        let recomposed = limbs.iter().rev().fold(BigUint::from(0u64), |acc, limb| {
+            (acc << 128) + fe_to_biguint(limb)
+        });
+        assert_eq!(recomposed, e);
+    }
+
+    /// Slices `number_of_limbs` limbs of `bit_len` bits out of `e` one bit at a time.
+    fn reference_limbs(e: &BigUint, number_of_limbs: usize, bit_len: usize) -> Vec<u64> {
+        (0..number_of_limbs)
+            .map(|i| {
+                (0..bit_len).fold(0u64, |limb, j| {
+                    limb | ((e.bit((i * bit_len + j) as u64) as u64) << j)
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_decompose_u64_digits_to_limbs_matches_bit_slicing() {
+        let mut rng = thread_rng();
+        for bit_len in [12, 24, 48, 60, 64] {
+            for num_bits in [1, 63, 64, 65, 300, 2048] {
+                for _ in 0..16 {
+                    let e = rng.gen_biguint(num_bits);
+                    // One more limb than needed, to check the high limbs are zero.
+                    let number_of_limbs = num_bits as usize / bit_len + 2;
+                    assert_eq!(
+                        decompose_u64_digits_to_limbs(e.to_u64_digits(), number_of_limbs, bit_len),
+                        reference_limbs(&e, number_of_limbs, bit_len),
+                        "bit_len = {}, e = {:x}",
+                        bit_len,
+                        e
+                    );
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,458 @@
+//! A builder of [`RSAConfig`] validating its parameters before configuring the columns.
+
+use crate::{BigUintConfig, RSAConfig};
+use halo2_base::halo2_proofs::plonk::ConstraintSystem;
+use halo2_base::utils::PrimeField;
+use halo2_base::Context;
+#[cfg(feature = "sha256")]
+use halo2_dynamic_sha256::Sha256DynamicConfig;
+use std::fmt;
+
+/// An incompatible combination of parameters passed to [`RSAConfigBuilder`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RSAConfigBuilderError {
+    /// A lookup table of `lookup_bits` bits does not fit in the `2^k` rows of the circuit.
+    LookupBits { lookup_bits: usize, k: usize },
+    /// The limb bits are not a non-zero multiple of 8 dividing the bit length of the modulus.
+    LimbBits { bits_len: usize, limb_bits: usize },
+    /// The exponent bits are fewer than 2 or not less than the field capacity, see [`RSAConfig::construct`].
+    ExpLimbBits,
+    /// The exponent windows have no bits.
+    ExpWindowBits,
+    /// A column count is zero.
+    NoColumns,
+    /// A SHA256 slot size is zero or not a multiple of the 64-byte block.
+    Sha256ByteSize(usize),
+}
+
+impl fmt::Display for RSAConfigBuilderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LookupBits { lookup_bits, k } => write!(
+                f,
+                "lookup_bits ({lookup_bits}) must be positive and smaller than k ({k})"
+            ),
+            Self::LimbBits {
+                bits_len,
+                limb_bits,
+            } => write!(
+                f,
+                "limb_bits ({limb_bits}) must be a positive multiple of 8 dividing bits_len ({bits_len})"
+            ),
+            Self::ExpLimbBits => write!(
+                f,
+                "exp_limb_bits must be at least 2 and less than the field capacity"
+            ),
+            Self::ExpWindowBits => write!(f, "exp_window_bits must be positive"),
+            Self::NoColumns => write!(f, "every column count must be positive"),
+            Self::Sha256ByteSize(size) => write!(
+                f,
+                "SHA256 max byte size ({size}) must be a positive multiple of 64"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for RSAConfigBuilderError {}
+
+/// The cells assigned by one synthesis pass, to estimate the degree of a circuit with [`RSAConfigBuilder::estimate_k`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CircuitUsage {
+    /// the advice cells over every advice column.
+    pub advice_cells: usize,
+    /// the cells copied into the lookup advice columns for range checks.
+    pub lookup_cells: usize,
+    /// the cells over every fixed column, i.e. the constants.
+    pub fixed_cells: usize,
+}
+
+impl CircuitUsage {
+    /// Reads the usage of a context after its gadgets are synthesized, e.g. to report the diagnostics of a circuit.
+    ///
+    /// # Arguments
+    /// * ctx - a region context in which every gadget has been synthesized.
+    ///
+    /// # Return values
+    /// Returns the [`CircuitUsage`] of `ctx`.
+    pub fn from_context<F: PrimeField>(ctx: &Context<'_, F>) -> Self {
+        Self {
+            advice_cells: ctx.total_advice,
+            lookup_cells: ctx.cells_to_lookup.len(),
+            fixed_cells: ctx.total_fixed,
+        }
+    }
+}
+
+/// A builder of [`RSAConfig`] and its [`Sha256DynamicConfig`] with named parameters.
+///
+/// The defaults are those of `TestRSASignatureWithHashCircuit1`: 2048-bit keys with 64-bit limbs at `k = 15`
+/// with 80 advice, 16 lookup advice and 1 fixed columns, and a single SHA256 slot of 1024 bytes.
+#[derive(Debug, Clone)]
+pub struct RSAConfigBuilder {
+    k: usize,
+    bits_len: usize,
+    limb_bits: usize,
+    exp_limb_bits: usize,
+    exp_window_bits: usize,
+    num_advice: usize,
+    num_lookup_advice: usize,
+    num_fixed: usize,
+    lookup_bits: usize,
+    sha256_max_byte_sizes: Vec<usize>,
+    sha256_lookup_bits: usize,
+    sha256_lookup_advice: usize,
+}
+
+impl Default for RSAConfigBuilder {
+    fn default() -> Self {
+        Self {
+            k: 15,
+            bits_len: 2048,
+            limb_bits: 64,
+            exp_limb_bits: 5,
+            exp_window_bits: 1,
+            num_advice: 80,
+            num_lookup_advice: 16,
+            num_fixed: 1,
+            lookup_bits: 12,
+            sha256_max_byte_sizes: vec![1024],
+            sha256_lookup_bits: 8,
+            sha256_lookup_advice: 8,
+        }
+    }
+}
+
+impl RSAConfigBuilder {
+    /// Creates new [`RSAConfigBuilder`] with the default parameters.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the degree of the circuit, i.e. it has `2^k` rows.
+    pub fn k(mut self, k: usize) -> Self {
+        self.k = k;
+        self
+    }
+
+    /// Sets the bit length of the modulus.
+    pub fn bits_len(mut self, bits_len: usize) -> Self {
+        self.bits_len = bits_len;
+        self
+    }
+
+    /// Sets the bit length of limbs.
+    pub fn limb_bits(mut self, limb_bits: usize) -> Self {
+        self.limb_bits = limb_bits;
+        self
+    }
+
+    /// Sets the bit length of exponent limbs.
+    pub fn exp_limb_bits(mut self, exp_limb_bits: usize) -> Self {
+        self.exp_limb_bits = exp_limb_bits;
+        self
+    }
+
+    /// Sets the window bit length of the modular power by a variable exponent, see [`RSAConfig::with_exp_window_bits`].
+    pub fn exp_window_bits(mut self, exp_window_bits: usize) -> Self {
+        self.exp_window_bits = exp_window_bits;
+        self
+    }
+
+    /// Sets the number of advice columns, see [`RSAConfigBuilder::suggest_num_advice`].
+    pub fn num_advice(mut self, num_advice: usize) -> Self {
+        self.num_advice = num_advice;
+        self
+    }
+
+    /// Sets the number of advice columns copied into the lookup table of range checks.
+    pub fn num_lookup_advice(mut self, num_lookup_advice: usize) -> Self {
+        self.num_lookup_advice = num_lookup_advice;
+        self
+    }
+
+    /// Sets the number of fixed columns.
+    pub fn num_fixed(mut self, num_fixed: usize) -> Self {
+        self.num_fixed = num_fixed;
+        self
+    }
+
+    /// Sets the bit length of the lookup table of range checks.
+    pub fn lookup_bits(mut self, lookup_bits: usize) -> Self {
+        self.lookup_bits = lookup_bits;
+        self
+    }
+
+    /// Sets the maximum byte sizes of the SHA256 slots, see [`crate::sha256_padded_len`].
+    pub fn sha256_max_byte_sizes(mut self, sha256_max_byte_sizes: Vec<usize>) -> Self {
+        self.sha256_max_byte_sizes = sha256_max_byte_sizes;
+        self
+    }
+
+    /// Sets the bit length of the lookup table of the SHA256 config.
+    pub fn sha256_lookup_bits(mut self, sha256_lookup_bits: usize) -> Self {
+        self.sha256_lookup_bits = sha256_lookup_bits;
+        self
+    }
+
+    /// Sets the number of advice columns of the SHA256 config.
+    pub fn sha256_lookup_advice(mut self, sha256_lookup_advice: usize) -> Self {
+        self.sha256_lookup_advice = sha256_lookup_advice;
+        self
+    }
+
+    /// Checks that the parameters are compatible.
+    ///
+    /// # Return values
+    /// Returns the first incompatibility found as [`RSAConfigBuilderError`].
+    pub fn validate(&self) -> Result<(), RSAConfigBuilderError> {
+        for lookup_bits in [self.lookup_bits, self.sha256_lookup_bits] {
+            if lookup_bits == 0 || lookup_bits >= self.k {
+                return Err(RSAConfigBuilderError::LookupBits {
+                    lookup_bits,
+                    k: self.k,
+                });
+            }
+        }
+        if self.limb_bits == 0 || self.limb_bits % 8 != 0 || self.bits_len % self.limb_bits != 0 {
+            return Err(RSAConfigBuilderError::LimbBits {
+                bits_len: self.bits_len,
+                limb_bits: self.limb_bits,
+            });
+        }
+        if self.exp_limb_bits < 2 {
+            return Err(RSAConfigBuilderError::ExpLimbBits);
+        }
+        if self.exp_window_bits == 0 {
+            return Err(RSAConfigBuilderError::ExpWindowBits);
+        }
+        if [
+            self.num_advice,
+            self.num_lookup_advice,
+            self.num_fixed,
+            self.sha256_lookup_advice,
+        ]
+        .contains(&0)
+        {
+            return Err(RSAConfigBuilderError::NoColumns);
+        }
+        if let Some(size) = self
+            .sha256_max_byte_sizes
+            .iter()
+            .find(|size| **size == 0 || **size % 64 != 0)
+        {
+            return Err(RSAConfigBuilderError::Sha256ByteSize(*size));
+        }
+        if self.sha256_max_byte_sizes.is_empty() {
+            return Err(RSAConfigBuilderError::Sha256ByteSize(0));
+        }
+        Ok(())
+    }
+
+    /// Estimates the minimal degree `k` at which a circuit configured with these parameters fits the given usage.
+    ///
+    /// The rows of every advice, lookup advice and fixed column, as well as the lookup tables of the range checks and the SHA256 config, must fit in the usable rows of `2^k`.
+    /// The usage is independent of `k`, so it can be measured once at any `k` that fits, e.g. by a [`crate::RSASignatureVerifier`] circuit at a large `k`.
+    /// Cells assigned outside the context, e.g. in the spread columns of the SHA256 config, are not counted.
+    ///
+    /// # Arguments
+    /// * usage - the cells assigned by one synthesis pass, see [`CircuitUsage::from_context`].
+    ///
+    /// # Return values
+    /// Returns the minimal `k`, ignoring the one set by [`RSAConfigBuilder::k`].
+    /// Returns the [`RSAConfigBuilderError`] returned by [`RSAConfigBuilder::build`] for other incompatible parameters.
+    #[cfg(feature = "sha256")]
+    pub fn estimate_k<F: PrimeField>(
+        &self,
+        usage: &CircuitUsage,
+    ) -> Result<u32, RSAConfigBuilderError> {
+        let min_k = self.lookup_bits.max(self.sha256_lookup_bits) + 1;
+        let mut meta = ConstraintSystem::<F>::default();
+        Self {
+            k: min_k,
+            ..self.clone()
+        }
+        .build(&mut meta)?;
+        // A gate is never split across advice columns, so each column may leave a few rows unused at its end.
+        let advice_rows = (usage.advice_cells + self.num_advice - 1) / self.num_advice + 3;
+        let lookup_rows =
+            (usage.lookup_cells + self.num_lookup_advice - 1) / self.num_lookup_advice;
+        let fixed_rows = (usage.fixed_cells + self.num_fixed - 1) / self.num_fixed;
+        let rows = advice_rows
+            .max(lookup_rows)
+            .max(fixed_rows)
+            .max(1 << self.lookup_bits)
+            .max(1 << self.sha256_lookup_bits);
+        let mut k = min_k;
+        while (1 << k) < rows + meta.minimum_rows() {
+            k += 1;
+        }
+        Ok(k as u32)
+    }
+
+    /// Suggests the column counts at which a circuit configured with these parameters fits the given usage at the degree set by [`RSAConfigBuilder::k`].
+    ///
+    /// This is the converse of [`RSAConfigBuilder::estimate_k`]: each extra degree roughly halves the suggested columns,
+    /// trading a slower prover, which handles polynomials of twice the degree, for a smaller proof and a faster verification.
+    /// The usage is independent of the column counts, so it can be measured once with any columns that fit, e.g. those of the defaults.
+    /// Cells assigned outside the context, e.g. in the spread columns of the SHA256 config, are not counted.
+    ///
+    /// # Arguments
+    /// * usage - the cells assigned by one synthesis pass, see [`CircuitUsage::from_context`].
+    ///
+    /// # Return values
+    /// Returns `(num_advice, num_lookup_advice)`, ignoring the ones set by [`RSAConfigBuilder::num_advice`] and [`RSAConfigBuilder::num_lookup_advice`].
+    /// Returns the [`RSAConfigBuilderError`] returned by [`RSAConfigBuilder::build`] for other incompatible parameters.
+    #[cfg(feature = "sha256")]
+    pub fn suggest_num_advice<F: PrimeField>(
+        &self,
+        usage: &CircuitUsage,
+    ) -> Result<(usize, usize), RSAConfigBuilderError> {
+        let mut meta = ConstraintSystem::<F>::default();
+        self.build(&mut meta)?;
+        let rows = (1usize << self.k)
+            .saturating_sub(meta.minimum_rows())
+            .max(1);
+        // A gate chain is never split across advice columns, so each column may leave its last rows unused.
+        // The longest chains are the inner products over the limbs and the bit decompositions of a limb, of three rows per term.
+        let max_chain_rows = 3 * (self.bits_len / self.limb_bits).max(self.limb_bits) + 1;
+        let advice_rows = rows.saturating_sub(max_chain_rows).max(1);
+        let num_advice = (usage.advice_cells + advice_rows - 1) / advice_rows;
+        let num_lookup_advice = (usage.lookup_cells + rows - 1) / rows;
+        Ok((num_advice.max(1), num_lookup_advice.max(1)))
+    }
+
+    /// Configures a [`RSAConfig`] without a SHA256 config, e.g. for [`crate::RSAInstructions::verify_pkcs1v15_signature_with_hash`].
+    ///
+    /// # Arguments
+    /// * meta - a constraint system.
+    ///
+    /// # Return values
+    /// Returns the configured [`RSAConfig`], or the [`RSAConfigBuilderError`] returned by [`RSAConfigBuilder::validate`].
+    pub fn build_rsa<F: PrimeField>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+    ) -> Result<RSAConfig<F>, RSAConfigBuilderError> {
+        self.validate()?;
+        if !RSAConfig::<F>::is_valid_exp_bits(self.exp_limb_bits) {
+            return Err(RSAConfigBuilderError::ExpLimbBits);
+        }
+        let biguint_config = BigUintConfig::configure(
+            meta,
+            self.num_advice,
+            self.num_lookup_advice,
+            self.num_fixed,
+            self.lookup_bits,
+            self.k,
+            self.limb_bits,
+        );
+        Ok(
+            RSAConfig::construct(biguint_config, self.bits_len, self.exp_limb_bits)
+                .with_exp_window_bits(self.exp_window_bits),
+        )
+    }
+
+    /// Configures a [`RSAConfig`] and a [`Sha256DynamicConfig`] sharing its range config, e.g. for [`crate::RSASignatureVerifier::new`].
+    ///
+    /// # Arguments
+    /// * meta - a constraint system.
+    ///
+    /// # Return values
+    /// Returns the configured [`RSAConfig`] and [`Sha256DynamicConfig`], or the [`RSAConfigBuilderError`] returned by [`RSAConfigBuilder::validate`].
+    #[cfg(feature = "sha256")]
+    pub fn build<F: PrimeField>(
+        &self,
+        meta: &mut ConstraintSystem<F>,
+    ) -> Result<(RSAConfig<F>, Sha256DynamicConfig<F>), RSAConfigBuilderError> {
+        let rsa_config = self.build_rsa(meta)?;
+        let sha256_config = Sha256DynamicConfig::configure(
+            meta,
+            self.sha256_max_byte_sizes.clone(),
+            rsa_config.range().clone(),
+            self.sha256_lookup_bits,
+            self.sha256_lookup_advice,
+            true,
+        );
+        Ok((rsa_config, sha256_config))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    #[test]
+    fn test_rsa_config_builder() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let rsa_config = RSAConfigBuilder::new()
+            .bits_len(1024)
+            .build_rsa(&mut meta)
+            .unwrap();
+        assert_eq!(rsa_config.default_bits(), 1024);
+        assert_eq!(rsa_config.num_limbs(), 1024 / 64);
+        assert_eq!(meta.num_advice_columns(), 80 + 16);
+
+        let invalid = [
+            (
+                RSAConfigBuilder::new().lookup_bits(15),
+                RSAConfigBuilderError::LookupBits {
+                    lookup_bits: 15,
+                    k: 15,
+                },
+            ),
+            (
+                RSAConfigBuilder::new().k(8),
+                RSAConfigBuilderError::LookupBits {
+                    lookup_bits: 12,
+                    k: 8,
+                },
+            ),
+            (
+                RSAConfigBuilder::new().limb_bits(60),
+                RSAConfigBuilderError::LimbBits {
+                    bits_len: 2048,
+                    limb_bits: 60,
+                },
+            ),
+            (
+                RSAConfigBuilder::new().bits_len(2000),
+                RSAConfigBuilderError::LimbBits {
+                    bits_len: 2000,
+                    limb_bits: 64,
+                },
+            ),
+            (
+                RSAConfigBuilder::new().exp_limb_bits(1),
+                RSAConfigBuilderError::ExpLimbBits,
+            ),
+            (
+                RSAConfigBuilder::new().exp_limb_bits(254),
+                RSAConfigBuilderError::ExpLimbBits,
+            ),
+            (
+                RSAConfigBuilder::new().exp_window_bits(0),
+                RSAConfigBuilderError::ExpWindowBits,
+            ),
+            (
+                RSAConfigBuilder::new().num_advice(0),
+                RSAConfigBuilderError::NoColumns,
+            ),
+            (
+                RSAConfigBuilder::new().sha256_max_byte_sizes(vec![1000]),
+                RSAConfigBuilderError::Sha256ByteSize(1000),
+            ),
+        ];
+        for (builder, err) in invalid {
+            let mut meta = ConstraintSystem::<Fr>::default();
+            assert_eq!(builder.build_rsa(&mut meta).unwrap_err(), err);
+        }
+        assert_eq!(
+            RSAConfigBuilderError::LookupBits {
+                lookup_bits: 15,
+                k: 15
+            }
+            .to_string(),
+            "lookup_bits (15) must be positive and smaller than k (15)"
+        );
+    }
+}
@@ -1,9 +1,15 @@
 use crate::big_uint::BigUintInstructions;
+use crate::utils::bits_to_num;
 use crate::{
-    AssignedBigUint, AssignedRSAPubE, AssignedRSAPublicKey, AssignedRSASignature, BigUintConfig,
-    Fresh, RSAInstructions, RSAPubE, RSAPublicKey, RSASignature,
+    AssignedBigUint, AssignedModPowState, AssignedRSAPubE, AssignedRSAPublicKey,
+    AssignedRSASignature, BigUintConfig, Endianness, Fresh, ModPowStateCells, RSACrtComponents,
+    RSAInstructions, RSAPubE, RSAPublicKey, RSAPublicKeyCells, RSASignature, RsaCircuitError,
+    RsaHashAlgo,
+};
+use halo2_base::halo2_proofs::{
+    circuit::{Layouter, Region, Value},
+    plonk::{Column, Error, Instance},
 };
-use halo2_base::halo2_proofs::{circuit::Region, plonk::Error};
 use halo2_base::QuantumCell;
 use halo2_base::{
     gates::{flex_gate::FlexGateConfig, range::RangeConfig, GateInstructions, RangeInstructions},
@@ -22,31 +28,58 @@ pub struct RSAConfig<F: PrimeField> {
     default_bits: usize,
     /// The bit length of exponents.
     exp_bits: usize,
+    /// The salt length of RSASSA-PSS signatures in bytes.
+    pss_salt_len: usize,
+    /// The window bit length of the modular power by a variable exponent, where one means square-and-multiply.
+    exp_window_bits: usize,
 }
 
 impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
     /// Assigns a [`AssignedRSAPublicKey`].
     ///
+    /// Degenerate keys are rejected: `e` must be at least 3, and `n` must be odd and have its top bit set at the default bit length.
+    /// A variable `e` and `n` are constrained accordingly, while a fixed `e` is checked at synthesis.
+    /// Otherwise, `e = 1` would make the padded message itself a valid signature.
+    ///
     /// # Arguments
     /// * `ctx` - a region context.
     /// * `public_key` - a RSA public key to assign.
     ///
     /// # Return values
     /// Returns a new [`AssignedRSAPublicKey`].
+    /// Returns [`RsaCircuitError::InvalidKey`] if `e` is fixed to a value less than 3, or if a known variable `e` is longer than the exponent bits of [`RSAConfig::construct`].
     fn assign_public_key<'v>(
         &self,
         ctx: &mut Context<'v, F>,
         public_key: RSAPublicKey<F>,
-    ) -> Result<AssignedRSAPublicKey<'v, F>, Error> {
+    ) -> Result<AssignedRSAPublicKey<'v, F>, RsaCircuitError> {
         let biguint_config = self.biguint_config();
+        let gate = self.gate();
+        let range = self.range();
         let n = biguint_config.assign_integer(ctx, public_key.n, self.default_bits)?;
+        self.assert_nondegenerate_modulus(ctx, &n);
         let e = match public_key.e {
             RSAPubE::Var(e) => {
-                let assigned = self.gate().load_witness(ctx, e.map(|v| biguint_to_fe(&v)));
-                self.range().range_check(ctx, &assigned, self.exp_bits);
+                // A wider `e` would only fail the range check below, so it is reported as an invalid key instead.
+                e.error_if_known_and(|e| e.bits() as usize > self.exp_bits)
+                    .map_err(|_| RsaCircuitError::InvalidKey)?;
+                let assigned = gate.load_witness(ctx, e.map(|v| biguint_to_fe(&v)));
+                range.range_check(ctx, &assigned, self.exp_bits);
+                // e - 3 wraps around the field unless e >= 3.
+                let e_minus_three = gate.sub(
+                    ctx,
+                    QuantumCell::Existing(&assigned),
+                    QuantumCell::Constant(F::from(3)),
+                );
+                range.range_check(ctx, &e_minus_three, self.exp_bits);
                 AssignedRSAPubE::Var(assigned)
             }
-            RSAPubE::Fix(e) => AssignedRSAPubE::Fix(e),
+            RSAPubE::Fix(e) => {
+                if e < BigUint::from(3u64) {
+                    return Err(RsaCircuitError::InvalidKey);
+                }
+                AssignedRSAPubE::Fix(e)
+            }
         };
         Ok(AssignedRSAPublicKey::new(n, e))
     }
@@ -63,9 +96,9 @@ impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
         &self,
         ctx: &mut Context<'v, F>,
         signature: RSASignature<F>,
-    ) -> Result<AssignedRSASignature<'v, F>, Error> {
+    ) -> Result<AssignedRSASignature<'v, F>, RsaCircuitError> {
         let biguint_config = self.biguint_config();
-        let c = biguint_config.assign_integer(ctx, signature.c, self.default_bits)?;
+        let c = biguint_config.assign_integer(ctx, signature.c.clone(), self.default_bits)?;
         Ok(AssignedRSASignature::new(c))
     }
 
@@ -87,9 +120,7 @@ impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
         let biguint_config = self.biguint_config();
         biguint_config.assert_in_field(ctx, x, &public_key.n)?;
         let powed = match &public_key.e {
-            AssignedRSAPubE::Var(e) => {
-                biguint_config.pow_mod(ctx, x, e, &public_key.n, self.exp_bits)
-            }
+            AssignedRSAPubE::Var(e) => self.pow_mod_var_exp(ctx, x, e, &public_key.n),
             AssignedRSAPubE::Fix(e) => biguint_config.pow_mod_fixed_exp(ctx, x, e, &public_key.n),
         }?;
         Ok(powed)
@@ -100,13 +131,14 @@ impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
     /// # Arguments
     /// * `ctx` - a region context.
     /// * `public_key` - an assigned RSA public key.
-    /// * `hashed_msg` - an assigned integer of the message hashed with SHA256.
+    /// * `hashed_msg` - the limbs of an assigned integer of the message hashed with SHA256, i.e. `ceil(256 / limb_bits)` limbs in little-endian order.
     /// * `signature` - an assigned pkcs1v15 signature.
     ///
     /// # Return values
     /// Returns the assigned bit as [`AssignedValue<F>`].
     /// If `signature` is valid for `public_key` and `hashed_msg`, the bit is equivalent to one.
     /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`Error::Synthesis`] if the number of limbs of `hashed_msg` does not match the limb bits.
     fn verify_pkcs1v15_signature<'v>(
         &self,
         ctx: &mut Context<'v, F>,
@@ -114,7 +146,34 @@ impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
         hashed_msg: &[AssignedValue<'v, F>],
         signature: &AssignedRSASignature<'v, F>,
     ) -> Result<AssignedValue<'v, F>, Error> {
-        assert_eq!(self.biguint_config.limb_bits(), 64);
+        let limb_bits = self.biguint_config.limb_bits();
+        if limb_bits != 64 {
+            // The constant limbs below are specific to 64-bit limbs, so other widths compare the encoded message byte by byte.
+            let hash_bits = RsaHashAlgo::Sha256.output_len() * 8;
+            if hashed_msg.len() != (hash_bits + limb_bits - 1) / limb_bits {
+                return Err(Error::Synthesis);
+            }
+            let gate = self.gate();
+            let mut le_bits = vec![];
+            for limb in hashed_msg.iter() {
+                le_bits.extend(gate.num_to_bits(ctx, limb, limb_bits));
+            }
+            for bit in le_bits[hash_bits..].iter() {
+                gate.assert_is_const(ctx, bit, F::zero());
+            }
+            let hashed_bytes = le_bits[..hash_bits]
+                .chunks(8)
+                .rev()
+                .map(|bits| bits_to_num(gate, ctx, bits))
+                .collect::<Vec<_>>();
+            return self.verify_pkcs1v15_signature_with_hash(
+                ctx,
+                public_key,
+                RsaHashAlgo::Sha256,
+                &hashed_bytes,
+                signature,
+            );
+        }
         let gate = self.gate();
         let mut is_eq = gate.load_constant(ctx, F::one());
         let powed = self.modpow_public_key(ctx, &signature.c, public_key)?;
@@ -208,7 +267,7 @@ impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
             QuantumCell::Existing(&is_eq),
             QuantumCell::Existing(&is_ff_32_eq),
         );
-        let num_limbs = self.default_bits / self.biguint_config().limb_bits();
+        let num_limbs = self.num_limbs();
         for limb in powed.limbs()[(hash_len + 3)..(num_limbs - 1)].iter() {
             let is_ff_64_eq = gate.is_equal(
                 ctx,
@@ -234,9 +293,39 @@ impl<F: PrimeField> RSAInstructions<F> for RSAConfig<F> {
         );
         Ok(is_eq.clone())
     }
+
+    /// Given a RSA public key, a message hashed with `hash_algo`, and a pkcs1v15 signature, verifies the signature with the public key and the hashed messaged.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `public_key` - an assigned RSA public key.
+    /// * `hash_algo` - the hash algorithm, which determines the DigestInfo prefix.
+    /// * `hashed_bytes` - assigned bytes of the hashed message in big-endian order. They are assumed to be already constrained to 8 bits.
+    /// * `signature` - an assigned pkcs1v15 signature.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as [`AssignedValue<F>`].
+    /// If `signature` is valid for `public_key` and `hashed_bytes`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`Error::Synthesis`] if the length of `hashed_bytes` does not match `hash_algo` or the modulus is too short for the encoded message.
+    fn verify_pkcs1v15_signature_with_hash<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+        hash_algo: RsaHashAlgo,
+        hashed_bytes: &[AssignedValue<'v, F>],
+        signature: &AssignedRSASignature<'v, F>,
+    ) -> Result<AssignedValue<'v, F>, Error> {
+        self.check_pkcs1v15_lengths(hash_algo, hashed_bytes)?;
+        let powed = self.modpow_public_key(ctx, &signature.c, public_key)?;
+        self.is_pkcs1v15_encoded_message(ctx, &powed, hash_algo, hashed_bytes)
+    }
 }
 
 impl<F: PrimeField> RSAConfig<F> {
+    /// The default salt length of RSASSA-PSS signatures, i.e. the byte length of a SHA256 hash.
+    pub const DEFAULT_PSS_SALT_LEN: usize = 32;
+
     /// Creates new [`RSAConfig`] from [`BigUintInstructions`].
     ///
     /// # Arguments
@@ -246,28 +335,634 @@ impl<F: PrimeField> RSAConfig<F> {
     ///
     /// # Return values
     /// Returns new [`RSAConfig`].
+    /// The salt length of RSASSA-PSS signatures is [`RSAConfig::DEFAULT_PSS_SALT_LEN`].
+    ///
+    /// # Circuit size
+    /// `default_bits` is the bit length of the modulus, e.g. 1024, 2048, 3072 or 4096, and must be a multiple of the limb bits.
+    /// The tests verify pkcs1v15 signatures with `e = 65537` for all of these sizes at `k = 15` with 80 advice and 16 lookup advice columns, and 64-bit limbs.
+    /// See [`crate::RSAConfigBuilder::suggest_num_advice`] for the column counts at another `k`.
+    ///
+    /// # Limb widths
+    /// The limb bits of `biguint_config` must be a multiple of 8 that divides `default_bits`, so that every limb consists of whole bytes of the encoded message.
+    /// Otherwise, the verification methods return [`Error::Synthesis`].
+    /// The tests cover 64-bit limbs, which are the cheapest, and 32-bit limbs, which fit a smaller lookup table at the cost of four times as many limb products.
+    /// The cost of a modular multiplication grows quadratically in [`RSAConfig::num_limbs`], so fewer advice columns require a larger `k`, particularly for 4096-bit keys.
+    ///
+    /// # Exponent bits
+    /// `exp_bits` is the bit length of a variable `e` ([`RSAPubE::Var`]), which is decomposed into that many bits, so it need not divide any other length.
+    /// A fixed `e` ([`RSAPubE::Fix`]) is a constant of the circuit and may be longer, e.g. `e = 65537` works with any valid `exp_bits`.
+    /// Since the variable `e` is only known at assignment, a wider one is rejected there as [`RsaCircuitError::InvalidKey`];
+    /// use [`RSAConfig::construct_for_exponent`] to reject `exp_bits` too short for a known bound of `e` here instead.
+    /// `exp_bits` must be at least 2, since `e` must be at least 3, and less than the capacity of `F`, so that the bit decomposition is unique.
+    ///
+    /// # Panics
+    /// Panics if `exp_bits` is out of that range.
     pub fn construct(
         biguint_config: BigUintConfig<F>,
         default_bits: usize,
         exp_bits: usize,
     ) -> Self {
+        Self::construct_with_pss_salt_len(
+            biguint_config,
+            default_bits,
+            exp_bits,
+            Self::DEFAULT_PSS_SALT_LEN,
+        )
+    }
+
+    /// Creates new [`RSAConfig`] from [`BigUintInstructions`] and the salt length of RSASSA-PSS signatures.
+    ///
+    /// # Arguments
+    /// * biguint_config - a configuration for [`BigUintConfig`].
+    /// * default_bits - the default bit length of [`Fresh`] type integers in this chip.
+    /// * exp_bits - the bit length of exponents.
+    /// * pss_salt_len - the salt length of RSASSA-PSS signatures in bytes.
+    ///
+    /// # Return values
+    /// Returns new [`RSAConfig`].
+    ///
+    /// # Panics
+    /// Panics if `exp_bits` is invalid, see [`RSAConfig::construct`].
+    pub fn construct_with_pss_salt_len(
+        biguint_config: BigUintConfig<F>,
+        default_bits: usize,
+        exp_bits: usize,
+        pss_salt_len: usize,
+    ) -> Self {
+        assert!(
+            Self::is_valid_exp_bits(exp_bits),
+            "exp_bits ({exp_bits}) must be at least 2 and less than the field capacity ({})",
+            F::CAPACITY
+        );
         Self {
             biguint_config,
             default_bits,
             exp_bits,
+            pss_salt_len,
+            exp_window_bits: 1,
+        }
+    }
+
+    /// Creates new [`RSAConfig`] from [`BigUintInstructions`] for public keys whose variable `e` ([`RSAPubE::Var`]) is at most `max_e`.
+    ///
+    /// # Arguments
+    /// * biguint_config - a configuration for [`BigUintConfig`].
+    /// * default_bits - the default bit length of [`Fresh`] type integers in this chip.
+    /// * exp_bits - the bit length of exponents.
+    /// * max_e - the largest variable `e` to be assigned, e.g. 65537.
+    ///
+    /// # Return values
+    /// Returns new [`RSAConfig`], as [`RSAConfig::construct`] does.
+    ///
+    /// # Panics
+    /// Panics if `exp_bits` is invalid, see [`RSAConfig::construct`], or fewer than the bits of `max_e`, e.g. below 17 for `e = 65537`.
+    pub fn construct_for_exponent(
+        biguint_config: BigUintConfig<F>,
+        default_bits: usize,
+        exp_bits: usize,
+        max_e: &BigUint,
+    ) -> Self {
+        assert!(
+            max_e.bits() as usize <= exp_bits,
+            "exp_bits ({exp_bits}) cannot represent the exponent {max_e} of {} bits",
+            max_e.bits()
+        );
+        Self::construct(biguint_config, default_bits, exp_bits)
+    }
+
+    /// Returns whether a variable `e` of `exp_bits` bits can be at least 3 and is uniquely decomposed into bits of `F`.
+    pub fn is_valid_exp_bits(exp_bits: usize) -> bool {
+        exp_bits >= 2 && exp_bits < F::CAPACITY as usize
+    }
+
+    /// Sets the window bit length of the modular power by a variable `e`, see [`BigUintConfig::pow_mod_windowed`].
+    ///
+    /// The default of one takes a multiplication per bit of `e`, which is cheaper for the short exponents of [`RSAConfig::construct`],
+    /// whereas a 4-bit window is cheaper for exponents of more than about 20 bits.
+    ///
+    /// # Arguments
+    /// * exp_window_bits - the bit length of a window.
+    ///
+    /// # Return values
+    /// Returns the [`RSAConfig`] using windows of `exp_window_bits` bits.
+    pub fn with_exp_window_bits(mut self, exp_window_bits: usize) -> Self {
+        self.exp_window_bits = exp_window_bits;
+        self
+    }
+
+    /// Getter for the window bit length of the modular power by a variable `e`.
+    pub fn exp_window_bits(&self) -> usize {
+        self.exp_window_bits
+    }
+
+    /// Performs `x^e mod n` for a variable `e` of `exp_bits` bits, windowed as set by [`RSAConfig::with_exp_window_bits`].
+    fn pow_mod_var_exp<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        x: &AssignedBigUint<'v, F, Fresh>,
+        e: &AssignedValue<'v, F>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        let biguint_config = self.biguint_config();
+        if self.exp_window_bits > 1 {
+            biguint_config.pow_mod_windowed(ctx, x, e, n, self.exp_bits, self.exp_window_bits)
+        } else {
+            biguint_config.pow_mod(ctx, x, e, n, self.exp_bits)
         }
     }
 
+    /// Constrains `n` to be odd and to have its top bit set at the default bit length.
+    ///
+    /// The least significant limb is `2 * h + 1` and the most significant limb is `2^(limb_bits - 1) + t` for witnesses `h` and `t` of `limb_bits - 1` bits.
+    fn assert_nondegenerate_modulus<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        n: &AssignedBigUint<'v, F, Fresh>,
+    ) {
+        let gate = self.gate();
+        let range = self.range();
+        let limb_bits = self.biguint_config.limb_bits();
+        let lowest = n.limb(0);
+        let half = gate.load_witness(
+            ctx,
+            lowest
+                .value()
+                .map(|v| biguint_to_fe::<F>(&(fe_to_biguint(v) >> 1))),
+        );
+        range.range_check(ctx, &half, limb_bits - 1);
+        let odd = gate.mul_add(
+            ctx,
+            QuantumCell::Existing(&half),
+            QuantumCell::Constant(F::from(2)),
+            QuantumCell::Constant(F::one()),
+        );
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(lowest),
+            QuantumCell::Existing(&odd),
+        );
+
+        let highest = n.limb(n.num_limbs() - 1);
+        let top_bit = gate.pow_of_two()[limb_bits - 1];
+        let rest = gate.sub(
+            ctx,
+            QuantumCell::Existing(highest),
+            QuantumCell::Constant(top_bit),
+        );
+        range.range_check(ctx, &rest, limb_bits - 1);
+    }
+
     /// Return [`Context<F>`]
     pub fn new_context<'a, 'b>(&'b self, region: Region<'a, F>) -> Context<'a, F> {
         self.biguint_config.new_context(region)
     }
 
+    /// Constrains the public key to the instance column, the limbs of `n` first and then a variable `e`.
+    ///
+    /// # Arguments
+    /// * layouter - a layouter.
+    /// * public_key - cells of an assigned public key returned by [`AssignedRSAPublicKey::cells`].
+    /// * column - an instance column.
+    /// * offset - the row of the instance column where `n` starts.
+    ///
+    /// # Return values
+    /// Returns the next free row of the instance column.
+    pub fn expose_public_key_instance(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        public_key: &RSAPublicKeyCells,
+        column: Column<Instance>,
+        offset: usize,
+    ) -> Result<usize, Error> {
+        let mut row = offset;
+        for cell in public_key.n.iter().chain(public_key.e.iter()) {
+            layouter.constrain_instance(*cell, column, row)?;
+            row += 1;
+        }
+        Ok(row)
+    }
+
+    /// Starts the right-to-left modular power `x^e mod n` by the public key, whose intermediate states can be split across circuits.
+    ///
+    /// The monolithic [`RSAInstructions::modpow_public_key`] is equivalent to [`RSAConfig::modpow_ladder_start`],
+    /// [`RSAConfig::modpow_ladder_steps`] over all the bits of `e`, and [`RSAConfig::modpow_ladder_finish`].
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `x` - a base integer.
+    /// * `public_key` - an assigned RSA public key.
+    ///
+    /// # Return values
+    /// Returns the [`AssignedModPowState`] before any bit of `e` is processed, i.e. `acc = 1` and `squared = x`.
+    pub fn modpow_ladder_start<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        x: &AssignedBigUint<'v, F, Fresh>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+    ) -> Result<AssignedModPowState<'v, F>, Error> {
+        let biguint_config = self.biguint_config();
+        biguint_config.assert_in_field(ctx, x, &public_key.n)?;
+        Ok(AssignedModPowState {
+            acc: biguint_config.assign_one(ctx, x.num_limbs())?,
+            squared: x.clone(),
+            num_bits: 0,
+        })
+    }
+
+    /// Processes the next `num_bits` bits of `e`, from the least significant one.
+    ///
+    /// A fixed `e` only multiplies at its set bits, while a variable `e` is decomposed into [`RSAConfig`]'s exponent bits and selects the product at every bit.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `state` - the state returned by [`RSAConfig::modpow_ladder_start`], [`RSAConfig::assign_modpow_state`] or a previous call.
+    /// * `public_key` - an assigned RSA public key.
+    /// * `num_bits` - the number of bits to process.
+    ///
+    /// # Return values
+    /// Returns the [`AssignedModPowState`] after `state.num_bits + num_bits` bits.
+    /// Returns [`Error::Synthesis`] if a variable `e` has fewer bits than requested.
+    pub fn modpow_ladder_steps<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        state: AssignedModPowState<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+        num_bits: usize,
+    ) -> Result<AssignedModPowState<'v, F>, Error> {
+        let biguint_config = self.biguint_config();
+        let n = &public_key.n;
+        let AssignedModPowState {
+            mut acc,
+            mut squared,
+            num_bits: start,
+        } = state;
+        let end = start + num_bits;
+        match &public_key.e {
+            AssignedRSAPubE::Var(e) => {
+                if end > self.exp_bits {
+                    return Err(Error::Synthesis);
+                }
+                let e_bits = self.gate().num_to_bits(ctx, e, self.exp_bits);
+                for e_bit in e_bits[start..end].iter() {
+                    let muled = biguint_config.mul_mod(ctx, &acc, &squared, n)?;
+                    acc = biguint_config.select(ctx, &muled, &acc, e_bit)?;
+                    squared = biguint_config.square_mod(ctx, &squared, n)?;
+                }
+            }
+            AssignedRSAPubE::Fix(e) => {
+                let mut is_one = start == 0;
+                for i in start..end {
+                    if e.bit(i as u64) {
+                        // `acc` is still the constant one until the first set bit of a ladder started in this circuit.
+                        acc = if is_one {
+                            squared.clone()
+                        } else {
+                            biguint_config.mul_mod(ctx, &acc, &squared, n)?
+                        };
+                        is_one = false;
+                    }
+                    squared = biguint_config.square_mod(ctx, &squared, n)?;
+                }
+            }
+        }
+        Ok(AssignedModPowState {
+            acc,
+            squared,
+            num_bits: end,
+        })
+    }
+
+    /// Finishes the modular power started by [`RSAConfig::modpow_ladder_start`].
+    ///
+    /// # Arguments
+    /// * `state` - the state after every bit of `e` is processed.
+    /// * `public_key` - an assigned RSA public key.
+    ///
+    /// # Return values
+    /// Returns the modular power result `x^e mod n` as [`AssignedBigUint<F, Fresh>`].
+    /// Returns [`Error::Synthesis`] if some bits of `e` are not processed yet.
+    pub fn modpow_ladder_finish<'v>(
+        &self,
+        state: AssignedModPowState<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        let remaining = match &public_key.e {
+            AssignedRSAPubE::Var(_) => state.num_bits < self.exp_bits,
+            AssignedRSAPubE::Fix(e) => (state.num_bits as u64) < e.bits(),
+        };
+        if remaining {
+            return Err(Error::Synthesis);
+        }
+        Ok(state.acc)
+    }
+
+    /// Assigns an [`AssignedModPowState`] exposed by another circuit, e.g. whose limbs are then constrained by [`RSAConfig::expose_modpow_state_instance`].
+    ///
+    /// Both integers are constrained to be less than `n`.
+    /// The values are not otherwise related to `n` here: soundness relies on the other circuit exposing the same `n` and `num_bits`.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `acc` - the integer `x^(e mod 2^num_bits) mod n`.
+    /// * `squared` - the integer `x^(2^num_bits) mod n`.
+    /// * `num_bits` - the number of bits of `e` processed by the other circuit.
+    /// * `n` - an assigned modulus.
+    ///
+    /// # Return values
+    /// Returns the assigned [`AssignedModPowState`].
+    pub fn assign_modpow_state<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        acc: Value<BigUint>,
+        squared: Value<BigUint>,
+        num_bits: usize,
+        n: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<AssignedModPowState<'v, F>, Error> {
+        let biguint_config = self.biguint_config();
+        let acc = biguint_config.assign_integer(ctx, acc, self.default_bits)?;
+        let squared = biguint_config.assign_integer(ctx, squared, self.default_bits)?;
+        biguint_config.assert_in_field(ctx, &acc, n)?;
+        biguint_config.assert_in_field(ctx, &squared, n)?;
+        Ok(AssignedModPowState {
+            acc,
+            squared,
+            num_bits,
+        })
+    }
+
+    /// Constrains an intermediate state of the modular power to the instance column.
+    ///
+    /// The layout is the [`RSAConfig::num_limbs`] limbs of `acc` followed by those of `squared`, each from the least significant limb.
+    /// `num_bits` is not exposed since it is fixed by both circuits, and `n` must be exposed by both, e.g. with [`RSAConfig::expose_public_key_instance`].
+    ///
+    /// # Arguments
+    /// * layouter - a layouter.
+    /// * state - cells of an assigned state returned by [`AssignedModPowState::cells`].
+    /// * column - an instance column.
+    /// * offset - the row of the instance column where `acc` starts.
+    ///
+    /// # Return values
+    /// Returns the next free row of the instance column.
+    pub fn expose_modpow_state_instance(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        state: &ModPowStateCells,
+        column: Column<Instance>,
+        offset: usize,
+    ) -> Result<usize, Error> {
+        let mut row = offset;
+        for cell in state.acc.iter().chain(state.squared.iter()) {
+            layouter.constrain_instance(*cell, column, row)?;
+            row += 1;
+        }
+        Ok(row)
+    }
+
+    /// Checks whether an encoded message, e.g. returned by [`RSAConfig::recover_encoded_message`] or [`RSAConfig::modpow_ladder_finish`], is the pkcs1v15 encoding of a hash.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `em` - an assigned encoded message of [`RSAConfig::num_limbs`] limbs.
+    /// * `hash_algo` - the hash algorithm, which determines the DigestInfo prefix.
+    /// * `hashed_bytes` - assigned bytes of the hashed message in big-endian order. They are assumed to be already constrained to 8 bits.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as [`AssignedValue<F>`], which is one iff `em` equals `0x00 || 0x01 || PS || 0x00 || DigestInfo prefix || H`.
+    /// Returns [`Error::Synthesis`] under the same conditions as [`RSAInstructions::verify_pkcs1v15_signature_with_hash`].
+    pub fn is_pkcs1v15_encoded_message<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        em: &AssignedBigUint<'v, F, Fresh>,
+        hash_algo: RsaHashAlgo,
+        hashed_bytes: &[AssignedValue<'v, F>],
+    ) -> Result<AssignedValue<'v, F>, Error> {
+        self.check_pkcs1v15_lengths(hash_algo, hashed_bytes)?;
+        let limb_bits = self.biguint_config.limb_bits();
+        let em_len = self.default_bits / 8;
+        let prefix = hash_algo.digest_info_prefix();
+        let gate = self.gate();
+        let powed = em;
+
+        // EM = 0x00 || 0x01 || PS || 0x00 || DigestInfo prefix || H, where PS consists of 0xff bytes.
+        let ps_len = em_len - prefix.len() - hashed_bytes.len() - 3;
+        let mut em = vec![
+            QuantumCell::Constant(F::zero()),
+            QuantumCell::Constant(F::one()),
+        ];
+        em.extend((0..ps_len).map(|_| QuantumCell::Constant(F::from(0xff))));
+        em.push(QuantumCell::Constant(F::zero()));
+        em.extend(
+            prefix
+                .iter()
+                .map(|byte| QuantumCell::Constant(F::from(*byte as u64))),
+        );
+        em.extend(hashed_bytes.iter().map(QuantumCell::Existing));
+
+        // The limbs are little-endian, while EM is big-endian.
+        let limb_bytes = limb_bits / 8;
+        let bases = (0..limb_bytes)
+            .map(|i| QuantumCell::Constant(gate.pow_of_two()[8 * i]))
+            .collect::<Vec<QuantumCell<F>>>();
+        let mut is_eq = gate.load_constant(ctx, F::one());
+        for (limb, bytes) in powed.limbs().iter().zip(em.rchunks(limb_bytes)) {
+            let expected = gate.inner_product(
+                ctx,
+                bytes.iter().rev().cloned().collect::<Vec<_>>(),
+                bases.clone(),
+            );
+            let is_limb_eq = gate.is_equal(
+                ctx,
+                QuantumCell::Existing(limb),
+                QuantumCell::Existing(&expected),
+            );
+            is_eq = gate.and(
+                ctx,
+                QuantumCell::Existing(&is_eq),
+                QuantumCell::Existing(&is_limb_eq),
+            );
+        }
+        Ok(is_eq)
+    }
+
+    /// Returns [`Error::Synthesis`] if the length of `hashed_bytes` does not match `hash_algo` or the modulus is too short for the encoded message.
+    fn check_pkcs1v15_lengths<'v>(
+        &self,
+        hash_algo: RsaHashAlgo,
+        hashed_bytes: &[AssignedValue<'v, F>],
+    ) -> Result<(), Error> {
+        let limb_bits = self.biguint_config.limb_bits();
+        let em_len = self.default_bits / 8;
+        if hashed_bytes.len() != hash_algo.output_len()
+            || limb_bits % 8 != 0
+            || self.default_bits % limb_bits != 0
+            || em_len < hash_algo.digest_info_prefix().len() + hashed_bytes.len() + 11
+        {
+            return Err(Error::Synthesis);
+        }
+        Ok(())
+    }
+
+    /// Given a RSA public key and a signature, recovers the encoded message `EM = signature^e mod n` without checking its padding.
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * public_key - an assigned RSA public key.
+    /// * signature - an assigned signature.
+    ///
+    /// # Return values
+    /// Returns the assigned `EM` as [`AssignedBigUint<F, Fresh>`], so that callers can apply their own constraints, e.g. to a non-standard padding.
+    /// [`RSAConfig::encoded_message_bytes`] decomposes it into bytes.
+    pub fn recover_encoded_message<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+        signature: &AssignedRSASignature<'v, F>,
+    ) -> Result<AssignedBigUint<'v, F, Fresh>, Error> {
+        self.modpow_public_key(ctx, &signature.c, public_key)
+    }
+
+    /// Decomposes an encoded message returned by [`RSAConfig::recover_encoded_message`] into bytes.
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * em - an assigned encoded message of [`RSAConfig::num_limbs`] limbs.
+    ///
+    /// # Return values
+    /// Returns `default_bits / 8` assigned bytes of `em` in big-endian order, each of which is constrained to 8 bits.
+    /// Returns [`Error::Synthesis`] if the limb bits are not a multiple of 8.
+    pub fn encoded_message_bytes<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        em: &AssignedBigUint<'v, F, Fresh>,
+    ) -> Result<Vec<AssignedValue<'v, F>>, Error> {
+        if self.biguint_config.limb_bits() % 8 != 0 {
+            return Err(Error::Synthesis);
+        }
+        Ok(self
+            .biguint_config
+            .to_assigned_bytes(ctx, em, Endianness::Big))
+    }
+
+    /// Given a RSA public key, a message hashed with `hash_algo`, a pkcs1v15 signature, and the CRT components of the private key, verifies the signature modulo each prime factor.
+    ///
+    /// The signature power is computed modulo `p` and `q` of `default_bits / 2` bits instead of `n`.
+    /// Since the cost of a modular multiplication is quadratic in the number of limbs, each of the two powers costs about a quarter of [`RSAInstructions::modpow_public_key`].
+    /// The prover must know the factorization of `n`, which is why this is opt-in.
+    ///
+    /// # Soundness
+    /// The circuit constrains `p * q = n` and `qinv * q = 1 mod p`, so `p` and `q` are coprime, and both are greater than one because they fit in `default_bits / 2` bits while `n` has its top bit set.
+    /// It then constrains `EM = signature^e mod p` and `EM = signature^e mod q` for the expected encoded message `EM`.
+    /// By the CRT, `EM = signature^e mod n` follows since `EM < n`, i.e. the same relation as [`RSAInstructions::verify_pkcs1v15_signature_with_hash`], which only depends on the public key.
+    /// A wrong factorization or coefficient violates the constraints rather than setting the returned bit to zero.
+    ///
+    /// # Arguments
+    /// * `ctx` - a region context.
+    /// * `public_key` - an assigned RSA public key.
+    /// * `hash_algo` - the hash algorithm, which determines the DigestInfo prefix.
+    /// * `hashed_bytes` - assigned bytes of the hashed message in big-endian order. They are assumed to be already constrained to 8 bits.
+    /// * `signature` - an assigned pkcs1v15 signature.
+    /// * `crt` - the CRT components of the private key.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as [`AssignedValue<F>`].
+    /// If `signature` is valid for `public_key` and `hashed_bytes`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`Error::Synthesis`] under the same conditions as [`RSAInstructions::verify_pkcs1v15_signature_with_hash`] or if `default_bits / 2` is not a multiple of the limb bits.
+    pub fn verify_pkcs1v15_signature_crt<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+        hash_algo: RsaHashAlgo,
+        hashed_bytes: &[AssignedValue<'v, F>],
+        signature: &AssignedRSASignature<'v, F>,
+        crt: RSACrtComponents<F>,
+    ) -> Result<AssignedValue<'v, F>, Error> {
+        let biguint_config = self.biguint_config();
+        let limb_bits = biguint_config.limb_bits();
+        let em_len = self.default_bits / 8;
+        let prefix = hash_algo.digest_info_prefix();
+        let half_bits = self.default_bits / 2;
+        if hashed_bytes.len() != hash_algo.output_len()
+            || limb_bits % 8 != 0
+            || half_bits % limb_bits != 0
+            || em_len < prefix.len() + hashed_bytes.len() + 11
+        {
+            return Err(Error::Synthesis);
+        }
+        let gate = self.gate();
+        let n = &public_key.n;
+        let p = biguint_config.assign_integer(ctx, crt.p.clone(), half_bits)?;
+        let q = biguint_config.assign_integer(ctx, crt.q.clone(), half_bits)?;
+        let qinv = biguint_config.assign_integer(ctx, crt.qinv.clone(), half_bits)?;
+
+        // 1. Check `p * q = n` and `qinv * q = 1 mod p`.
+        let pq = biguint_config.mul(ctx, &p, &q)?;
+        let half_limbs = p.num_limbs();
+        let pq = biguint_config.carry_muled(ctx, &pq, n.num_limbs(), half_limbs)?;
+        biguint_config.assert_equal(ctx, &pq, n);
+        let zero = gate.load_zero(ctx);
+        let one = biguint_config
+            .assign_constant(ctx, BigUint::from(1u64))?
+            .extend_limbs(half_limbs - 1, zero);
+        let qinv_q = biguint_config.mul_mod(ctx, &qinv, &q, &p)?;
+        biguint_config.assert_equal(ctx, &qinv_q, &one);
+
+        // 2. Assign EM = 0x00 || 0x01 || PS || 0x00 || DigestInfo prefix || H, where PS consists of 0xff bytes.
+        let ps_len = em_len - prefix.len() - hashed_bytes.len() - 3;
+        let mut em_bytes = vec![F::zero(), F::one()];
+        em_bytes.extend((0..ps_len).map(|_| F::from(0xff)));
+        em_bytes.push(F::zero());
+        em_bytes.extend(prefix.iter().map(|byte| F::from(*byte as u64)));
+        let mut em_bytes = em_bytes
+            .into_iter()
+            .map(|byte| gate.load_constant(ctx, byte))
+            .collect::<Vec<_>>();
+        em_bytes.extend_from_slice(hashed_bytes);
+        let em = biguint_config.from_assigned_bytes(ctx, &em_bytes, Endianness::Big);
+
+        // 3. Check `EM = signature^e` modulo each prime factor.
+        biguint_config.assert_in_field(ctx, &signature.c, n)?;
+        let mut is_eq = gate.load_constant(ctx, F::one());
+        for prime in [&p, &q] {
+            // Multiplying by one reduces an integer of `n`'s size modulo the prime.
+            let reduced = biguint_config.mul_mod(ctx, &one, &signature.c, prime)?;
+            let powed = match &public_key.e {
+                AssignedRSAPubE::Var(e) => self.pow_mod_var_exp(ctx, &reduced, e, prime),
+                AssignedRSAPubE::Fix(e) => {
+                    biguint_config.pow_mod_fixed_exp(ctx, &reduced, e, prime)
+                }
+            }?;
+            let em_reduced = biguint_config.mul_mod(ctx, &one, &em, prime)?;
+            let is_prime_eq = biguint_config.is_equal(ctx, &powed, &em_reduced);
+            is_eq = gate.and(
+                ctx,
+                QuantumCell::Existing(&is_eq),
+                QuantumCell::Existing(&is_prime_eq),
+            );
+        }
+        Ok(is_eq)
+    }
+
     /// Getter for [`BigUintConfig`].
     pub fn biguint_config(&self) -> &BigUintConfig<F> {
         &self.biguint_config
     }
 
+    /// Getter for the default bit length of [`Fresh`] type integers.
+    pub fn default_bits(&self) -> usize {
+        self.default_bits
+    }
+
+    /// Returns the number of limbs of [`Fresh`] type integers in this chip, i.e. of the modulus and signatures.
+    pub fn num_limbs(&self) -> usize {
+        self.default_bits / self.biguint_config.limb_bits()
+    }
+
+    /// Getter for the salt length of RSASSA-PSS signatures.
+    pub fn pss_salt_len(&self) -> usize {
+        self.pss_salt_len
+    }
+
     /// Getter for [`FlexGateConfig`].
     pub fn gate(&self) -> &FlexGateConfig<F> {
         &self.biguint_config.gate()
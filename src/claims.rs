@@ -0,0 +1,683 @@
+//! A one-call API proving claims about the holder of a signed QR code.
+//!
+//! [`AadhaarClaimsCircuit`] verifies the RSA-SHA256 signature of a format v2 payload and constrains its fields
+//! against the claims listed in a [`ClaimsRequest`]: a minimum age on the signing date, a gender, a pincode range,
+//! a nullifier and the revealed fields.
+//! [`prove_aadhaar_claims`] builds the circuit and its [`PublicInputs`] and produces a KZG proof checked by [`verify_aadhaar_claims`].
+//!
+//! Claims that are not requested are encoded as trivially satisfied public parameters,
+//! so that a single pair of keys serves every request.
+
+pub use crate::extractors::pincode_extractor::MAX_PINCODE;
+
+use crate::big_uint::decompose_biguint;
+use crate::extractors::{
+    age_extractor::{extract_age_years, is_at_least},
+    gender_extractor::{extract_gender, Gender},
+    photo_extractor::{extract_photo, photo_region_native},
+    pincode_extractor::{extract_pincode, parse_pincode},
+};
+use crate::keygen;
+use crate::nullifier::{compute_nullifier, compute_nullifier_native};
+use crate::poseidon::{StandardPoseidonChip, R_F, R_P};
+use crate::qr_spec::{field_range, QrField, QrFormat, SIGNATURE_LEN};
+use crate::utils::pack_bytes;
+use crate::{
+    sha256_padded_len, BigUintConfig, RSAConfig, RSAInstructions, RSAPubE, RSAPublicKey,
+    RSASignature, RsaCircuitError, RsaHashAlgo,
+};
+use halo2_base::halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, verify_proof, Circuit, Column, ConstraintSystem, Error, Instance, ProvingKey,
+        VerifyingKey,
+    },
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::{ProverSHPLONK, VerifierSHPLONK},
+        strategy::SingleStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+use halo2_base::{
+    gates::{
+        range::{RangeConfig, RangeStrategy::Vertical},
+        GateInstructions, RangeInstructions,
+    },
+    utils::PrimeField,
+    QuantumCell, SKIP_FIRST_PASS,
+};
+use halo2_dynamic_sha256::Sha256DynamicConfig;
+use num_bigint::BigUint;
+use rand::rngs::OsRng;
+use rsa::{traits::PublicKeyParts, RsaPublicKey};
+use std::path::Path;
+
+/// The maximum byte length of the signed payload, i.e. the QR payload without its signature.
+///
+/// A decompressed v2 payload carries a JPEG2000 photo of one to two kilobytes next to the demographic fields.
+pub const MAX_SIGNED_DATA_LEN: usize = 2039;
+
+/// The byte size of the SHA256 slot hashing the signed payload, i.e. [`MAX_SIGNED_DATA_LEN`] after SHA256 padding.
+pub const SIGNED_DATA_SLOT_LEN: usize = sha256_padded_len(MAX_SIGNED_DATA_LEN);
+
+/// The claims to be proven about the holder of a QR code.
+///
+/// Every claim is optional. Dates are compared against the signing date embedded in the reference id.
+#[derive(Debug, Clone, Default)]
+pub struct ClaimsRequest<F: PrimeField> {
+    /// The minimum age in years on the signing date, at most 255.
+    pub min_age: Option<u64>,
+    /// The expected gender.
+    pub gender_match: Option<Gender>,
+    /// The inclusive range the pincode must fall in.
+    pub pincode_range: Option<(u32, u32)>,
+    /// A non-zero external nullifier (app ID) scoping the exposed nullifier.
+    pub nullifier: Option<F>,
+    /// Whether the gender is exposed.
+    pub reveal_gender: bool,
+    /// Whether the pincode is exposed.
+    pub reveal_pincode: bool,
+}
+
+/// The public inputs of [`AadhaarClaimsCircuit`].
+///
+/// Unrequested claims and unrevealed fields are zero, except `pincode_max` which defaults to [`MAX_PINCODE`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicInputs<F: PrimeField> {
+    /// The limbs of the RSA modulus, least significant first.
+    pub public_key_limbs: Vec<F>,
+    /// The minimum age.
+    pub min_age: F,
+    /// The [`Gender::code`] of the expected gender.
+    pub gender_match: F,
+    /// The lower bound of the pincode.
+    pub pincode_min: F,
+    /// The upper bound of the pincode.
+    pub pincode_max: F,
+    /// The external nullifier.
+    pub external_nullifier: F,
+    /// The nullifier, i.e. `Poseidon(external_nullifier, photo...)`.
+    pub nullifier: F,
+    /// The [`Gender::code`] of the revealed gender, or zero if the gender field is malformed.
+    pub gender: F,
+    /// The revealed pincode.
+    pub pincode: F,
+}
+
+impl<F: PrimeField> PublicInputs<F> {
+    /// Computes the public inputs of [`AadhaarClaimsCircuit`] off-circuit.
+    ///
+    /// # Arguments
+    /// * qr_data - the decoded QR payload, including the trailing signature.
+    /// * public_key - the RSA public key of the signer.
+    /// * request - the requested claims.
+    ///
+    /// # Return values
+    /// Returns the public inputs.
+    /// Returns [`RsaCircuitError::Parse`] or [`RsaCircuitError::BadLength`] if `qr_data` is not a well-formed format v2 payload,
+    /// and [`RsaCircuitError::OutOfRange`] if its pincode is out of range, see [`parse_pincode`].
+    /// The claims themselves are not checked here; an unsatisfied claim fails the proof instead.
+    pub fn new(
+        qr_data: &[u8],
+        public_key: &RsaPublicKey,
+        request: &ClaimsRequest<F>,
+    ) -> Result<Self, RsaCircuitError> {
+        if QrFormat::detect(qr_data) != QrFormat::V2 {
+            return Err(RsaCircuitError::Parse(QrField::Version));
+        }
+        let signed_len =
+            qr_data
+                .len()
+                .checked_sub(SIGNATURE_LEN)
+                .ok_or(RsaCircuitError::BadLength {
+                    len: qr_data.len(),
+                    max_len: None,
+                })?;
+        let signed_data = &qr_data[..signed_len];
+        let gender_range =
+            field_range(qr_data, QrField::Gender).ok_or(RsaCircuitError::Parse(QrField::Gender))?;
+        let gender = match qr_data[gender_range] {
+            [byte] => Gender::from_byte(byte).map_or(0, Gender::code),
+            _ => 0,
+        };
+        let pincode = parse_pincode(qr_data)?;
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let (pincode_min, pincode_max) = request.pincode_range.unwrap_or((0, MAX_PINCODE));
+        let external_nullifier = request.nullifier.unwrap_or(F::zero());
+        let nullifier = match request.nullifier {
+            Some(external_nullifier) => compute_nullifier_native(
+                external_nullifier,
+                &photo_region_native(signed_data, SIGNED_DATA_SLOT_LEN)
+                    .ok_or(RsaCircuitError::Parse(QrField::Photo))?,
+            )?,
+            None => F::zero(),
+        };
+        Ok(Self {
+            public_key_limbs: decompose_biguint::<F>(
+                &n,
+                AadhaarClaimsCircuit::<F>::BITS_LEN / AadhaarClaimsCircuit::<F>::LIMB_BITS,
+                AadhaarClaimsCircuit::<F>::LIMB_BITS,
+            ),
+            min_age: F::from(request.min_age.unwrap_or(0)),
+            gender_match: F::from(request.gender_match.map_or(0, Gender::code)),
+            pincode_min: F::from(pincode_min as u64),
+            pincode_max: F::from(pincode_max as u64),
+            external_nullifier,
+            nullifier,
+            gender: F::from(request.reveal_gender as u64 * gender),
+            pincode: F::from(request.reveal_pincode as u64 * pincode as u64),
+        })
+    }
+
+    /// Parses the values of the instance column returned by [`PublicInputs::to_instances`].
+    ///
+    /// # Arguments
+    /// * instances - the values of the instance column in order.
+    ///
+    /// # Return values
+    /// Returns the public inputs, or `None` if the number of values does not match [`AadhaarClaimsCircuit`].
+    pub fn from_instances(instances: &[F]) -> Option<Self> {
+        let num_limbs = AadhaarClaimsCircuit::<F>::BITS_LEN / AadhaarClaimsCircuit::<F>::LIMB_BITS;
+        let (public_key_limbs, claims) = instances.split_at(instances.len().min(num_limbs));
+        match *claims {
+            [min_age, gender_match, pincode_min, pincode_max, external_nullifier, nullifier, gender, pincode] => {
+                Some(Self {
+                    public_key_limbs: public_key_limbs.to_vec(),
+                    min_age,
+                    gender_match,
+                    pincode_min,
+                    pincode_max,
+                    external_nullifier,
+                    nullifier,
+                    gender,
+                    pincode,
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the values of the instance column in order.
+    pub fn to_instances(&self) -> Vec<F> {
+        let mut instances = self.public_key_limbs.clone();
+        instances.extend([
+            self.min_age,
+            self.gender_match,
+            self.pincode_min,
+            self.pincode_max,
+            self.external_nullifier,
+            self.nullifier,
+            self.gender,
+            self.pincode,
+        ]);
+        instances
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AadhaarClaimsConfig<F: PrimeField> {
+    rsa_config: RSAConfig<F>,
+    sha256_config: Sha256DynamicConfig<F>,
+    poseidon: StandardPoseidonChip<F>,
+    instance: Column<Instance>,
+}
+
+/// A circuit proving the claims of a [`ClaimsRequest`] about a format v2 payload signed with RSA-2048 and SHA256.
+/// Its instance column holds [`PublicInputs::to_instances`].
+#[derive(Debug, Clone)]
+pub struct AadhaarClaimsCircuit<F: PrimeField> {
+    signed_data: Vec<u8>,
+    signature: Value<BigUint>,
+    public_key_n: Value<BigUint>,
+    request: ClaimsRequest<F>,
+}
+
+impl<F: PrimeField> AadhaarClaimsCircuit<F> {
+    pub const K: usize = 15;
+    const BITS_LEN: usize = 2048;
+    const LIMB_BITS: usize = 64;
+    const EXP_LIMB_BITS: usize = 5;
+    const DEFAULT_E: u128 = 65537;
+    const NUM_ADVICE: usize = 80;
+    const NUM_FIXED: usize = 1;
+    const NUM_LOOKUP_ADVICE: usize = 16;
+    const LOOKUP_BITS: usize = 12;
+    const SHA256_LOOKUP_BITS: usize = 8;
+    const SHA256_LOOKUP_ADVICE: usize = 8;
+
+    /// Creates a circuit for a QR payload.
+    ///
+    /// # Arguments
+    /// * qr_data - the decoded QR payload, including the trailing signature.
+    /// * public_key - the RSA public key of the signer.
+    /// * request - the requested claims.
+    ///
+    /// # Return values
+    /// Returns the circuit.
+    /// Returns [`RsaCircuitError::BadLength`] if the signed payload is empty or longer than [`MAX_SIGNED_DATA_LEN`].
+    pub fn new(
+        qr_data: &[u8],
+        public_key: &RsaPublicKey,
+        request: ClaimsRequest<F>,
+    ) -> Result<Self, RsaCircuitError> {
+        let signed_len = qr_data
+            .len()
+            .checked_sub(SIGNATURE_LEN)
+            .filter(|len| *len != 0 && *len <= MAX_SIGNED_DATA_LEN)
+            .ok_or(RsaCircuitError::BadLength {
+                len: qr_data.len(),
+                max_len: Some(MAX_SIGNED_DATA_LEN + SIGNATURE_LEN),
+            })?;
+        let (signed_data, signature) = qr_data.split_at(signed_len);
+        Ok(Self {
+            signed_data: signed_data.to_vec(),
+            signature: Value::known(BigUint::from_bytes_be(signature)),
+            public_key_n: Value::known(BigUint::from_bytes_be(&public_key.n().to_bytes_be())),
+            request,
+        })
+    }
+
+    /// Returns a circuit without witnesses from which the keys serving every [`ClaimsRequest`] are generated.
+    pub(crate) fn keygen_circuit() -> Self {
+        Self {
+            signed_data: vec![0; MAX_SIGNED_DATA_LEN / 2],
+            signature: Value::unknown(),
+            public_key_n: Value::unknown(),
+            request: ClaimsRequest::default(),
+        }
+    }
+}
+
+/// Wipes the signed payload, which holds the personal data of the resident, and the signature witness.
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Drop for AadhaarClaimsCircuit<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(&mut self.signed_data);
+        crate::utils::zeroize_value_biguint(&mut self.signature);
+    }
+}
+
+impl<F: PrimeField> Circuit<F> for AadhaarClaimsCircuit<F> {
+    type Config = AadhaarClaimsConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            signed_data: vec![0; self.signed_data.len()],
+            signature: Value::unknown(),
+            public_key_n: Value::unknown(),
+            request: self.request.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let range_config = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[Self::NUM_ADVICE],
+            &[Self::NUM_LOOKUP_ADVICE],
+            Self::NUM_FIXED,
+            Self::LOOKUP_BITS,
+            0,
+            Self::K,
+        );
+        let bigint_config = BigUintConfig::construct(range_config.clone(), Self::LIMB_BITS);
+        let rsa_config = RSAConfig::construct(bigint_config, Self::BITS_LEN, Self::EXP_LIMB_BITS);
+        let sha256_config = Sha256DynamicConfig::configure(
+            meta,
+            vec![SIGNED_DATA_SLOT_LEN],
+            range_config,
+            Self::SHA256_LOOKUP_BITS,
+            Self::SHA256_LOOKUP_ADVICE,
+            true,
+        );
+        let poseidon = StandardPoseidonChip::new(R_F, R_P);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        AadhaarClaimsConfig {
+            rsa_config,
+            sha256_config,
+            poseidon,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let biguint_config = config.rsa_config.biguint_config();
+        config.sha256_config.load(&mut layouter)?;
+        biguint_config.range().load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let public_cells = layouter.assign_region(
+            || "aadhaar claims",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(None);
+                }
+                let mut aux = config.rsa_config.new_context(region);
+                let ctx = &mut aux;
+                let range = config.rsa_config.range();
+                let gate = range.gate();
+
+                // The payload is signed by the public key.
+                let signature = config
+                    .rsa_config
+                    .assign_signature(ctx, RSASignature::new(self.signature.clone()))?;
+                let public_key = config.rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(
+                        self.public_key_n.clone(),
+                        RSAPubE::Fix(BigUint::from(Self::DEFAULT_E)),
+                    ),
+                )?;
+                let mut sha256 = config.sha256_config.clone();
+                let hashed = sha256.digest(ctx, &self.signed_data, None)?;
+                let is_valid = config.rsa_config.verify_pkcs1v15_signature_with_hash(
+                    ctx,
+                    &public_key,
+                    RsaHashAlgo::Sha256,
+                    &hashed.output_bytes,
+                    &signature,
+                )?;
+                gate.assert_is_const(ctx, &is_valid, F::one());
+                let qr_bytes = &hashed.input_bytes[..SIGNED_DATA_SLOT_LEN];
+
+                // Age in years on the signing date, i.e. the `YYYYMMDD` following the last 4 digits of the reference id.
+                let age = extract_age_years(range, ctx, qr_bytes)?;
+                let min_age = gate.load_witness(
+                    ctx,
+                    Value::known(F::from(self.request.min_age.unwrap_or(0))),
+                );
+                let is_old_enough = is_at_least(range, ctx, &age, &min_age);
+                gate.assert_is_const(ctx, &is_old_enough, F::one());
+
+                // The gender matches unless no gender is requested.
+                let gender = extract_gender(range, ctx, qr_bytes)?.value;
+                let gender_match = gate.load_witness(
+                    ctx,
+                    Value::known(F::from(self.request.gender_match.map_or(0, Gender::code))),
+                );
+                let is_any_gender = gate.is_zero(ctx, &gender_match);
+                let is_gender_eq = gate.is_equal(
+                    ctx,
+                    QuantumCell::Existing(&gender),
+                    QuantumCell::Existing(&gender_match),
+                );
+                let is_gender_ok = gate.or(
+                    ctx,
+                    QuantumCell::Existing(&is_any_gender),
+                    QuantumCell::Existing(&is_gender_eq),
+                );
+                gate.assert_is_const(ctx, &is_gender_ok, F::one());
+
+                // pincode_min <= pincode <= pincode_max
+                let pincode = extract_pincode(range, ctx, qr_bytes)?.limbs()[0].clone();
+                let (pincode_min, pincode_max) =
+                    self.request.pincode_range.unwrap_or((0, MAX_PINCODE));
+                let pincode_min = gate.load_witness(ctx, Value::known(F::from(pincode_min as u64)));
+                let pincode_max = gate.load_witness(ctx, Value::known(F::from(pincode_max as u64)));
+                for value in [&pincode, &pincode_min, &pincode_max] {
+                    range.range_check(ctx, value, 20);
+                }
+                for (a, b) in [(&pincode, &pincode_min), (&pincode_max, &pincode)] {
+                    let is_out_of_range = range.is_less_than(
+                        ctx,
+                        QuantumCell::Existing(a),
+                        QuantumCell::Existing(b),
+                        20,
+                    );
+                    gate.assert_is_const(ctx, &is_out_of_range, F::zero());
+                }
+
+                // The nullifier is derived from the bytes after the last delimiter and zero unless requested.
+                let external_nullifier = gate.load_witness(
+                    ctx,
+                    Value::known(self.request.nullifier.unwrap_or(F::zero())),
+                );
+                let photo = extract_photo(range, ctx, qr_bytes, &hashed.input_len);
+                let packed_photo = pack_bytes(gate, ctx, &photo);
+                let nullifier = compute_nullifier(
+                    &config.poseidon,
+                    gate,
+                    ctx,
+                    &external_nullifier,
+                    &packed_photo,
+                );
+                let is_nullifier_off = gate.is_zero(ctx, &external_nullifier);
+                let nullifier = gate.select(
+                    ctx,
+                    QuantumCell::Constant(F::zero()),
+                    QuantumCell::Existing(&nullifier),
+                    QuantumCell::Existing(&is_nullifier_off),
+                );
+
+                // Revealed fields are zero unless revealed.
+                let mut revealed = vec![];
+                for (flag, value) in [
+                    (self.request.reveal_gender, &gender),
+                    (self.request.reveal_pincode, &pincode),
+                ] {
+                    let flag = gate.load_witness(ctx, Value::known(F::from(flag as u64)));
+                    range.range_check(ctx, &flag, 1);
+                    revealed.push(gate.mul(
+                        ctx,
+                        QuantumCell::Existing(&flag),
+                        QuantumCell::Existing(value),
+                    ));
+                }
+                range.finalize(ctx);
+
+                let claim_cells = [
+                    &min_age,
+                    &gender_match,
+                    &pincode_min,
+                    &pincode_max,
+                    &external_nullifier,
+                    &nullifier,
+                    &revealed[0],
+                    &revealed[1],
+                ]
+                .map(|value| value.cell());
+                Ok(Some((public_key.cells(), claim_cells)))
+            },
+        )?;
+        if let Some((public_key_cells, claim_cells)) = public_cells {
+            let offset = config.rsa_config.expose_public_key_instance(
+                &mut layouter,
+                &public_key_cells,
+                config.instance,
+                0,
+            )?;
+            for (i, cell) in claim_cells.into_iter().enumerate() {
+                layouter.constrain_instance(cell, config.instance, offset + i)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the proving and verifying keys of [`AadhaarClaimsCircuit`], cached at `path` by [`keygen::setup`].
+///
+/// # Arguments
+/// * params - the KZG parameters of degree [`AadhaarClaimsCircuit::K`].
+/// * path - the path of the cache file.
+///
+/// # Return values
+/// Returns the proving key and the verifying key, which serve every [`ClaimsRequest`].
+pub fn setup_aadhaar_claims(
+    params: &ParamsKZG<Bn256>,
+    path: impl AsRef<Path>,
+) -> Result<(ProvingKey<G1Affine>, VerifyingKey<G1Affine>), Error> {
+    keygen::setup(&AadhaarClaimsCircuit::<Fr>::keygen_circuit(), params, path)
+}
+
+/// Proves the claims of `request` about the holder of a QR code.
+///
+/// # Arguments
+/// * params - the KZG parameters of degree [`AadhaarClaimsCircuit::K`].
+/// * pk - the proving key returned by [`setup_aadhaar_claims`].
+/// * qr_data - the decoded format v2 QR payload, including the trailing signature.
+/// * public_key - the RSA public key of the signer.
+/// * request - the requested claims.
+///
+/// # Return values
+/// Returns the proof and its [`PublicInputs`].
+/// Returns the [`RsaCircuitError`] of [`PublicInputs::new`] or [`AadhaarClaimsCircuit::new`] if `qr_data` is malformed or too long,
+/// and [`RsaCircuitError::Halo2`] if the proof cannot be created. An unsatisfied claim yields a proof that fails verification.
+pub fn prove_aadhaar_claims(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    qr_data: &[u8],
+    public_key: &RsaPublicKey,
+    request: ClaimsRequest<Fr>,
+) -> Result<(Vec<u8>, PublicInputs<Fr>), RsaCircuitError> {
+    let public_inputs = PublicInputs::new(qr_data, public_key, &request)?;
+    let circuit = AadhaarClaimsCircuit::new(qr_data, public_key, request)?;
+    let instances = public_inputs.to_instances();
+    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[&[&instances]],
+        OsRng,
+        &mut transcript,
+    )?;
+    Ok((transcript.finalize(), public_inputs))
+}
+
+/// Verifies a proof returned by [`prove_aadhaar_claims`].
+///
+/// # Arguments
+/// * params - the KZG parameters of degree [`AadhaarClaimsCircuit::K`].
+/// * vk - the verifying key returned by [`setup_aadhaar_claims`].
+/// * proof - the proof.
+/// * public_inputs - the public inputs of the proof.
+///
+/// # Return values
+/// Returns `true` if the proof is valid for `public_inputs`.
+pub fn verify_aadhaar_claims(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    proof: &[u8],
+    public_inputs: &PublicInputs<Fr>,
+) -> bool {
+    let instances = public_inputs.to_instances();
+    let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(proof);
+    verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+        params,
+        vk,
+        SingleStrategy::new(params),
+        &[&[&instances]],
+        &mut transcript,
+    )
+    .is_ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::qr_spec::{DELIMITER, NUM_DELIMITERS};
+    use crate::test_utils::test_private_key;
+    use rsa::{
+        pkcs1v15::SigningKey,
+        signature::{SignatureEncoding, Signer},
+        RsaPrivateKey,
+    };
+
+    fn sample_qr(private_key: &RsaPrivateKey, photo_len: u32) -> Vec<u8> {
+        let fields: [&[u8]; NUM_DELIMITERS] = [
+            b"V2",
+            b"0",
+            b"269720240718124553876",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+            b"C/O Ishwar Chand",
+            b"East Delhi",
+            b"",
+            b"B-31, 3rd Floor",
+            b"",
+            b"110051",
+            b"Krishna Nagar",
+            b"Delhi",
+            b"Radhey Shyam Park Extension",
+            b"Gandhi Nagar",
+            b"Krishna Nagar",
+            b"1234",
+        ];
+        let mut data = fields.join(&DELIMITER);
+        data.push(DELIMITER);
+        data.extend((0..photo_len).map(|i| (i * 7 % 256) as u8));
+        let signing_key = SigningKey::<rsa::sha2::Sha256>::new(private_key.clone());
+        let signature = signing_key.sign(&data).to_vec();
+        data.extend(signature);
+        data
+    }
+
+    #[test]
+    fn test_prove_aadhaar_claims() {
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        // A photo of the size found in real QR codes, which would not fit a 1024 byte slot.
+        let qr_data = sample_qr(&private_key, 1600);
+        assert!(qr_data.len() - SIGNATURE_LEN > 1024);
+        let request = ClaimsRequest {
+            min_age: Some(18),
+            nullifier: Some(Fr::from(12345678u64)),
+            ..Default::default()
+        };
+
+        let params = ParamsKZG::<Bn256>::setup(AadhaarClaimsCircuit::<Fr>::K as u32, OsRng);
+        let dir = tempfile::tempdir().unwrap();
+        let (pk, vk) = setup_aadhaar_claims(&params, dir.path().join("claims.pk")).unwrap();
+
+        let (proof, public_inputs) =
+            prove_aadhaar_claims(&params, &pk, &qr_data, &public_key, request).unwrap();
+        assert_eq!(public_inputs.min_age, Fr::from(18u64));
+        let photo = photo_region_native(
+            &qr_data[..qr_data.len() - SIGNATURE_LEN],
+            SIGNED_DATA_SLOT_LEN,
+        )
+        .unwrap();
+        assert_eq!(
+            public_inputs.nullifier,
+            compute_nullifier_native(Fr::from(12345678u64), &photo).unwrap()
+        );
+        assert!(verify_aadhaar_claims(&params, &vk, &proof, &public_inputs));
+
+        // The proof is bound to its public inputs.
+        let mut tampered = public_inputs.clone();
+        tampered.min_age = Fr::from(21u64);
+        assert!(!verify_aadhaar_claims(&params, &vk, &proof, &tampered));
+        let mut tampered = public_inputs;
+        tampered.nullifier += Fr::one();
+        assert!(!verify_aadhaar_claims(&params, &vk, &proof, &tampered));
+    }
+
+    #[test]
+    fn test_aadhaar_claims_payload_limit() {
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let fields_len = sample_qr(&private_key, 0).len() - SIGNATURE_LEN;
+
+        // The longest payload fitting the slot, and one byte more.
+        let max_len = MAX_SIGNED_DATA_LEN;
+        let qr_data = sample_qr(&private_key, (max_len - fields_len) as u32);
+        assert!(AadhaarClaimsCircuit::<Fr>::new(&qr_data, &public_key, Default::default()).is_ok());
+        let qr_data = sample_qr(&private_key, (max_len + 1 - fields_len) as u32);
+        assert!(matches!(
+            AadhaarClaimsCircuit::<Fr>::new(&qr_data, &public_key, Default::default()),
+            Err(RsaCircuitError::BadLength { len, max_len: Some(max) })
+                if len == qr_data.len() && max == max_len + SIGNATURE_LEN
+        ));
+    }
+}
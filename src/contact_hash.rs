@@ -0,0 +1,143 @@
+//! Verification of the email hash appended to the QR payload.
+//!
+//! When the email-mobile indicator is `1` (email only) or `3` (email and mobile), i.e. its bit 0 is set, the payload carries `SHA256(last4 || email)`,
+//! where `last4` are the last four digits of the Aadhaar number, i.e. the first four bytes of the reference id.
+
+use halo2_base::halo2_proofs::plonk::Error;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
+};
+use halo2_dynamic_sha256::Sha256DynamicConfig;
+
+/// The byte length of the stored email hash.
+pub const EMAIL_HASH_LEN: usize = 32;
+
+/// Verifies a claimed email against the email hash stored in the QR payload.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * sha256 - a [`Sha256DynamicConfig`] with a free slot for a message of `4 + email.len()` bytes.
+/// * ctx - a region context.
+/// * email_mobile_present - the assigned integer value of the email-mobile indicator field. Its bit 0 indicates the email hash.
+/// * last4 - assigned ASCII bytes of the last four digits of the Aadhaar number.
+/// * email - the claimed email, a private witness.
+/// * stored_hash - assigned bytes of the email hash field. They are ignored if the email hash is absent.
+///
+/// # Return values
+/// Returns the assigned bit as `AssignedValue<F>`.
+/// If the email hash is present and equals `SHA256(last4 || email)`, the bit is equivalent to one.
+/// Otherwise, including when the email hash is absent, the bit is equivalent to zero.
+pub fn verify_email_hash<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    sha256: &mut Sha256DynamicConfig<F>,
+    ctx: &mut Context<'v, F>,
+    email_mobile_present: &AssignedValue<'v, F>,
+    last4: &[AssignedValue<'v, F>],
+    email: &[u8],
+    stored_hash: &[AssignedValue<'v, F>],
+) -> Result<AssignedValue<'v, F>, Error> {
+    if last4.len() != 4 || stored_hash.len() != EMAIL_HASH_LEN {
+        return Err(Error::Synthesis);
+    }
+    let gate = range.gate();
+    let mut input = last4
+        .iter()
+        .map(|byte| {
+            let mut value = 0u8;
+            byte.value().map(|v| value = v.get_lower_32() as u8);
+            value
+        })
+        .collect::<Vec<u8>>();
+    input.extend_from_slice(email);
+    let result = sha256.digest(ctx, &input, None)?;
+    // The hashed `last4` must be the one in the signed payload.
+    for (hashed, byte) in result.input_bytes.iter().zip(last4.iter()) {
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(hashed),
+            QuantumCell::Existing(byte),
+        );
+    }
+
+    let indicator_bits = gate.num_to_bits(ctx, email_mobile_present, 2);
+    let mut is_eq = gate.load_constant(ctx, F::one());
+    for (computed, stored) in result.output_bytes.iter().zip(stored_hash.iter()) {
+        let is_byte_eq = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(computed),
+            QuantumCell::Existing(stored),
+        );
+        is_eq = gate.and(
+            ctx,
+            QuantumCell::Existing(&is_eq),
+            QuantumCell::Existing(&is_byte_eq),
+        );
+    }
+    Ok(gate.and(
+        ctx,
+        QuantumCell::Existing(&indicator_bits[0]),
+        QuantumCell::Existing(&is_eq),
+    ))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::mock_prove_with_sha256;
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+    use sha2::{Digest, Sha256};
+
+    const LAST4: &[u8] = b"2697";
+    const EMAIL: &[u8] = b"sumit.kumar@example.com";
+
+    fn email_hash_is_verified(
+        indicator: u64,
+        email: &'static [u8],
+        stored_hash: Vec<u8>,
+        expected: bool,
+    ) {
+        let prover = mock_prove_with_sha256(
+            move |ctx, range, sha256| {
+                let gate = range.gate();
+                let mut assign_bytes = |bytes: &[u8]| {
+                    bytes
+                        .iter()
+                        .map(|byte| gate.load_witness(ctx, Value::known(Fr::from(*byte as u64))))
+                        .collect::<Vec<_>>()
+                };
+                let last4 = assign_bytes(LAST4);
+                let stored_hash = assign_bytes(&stored_hash);
+                let indicator = gate.load_witness(ctx, Value::known(Fr::from(indicator)));
+                let is_verified =
+                    verify_email_hash(range, sha256, ctx, &indicator, &last4, email, &stored_hash)?;
+                Ok(vec![is_verified])
+            },
+            vec![Fr::from(expected as u64)],
+        );
+        prover.assert_satisfied();
+    }
+
+    fn stored_email_hash() -> Vec<u8> {
+        Sha256::digest([LAST4, EMAIL].concat()).to_vec()
+    }
+
+    #[test]
+    fn test_email_hash_present_matching() {
+        email_hash_is_verified(3, EMAIL, stored_email_hash(), true);
+        email_hash_is_verified(1, EMAIL, stored_email_hash(), true);
+    }
+
+    #[test]
+    fn test_email_hash_present_mismatching() {
+        email_hash_is_verified(3, b"someone.else@example.com", stored_email_hash(), false);
+    }
+
+    #[test]
+    fn test_email_hash_absent() {
+        email_hash_is_verified(2, EMAIL, vec![0; EMAIL_HASH_LEN], false);
+        email_hash_is_verified(2, EMAIL, stored_email_hash(), false);
+        email_hash_is_verified(0, EMAIL, stored_email_hash(), false);
+    }
+}
@@ -0,0 +1,74 @@
+//! The error type of the key parsing, assignment and extraction APIs.
+
+use crate::hash_algo::RsaHashAlgo;
+use crate::qr_spec::QrField;
+use halo2_base::halo2_proofs::plonk::Error;
+use std::fmt;
+
+/// An error telling which input made the assignment or the extraction fail.
+///
+/// It converts into halo2's [`Error`] so that it can be propagated with `?` from [`halo2_base::halo2_proofs::plonk::Circuit::synthesize`],
+/// and halo2's [`Error`] converts into [`RsaCircuitError::Halo2`].
+#[derive(Debug)]
+pub enum RsaCircuitError {
+    /// The RSA key cannot be decoded or is degenerate, e.g. its exponent is less than 3.
+    InvalidKey,
+    /// A payload of byte length `len` is too short or too long, e.g. for a limit of `max_len` bytes such as the largest SHA256 bucket.
+    BadLength { len: usize, max_len: Option<usize> },
+    /// A field of the QR payload holds a value outside its allowed range.
+    OutOfRange(QrField),
+    /// A field of the QR payload is missing or malformed.
+    Parse(QrField),
+    /// The compressed QR payload is missing or cannot be inflated.
+    Inflate,
+    /// The hash algorithm has no in-circuit digest chip, so the message must be hashed outside of this crate.
+    UnsupportedHash(RsaHashAlgo),
+    /// A file, e.g. of a PEM key, cannot be read.
+    Io(std::io::Error),
+    /// An error of the underlying halo2 synthesis.
+    Halo2(Error),
+}
+
+impl fmt::Display for RsaCircuitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidKey => write!(f, "invalid RSA key"),
+            Self::BadLength {
+                len,
+                max_len: Some(max_len),
+            } => write!(f, "payload of invalid length {len}, at most {max_len}"),
+            Self::BadLength { len, max_len: None } => write!(f, "payload of invalid length {len}"),
+            Self::OutOfRange(field) => write!(f, "{field:?} field out of range"),
+            Self::Parse(field) => write!(f, "{field:?} field missing or malformed"),
+            Self::Inflate => write!(f, "compressed payload missing or invalid"),
+            Self::UnsupportedHash(algo) => write!(f, "no in-circuit digest chip for {algo:?}"),
+            Self::Io(e) => write!(f, "cannot read file: {e}"),
+            Self::Halo2(e) => write!(f, "synthesis failed: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for RsaCircuitError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Halo2(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for RsaCircuitError {
+    fn from(e: Error) -> Self {
+        Self::Halo2(e)
+    }
+}
+
+impl From<RsaCircuitError> for Error {
+    fn from(e: RsaCircuitError) -> Self {
+        match e {
+            RsaCircuitError::Halo2(e) => e,
+            _ => Error::Synthesis,
+        }
+    }
+}
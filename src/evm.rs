@@ -0,0 +1,184 @@
+//! Export of an EVM verifier for the proofs of the signature circuits.
+//!
+//! [`gen_evm_verifier`] emits the Yul source of a contract verifying SHPLONK proofs for a verifying key,
+//! and [`compile_evm_verifier`] compiles it with `solc` into its deployment code.
+//! The proof must be generated by [`gen_evm_proof`], whose transcript hashes with Keccak256 as the contract does.
+//! The contract is called with [`encode_calldata`], i.e. every instance as a 32 bytes big-endian word, column by column, followed by the proof.
+//! For a circuit exposing the modulus limbs in its first instance column and the hash bytes in the second one,
+//! the instances are returned by [`crate::compute_public_inputs`].
+
+use std::rc::Rc;
+
+use halo2_base::halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fq, Fr, G1Affine},
+    plonk::{create_proof, Circuit, Error, ProvingKey, VerifyingKey},
+    poly::{
+        commitment::ParamsProver,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::ProverSHPLONK,
+        },
+    },
+    transcript::TranscriptWriterBuffer,
+};
+use rand::rngs::OsRng;
+use snark_verifier::{
+    loader::evm::{self, Address, EvmLoader, ExecutorBuilder},
+    pcs::kzg::{Bdfg21, Kzg},
+    system::halo2::{compile, transcript::evm::EvmTranscript, Config},
+    verifier::{self, PlonkVerifier},
+};
+
+type Plonk = verifier::Plonk<Kzg<Bn256, Bdfg21>>;
+
+/// Generates the Yul source of a contract verifying the proofs of a circuit.
+///
+/// # Arguments
+/// * params - the KZG parameters of the circuit.
+/// * vk - the verifying key of the circuit.
+/// * num_instance - the number of values of each instance column, e.g. `[BITS_LEN / 64, 32]` for a 2048 bits modulus in 64 bits limbs and a SHA256 hash.
+///
+/// # Return values
+/// Returns the Yul source, to be compiled by [`compile_evm_verifier`] or with `solc --bin --yul`.
+/// Returns [`Error::Synthesis`] if `vk` does not match `params`.
+pub fn gen_evm_verifier(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    num_instance: Vec<usize>,
+) -> Result<String, Error> {
+    let svk = params.get_g()[0].into();
+    let dk = (params.g2(), params.s_g2()).into();
+    let protocol = compile(
+        params,
+        vk,
+        Config::kzg().with_num_instance(num_instance.clone()),
+    );
+
+    let loader = EvmLoader::new::<Fq, Fr>();
+    let protocol = protocol.loaded(&loader);
+    let mut transcript = EvmTranscript::<_, Rc<EvmLoader>, _, _>::new(&loader);
+    let instances = transcript.load_instances(num_instance);
+    let proof = Plonk::read_proof(&svk, &protocol, &instances, &mut transcript)
+        .map_err(|_| Error::Synthesis)?;
+    Plonk::verify(&svk, &dk, &protocol, &instances, &proof).map_err(|_| Error::Synthesis)?;
+    Ok(loader.yul_code())
+}
+
+/// Compiles the Yul source returned by [`gen_evm_verifier`]. `solc` must be installed.
+///
+/// # Arguments
+/// * yul - the Yul source of the verifier.
+///
+/// # Return values
+/// Returns the deployment code of the verifier.
+pub fn compile_evm_verifier(yul: &str) -> Vec<u8> {
+    evm::compile_yul(yul)
+}
+
+/// Generates a SHPLONK proof verifiable by the contract of [`gen_evm_verifier`].
+///
+/// # Arguments
+/// * params - the KZG parameters of the circuit.
+/// * pk - the proving key of the circuit.
+/// * circuit - the circuit with its witnesses.
+/// * instances - the values of each instance column.
+///
+/// # Return values
+/// Returns the proof.
+pub fn gen_evm_proof<C: Circuit<Fr>>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    instances: &[Vec<Fr>],
+) -> Result<Vec<u8>, Error> {
+    let instances = instances.iter().map(Vec::as_slice).collect::<Vec<_>>();
+    let mut transcript = TranscriptWriterBuffer::<_, G1Affine, _>::init(vec![]);
+    create_proof::<
+        KZGCommitmentScheme<Bn256>,
+        ProverSHPLONK<'_, Bn256>,
+        _,
+        _,
+        EvmTranscript<_, _, _, _>,
+        _,
+    >(
+        params,
+        pk,
+        &[circuit],
+        &[instances.as_slice()],
+        OsRng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+/// Encodes the calldata of a call to the contract of [`gen_evm_verifier`].
+///
+/// # Arguments
+/// * instances - the values of each instance column.
+/// * proof - the proof returned by [`gen_evm_proof`].
+///
+/// # Return values
+/// Returns the calldata.
+pub fn encode_calldata(instances: &[Vec<Fr>], proof: &[u8]) -> Vec<u8> {
+    evm::encode_calldata(instances, proof)
+}
+
+/// Deploys a verifier in a local EVM and calls it.
+///
+/// # Arguments
+/// * deployment_code - the deployment code returned by [`compile_evm_verifier`].
+/// * calldata - the calldata returned by [`encode_calldata`].
+///
+/// # Return values
+/// Returns `true` if the call succeeds, i.e. the proof is accepted.
+pub fn evm_verify(deployment_code: Vec<u8>, calldata: Vec<u8>) -> bool {
+    let mut evm = ExecutorBuilder::default()
+        .with_gas_limit(u64::MAX.into())
+        .build();
+    let caller = Address::from_low_u64_be(0xfe);
+    let Some(verifier) = evm.deploy(caller, deployment_code.into(), 0.into()).address else {
+        return false;
+    };
+    !evm.call_raw(caller, verifier, calldata.into(), 0.into())
+        .reverted
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::test_private_key;
+    use crate::{compute_public_inputs, TestRSASignatureWithHashCircuit1};
+    use halo2_base::halo2_proofs::plonk::{keygen_pk, keygen_vk};
+    use rand::{thread_rng, Rng};
+    use rsa::RsaPublicKey;
+
+    #[test]
+    fn test_evm_verify_signature_proof() {
+        let mut rng = thread_rng();
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let msg = (0..128).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let instances = compute_public_inputs(&public_key, &msg);
+        let circuit = TestRSASignatureWithHashCircuit1::<Fr>::new(private_key, public_key, msg);
+
+        let params = ParamsKZG::<Bn256>::setup(15, OsRng);
+        let vk = keygen_vk(&params, &circuit).unwrap();
+        let pk = keygen_pk(&params, vk.clone(), &circuit).unwrap();
+        let proof = gen_evm_proof(&params, &pk, circuit, &instances).unwrap();
+
+        let yul = gen_evm_verifier(&params, &vk, vec![2048 / 64, 32]).unwrap();
+        let deployment_code = compile_evm_verifier(&yul);
+        assert!(evm_verify(
+            deployment_code.clone(),
+            encode_calldata(&instances, &proof)
+        ));
+
+        // The proof is bound to the hash.
+        let mut tampered = instances;
+        tampered[1][0] += Fr::one();
+        assert!(!evm_verify(
+            deployment_code,
+            encode_calldata(&tampered, &proof)
+        ));
+    }
+}
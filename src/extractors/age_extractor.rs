@@ -1,246 +1,560 @@
-/*use halo2_base::halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{AssignedCell, Chip, Layouter, Region, SimpleFloorPlanner, Value},
-    plonk::{Circuit, ConstraintSystem, Error, Expression, Selector},
-    poly::Rotation,
+//! Extraction of the age of the holder from the date of birth `DD-MM-YYYY` in the QR payload.
+//!
+//! Every age is derived from the day numbers, i.e. the days since `MIN_BIRTH_YEAR`-01-01, of the date of birth and of a reference date in IST.
+//! Ages are floored: an age in years counts the completed anniversaries of the date of birth,
+//! and an age in days counts the days between both dates, the time of day being ignored.
+//! A holder born on 29 February completes a year on 1 March in common years.
+
+use super::extractor::extract_fixed_len_field;
+use super::timestamp_extractor::extract_timestamp_parts;
+use crate::qr_spec::QrField;
+use crate::timestamp::{
+    assign_is_leap_year, count_leap_years, to_unix_timestamp, unix_timestamp_to_date_native,
+    TimeZone, TimestampParts, DAYS_TILL_PREVIOUS_MONTH,
+};
+use crate::utils::ascii_digits_to_int;
+use crate::RsaCircuitError;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
 };
 
-pub struct AgeExtractorCircuit<F: FieldExt> {
-    pub n_delimited_data: Vec<Value<F>>,
-    pub start_delimiter_index: Value<F>,
-    pub current_year: Value<F>,
-    pub current_month: Value<F>,
-    pub current_day: Value<F>,
+/// The byte length of the date of birth.
+pub const DATE_OF_BIRTH_LEN: usize = 10;
+/// The number of bits of an age in years.
+pub const AGE_BITS: usize = 8;
+/// The number of bits of an age in days.
+pub const AGE_DAYS_BITS: usize = 17;
+/// The first supported year of birth, from which day numbers are counted.
+pub const MIN_BIRTH_YEAR: u64 = 1900;
+/// The number of years supported by day numbers from [`MIN_BIRTH_YEAR`].
+const NUM_YEARS: u64 = 256;
+/// The number of days of every month in a common year.
+const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// A date of birth extracted by [`extract_date_of_birth`].
+#[derive(Debug, Clone)]
+pub struct DateOfBirth<'v, F: PrimeField> {
+    pub year: AssignedValue<'v, F>,
+    pub month: AssignedValue<'v, F>,
+    pub day: AssignedValue<'v, F>,
 }
 
-struct AgeExtractorConfig {
-    q_enable: Selector,
-    n_delimited_data: Column<Advice>,
-    shifted_bytes: Column<Advice>,
-    start_delimiter_index: Column<Advice>,
-    current_year: Column<Advice>,
-    current_month: Column<Advice>,
-    current_day: Column<Advice>,
-    age: Column<Advice>,
+/// Extracts the date of birth.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the [`DateOfBirth`].
+/// The field is constrained to [`DATE_OF_BIRTH_LEN`] bytes, the separators to `-` and every other byte to a decimal digit.
+/// The date is constrained to exist, see [`assert_valid_date`].
+pub fn extract_date_of_birth<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<DateOfBirth<'v, F>, RsaCircuitError> {
+    let bytes = extract_fixed_len_field(
+        range,
+        ctx,
+        qr_bytes,
+        QrField::DateOfBirth,
+        DATE_OF_BIRTH_LEN,
+    )?;
+    for idx in [2, 5] {
+        range
+            .gate()
+            .assert_is_const(ctx, &bytes[idx], F::from(b'-' as u64));
+    }
+    let date_of_birth = DateOfBirth {
+        year: ascii_digits_to_int(range, ctx, &bytes[6..10]),
+        month: ascii_digits_to_int(range, ctx, &bytes[3..5]),
+        day: ascii_digits_to_int(range, ctx, &bytes[0..2]),
+    };
+    assert_valid_date(range, ctx, &date_of_birth);
+    Ok(date_of_birth)
 }
 
+/// Constrains a date to exist, i.e. its year to `MIN_BIRTH_YEAR..MIN_BIRTH_YEAR + 256`, its month to `1..=12`
+/// and its day to the length of the month, 29 February being valid in leap years only.
+fn assert_valid_date<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    date: &DateOfBirth<'v, F>,
+) {
+    let gate = range.gate();
+    let years = gate.sub(
+        ctx,
+        QuantumCell::Existing(&date.year),
+        QuantumCell::Constant(F::from(MIN_BIRTH_YEAR)),
+    );
+    range.check_less_than_safe(ctx, &years, NUM_YEARS);
+    let month_idx = gate.sub(
+        ctx,
+        QuantumCell::Existing(&date.month),
+        QuantumCell::Constant(F::one()),
+    );
+    range.check_less_than_safe(ctx, &month_idx, 12);
+    let day_idx = gate.sub(
+        ctx,
+        QuantumCell::Existing(&date.day),
+        QuantumCell::Constant(F::one()),
+    );
+    range.check_less_than_safe(ctx, &day_idx, 31);
+
+    let days_in_common_year = gate.select_from_idx(
+        ctx,
+        DAYS_IN_MONTH
+            .iter()
+            .map(|days| QuantumCell::Constant(F::from(*days)))
+            .collect::<Vec<_>>(),
+        QuantumCell::Existing(&month_idx),
+    );
+    let is_february = gate.is_equal(
+        ctx,
+        QuantumCell::Existing(&date.month),
+        QuantumCell::Constant(F::from(2u64)),
+    );
+    let is_leap_year = assign_is_leap_year(range, ctx, &date.year);
+    let leap_day = gate.and(
+        ctx,
+        QuantumCell::Existing(&is_leap_year),
+        QuantumCell::Existing(&is_february),
+    );
+    let days_in_month = gate.add(
+        ctx,
+        QuantumCell::Existing(&days_in_common_year),
+        QuantumCell::Existing(&leap_day),
+    );
+    // Both are less than 32 after the range checks above.
+    range.check_less_than(
+        ctx,
+        QuantumCell::Existing(&day_idx),
+        QuantumCell::Existing(&days_in_month),
+        5,
+    );
+}
 
-impl<F: FieldExt> Circuit<F> for AgeExtractorCircuit<F> {
-    type Config = AgeExtractorConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+/// Extracts the age in completed years of the holder on the signing date.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the assigned age, i.e. the number of anniversaries of the date of birth up to and including the signing date.
+/// The age is constrained to [`AGE_BITS`] bits, so a date of birth after the signing date fails the constraint.
+/// The year of birth must be in `MIN_BIRTH_YEAR..MIN_BIRTH_YEAR + 256`.
+pub fn extract_age_years<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<AssignedValue<'v, F>, RsaCircuitError> {
+    let signing_date = extract_timestamp_parts(range, ctx, qr_bytes)?;
+    let date_of_birth = extract_date_of_birth(range, ctx, qr_bytes)?;
+    Ok(years_on(
+        range,
+        ctx,
+        &date_of_birth,
+        &signing_date.year,
+        &signing_date.month,
+        &signing_date.day,
+    ))
+}
 
-    fn without_witnesses(&self) -> Self {
-        Self {
-            n_delimited_data: vec![Value::unknown(); self.n_delimited_data.len()],
-            start_delimiter_index: Value::unknown(),
-            current_year: Value::unknown(),
-            current_month: Value::unknown(),
-            current_day: Value::unknown(),
-        }
-    }
+/// Extracts the age in days of the holder on the signing date, e.g. to prove that a child is under a given number of days old.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the assigned number of days from the date of birth to the signing date, zero if the holder is born on the signing date.
+/// The age is constrained to [`AGE_DAYS_BITS`] bits, so a date of birth after the signing date fails the constraint.
+/// The year of birth must be in `MIN_BIRTH_YEAR..MIN_BIRTH_YEAR + 256`.
+pub fn extract_age_days<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<AssignedValue<'v, F>, RsaCircuitError> {
+    let signing_date = extract_timestamp_parts(range, ctx, qr_bytes)?;
+    let date_of_birth = extract_date_of_birth(range, ctx, qr_bytes)?;
+    let birth = day_number(
+        range,
+        ctx,
+        &date_of_birth.year,
+        &date_of_birth.month,
+        &date_of_birth.day,
+    );
+    let reference = day_number(
+        range,
+        ctx,
+        &signing_date.year,
+        &signing_date.month,
+        &signing_date.day,
+    );
+    let age = range.gate().sub(
+        ctx,
+        QuantumCell::Existing(&reference),
+        QuantumCell::Existing(&birth),
+    );
+    range.range_check(ctx, &age, AGE_DAYS_BITS);
+    Ok(age)
+}
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let q_enable = meta.selector();
-        let n_delimited_data = meta.advice_column();
-        let shifted_bytes = meta.advice_column();
-        let start_delimiter_index = meta.advice_column();
-        let current_year = meta.advice_column();
-        let current_month = meta.advice_column();
-        let current_day = meta.advice_column();
-        let age = meta.advice_column();
-
-        meta.enable_equality(n_delimited_data);
-        meta.enable_equality(shifted_bytes);
-        meta.enable_equality(start_delimiter_index);
-        meta.enable_equality(current_year);
-        meta.enable_equality(current_month);
-        meta.enable_equality(current_day);
-        meta.enable_equality(age);
-
-        meta.create_gate("shift data", |meta| {
-            let q_enable = meta.query_selector(q_enable);
-            let n_delimited_data = meta.query_advice(n_delimited_data, Rotation::cur());
-            let shifted_bytes = meta.query_advice(shifted_bytes, Rotation::next());
-            let start_delimiter_index = meta.query_advice(start_delimiter_index, Rotation::cur());
-
-            // Implement the shift logic here
-            vec![
-                q_enable * (shifted_bytes - n_delimited_data.shifted_by(start_delimiter_index)),
-            ]
-        });
-
-        // Additional constraints for date validation and age calculation
-        meta.create_gate("date validation and age calculation", |meta| {
-            let q_enable = meta.query_selector(q_enable);
-            let shifted_bytes = meta.query_advice(shifted_bytes, Rotation::cur());
-            let current_year = meta.query_advice(current_year, Rotation::cur());
-            let current_month = meta.query_advice(current_month, Rotation::cur());
-            let current_day = meta.query_advice(current_day, Rotation::cur());
-            let age = meta.query_advice(age, Rotation::cur());
-
-            let year = Expression::from(shifted_bytes[7]) * 1000
-                + Expression::from(shifted_bytes[8]) * 100
-                + Expression::from(shifted_bytes[9]) * 10
-                + Expression::from(shifted_bytes[10]);
-            let month = Expression::from(shifted_bytes[4]) * 10
-                + Expression::from(shifted_bytes[5]);
-            let day = Expression::from(shifted_bytes[1]) * 10
-                + Expression::from(shifted_bytes[2]);
-
-            let age_by_year = current_year - year - 1;
-
-            let month_gt = current_month - month;
-            let month_eq = current_month - month;
-            let day_gt = current_day - day;
-
-            let is_higher_day_on_same_month = month_eq * day_gt;
-
-            let final_age = age_by_year + month_gt + is_higher_day_on_same_month;
+/// Extracts the age in completed years of the holder at a given time.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * current_timestamp - the assigned UNIX timestamp at which the age is computed.
+///
+/// # Return values
+/// Returns the assigned age on the date of `current_timestamp` in IST, floored to whole years as in [`extract_age_years`].
+/// The date is witnessed and constrained so that its midnight in IST is at most `current_timestamp` and less than a day before it.
+/// As in [`to_unix_timestamp`], the date must lie in `MIN_TIMESTAMP_YEAR..=MAX_TIMESTAMP_YEAR`.
+pub fn extract_age_at<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    current_timestamp: &AssignedValue<'v, F>,
+) -> Result<AssignedValue<'v, F>, RsaCircuitError> {
+    let gate = range.gate();
+    let date_of_birth = extract_date_of_birth(range, ctx, qr_bytes)?;
+    let local_timestamp = gate.add(
+        ctx,
+        QuantumCell::Existing(current_timestamp),
+        QuantumCell::Constant(F::from(TimeZone::Ist.offset_seconds())),
+    );
+    let date = local_timestamp
+        .value()
+        .map(|v| unix_timestamp_to_date_native(v.get_lower_32() as u64));
+    let midnight = TimestampParts {
+        year: gate.load_witness(ctx, date.map(|(year, _, _)| F::from(year))),
+        month: gate.load_witness(ctx, date.map(|(_, month, _)| F::from(month))),
+        day: gate.load_witness(ctx, date.map(|(_, _, day)| F::from(day))),
+        hour: gate.load_zero(ctx),
+        minute: gate.load_zero(ctx),
+        second: gate.load_zero(ctx),
+    };
+    let midnight_timestamp = to_unix_timestamp(range, ctx, &midnight);
+    let seconds_since_midnight = gate.sub(
+        ctx,
+        QuantumCell::Existing(&local_timestamp),
+        QuantumCell::Existing(&midnight_timestamp),
+    );
+    range.check_less_than_safe(ctx, &seconds_since_midnight, 86400);
+    Ok(years_on(
+        range,
+        ctx,
+        &date_of_birth,
+        &midnight.year,
+        &midnight.month,
+        &midnight.day,
+    ))
+}
+
+/// Returns whether `age` is at least `threshold`.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * age - an assigned age of at most [`AGE_BITS`] bits, e.g. the output of [`extract_age_years`].
+/// * threshold - the assigned minimum age. It is constrained to [`AGE_BITS`] bits.
+///
+/// # Return values
+/// Returns the assigned bit `age >= threshold`.
+pub fn is_at_least<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    age: &AssignedValue<'v, F>,
+    threshold: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    range.range_check(ctx, threshold, AGE_BITS);
+    let is_under_age = range.is_less_than(
+        ctx,
+        QuantumCell::Existing(age),
+        QuantumCell::Existing(threshold),
+        AGE_BITS,
+    );
+    range.gate().not(ctx, QuantumCell::Existing(&is_under_age))
+}
 
+/// Computes the age in completed years on the given date, constrained to [`AGE_BITS`] bits.
+///
+/// The age is witnessed and constrained so that the day number of the last anniversary is at most that of the date,
+/// and the day number of the next anniversary is greater.
+fn years_on<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    date_of_birth: &DateOfBirth<'v, F>,
+    year: &AssignedValue<'v, F>,
+    month: &AssignedValue<'v, F>,
+    day: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let date =
+        |year: &AssignedValue<'v, F>, month: &AssignedValue<'v, F>, day: &AssignedValue<'v, F>| {
+            year.value()
+                .zip(month.value())
+                .zip(day.value())
+                .map(|((year, month), day)| {
+                    (
+                        year.get_lower_32() as i64,
+                        month.get_lower_32(),
+                        day.get_lower_32(),
+                    )
+                })
+        };
+    let age = date(year, month, day)
+        .zip(date(
+            &date_of_birth.year,
+            &date_of_birth.month,
+            &date_of_birth.day,
+        ))
+        .map(
+            |((year, month, day), (birth_year, birth_month, birth_day))| {
+                let age = year - birth_year - ((month, day) < (birth_month, birth_day)) as i64;
+                F::from(age.max(0) as u64)
+            },
+        );
+    let age = gate.load_witness(ctx, age);
+    range.range_check(ctx, &age, AGE_BITS);
+
+    let reference = day_number(range, ctx, year, month, day);
+    let [anniversary, next_anniversary] = [0u64, 1].map(|offset| {
+        let year = gate.sum(
+            ctx,
             vec![
-                q_enable * (age - final_age),
-            ]
-        });
-
-        AgeExtractorConfig {
-            q_enable,
-            n_delimited_data,
-            shifted_bytes,
-            start_delimiter_index,
-            current_year,
-            current_month,
-            current_day,
-            age,
+                QuantumCell::Existing(&date_of_birth.year),
+                QuantumCell::Existing(&age),
+                QuantumCell::Constant(F::from(offset)),
+            ],
+        );
+        day_number(range, ctx, &year, &date_of_birth.month, &date_of_birth.day)
+    });
+    let days_since_anniversary = gate.sub(
+        ctx,
+        QuantumCell::Existing(&reference),
+        QuantumCell::Existing(&anniversary),
+    );
+    range.range_check(ctx, &days_since_anniversary, AGE_DAYS_BITS);
+    range.check_less_than(
+        ctx,
+        QuantumCell::Existing(&reference),
+        QuantumCell::Existing(&next_anniversary),
+        AGE_DAYS_BITS,
+    );
+    age
+}
+
+/// Converts a date into its day number, i.e. the number of days since `MIN_BIRTH_YEAR`-01-01.
+///
+/// The year is constrained to `MIN_BIRTH_YEAR..MIN_BIRTH_YEAR + 256`, the month to `1..=12` and the day to `1..=31`,
+/// so the day number has at most [`AGE_DAYS_BITS`] bits.
+/// A day beyond the end of its month rolls over into the next month, e.g. 29 February of a common year is 1 March,
+/// which dates the anniversaries of a holder born on 29 February. A date of birth itself is validated by [`assert_valid_date`].
+fn day_number<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    year: &AssignedValue<'v, F>,
+    month: &AssignedValue<'v, F>,
+    day: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let years = gate.sub(
+        ctx,
+        QuantumCell::Existing(year),
+        QuantumCell::Constant(F::from(MIN_BIRTH_YEAR)),
+    );
+    range.check_less_than_safe(ctx, &years, NUM_YEARS);
+    let month_idx = gate.sub(
+        ctx,
+        QuantumCell::Existing(month),
+        QuantumCell::Constant(F::one()),
+    );
+    range.check_less_than_safe(ctx, &month_idx, 12);
+    let day_idx = gate.sub(
+        ctx,
+        QuantumCell::Existing(day),
+        QuantumCell::Constant(F::one()),
+    );
+    range.check_less_than_safe(ctx, &day_idx, 31);
+
+    let leap_years_before = count_leap_years(range, ctx, year, MIN_BIRTH_YEAR);
+    let is_leap_year = assign_is_leap_year(range, ctx, year);
+    let days_till_previous_month = gate.select_from_idx(
+        ctx,
+        DAYS_TILL_PREVIOUS_MONTH
+            .iter()
+            .map(|days| QuantumCell::Constant(F::from(*days)))
+            .collect::<Vec<_>>(),
+        QuantumCell::Existing(&month_idx),
+    );
+    let is_after_february = range.is_less_than(
+        ctx,
+        QuantumCell::Constant(F::from(2u64)),
+        QuantumCell::Existing(month),
+        4,
+    );
+    let leap_day = gate.and(
+        ctx,
+        QuantumCell::Existing(&is_leap_year),
+        QuantumCell::Existing(&is_after_february),
+    );
+    let days = gate.sum(
+        ctx,
+        vec![
+            QuantumCell::Existing(&leap_years_before),
+            QuantumCell::Existing(&days_till_previous_month),
+            QuantumCell::Existing(&leap_day),
+            QuantumCell::Existing(&day_idx),
+        ],
+    );
+    gate.mul_add(
+        ctx,
+        QuantumCell::Existing(&years),
+        QuantumCell::Constant(F::from(365u64)),
+        QuantumCell::Existing(&days),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::qr_spec::NUM_DELIMITERS;
+    use crate::test_utils::{load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use crate::timestamp::unix_timestamp_native;
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+
+    /// Extracts the ages in years and in days of a holder born on `date_of_birth` from a payload signed on `signing_date` in the `YYYYMMDD` form.
+    fn ages_are_extracted(
+        date_of_birth: &'static [u8],
+        signing_date: &[u8],
+        expected: Option<(u64, u64)>,
+    ) {
+        let reference_id = [&b"2697"[..], signing_date, b"124553876"].concat();
+        let mut fields: [&[u8]; NUM_DELIMITERS] = SAMPLE_QR_FIELDS;
+        fields[QrField::ReferenceId.position()] = &reference_id;
+        fields[QrField::DateOfBirth.position()] = date_of_birth;
+        let data = sample_qr_payload(&fields, 32);
+        let (years, days) = expected.unwrap_or((0, 0));
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                Ok(vec![
+                    extract_age_years(range, ctx, &qr_bytes)?,
+                    extract_age_days(range, ctx, &qr_bytes)?,
+                ])
+            },
+            vec![Fr::from(years), Fr::from(days)],
+        );
+        match expected {
+            Some(_) => prover.assert_satisfied(),
+            None => assert!(prover.verify().is_err()),
         }
     }
 
-    fn synthesize(
-        &self,
-        config: AgeExtractorConfig,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "age extraction",
-            |mut region: Region<'_, F>| {
-                let offset = 0;
-
-                config.q_enable.enable(&mut region, offset)?;
-
-                for (i, &data) in self.n_delimited_data.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("n_delimited_data_{}", i),
-                        config.n_delimited_data,
-                        offset + i,
-                        || data.ok_or(Error::SynthesisError),
-                    )?;
-                }
-
-                let start_delimiter_index = region.assign_advice(
-                    || "start_delimiter_index",
-                    config.start_delimiter_index,
-                    offset,
-                    || self.start_delimiter_index.ok_or(Error::SynthesisError),
-                )?;
-
-                let current_year = region.assign_advice(
-                    || "current_year",
-                    config.current_year,
-                    offset,
-                    || self.current_year.ok_or(Error::SynthesisError),
-                )?;
-
-                let current_month = region.assign_advice(
-                    || "current_month",
-                    config.current_month,
-                    offset,
-                    || self.current_month.ok_or(Error::SynthesisError),
-                )?;
-
-                let current_day = region.assign_advice(
-                    || "current_day",
-                    config.current_day,
-                    offset,
-                    || self.current_day.ok_or(Error::SynthesisError),
-                )?;
-
-                let shifted_bytes: Vec<AssignedCell<F, F>> = self
-                    .n_delimited_data
-                    .iter()
-                    .enumerate()
-                    .map(|(i, &data)| {
-                        region.assign_advice(
-                            || format!("shifted_bytes_{}", i),
-                            config.shifted_bytes,
-                            offset + i,
-                            || {
-                                let shift = start_delimiter_index.value().map(|&s| s as usize);
-                                data.ok_or(Error::SynthesisError).map(|d| d.shifted_by(shift))
-                            },
-                        )
-                    })
-                    .collect::<Result<Vec<_>, Error>>()?;
-
-                // Implement date conversion and age calculation logic
-                let year = region.assign_advice(
-                    || "year",
-                    config.shifted_bytes,
-                    offset + 7,
-                    || {
-                        shifted_bytes[7]
-                            .value()
-                            .and_then(|&b7| shifted_bytes[8].value().map(|&b8| (b7 * 1000) + (b8 * 100)))
-                            .and_then(|v| shifted_bytes[9].value().map(|&b9| v + (b9 * 10)))
-                            .and_then(|v| shifted_bytes[10].value().map(|&b10| v + b10))
-                            .ok_or(Error::SynthesisError)
-                    },
-                )?;
-
-                let month = region.assign_advice(
-                    || "month",
-                    config.shifted_bytes,
-                    offset + 4,
-                    || {
-                        shifted_bytes[4]
-                            .value()
-                            .and_then(|&b4| shifted_bytes[5].value().map(|&b5| (b4 * 10) + b5))
-                            .ok_or(Error::SynthesisError)
-                    },
-                )?;
-
-                let day = region.assign_advice(
-                    || "day",
-                    config.shifted_bytes,
-                    offset + 1,
-                    || {
-                        shifted_bytes[1]
-                            .value()
-                            .and_then(|&b1| shifted_bytes[2].value().map(|&b2| (b1 * 10) + b2))
-                            .ok_or(Error::SynthesisError)
-                    },
-                )?;
-
-                let age_by_year = current_year.value().map(|&cy| cy - year.value().unwrap() - 1);
-                let month_gt = current_month.value().map(|&cm| cm - month.value().unwrap());
-                let month_eq = current_month.value().map(|&cm| cm - month.value().unwrap());
-                let day_gt = current_day.value().map(|&cd| cd - day.value().unwrap());
-
-                let is_higher_day_on_same_month = month_eq.zip(day_gt).map(|(me, dg)| me * dg);
-
-                let final_age = age_by_year
-                    .zip(month_gt)
-                    .zip(is_higher_day_on_same_month)
-                    .map(|((ay, mg), ihd)| ay + mg + ihd);
-
-                region.assign_advice(
-                    || "age",
-                    config.age,
-                    offset,
-                    || final_age.ok_or(Error::SynthesisError),
-                )?;
-
-                Ok(())
+    /// Computes the age of a holder born on 2006-07-18 at `timestamp` and whether it is at least 18.
+    fn age_at_is_extracted(timestamp: u64, expected_age: u64) {
+        let mut fields = SAMPLE_QR_FIELDS;
+        fields[QrField::DateOfBirth.position()] = b"18-07-2006";
+        let data = sample_qr_payload(&fields, 32);
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let current_timestamp = range
+                    .gate
+                    .load_witness(ctx, Value::known(Fr::from(timestamp)));
+                let age = extract_age_at(range, ctx, &qr_bytes, &current_timestamp)?;
+                let threshold = range.gate.load_constant(ctx, Fr::from(18));
+                let is_adult = is_at_least(range, ctx, &age, &threshold);
+                Ok(vec![age, is_adult])
             },
-        )
+            vec![
+                Fr::from(expected_age),
+                Fr::from((expected_age >= 18) as u64),
+            ],
+        );
+        prover.assert_satisfied();
     }
-}*/
 
+    #[test]
+    fn test_extract_age_at_18th_birthday() {
+        let offset = TimeZone::Ist.offset_seconds();
+        let birthday = unix_timestamp_native(2024, 7, 18, 0, 0, 0) - offset;
+        age_at_is_extracted(birthday - 1, 17);
+        age_at_is_extracted(birthday, 18);
+        age_at_is_extracted(birthday + 86399, 18);
+        // 2024-07-18 in UTC is still 2024-07-17 in IST a few hours before.
+        age_at_is_extracted(unix_timestamp_native(2024, 7, 17, 18, 0, 0), 17);
+        age_at_is_extracted(unix_timestamp_native(2025, 1, 1, 0, 0, 0), 18);
+        age_at_is_extracted(unix_timestamp_native(2025, 7, 18, 0, 0, 0), 19);
+    }
+
+    #[test]
+    fn test_extract_age_years_and_days() {
+        ages_are_extracted(b"01-01-1984", b"20240718", Some((40, 14809)));
+        ages_are_extracted(b"18-07-1984", b"20240718", Some((40, 14610)));
+        ages_are_extracted(b"19-07-1984", b"20240718", Some((39, 14609)));
+        ages_are_extracted(b"18-07-2024", b"20240718", Some((0, 0)));
+        // Born before the UNIX epoch.
+        ages_are_extracted(b"15-08-1947", b"20240718", Some((76, 28097)));
+        // Born after the signing date.
+        ages_are_extracted(b"19-07-2024", b"20240718", None);
+        // Malformed separators.
+        ages_are_extracted(b"18/07/1984", b"20240718", None);
+        // Not a date.
+        ages_are_extracted(b"00-13-1984", b"20240718", None);
+    }
+
+    #[test]
+    fn test_extract_age_born_on_leap_day() {
+        // The anniversary falls on 1 March in common years.
+        ages_are_extracted(b"29-02-2004", b"20230228", Some((18, 6939)));
+        ages_are_extracted(b"29-02-2004", b"20230301", Some((19, 6940)));
+        ages_are_extracted(b"29-02-2004", b"20240228", Some((19, 7304)));
+        ages_are_extracted(b"29-02-2004", b"20240229", Some((20, 7305)));
+    }
+
+    #[test]
+    fn test_extract_date_of_birth_validates_day() {
+        for (date_of_birth, is_valid) in [
+            (&b"31-01-1984"[..], true),
+            (b"31-12-1984", true),
+            (b"30-04-1984", true),
+            (b"31-04-1984", false),
+            (b"28-02-2003", true),
+            (b"29-02-2003", false),
+            (b"29-02-2004", true),
+            (b"30-02-2004", false),
+            // 2000 is a leap year, 1900 is not.
+            (b"29-02-2000", true),
+            (b"29-02-1900", false),
+            (b"00-01-1984", false),
+            (b"32-01-1984", false),
+        ] {
+            let mut fields = SAMPLE_QR_FIELDS;
+            fields[QrField::DateOfBirth.position()] = date_of_birth;
+            let data = sample_qr_payload(&fields, 32);
+            let day = std::str::from_utf8(&date_of_birth[..2])
+                .unwrap()
+                .parse::<u64>()
+                .unwrap();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                    Ok(vec![extract_date_of_birth(range, ctx, &qr_bytes)?.day])
+                },
+                vec![Fr::from(day)],
+            );
+            assert_eq!(prover.verify().is_ok(), is_valid, "{date_of_birth:?}");
+        }
+    }
+}
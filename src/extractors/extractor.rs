@@ -1,190 +1,283 @@
+//! Generic gadgets locating a field or a byte in the QR payload, shared by the attribute extractors.
+
+use crate::address::extract_delimited_region;
+use crate::qr_spec::{FieldLayout, QrField};
+use crate::RsaCircuitError;
 use halo2_base::{
-    arithmetic::FieldExt,
-    circuit::{Layouter, SimpleFloorPlanner, Value},
-    dev::MockProver,
-    plonk::{Circuit, ConstraintSystem, Error, Selector, Column, Advice, Instance},
-    poly::Rotation,
+    gates::{range::RangeConfig, GateInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
 };
-use std::marker::PhantomData;
 
-#[derive(Clone, Debug)]
-struct ExtractAndPackAsIntConfig {
-    advice: [Column<Advice>; 4],
-    instance: Column<Instance>,
-    selector: Selector,
+/// Extracts a variable-length field of the format v2 payload.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * field - the delimited field to be extracted.
+/// * max_len - the maximum byte length of the field.
+///
+/// # Return values
+/// Returns the field bytes zero-padded to `max_len` and the assigned length of the field.
+/// Returns [`RsaCircuitError::Parse`] if `field` is not delimited.
+pub fn extract_field<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    field: QrField,
+    max_len: usize,
+) -> Result<(Vec<AssignedValue<'v, F>>, AssignedValue<'v, F>), RsaCircuitError> {
+    if field.spec().layout != FieldLayout::Delimited {
+        return Err(RsaCircuitError::Parse(field));
+    }
+    let position = field.position();
+    Ok(extract_delimited_region(
+        range, ctx, qr_bytes, position, position, max_len,
+    )?)
 }
 
-struct ExtractAndPackAsIntCircuit<F: FieldExt> {
-    n_delimited_data: Vec<Value<F>>,
-    delimiter_indices: Vec<Value<F>>,
-    extract_position: usize,
-    extract_max_length: usize,
-    _marker: PhantomData<F>,
+/// Extracts a field of the format v2 payload whose length is fixed.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * field - the delimited field to be extracted.
+/// * len - the byte length of the field.
+///
+/// # Return values
+/// Returns the `len` field bytes. The length of the field is constrained to `len`.
+/// Returns [`RsaCircuitError::Parse`] if `field` is not delimited.
+pub fn extract_fixed_len_field<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    field: QrField,
+    len: usize,
+) -> Result<Vec<AssignedValue<'v, F>>, RsaCircuitError> {
+    let (bytes, field_len) = extract_field(range, ctx, qr_bytes, field, len)?;
+    range
+        .gate()
+        .assert_is_const(ctx, &field_len, F::from(len as u64));
+    Ok(bytes)
 }
 
-impl<F: FieldExt> Circuit<F> for ExtractAndPackAsIntCircuit<F> {
-    type Config = ExtractAndPackAsIntConfig;
-
-    fn configure(cs: &mut ConstraintSystem<F>) -> Self::Config {
-        let advice = [
-            cs.advice_column(),
-            cs.advice_column(),
-            cs.advice_column(),
-            cs.advice_column(),
-        ];
-        let instance = cs.instance_column();
-        let selector = cs.selector();
-
-        for column in &advice {
-            cs.enable_equality(*column);
-        }
-        cs.enable_equality(instance);
-
-        cs.create_gate("extract and pack as int", |meta| {
-            let s = meta.query_selector(selector);
-            let n_delimited_data_cur = meta.query_advice(advice[0], Rotation::cur());
-            let delimiter_indices_cur = meta.query_advice(advice[1], Rotation::cur());
-            let out = meta.query_advice(advice[2], Rotation::cur());
-            let packed_data = meta.query_advice(advice[3], Rotation::cur());
-
-            let start_delimiter_index = meta.query_advice(advice[0], Rotation::prev());
-            let end_delimiter_index = meta.query_advice(advice[1], Rotation::prev());
-
-            let mut constraints = Vec::new();
-
-            // Assert that the first byte is the delimiter (255 * position of the field)
-            constraints.push(s.clone() * (n_delimited_data_cur - F::from((self.extract_position * 255) as u64)));
-
-            // Assert that last byte is the delimiter (255 * (position of the field + 1))
-            constraints.push(s.clone() * (out - F::from(((self.extract_position + 1) * 255) as u64)));
-
-            // Pack the bytes into an integer
-            let mut packed_value = F::zero();
-            let mut power = F::one();
-
-            for i in 0..self.extract_max_length {
-                let byte_value = meta.query_advice(advice[3], Rotation(i as i32));
-                packed_value += byte_value * power;
-                power *= F::from(256); // Each byte shift
-            }
-
-            constraints.push(s * (packed_value - packed_data));
-
-            constraints
-        });
-
-        ExtractAndPackAsIntConfig {
-            advice,
-            instance,
-            selector,
-        }
+/// Finds the first occurrence of `needle` in `haystack`.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * haystack - assigned values to be searched.
+/// * needle - the value to be found.
+///
+/// # Return values
+/// Returns the assigned index of the first occurrence and a bit indicating whether `needle` was found.
+/// The index is `haystack.len()` if `needle` was not found.
+/// Both are computed from the whole haystack rather than witnessed, so the prover cannot choose another occurrence.
+pub fn find_byte<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    haystack: &[AssignedValue<'v, F>],
+    needle: F,
+) -> (AssignedValue<'v, F>, AssignedValue<'v, F>) {
+    // `not_before` is one iff `needle` does not occur before the current index.
+    let mut not_before = gate.load_constant(ctx, F::one());
+    let mut is_firsts = vec![];
+    for value in haystack.iter() {
+        let is_needle = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(value),
+            QuantumCell::Constant(needle),
+        );
+        let is_first = gate.mul(
+            ctx,
+            QuantumCell::Existing(&is_needle),
+            QuantumCell::Existing(&not_before),
+        );
+        not_before = gate.sub(
+            ctx,
+            QuantumCell::Existing(&not_before),
+            QuantumCell::Existing(&is_first),
+        );
+        is_firsts.push(is_first);
     }
+    let first_idx = gate.inner_product(
+        ctx,
+        is_firsts
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+        (0..haystack.len())
+            .map(|i| QuantumCell::Constant(F::from(i as u64)))
+            .collect::<Vec<_>>(),
+    );
+    let idx = gate.mul_add(
+        ctx,
+        QuantumCell::Existing(&not_before),
+        QuantumCell::Constant(F::from(haystack.len() as u64)),
+        QuantumCell::Existing(&first_idx),
+    );
+    let found = gate.not(ctx, QuantumCell::Existing(&not_before));
+    (idx, found)
+}
 
-    fn synthesize(
-        &self,
-        config: ExtractAndPackAsIntConfig,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "extract and pack as int region",
-            |mut region| {
-                config.selector.enable(&mut region, 0)?;
-
-                let start_index = self.delimiter_indices[self.extract_position - 1];
-                let end_index = self.delimiter_indices[self.extract_position];
-
-                for (i, value) in self.n_delimited_data.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("n delimited data {}", i),
-                        config.advice[0],
-                        i,
-                        || *value,
-                    )?;
-                }
-
-                for (i, value) in self.delimiter_indices.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("delimiter indices {}", i),
-                        config.advice[1],
-                        i,
-                        || *value,
-                    )?;
-                }
-
-                let n_delimited_data_cur = region.assign_advice(
-                    || "n delimited data cur",
-                    config.advice[0],
-                    0,
-                    || self.n_delimited_data[start_index.get()? as usize],
-                )?;
-
-                let delimiter_indices_cur = region.assign_advice(
-                    || "delimiter indices cur",
-                    config.advice[1],
-                    0,
-                    || self.delimiter_indices[start_index.get()? as usize],
-                )?;
-
-                let out = region.assign_advice(
-                    || "out",
-                    config.advice[2],
-                    0,
-                    || Value::known(F::from(((self.extract_position + 1) * 255) as u64)),
-                )?;
-
-                let mut packed_value = F::zero();
-                let mut power = F::one();
-                
-                for i in 0..self.extract_max_length {
-                    let byte_value = self.n_delimited_data[start_index.get()? as usize + i + 1];
-                    region.assign_advice(
-                        || format!("shifted bytes {}", i),
-                        config.advice[3],
-                        i,
-                        || byte_value,
-                    )?;
-                    packed_value += byte_value.get()? * power;
-                    power *= F::from(256); // Each byte shift
-                }
-
-                region.assign_advice(
-                    || "packed data",
-                    config.advice[3],
-                    0,
-                    || Value::known(packed_value),
-                )?;
-
-                region.constrain_equal(out.cell(), config.instance)?;
-
-                Ok(())
-            },
-        )
+/// Returns whether a variable-length region equals a known byte string.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * region - assigned region bytes, zero-padded after `region_len` as returned by [`extract_field`].
+/// * region_len - the assigned length of the region.
+/// * template - the expected bytes, e.g. a state name supplied by the verifier.
+///
+/// # Return values
+/// Returns the assigned bit `region[..region_len] == template`.
+/// Regions of another length are never equal, even if one is a prefix of the other.
+pub fn region_equals<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    region: &[AssignedValue<'v, F>],
+    region_len: &AssignedValue<'v, F>,
+    template: &[u8],
+) -> AssignedValue<'v, F> {
+    if template.len() > region.len() {
+        return gate.load_zero(ctx);
+    }
+    let mut is_equal = gate.is_equal(
+        ctx,
+        QuantumCell::Existing(region_len),
+        QuantumCell::Constant(F::from(template.len() as u64)),
+    );
+    // The bytes after the template are not compared, as the lengths are equal.
+    for (byte, expected) in region.iter().zip(template.iter()) {
+        let is_byte_equal = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(byte),
+            QuantumCell::Constant(F::from(*expected as u64)),
+        );
+        is_equal = gate.mul(
+            ctx,
+            QuantumCell::Existing(&is_equal),
+            QuantumCell::Existing(&is_byte_equal),
+        );
     }
+    is_equal
 }
 
 #[cfg(test)]
-mod tests {
+mod test {
     use super::*;
-    use halo2_base::halo2_proofs::{dev::MockProver, circuit::Value};
-    use std::marker::PhantomData;
+    use crate::test_utils::{load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+
+    const NAME_MAX_LEN: usize = 16;
+
+    #[test]
+    fn test_extract_field() {
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        let mut public_inputs = SAMPLE_QR_FIELDS[3]
+            .iter()
+            .map(|byte| Fr::from(*byte as u64))
+            .collect::<Vec<_>>();
+        public_inputs.resize(NAME_MAX_LEN, Fr::zero());
+        public_inputs.push(Fr::from(SAMPLE_QR_FIELDS[3].len() as u64));
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let (mut bytes, len) =
+                    extract_field(range, ctx, &qr_bytes, QrField::Name, NAME_MAX_LEN)?;
+                let gender = extract_fixed_len_field(range, ctx, &qr_bytes, QrField::Gender, 1)?;
+                range
+                    .gate()
+                    .assert_is_const(ctx, &gender[0], Fr::from(b'M' as u64));
+                assert!(matches!(
+                    extract_field(range, ctx, &qr_bytes, QrField::Photo, 1),
+                    Err(RsaCircuitError::Parse(QrField::Photo))
+                ));
+                bytes.push(len);
+                Ok(bytes)
+            },
+            public_inputs,
+        );
+        prover.assert_satisfied();
+    }
 
     #[test]
-    fn test_extractor() {
-        let k = 4;
-        let n_delimited_data = vec![Some(5), Some(10), Some(15), Some(255), Some(1), Some(2)];
-        let delimiter_indices = vec![Some(1), Some(2), Some(3)];
+    fn test_extract_fixed_len_field_wrong_len() {
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                // The gender field is a single byte.
+                extract_fixed_len_field(range, ctx, &qr_bytes, QrField::Gender, 2)?;
+                Ok(vec![])
+            },
+            vec![],
+        );
+        assert!(prover.verify().is_err());
+    }
 
-        let circuit = ExtractAndPackAsIntCircuit {
-            n_delimited_data: n_delimited_data.iter().map(|&v| Value::known(F::from(v.unwrap()))).collect(),
-            delimiter_indices: delimiter_indices.iter().map(|&v| Value::known(F::from(v.unwrap()))).collect(),
-            extract_position: 1, // Example value
-            extract_max_length: 31, // Example value
-            _marker: PhantomData,
-        };
+    fn byte_is_found(haystack: &'static [u8], needle: u8, expected: Option<usize>) {
+        let prover = mock_prove(
+            move |ctx, range| {
+                let haystack = load_bytes(ctx, range, haystack, haystack.len());
+                let (idx, found) = find_byte(range.gate(), ctx, &haystack, Fr::from(needle as u64));
+                Ok(vec![idx, found])
+            },
+            vec![
+                Fr::from(expected.unwrap_or(haystack.len()) as u64),
+                Fr::from(expected.is_some() as u64),
+            ],
+        );
+        prover.assert_satisfied();
+    }
 
-        let public_inputs = vec![Some(5).into()]; // Example
+    #[test]
+    fn test_find_byte() {
+        byte_is_found(b"abcabc", b'a', Some(0));
+        byte_is_found(b"abcabc", b'c', Some(2));
+        byte_is_found(b"abcabd", b'd', Some(5));
+        byte_is_found(b"abcabc", b'x', None);
+        byte_is_found(&[1, 255, 2, 255], 255, Some(1));
+    }
 
-        let prover = MockProver::run(k, &circuit, vec![public_inputs]).unwrap();
+    fn region_is_equal(region: &'static [u8], region_len: usize, template: &'static [u8]) -> bool {
+        const REGION_MAX_LEN: usize = 16;
+        let expected = region_len <= region.len() && &region[..region_len] == template;
+        let prover = mock_prove(
+            move |ctx, range| {
+                let bytes = load_bytes(ctx, range, region, REGION_MAX_LEN);
+                let len = range
+                    .gate
+                    .load_witness(ctx, Value::known(Fr::from(region_len as u64)));
+                Ok(vec![region_equals(
+                    range.gate(),
+                    ctx,
+                    &bytes,
+                    &len,
+                    template,
+                )])
+            },
+            vec![Fr::from(expected as u64)],
+        );
         prover.assert_satisfied();
+        expected
+    }
+
+    #[test]
+    fn test_region_equals() {
+        assert!(region_is_equal(b"Karnataka", 9, b"Karnataka"));
+        assert!(region_is_equal(b"", 0, b""));
+        // A prefix of the template.
+        assert!(!region_is_equal(b"Karnat", 6, b"Karnataka"));
+        // The template is a prefix of the region.
+        assert!(!region_is_equal(b"Karnataka", 9, b"Karnat"));
+        // Off by one length, in both directions.
+        assert!(!region_is_equal(b"Karnataka", 10, b"Karnataka"));
+        assert!(!region_is_equal(b"Karnataka", 8, b"Karnataka"));
+        assert!(!region_is_equal(b"Kerala", 6, b"Karnataka"));
+        assert!(!region_is_equal(b"Karnataka", 9, b"Karnataka, India"));
+        assert!(!region_is_equal(b"Karnataka", 9, b"Karnataka and Kerala"));
     }
 }
@@ -1,97 +1,180 @@
-/*use halo2_base::halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+//! Extraction of the gender of the holder from the QR payload.
+//!
+//! The gender field is a single byte `M`, `F` or `T`, encoded as the small value of [`Gender::code`].
+
+use crate::address::delimiter_index;
+use crate::qr_spec::QrField;
+use crate::RsaCircuitError;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
 };
 
-pub struct GenderExtractorConfig {
-    q_enable: Selector,
-    n_delimited_data_shifted_to_dob: Column<Advice>,
-    out: Column<Advice>,
-    gender_position: Expression<F>,
+/// A gender of the holder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gender {
+    Male,
+    Female,
+    Transgender,
 }
 
-pub struct GenderExtractorCircuit<F: FieldExt> {
-    pub n_delimited_data_shifted_to_dob: Vec<Value<F>>,
-}
-
-impl<F: FieldExt> Circuit<F> for GenderExtractorCircuit<F> {
-    type Config = GenderExtractorConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+impl Gender {
+    /// All genders in the order of their codes.
+    pub const ALL: [Gender; 3] = [Gender::Male, Gender::Female, Gender::Transgender];
 
-    fn without_witnesses(&self) -> Self {
-        Self {
-            n_delimited_data_shifted_to_dob: vec![Value::unknown(); self.n_delimited_data_shifted_to_dob.len()],
+    /// Returns the byte of the gender in the QR payload.
+    pub fn byte(self) -> u8 {
+        match self {
+            Self::Male => b'M',
+            Self::Female => b'F',
+            Self::Transgender => b'T',
         }
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let q_enable = meta.selector();
-        let n_delimited_data_shifted_to_dob = meta.advice_column();
-        let out = meta.advice_column();
-
-        meta.enable_equality(n_delimited_data_shifted_to_dob);
-        meta.enable_equality(out);
-
-        meta.create_gate("gender extraction", |meta| {
-            let q_enable = meta.query_selector(q_enable);
-            let n_delimited_data_shifted_to_dob = meta.query_advice(n_delimited_data_shifted_to_dob, Rotation::cur());
-
-            // Gender byte position validation
-            let gender_position_validation_1 = n_delimited_data_shifted_to_dob[11] - (gender_position() * 255);
-            let gender_position_validation_2 = n_delimited_data_shifted_to_dob[13] - ((gender_position() + 1) * 255);
-
-            vec![
-                q_enable.clone() * gender_position_validation_1,
-                q_enable * gender_position_validation_2,
-            ]
-        });
-
-        GenderExtractorConfig {
-            q_enable,
-            n_delimited_data_shifted_to_dob,
-            out,
-            gender_position: Expression::Constant(F::from(12)),
+    /// Returns the encoded value of the gender, one of `1`, `2` or `3`.
+    pub fn code(self) -> u64 {
+        match self {
+            Self::Male => 1,
+            Self::Female => 2,
+            Self::Transgender => 3,
         }
     }
 
-    fn synthesize(
-        &self,
-        config: GenderExtractorConfig,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "gender extraction",
-            |mut region| {
-                let offset = 0;
-
-                config.q_enable.enable(&mut region, offset)?;
+    /// Returns the gender of a byte in the QR payload, or `None` if the byte is not a gender.
+    pub fn from_byte(byte: u8) -> Option<Self> {
+        Self::ALL.into_iter().find(|gender| gender.byte() == byte)
+    }
+}
 
-                for (i, &data) in self.n_delimited_data_shifted_to_dob.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("n_delimited_data_shifted_to_dob_{}", i),
-                        config.n_delimited_data_shifted_to_dob,
-                        offset + i,
-                        || data.ok_or(Error::SynthesisError),
-                    )?;
-                }
+/// A gender extracted by [`extract_gender`].
+#[derive(Debug, Clone)]
+pub struct ExtractedGender<'v, F: PrimeField> {
+    /// The [`Gender::code`] of the gender, or zero if the field is malformed.
+    pub value: AssignedValue<'v, F>,
+    /// Whether the field is a single byte of a [`Gender`].
+    pub is_valid: AssignedValue<'v, F>,
+}
 
-                // Gender byte
-                let gender = region.assign_advice(
-                    || "gender",
-                    config.out,
-                    offset,
-                    || self.n_delimited_data_shifted_to_dob[12].ok_or(Error::SynthesisError),
-                )?;
+/// Extracts the gender.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the [`ExtractedGender`]. A field that is empty, longer than one byte or holds another byte is not an error;
+/// it yields a zero `is_valid` bit and a zero `value`, so `value` is always one of `0..=3`.
+pub fn extract_gender<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<ExtractedGender<'v, F>, RsaCircuitError> {
+    let gate = range.gate();
+    let position = QrField::Gender.position();
+    let prev = delimiter_index(range, ctx, qr_bytes, position - 1);
+    let end = delimiter_index(range, ctx, qr_bytes, position);
+    let start = gate.add(
+        ctx,
+        QuantumCell::Existing(&prev),
+        QuantumCell::Constant(F::one()),
+    );
+    let len = gate.sub(
+        ctx,
+        QuantumCell::Existing(&end),
+        QuantumCell::Existing(&start),
+    );
+    let is_single_byte = gate.is_equal(
+        ctx,
+        QuantumCell::Existing(&len),
+        QuantumCell::Constant(F::one()),
+    );
+    // For an empty field, `start` is the terminating delimiter, which is not a gender.
+    let byte = gate.select_from_idx(
+        ctx,
+        qr_bytes
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+        QuantumCell::Existing(&start),
+    );
+    let is_genders = Gender::ALL
+        .iter()
+        .map(|gender| {
+            gate.is_equal(
+                ctx,
+                QuantumCell::Existing(&byte),
+                QuantumCell::Constant(F::from(gender.byte() as u64)),
+            )
+        })
+        .collect::<Vec<_>>();
+    // At most one of `is_genders` is set.
+    let is_gender = gate.sum(
+        ctx,
+        is_genders
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+    );
+    let code = gate.inner_product(
+        ctx,
+        is_genders
+            .iter()
+            .map(QuantumCell::Existing)
+            .collect::<Vec<_>>(),
+        Gender::ALL
+            .iter()
+            .map(|gender| QuantumCell::Constant(F::from(gender.code())))
+            .collect::<Vec<_>>(),
+    );
+    let is_valid = gate.mul(
+        ctx,
+        QuantumCell::Existing(&is_single_byte),
+        QuantumCell::Existing(&is_gender),
+    );
+    let value = gate.mul(
+        ctx,
+        QuantumCell::Existing(&is_single_byte),
+        QuantumCell::Existing(&code),
+    );
+    Ok(ExtractedGender { value, is_valid })
+}
 
-                Ok(())
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    fn gender_is_extracted(field: &'static [u8], expected: Option<Gender>) {
+        let mut fields = SAMPLE_QR_FIELDS;
+        fields[5] = field;
+        let data = sample_qr_payload(&fields, 32);
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let gender = extract_gender(range, ctx, &qr_bytes)?;
+                Ok(vec![gender.value, gender.is_valid])
             },
-        )
+            vec![
+                Fr::from(expected.map_or(0, Gender::code)),
+                Fr::from(expected.is_some() as u64),
+            ],
+        );
+        prover.assert_satisfied();
     }
-}
 
-impl<F: FieldExt> GenderExtractorCircuit<F> {
-    pub fn new(n_delimited_data_shifted_to_dob: Vec<Value<F>>) -> Self {
-        Self { n_delimited_data_shifted_to_dob }
+    #[test]
+    fn test_extract_gender() {
+        for gender in Gender::ALL {
+            assert_eq!(Gender::from_byte(gender.byte()), Some(gender));
+        }
+        gender_is_extracted(b"M", Some(Gender::Male));
+        gender_is_extracted(b"F", Some(Gender::Female));
+        gender_is_extracted(b"T", Some(Gender::Transgender));
+        gender_is_extracted(b"X", None);
+        gender_is_extracted(b"", None);
+        gender_is_extracted(b"MF", None);
     }
-}*/
+}
@@ -0,0 +1,141 @@
+//! Gadgets extracting the attributes of the holder from a format v2 QR payload.
+//!
+//! Every extractor takes the assigned bytes of the signed payload, i.e. the payload without its signature, zero-padded to a fixed length.
+//! The bytes are assumed to be already constrained to 8 bits, e.g. as SHA256 inputs.
+//! Fields are located through their delimiters, see [`crate::qr_spec`], so a prover cannot shift a field to another position.
+
+pub mod age_extractor;
+pub mod extractor;
+pub mod gender_extractor;
+pub mod photo_extractor;
+pub mod pincode_extractor;
+pub mod qrdata_extractor;
+pub mod timestamp_extractor;
+
+#[cfg(test)]
+mod test {
+    use super::age_extractor::{extract_age_days, extract_age_years, extract_date_of_birth};
+    use super::gender_extractor::{extract_gender, Gender};
+    use super::photo_extractor::{extract_photo, photo_region_native};
+    use super::pincode_extractor::{extract_pincode, parse_pincode};
+    use super::qrdata_extractor::{
+        decode_qr_bignum, extract_last4, extract_name, extract_state, inflate, split_fields,
+    };
+    use super::timestamp_extractor::{extract_timestamp, extract_timestamp_parts};
+    use crate::address::region_commitment_native;
+    use crate::poseidon::{StandardPoseidonChip, R_F, R_P};
+    use crate::qr_spec::{field_range, QrField, NUM_DELIMITERS, SIGNATURE_LEN};
+    use crate::test_utils::{load_bytes, mock_prove, SAMPLE_QR};
+    use crate::{RSAPubE, RSAPublicKey};
+    use halo2_base::gates::GateInstructions;
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+    use num_bigint::BigUint;
+    use rsa::{pkcs8::DecodePublicKey, Pkcs1v15Sign, RsaPublicKey};
+    use sha2::{Digest, Sha256};
+
+    const PUBLIC_KEY_DER: &[u8] = include_bytes!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/test_public_key.der"
+    ));
+    const PADDED_LEN: usize = 320;
+    const NAME_MAX_LEN: usize = 32;
+    const STATE_MAX_LEN: usize = 32;
+
+    #[test]
+    fn test_extractors_on_sample_qr() {
+        // Decoding: decimal string, gzip, then the signed data followed by the signature.
+        let payload = inflate(&decode_qr_bignum(SAMPLE_QR).unwrap()).unwrap();
+        assert_eq!(payload.len(), 515);
+        let (signed, signature) = payload.split_at(payload.len() - SIGNATURE_LEN);
+        let public_key = RsaPublicKey::from_public_key_der(PUBLIC_KEY_DER).unwrap();
+        public_key
+            .verify(
+                Pkcs1v15Sign::new::<Sha256>(),
+                &Sha256::digest(signed),
+                signature,
+            )
+            .unwrap();
+        let assigned_key = RSAPublicKey::<Fr>::from_der(PUBLIC_KEY_DER).unwrap();
+        assert!(matches!(assigned_key.e, RSAPubE::Fix(ref e) if e == &BigUint::from(65537u64)));
+
+        // Native parsing.
+        assert_eq!(&signed[..2], b"V2");
+        assert_eq!(
+            &signed[field_range(signed, QrField::Name).unwrap()],
+            b"Test Resident"
+        );
+        assert_eq!(&signed[field_range(signed, QrField::Gender).unwrap()], b"F");
+        assert_eq!(
+            &signed[field_range(signed, QrField::MobileLast4).unwrap()],
+            b"7007"
+        );
+        assert_eq!(parse_pincode(signed).unwrap(), 560001);
+
+        let signed = signed.to_vec();
+        let photo = photo_region_native(&signed, PADDED_LEN).unwrap();
+        // Signed on 2024-01-01 09:30:00 IST by a female holder born on 1990-08-15, at pincode 560001.
+        let timestamp = [1704081600, 2024, 1, 1, 9, 30, 0];
+        let date_of_birth = [1990, 8, 15];
+        let ages = [33, 12192];
+        let gender = [Gender::Female.code(), 1];
+        let pincode_last4_name_len = [560001, 4821, 13];
+        let mut expected = [
+            &timestamp[..],
+            &date_of_birth,
+            &ages,
+            &gender,
+            &pincode_last4_name_len,
+        ]
+        .concat()
+        .into_iter()
+        .map(Fr::from)
+        .collect::<Vec<_>>();
+        expected.push(region_commitment_native::<Fr>(b"Karnataka", STATE_MAX_LEN).unwrap());
+        expected.extend(photo.iter().map(|byte| Fr::from(*byte as u64)));
+        let prover = mock_prove(
+            move |ctx, range| {
+                let poseidon = StandardPoseidonChip::<Fr>::new(R_F, R_P);
+                let qr_bytes = load_bytes(ctx, range, &signed, PADDED_LEN);
+                let data_len = range
+                    .gate
+                    .load_witness(ctx, Value::known(Fr::from(signed.len() as u64)));
+                let parts = extract_timestamp_parts(range, ctx, &qr_bytes)?;
+                let dob = extract_date_of_birth(range, ctx, &qr_bytes)?;
+                let gender = extract_gender(range, ctx, &qr_bytes)?;
+                let fields = split_fields(range, ctx, &qr_bytes, NUM_DELIMITERS);
+                let (name, name_len) = extract_name(range, ctx, &qr_bytes, &fields, NAME_MAX_LEN)?;
+                for (byte, expected) in name.iter().zip(b"Test Resident") {
+                    range
+                        .gate
+                        .assert_is_const(ctx, byte, Fr::from(*expected as u64));
+                }
+                let state =
+                    extract_state(range, &poseidon, ctx, &qr_bytes, &fields, STATE_MAX_LEN)?;
+                let mut outputs = vec![
+                    extract_timestamp(range, ctx, &qr_bytes)?,
+                    parts.year,
+                    parts.month,
+                    parts.day,
+                    parts.hour,
+                    parts.minute,
+                    parts.second,
+                    dob.year,
+                    dob.month,
+                    dob.day,
+                    extract_age_years(range, ctx, &qr_bytes)?,
+                    extract_age_days(range, ctx, &qr_bytes)?,
+                    gender.value,
+                    gender.is_valid,
+                    extract_pincode(range, ctx, &qr_bytes)?.limbs()[0].clone(),
+                    extract_last4(range, ctx, &qr_bytes, &fields)?.limbs()[0].clone(),
+                    name_len,
+                    state.commitment,
+                ];
+                outputs.extend(extract_photo(range, ctx, &qr_bytes, &data_len));
+                Ok(outputs)
+            },
+            expected,
+        );
+        prover.assert_satisfied();
+    }
+}
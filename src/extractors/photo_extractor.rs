@@ -1,168 +1,327 @@
-/*use halo2_base::halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
-    poly::Rotation,
+//! Extraction of the photo of the holder from the QR payload.
+//!
+//! The photo runs from the last delimiter to the end of the signed payload. It includes the optional email and mobile hashes,
+//! which cannot be told apart from the photo without the email-mobile indicator.
+//!
+//! The photo has no terminating delimiter, so its length is dynamic: it starts right after the last delimiter,
+//! located by [`delimiter_index`], and ends at `data_len`, the length of the signed payload.
+//! `data_len` must be bound by the caller, e.g. as the input length of the SHA256 hash of the payload;
+//! otherwise a prover could cut the photo short or extend it into the padding.
+//!
+//! The photo commitment of [`extract_photo_commitment`] covers the photo bytes alone: they are shifted to index zero
+//! and cut before the email and mobile hashes, so it is the same for every payload carrying the same photo.
+
+use super::extractor::extract_fixed_len_field;
+use crate::address::delimiter_index;
+use crate::poseidon::{poseidon_hash, StandardPoseidonChip};
+use crate::qr_spec::{
+    delimiter_indices, trailing_hashes_len, QrField, CONTACT_HASH_LEN, DELIMITER, NUM_DELIMITERS,
+};
+use crate::utils::{pack_bytes, pack_bytes_native};
+use crate::RsaCircuitError;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
 };
 
-pub struct PhotoExtractorConfig {
-    q_enable: Selector,
-    n_delimited_data: Column<Advice>,
-    start_delimiter_index: Column<Advice>,
-    end_index: Column<Advice>,
-    out: Vec<Column<Advice>>,
-    photo_position: Expression<F>,
+/// Extracts the photo region.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * data_len - the assigned length of the signed payload, e.g. the input length of its SHA256 hash.
+///
+/// # Return values
+/// Returns `qr_bytes` with every byte outside the photo region zeroed.
+pub fn extract_photo<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    data_len: &AssignedValue<'v, F>,
+) -> Vec<AssignedValue<'v, F>> {
+    let gate = range.gate();
+    let last_delimiter = delimiter_index(range, ctx, qr_bytes, NUM_DELIMITERS - 1);
+    let idx_bits = (usize::BITS - qr_bytes.len().leading_zeros()) as usize;
+    qr_bytes
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| {
+            let is_after_delimiter = range.is_less_than(
+                ctx,
+                QuantumCell::Existing(&last_delimiter),
+                QuantumCell::Constant(F::from(i as u64)),
+                idx_bits,
+            );
+            let is_signed = range.is_less_than(
+                ctx,
+                QuantumCell::Constant(F::from(i as u64)),
+                QuantumCell::Existing(data_len),
+                idx_bits,
+            );
+            let is_photo = gate.and(
+                ctx,
+                QuantumCell::Existing(&is_after_delimiter),
+                QuantumCell::Existing(&is_signed),
+            );
+            gate.mul(
+                ctx,
+                QuantumCell::Existing(byte),
+                QuantumCell::Existing(&is_photo),
+            )
+        })
+        .collect()
 }
 
-pub struct PhotoExtractorCircuit<F: FieldExt> {
-    pub n_delimited_data: Vec<Value<F>>,
-    pub start_delimiter_index: Value<F>,
-    pub end_index: Value<F>,
-}
+/// Extracts the photo bytes and commits to them.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * data_len - the assigned length of the signed payload.
+///
+/// # Return values
+/// Returns the assigned commitment `Poseidon(pack_bytes(photo)...)`, where `photo` holds the photo bytes from index zero, zero-padded to `qr_bytes.len()`.
+/// The photo ends before the email and mobile hashes flagged by [`QrField::EmailMobilePresent`],
+/// so the commitment equals [`photo_commitment_native`] for every payload carrying the same photo.
+/// A JPEG2000 codestream ends with the `0xFFD9` marker, so the zero padding cannot be mistaken for photo bytes.
+/// Returns the [`RsaCircuitError`] of [`extract_fixed_len_field`] if the indicator cannot be extracted.
+pub fn extract_photo_commitment<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    poseidon: &StandardPoseidonChip<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    data_len: &AssignedValue<'v, F>,
+) -> Result<AssignedValue<'v, F>, RsaCircuitError> {
+    let gate = range.gate();
+    let last_delimiter = delimiter_index(range, ctx, qr_bytes, NUM_DELIMITERS - 1);
+    let start = gate.add(
+        ctx,
+        QuantumCell::Existing(&last_delimiter),
+        QuantumCell::Constant(F::one()),
+    );
+    // The indicator is a digit from `0` to `3`, whose bits 0 and 1 flag the email and mobile hashes.
+    let indicator = extract_fixed_len_field(range, ctx, qr_bytes, QrField::EmailMobilePresent, 1)?;
+    let indicator = gate.sub(
+        ctx,
+        QuantumCell::Existing(&indicator[0]),
+        QuantumCell::Constant(F::from(b'0' as u64)),
+    );
+    let indicator_bits = gate.num_to_bits(ctx, &indicator, 2);
+    let num_hashes = gate.add(
+        ctx,
+        QuantumCell::Existing(&indicator_bits[0]),
+        QuantumCell::Existing(&indicator_bits[1]),
+    );
+    let hashes_len = gate.mul(
+        ctx,
+        QuantumCell::Existing(&num_hashes),
+        QuantumCell::Constant(F::from(CONTACT_HASH_LEN as u64)),
+    );
+    let end = gate.sub(
+        ctx,
+        QuantumCell::Existing(data_len),
+        QuantumCell::Existing(&hashes_len),
+    );
+    let len = gate.sub(
+        ctx,
+        QuantumCell::Existing(&end),
+        QuantumCell::Existing(&start),
+    );
+    range.check_less_than_safe(ctx, &len, qr_bytes.len() as u64 + 1);
 
-impl<F: FieldExt> Circuit<F> for PhotoExtractorCircuit<F> {
-    type Config = PhotoExtractorConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+    let len_bits = (usize::BITS - qr_bytes.len().leading_zeros()) as usize;
+    let photo = shift_left(range, ctx, qr_bytes, &start)
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| {
+            let is_photo = range.is_less_than(
+                ctx,
+                QuantumCell::Constant(F::from(i as u64)),
+                QuantumCell::Existing(&len),
+                len_bits,
+            );
+            gate.mul(
+                ctx,
+                QuantumCell::Existing(byte),
+                QuantumCell::Existing(&is_photo),
+            )
+        })
+        .collect::<Vec<_>>();
+    let inputs = pack_bytes(gate, ctx, &photo);
+    Ok(poseidon.hash(gate, ctx, &inputs))
+}
 
-    fn without_witnesses(&self) -> Self {
-        Self {
-            n_delimited_data: vec![Value::unknown(); self.n_delimited_data.len()],
-            start_delimiter_index: Value::unknown(),
-            end_index: Value::unknown(),
-        }
+/// Returns `bytes[shift..]` zero-padded to `bytes.len()`.
+///
+/// The shift is decomposed into bits, and each bit `k` conditionally shifts by `2^k`, which takes `bytes.len()` selections per bit
+/// instead of a selection among every byte for each output byte. `shift` is constrained to be less than the next power of two of `bytes.len()`.
+fn shift_left<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    bytes: &[AssignedValue<'v, F>],
+    shift: &AssignedValue<'v, F>,
+) -> Vec<AssignedValue<'v, F>> {
+    let gate = range.gate();
+    let shift_bits = (usize::BITS - bytes.len().leading_zeros()) as usize;
+    let shift_bits = gate.num_to_bits(ctx, shift, shift_bits);
+    let zero = gate.load_zero(ctx);
+    let mut shifted = bytes.to_vec();
+    for (k, bit) in shift_bits.iter().enumerate() {
+        shifted = (0..shifted.len())
+            .map(|i| {
+                let moved = shifted.get(i + (1 << k)).unwrap_or(&zero);
+                gate.select(
+                    ctx,
+                    QuantumCell::Existing(moved),
+                    QuantumCell::Existing(&shifted[i]),
+                    QuantumCell::Existing(bit),
+                )
+            })
+            .collect();
     }
+    shifted
+}
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let q_enable = meta.selector();
-        let n_delimited_data = meta.advice_column();
-        let start_delimiter_index = meta.advice_column();
-        let end_index = meta.advice_column();
-        
-        // Assuming photoPackSize() returns a constant number
-        const PHOTO_PACK_SIZE: usize = 33;
-        let out: Vec<Column<Advice>> = (0..PHOTO_PACK_SIZE)
-            .map(|_| meta.advice_column())
-            .collect();
+/// Computes the photo region of [`extract_photo`] off-circuit.
+///
+/// # Arguments
+/// * signed_data - the signed payload.
+/// * padded_len - the number of assigned payload bytes.
+///
+/// # Return values
+/// Returns the payload with every byte outside the photo region zeroed, padded to `padded_len`,
+/// or `None` if the payload is longer than `padded_len` or has less than [`NUM_DELIMITERS`] delimiters.
+pub fn photo_region_native(signed_data: &[u8], padded_len: usize) -> Option<Vec<u8>> {
+    if signed_data.len() > padded_len {
+        return None;
+    }
+    let last_delimiter = signed_data
+        .iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == DELIMITER)
+        .nth(NUM_DELIMITERS - 1)?
+        .0;
+    let mut region = vec![0; padded_len];
+    region[last_delimiter + 1..signed_data.len()]
+        .copy_from_slice(&signed_data[last_delimiter + 1..]);
+    Some(region)
+}
 
-        for &column in &out {
-            meta.enable_equality(column);
-        }
+/// Returns the photo bytes of a signed payload, i.e. the bytes following the last delimiter up to the email and mobile hashes,
+/// or `None` if the payload is not a well-formed format v2 payload.
+pub fn photo_bytes_native(signed_data: &[u8]) -> Option<&[u8]> {
+    let start = delimiter_indices(signed_data).get(NUM_DELIMITERS - 1)? + 1;
+    let end = signed_data
+        .len()
+        .checked_sub(trailing_hashes_len(signed_data)?)?;
+    (start <= end).then(|| &signed_data[start..end])
+}
 
-        meta.create_gate("photo extraction", |meta| {
-            let q_enable = meta.query_selector(q_enable);
-            let n_delimited_data = meta.query_advice(n_delimited_data, Rotation::cur());
-            let start_delimiter_index = meta.query_advice(start_delimiter_index, Rotation::cur());
-            let end_index = meta.query_advice(end_index, Rotation::cur());
+/// Computes the commitment of [`extract_photo_commitment`] off-circuit.
+///
+/// # Arguments
+/// * signed_data - the signed payload.
+/// * padded_len - the number of assigned payload bytes.
+///
+/// # Return values
+/// Returns the commitment, or `None` if the payload is longer than `padded_len`, under the conditions of [`photo_bytes_native`] or of [`poseidon_hash`].
+pub fn photo_commitment_native<F: PrimeField>(signed_data: &[u8], padded_len: usize) -> Option<F> {
+    if signed_data.len() > padded_len {
+        return None;
+    }
+    let mut photo = photo_bytes_native(signed_data)?.to_vec();
+    photo.resize(padded_len, 0);
+    poseidon_hash(&pack_bytes_native::<F>(&photo)).ok()
+}
 
-            // Photo byte position validation
-            let photo_position = meta.query_advice(n_delimited_data, Rotation::cur()); // This needs to be implemented based on the data structure
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::poseidon::{R_F, R_P};
+    use crate::test_utils::{load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
 
-            let start_delimiter_validation = n_delimited_data[start_delimiter_index as usize] - (photo_position * 255);
+    const PADDED_LEN: usize = 320;
 
-            vec![
-                q_enable * start_delimiter_validation,
-            ]
-        });
+    fn photo_commitment_is_extracted(data: Vec<u8>, expected: Fr) {
+        let prover = mock_prove(
+            move |ctx, range| {
+                let poseidon = StandardPoseidonChip::new(R_F, R_P);
+                let qr_bytes = load_bytes(ctx, range, &data, PADDED_LEN);
+                let data_len = range
+                    .gate
+                    .load_witness(ctx, Value::known(Fr::from(data.len() as u64)));
+                Ok(vec![extract_photo_commitment(
+                    range, &poseidon, ctx, &qr_bytes, &data_len,
+                )?])
+            },
+            vec![expected],
+        );
+        prover.assert_satisfied();
+    }
 
-        PhotoExtractorConfig {
-            q_enable,
-            n_delimited_data,
-            start_delimiter_index,
-            end_index,
-            out,
-            photo_position: Expression::Constant(F::from(12)), // This should be set to the actual photo position
+    #[test]
+    fn test_extract_photo_commitment() {
+        for photo_len in [1, 32, 100] {
+            let data = sample_qr_payload(&SAMPLE_QR_FIELDS, photo_len);
+            let expected = photo_commitment_native::<Fr>(&data, PADDED_LEN).unwrap();
+            photo_commitment_is_extracted(data, expected);
         }
+        // A photo cut short yields another commitment.
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        assert_ne!(
+            photo_commitment_native::<Fr>(&data, PADDED_LEN),
+            photo_commitment_native::<Fr>(&data[..data.len() - 1], PADDED_LEN)
+        );
     }
 
-    fn synthesize(
-        &self,
-        config: PhotoExtractorConfig,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "photo extraction",
-            |mut region| {
-                let offset = 0;
-
-                config.q_enable.enable(&mut region, offset)?;
-
-                for (i, &data) in self.n_delimited_data.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("n_delimited_data_{}", i),
-                        config.n_delimited_data,
-                        offset + i,
-                        || data.ok_or(Error::SynthesisError),
-                    )?;
-                }
-
-                let start_index = region.assign_advice(
-                    || "start_delimiter_index",
-                    config.start_delimiter_index,
-                    offset,
-                    || self.start_delimiter_index.ok_or(Error::SynthesisError),
-                )?;
-
-                let end_index = region.assign_advice(
-                    || "end_index",
-                    config.end_index,
-                    offset,
-                    || self.end_index.ok_or(Error::SynthesisError),
-                )?;
-
-                // Shift the data to the right to until the photo index
-                // The following code assumes `SelectSubArray` has been implemented
-                let sub_array = self.n_delimited_data[start_index.get()..=end_index.get()]
-                    .iter()
-                    .copied()
-                    .collect::<Vec<_>>();
-                
-                let bytes_length = sub_array.len();
-                let mut shifted_bytes = vec![Value::unknown(); bytes_length];
-                for (i, &byte) in sub_array.iter().enumerate() {
-                    shifted_bytes[i] = byte;
-                }
-
-                // Assert that the first byte is the delimiter (255 * position of name field)
-                assert_eq!(shifted_bytes[0], config.photo_position * 255);
-
-                // Pack byte[] to int[]
-                let mut out_ints = vec![Value::unknown(); PHOTO_PACK_SIZE];
-                let photo_max_length = PHOTO_PACK_SIZE * 31; // assuming maxFieldByteSize() returns 31
-                for i in 0..PHOTO_PACK_SIZE {
-                    let mut int_value = F::zero();
-                    for j in 0..31 {
-                        if i * 31 + j < shifted_bytes.len() {
-                            int_value += shifted_bytes[i * 31 + j].get() * F::from(1u64 << (8 * j as u64));
-                        }
-                    }
-                    out_ints[i] = Value::known(int_value);
-                }
-
-                for (i, &out) in config.out.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("out_{}", i),
-                        out,
-                        offset,
-                        || out_ints[i].ok_or(Error::SynthesisError),
-                    )?;
-                }
-
-                Ok(())
-            },
-        )
+    #[test]
+    fn test_photo_commitment_covers_photo_only() {
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        let expected = photo_commitment_native::<Fr>(&data, PADDED_LEN).unwrap();
+        // Other preceding fields move the photo and change the payload length.
+        let mut fields = SAMPLE_QR_FIELDS;
+        fields[QrField::Name.position()] = b"Sumit";
+        let moved = sample_qr_payload(&fields, 32);
+        // The email and mobile hashes follow the photo.
+        fields[QrField::EmailMobilePresent.position()] = b"3";
+        let mut with_hashes = sample_qr_payload(&fields, 32);
+        with_hashes.extend([0x0b; 2 * CONTACT_HASH_LEN]);
+        for data in [moved, with_hashes] {
+            assert_eq!(photo_bytes_native(&data).unwrap().len(), 32);
+            assert_eq!(
+                photo_commitment_native::<Fr>(&data, PADDED_LEN),
+                Some(expected)
+            );
+            photo_commitment_is_extracted(data, expected);
+        }
     }
-}
 
-impl<F: FieldExt> PhotoExtractorCircuit<F> {
-    pub fn new(
-        n_delimited_data: Vec<Value<F>>,
-        start_delimiter_index: Value<F>,
-        end_index: Value<F>,
-    ) -> Self {
-        Self {
-            n_delimited_data,
-            start_delimiter_index,
-            end_index,
+    #[test]
+    fn test_extract_photo() {
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        let photo_start = data.len() - 32;
+        let mut expected = vec![Fr::zero(); PADDED_LEN];
+        for (i, byte) in data.iter().enumerate().skip(photo_start) {
+            expected[i] = Fr::from(*byte as u64);
         }
+        let prover = mock_prove(
+            move |ctx, range| {
+                let mut padded = data.clone();
+                // Bytes after the signed payload, e.g. SHA256 padding, are not part of the photo.
+                padded.resize(PADDED_LEN, 0x80);
+                let qr_bytes = load_bytes(ctx, range, &padded, PADDED_LEN);
+                let data_len = range
+                    .gate
+                    .load_witness(ctx, Value::known(Fr::from(data.len() as u64)));
+                Ok(extract_photo(range, ctx, &qr_bytes, &data_len))
+            },
+            expected,
+        );
+        prover.assert_satisfied();
     }
-}*/
+}
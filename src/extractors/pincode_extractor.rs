@@ -1,141 +1,156 @@
-/*use halo2_base::halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
-    poly::Rotation,
+//! Extraction of the pincode of the holder from the QR payload.
+
+use super::extractor::extract_fixed_len_field;
+use crate::big_uint::{AssignedBigUint, Fresh};
+use crate::qr_spec::{field_range, QrField};
+use crate::utils::ascii_digits_to_int;
+use crate::RsaCircuitError;
+use halo2_base::halo2_proofs::{
+    circuit::{Cell, Layouter},
+    plonk::{Column, Error, Instance},
 };
-
-pub struct PinCodeExtractorConfig {
-    q_enable: Selector,
-    n_delimited_data: Column<Advice>,
-    start_delimiter_index: Column<Advice>,
-    end_delimiter_index: Column<Advice>,
-    out: Column<Advice>,
-    pin_code_position: Expression<F>,
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::{fe_to_biguint, PrimeField},
+    AssignedValue, Context, QuantumCell,
+};
+use halo2_ecc::bigint::OverflowInteger;
+
+/// The number of digits of a pincode.
+pub const PINCODE_LEN: usize = 6;
+/// The number of bits of a pincode, i.e. the bits of the single limb returned by [`extract_pincode`].
+pub const PINCODE_BITS: usize = 20;
+/// The smallest pincode.
+pub const MIN_PINCODE: u32 = 100000;
+/// The largest pincode.
+pub const MAX_PINCODE: u32 = 999999;
+
+/// Extracts the pincode.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the pincode as an assigned integer of a single [`PINCODE_BITS`]-bit limb.
+/// The field is constrained to [`PINCODE_LEN`] decimal digits, and the pincode to `MIN_PINCODE..=MAX_PINCODE`.
+pub fn extract_pincode<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<AssignedBigUint<'v, F, Fresh>, RsaCircuitError> {
+    let digits = extract_fixed_len_field(range, ctx, qr_bytes, QrField::Pincode, PINCODE_LEN)?;
+    let pincode = ascii_digits_to_int(range, ctx, &digits);
+    // A leading zero makes the pincode smaller than `MIN_PINCODE`.
+    let offset = range.gate().sub(
+        ctx,
+        QuantumCell::Existing(&pincode),
+        QuantumCell::Constant(F::from(MIN_PINCODE as u64)),
+    );
+    range.check_less_than_safe(ctx, &offset, (MAX_PINCODE - MIN_PINCODE) as u64 + 1);
+    let value = pincode.value().map(|v| fe_to_biguint(v));
+    let int = OverflowInteger::construct(vec![pincode], PINCODE_BITS);
+    Ok(AssignedBigUint::new(int, value))
 }
 
-pub struct PinCodeExtractorCircuit<F: FieldExt> {
-    pub n_delimited_data: Vec<Value<F>>,
-    pub start_delimiter_index: Value<F>,
-    pub end_delimiter_index: Value<F>,
+/// Parses the pincode off-circuit with the same rules as [`extract_pincode`].
+///
+/// # Arguments
+/// * data - the QR payload.
+///
+/// # Return values
+/// Returns the pincode.
+/// Returns [`RsaCircuitError::Parse`] if the field is missing or not [`PINCODE_LEN`] decimal digits,
+/// and [`RsaCircuitError::OutOfRange`] if the pincode is not in `MIN_PINCODE..=MAX_PINCODE`.
+pub fn parse_pincode(data: &[u8]) -> Result<u32, RsaCircuitError> {
+    let digits = field_range(data, QrField::Pincode)
+        .map(|range| &data[range])
+        .filter(|digits| digits.len() == PINCODE_LEN && digits.iter().all(u8::is_ascii_digit))
+        .ok_or(RsaCircuitError::Parse(QrField::Pincode))?;
+    let pincode = digits
+        .iter()
+        .fold(0, |acc, digit| acc * 10 + (digit - b'0') as u32);
+    if !(MIN_PINCODE..=MAX_PINCODE).contains(&pincode) {
+        return Err(RsaCircuitError::OutOfRange(QrField::Pincode));
+    }
+    Ok(pincode)
 }
 
-impl<F: FieldExt> Circuit<F> for PinCodeExtractorCircuit<F> {
-    type Config = PinCodeExtractorConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+/// Constrains the pincode to the instance column, e.g. for geo-gating by the verifier.
+///
+/// # Arguments
+/// * layouter - a layouter.
+/// * pincode - the cell of the limb of a pincode returned by [`extract_pincode`].
+/// * column - an instance column.
+/// * offset - the row of the instance column holding the pincode.
+///
+/// # Return values
+/// Returns the next free row of the instance column.
+pub fn expose_pincode_instance<F: PrimeField>(
+    layouter: &mut impl Layouter<F>,
+    pincode: Cell,
+    column: Column<Instance>,
+    offset: usize,
+) -> Result<usize, Error> {
+    layouter.constrain_instance(pincode, column, offset)?;
+    Ok(offset + 1)
+}
 
-    fn without_witnesses(&self) -> Self {
-        Self {
-            n_delimited_data: vec![Value::unknown(); self.n_delimited_data.len()],
-            start_delimiter_index: Value::unknown(),
-            end_delimiter_index: Value::unknown(),
-        }
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    fn pincode_prover(pincode: &'static [u8], expected: u64) -> bool {
+        let mut fields = SAMPLE_QR_FIELDS;
+        fields[11] = pincode;
+        let data = sample_qr_payload(&fields, 32);
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let pincode = extract_pincode(range, ctx, &qr_bytes)?;
+                assert_eq!(pincode.num_limbs(), 1);
+                Ok(vec![pincode.limbs()[0].clone()])
+            },
+            vec![Fr::from(expected)],
+        );
+        prover.verify().is_ok()
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let q_enable = meta.selector();
-        let n_delimited_data = meta.advice_column();
-        let start_delimiter_index = meta.advice_column();
-        let end_delimiter_index = meta.advice_column();
-        let out = meta.advice_column();
-
-        meta.enable_equality(n_delimited_data);
-        meta.enable_equality(start_delimiter_index);
-        meta.enable_equality(end_delimiter_index);
-        meta.enable_equality(out);
-
-        meta.create_gate("pin code extraction", |meta| {
-            let q_enable = meta.query_selector(q_enable);
-            let n_delimited_data = meta.query_advice(n_delimited_data, Rotation::cur());
-            let start_delimiter_index = meta.query_advice(start_delimiter_index, Rotation::cur());
-            let end_delimiter_index = meta.query_advice(end_delimiter_index, Rotation::cur());
-
-            // Pin code byte position validation
-            let pin_code_position = meta.query_advice(n_delimited_data, Rotation::cur()); // This needs to be implemented based on the data structure
-
-            let start_delimiter_validation = n_delimited_data[start_delimiter_index as usize] - (pin_code_position * 255);
-            let end_delimiter_validation = n_delimited_data[end_delimiter_index as usize] - ((pin_code_position + 1) * 255);
-
-            vec![
-                q_enable.clone() * start_delimiter_validation,
-                q_enable * end_delimiter_validation,
-            ]
-        });
-
-        PinCodeExtractorConfig {
-            q_enable,
-            n_delimited_data,
-            start_delimiter_index,
-            end_delimiter_index,
-            out,
-            pin_code_position: Expression::Constant(F::from(12)), // This should be set to the actual pin code position
-        }
+    #[test]
+    fn test_extract_pincode() {
+        assert!(pincode_prover(b"110051", 110051));
+        assert!(pincode_prover(b"100000", 100000));
+        assert!(pincode_prover(b"999999", 999999));
     }
 
-    fn synthesize(
-        &self,
-        config: PinCodeExtractorConfig,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "pin code extraction",
-            |mut region| {
-                let offset = 0;
-
-                config.q_enable.enable(&mut region, offset)?;
-
-                for (i, &data) in self.n_delimited_data.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("n_delimited_data_{}", i),
-                        config.n_delimited_data,
-                        offset + i,
-                        || data.ok_or(Error::SynthesisError),
-                    )?;
-                }
-
-                let start_index = region.assign_advice(
-                    || "start_delimiter_index",
-                    config.start_delimiter_index,
-                    offset,
-                    || self.start_delimiter_index.ok_or(Error::SynthesisError),
-                )?;
-
-                let end_index = region.assign_advice(
-                    || "end_delimiter_index",
-                    config.end_delimiter_index,
-                    offset,
-                    || self.end_delimiter_index.ok_or(Error::SynthesisError),
-                )?;
-
-                let pin_code = region.assign_advice(
-                    || "pin_code",
-                    config.out,
-                    offset,
-                    || {
-                        let mut pin_code = 0;
-                        for i in 1..=6 {
-                            pin_code = pin_code * 10 + self.n_delimited_data[start_index + i].unwrap();
-                        }
-                        Value::known(pin_code.into())
-                    },
-                )?;
-
-                Ok(())
-            },
-        )
+    #[test]
+    fn test_extract_pincode_invalid() {
+        assert!(!pincode_prover(b"11005", 11005));
+        assert!(!pincode_prover(b"1100511", 1100511));
+        assert!(!pincode_prover(b"11O051", 0));
+        assert!(!pincode_prover(b"011005", 11005));
     }
-}
 
-impl<F: FieldExt> PinCodeExtractorCircuit<F> {
-    pub fn new(
-        n_delimited_data: Vec<Value<F>>,
-        start_delimiter_index: Value<F>,
-        end_delimiter_index: Value<F>,
-    ) -> Self {
-        Self {
-            n_delimited_data,
-            start_delimiter_index,
-            end_delimiter_index,
+    #[test]
+    fn test_parse_pincode() {
+        let parse = |pincode: &'static [u8]| {
+            let mut fields = SAMPLE_QR_FIELDS;
+            fields[QrField::Pincode.position()] = pincode;
+            parse_pincode(&sample_qr_payload(&fields, 32))
+        };
+        assert_eq!(parse(b"110051").unwrap(), 110051);
+        assert!(matches!(
+            parse(b"011005"),
+            Err(RsaCircuitError::OutOfRange(QrField::Pincode))
+        ));
+        for pincode in [&b"11005"[..], b"1100511", b"11O051", b""] {
+            assert!(matches!(
+                parse(pincode),
+                Err(RsaCircuitError::Parse(QrField::Pincode))
+            ));
         }
     }
-}*/
+}
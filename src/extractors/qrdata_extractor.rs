@@ -1,248 +1,704 @@
-use halo2_base::halo2_proofs::{
-    arithmetic::FieldExt,
-    circuit::{Chip, Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
-    poly::Rotation,
+//! Extraction of every supported attribute of the holder from the QR payload in one call,
+//! and splitting of the payload into its delimited fields.
+//!
+//! The secure QR code encodes its payload as a base-10 integer, see [`decode_qr_bignum`] and [`assert_decimal_encoding`],
+//! whose bytes are DEFLATE-compressed, see [`inflate`].
+
+use super::age_extractor::extract_age_years;
+use super::gender_extractor::{extract_gender, ExtractedGender};
+use super::photo_extractor::extract_photo;
+use super::pincode_extractor::extract_pincode;
+use super::timestamp_extractor::{extract_timestamp, REFERENCE_ID_LEN};
+use crate::address::{commit_region, extract_region, ExtractedRegion};
+use crate::big_uint::{AssignedBigUint, Fresh};
+use crate::poseidon::StandardPoseidonChip;
+use crate::qr_spec::{QrField, QrFormat, DELIMITER};
+use crate::utils::ascii_digits_to_int;
+use crate::RsaCircuitError;
+use flate2::read::{DeflateDecoder, GzDecoder, ZlibDecoder};
+use halo2_base::halo2_proofs::circuit::Value;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::{biguint_to_fe, fe_to_biguint, PrimeField},
+    AssignedValue, Context, QuantumCell,
 };
+use halo2_ecc::bigint::OverflowInteger;
+use num_bigint::BigUint;
+use std::io::Read;
+
+/// The number of bits of the integer returned by [`extract_last4`].
+pub const LAST4_BITS: usize = 14;
+/// The number of decimal digits recomposed at once by [`assert_decimal_encoding`], so that a chunk fits 60 bits.
+const DECIMAL_CHUNK_LEN: usize = 18;
+/// The number of bits of the limbs of the integer recomposed by [`assert_decimal_encoding`].
+const DECIMAL_LIMB_BITS: usize = 64;
+
+/// Decodes the base-10 integer read from a secure QR code into its big-endian bytes.
+///
+/// # Arguments
+/// * qr_decimal - the decimal digits of the QR code. Surrounding whitespace is ignored.
+///
+/// # Return values
+/// Returns the bytes without leading zeros, i.e. the compressed payload followed by its signature.
+/// Returns `None` if `qr_decimal` is empty or holds another character than a decimal digit.
+pub fn decode_qr_bignum(qr_decimal: &str) -> Option<Vec<u8>> {
+    let digits = qr_decimal.trim().as_bytes();
+    if digits.is_empty() || !digits.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    Some(BigUint::parse_bytes(digits, 10)?.to_bytes_be())
+}
 
-// Assuming the following constants/functions are defined:
-// maxDataLength, photoPackSize, photoPosition, dobPosition, statePosition, pinCodePosition, TimestampExtractor, AgeExtractor, GenderExtractor, PinCodeExtractor, ExtractAndPackAsInt, PhotoExtractor
-
-#[derive(Clone)]
-pub struct QRDataExtractorConfig {
-    q_enable: Selector,
-    data: Column<Advice>,
-    qr_data_padded_length: Column<Advice>,
-    delimiter_indices: Column<Advice>,
-    timestamp: Column<Advice>,
-    age_above_18: Column<Advice>,
-    gender: Column<Advice>,
-    state: Column<Advice>,
-    pin_code: Column<Advice>,
-    photo: Vec<Column<Advice>>,
-    n255_filter: Column<Advice>,
-    n_delimited_data: Column<Advice>,
+/// Inflates the bytes returned by [`decode_qr_bignum`].
+///
+/// # Arguments
+/// * compressed - the DEFLATE-compressed bytes, either in a gzip or zlib container, detected by their header, or raw.
+///
+/// # Return values
+/// Returns the inflated bytes, i.e. the payload of [`crate::qr_spec`].
+/// Returns `None` if `compressed` is not a valid DEFLATE stream.
+pub fn inflate(compressed: &[u8]) -> Option<Vec<u8>> {
+    let mut inflated = vec![];
+    let read = match compressed {
+        [0x1f, 0x8b, ..] => GzDecoder::new(compressed).read_to_end(&mut inflated),
+        [cmf, flg, ..] if cmf & 0x0f == 8 && (u16::from(*cmf) << 8 | u16::from(*flg)) % 31 == 0 => {
+            ZlibDecoder::new(compressed).read_to_end(&mut inflated)
+        }
+        _ => DeflateDecoder::new(compressed).read_to_end(&mut inflated),
+    };
+    read.ok().map(|_| inflated)
 }
 
-pub struct QRDataExtractorCircuit<F: FieldExt> {
-    pub data: Vec<Value<F>>,
-    pub qr_data_padded_length: Value<F>,
-    pub delimiter_indices: Vec<Value<F>>,
+/// Constrains decimal digits to encode the same integer as big-endian bytes, i.e. the in-circuit counterpart of [`decode_qr_bignum`].
+///
+/// The digits are recomposed by chunks of 18 digits with Horner's method into an integer of 64-bit limbs,
+/// the limbs and carries of every step being witnessed and range checked.
+/// The cost thus grows with the product of the numbers of digits and bytes.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * digits - the assigned digits, most significant first. They are constrained to `0..=9`.
+/// * bytes - the assigned bytes, most significant first. They are assumed to be already constrained to 8 bits, e.g. as SHA256 inputs.
+///
+/// # Return values
+/// Returns [`RsaCircuitError::BadLength`] if `digits` or `bytes` is empty.
+/// Leading zero digits and bytes are allowed, so the integer rather than its encoding is compared.
+pub fn assert_decimal_encoding<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    digits: &[AssignedValue<'v, F>],
+    bytes: &[AssignedValue<'v, F>],
+) -> Result<(), RsaCircuitError> {
+    if digits.is_empty() || bytes.is_empty() {
+        return Err(RsaCircuitError::BadLength {
+            len: digits.len().min(bytes.len()),
+            max_len: None,
+        });
+    }
+    let gate = range.gate();
+    for digit in digits.iter() {
+        range.check_less_than_safe(ctx, digit, 10);
+    }
+    let chunk_value = |ctx: &mut Context<'v, F>, chunk: &[AssignedValue<'v, F>]| {
+        gate.inner_product(
+            ctx,
+            chunk.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+            (0..chunk.len() as u32)
+                .rev()
+                .map(|exp| QuantumCell::Constant(biguint_to_fe(&BigUint::from(10u64).pow(exp))))
+                .collect::<Vec<_>>(),
+        )
+    };
+    // Only the most significant chunk may be shorter.
+    let first_len = (digits.len() - 1) % DECIMAL_CHUNK_LEN + 1;
+
+    // The little-endian limbs of the integer encoded by `bytes`.
+    let limbs = bytes
+        .rchunks(DECIMAL_LIMB_BITS / 8)
+        .map(|chunk| {
+            gate.inner_product(
+                ctx,
+                chunk.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                (0..chunk.len() as u32)
+                    .rev()
+                    .map(|exp| QuantumCell::Constant(F::from(256u64.pow(exp))))
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let mut acc = vec![chunk_value(ctx, &digits[..first_len])];
+    acc.extend((1..limbs.len()).map(|_| gate.load_zero(ctx)));
+    let chunk_base = F::from(10u64.pow(DECIMAL_CHUNK_LEN as u32));
+    let limb_base = biguint_to_fe::<F>(&(BigUint::from(1u64) << DECIMAL_LIMB_BITS));
+    for chunk in digits[first_len..].chunks(DECIMAL_CHUNK_LEN) {
+        // acc * 10^18 + chunk, the chunk being the initial carry into the least significant limb.
+        let mut carry = chunk_value(ctx, chunk);
+        let mut next = vec![];
+        for limb in acc.iter() {
+            let sum = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(limb),
+                QuantumCell::Constant(chunk_base),
+                QuantumCell::Existing(&carry),
+            );
+            let value = sum.value().map(|v| fe_to_biguint(v));
+            let low = gate.load_witness(
+                ctx,
+                value
+                    .as_ref()
+                    .map(|v| biguint_to_fe(&(v % (BigUint::from(1u64) << DECIMAL_LIMB_BITS)))),
+            );
+            let high =
+                gate.load_witness(ctx, value.map(|v| biguint_to_fe(&(v >> DECIMAL_LIMB_BITS))));
+            range.range_check(ctx, &low, DECIMAL_LIMB_BITS);
+            range.range_check(ctx, &high, DECIMAL_LIMB_BITS);
+            let recomposed = gate.mul_add(
+                ctx,
+                QuantumCell::Existing(&high),
+                QuantumCell::Constant(limb_base),
+                QuantumCell::Existing(&low),
+            );
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(&sum),
+                QuantumCell::Existing(&recomposed),
+            );
+            next.push(low);
+            carry = high;
+        }
+        // The integer must keep fitting the limbs of `bytes`.
+        gate.assert_is_const(ctx, &carry, F::zero());
+        acc = next;
+    }
+    // The first chunk is less than 10^18, which fits a single limb.
+    for (recomposed, limb) in acc.iter().zip(limbs.iter()) {
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(recomposed),
+            QuantumCell::Existing(limb),
+        );
+    }
+    Ok(())
+}
+
+/// The attributes extracted by [`extract_qr_data`].
+#[derive(Debug, Clone)]
+pub struct QrData<'v, F: PrimeField> {
+    /// The signing time as a UNIX timestamp.
+    pub timestamp: AssignedValue<'v, F>,
+    /// The age in years on the signing date.
+    pub age: AssignedValue<'v, F>,
+    /// The gender and its validity bit.
+    pub gender: ExtractedGender<'v, F>,
+    /// The pincode.
+    pub pincode: AssignedBigUint<'v, F, Fresh>,
+    /// The payload with every byte outside the photo region zeroed.
+    pub photo: Vec<AssignedValue<'v, F>>,
 }
 
-impl<F: FieldExt> Circuit<F> for QRDataExtractorCircuit<F> {
-    type Config = QRDataExtractorConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+/// Extracts the [`QrData`] of the payload.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * data_len - the assigned length of the signed payload.
+///
+/// # Return values
+/// Returns the [`QrData`], constrained as described by each extractor.
+pub fn extract_qr_data<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    data_len: &AssignedValue<'v, F>,
+) -> Result<QrData<'v, F>, RsaCircuitError> {
+    Ok(QrData {
+        timestamp: extract_timestamp(range, ctx, qr_bytes)?,
+        age: extract_age_years(range, ctx, qr_bytes)?,
+        gender: extract_gender(range, ctx, qr_bytes)?,
+        pincode: extract_pincode(range, ctx, qr_bytes)?,
+        photo: extract_photo(range, ctx, qr_bytes, data_len),
+    })
+}
 
-    fn without_witnesses(&self) -> Self {
-        Self {
-            data: vec![Value::unknown(); self.data.len()],
-            qr_data_padded_length: Value::unknown(),
-            delimiter_indices: vec![Value::unknown(); self.delimiter_indices.len()],
-        }
+/// Locates the first `num_fields` delimited fields of the payload in a single scan.
+///
+/// The scan computes the number of delimiters preceding every byte once.
+/// The terminating delimiter of each field is then witnessed and constrained to hold [`DELIMITER`] and to be preceded by exactly as many delimiters as the position of the field,
+/// so a prover can neither claim a delimiter where there is none nor skip one.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * num_fields - the number of fields to be located.
+///
+/// # Return values
+/// Returns the assigned `(start, end)` indexes of each field in order, where `start` is the index of its first byte
+/// and `end` is the index of its terminating delimiter, so the field spans `start..end`.
+/// A payload with less than `num_fields` delimiters fails the constraints.
+pub fn split_fields<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    num_fields: usize,
+) -> Vec<(AssignedValue<'v, F>, AssignedValue<'v, F>)> {
+    let gate = range.gate();
+    let mut delimiters = vec![];
+    // `num_before[i]` is the number of delimiters in `qr_bytes[..i]`.
+    let mut num_before = vec![gate.load_zero(ctx)];
+    for (idx, byte) in qr_bytes.iter().enumerate() {
+        byte.value().map(|v| {
+            if v.get_lower_32() == DELIMITER as u32 {
+                delimiters.push(idx);
+            }
+        });
+        let is_delimiter = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(byte),
+            QuantumCell::Constant(F::from(DELIMITER as u64)),
+        );
+        let num = gate.add(
+            ctx,
+            QuantumCell::Existing(&num_before[idx]),
+            QuantumCell::Existing(&is_delimiter),
+        );
+        num_before.push(num);
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let q_enable = meta.selector();
-        let data = meta.advice_column();
-        let qr_data_padded_length = meta.advice_column();
-        let delimiter_indices = meta.advice_column();
-        let timestamp = meta.advice_column();
-        let age_above_18 = meta.advice_column();
-        let gender = meta.advice_column();
-        let state = meta.advice_column();
-        let pin_code = meta.advice_column();
-
-        const PHOTO_PACK_SIZE: usize = 33;
-        let photo: Vec<Column<Advice>> = (0..PHOTO_PACK_SIZE)
-            .map(|_| meta.advice_column())
-            .collect();
-
-        for &column in &photo {
-            meta.enable_equality(column);
+    let mut start = gate.load_zero(ctx);
+    let mut fields = vec![];
+    for position in 0..num_fields {
+        let idx = delimiters.get(position).copied().unwrap_or(0);
+        let end = gate.load_witness(ctx, Value::known(F::from(idx as u64)));
+        // An out-of-range `end` selects zero and fails the first constraint.
+        let byte = gate.select_from_idx(
+            ctx,
+            qr_bytes
+                .iter()
+                .map(QuantumCell::Existing)
+                .collect::<Vec<_>>(),
+            QuantumCell::Existing(&end),
+        );
+        gate.assert_is_const(ctx, &byte, F::from(DELIMITER as u64));
+        let num = gate.select_from_idx(
+            ctx,
+            num_before[..qr_bytes.len()]
+                .iter()
+                .map(QuantumCell::Existing)
+                .collect::<Vec<_>>(),
+            QuantumCell::Existing(&end),
+        );
+        gate.assert_is_const(ctx, &num, F::from(position as u64));
+        let next_start = gate.add(
+            ctx,
+            QuantumCell::Existing(&end),
+            QuantumCell::Constant(F::one()),
+        );
+        fields.push((start, end));
+        start = next_start;
+    }
+    fields
+}
+
+/// Extracts the name of the holder from the fields located by [`split_fields`].
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * fields - the field boundaries returned by [`split_fields`] for the same payload.
+/// * max_len - the maximum byte length of the name.
+///
+/// # Return values
+/// Returns the name bytes zero-padded to `max_len` and the assigned length of the name, which is constrained to be at most `max_len`.
+/// The name is UTF-8 encoded and taken byte by byte, so non-ASCII characters are kept intact.
+/// Returns [`RsaCircuitError::Parse`] if `fields` does not reach the name field.
+pub fn extract_name<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    fields: &[(AssignedValue<'v, F>, AssignedValue<'v, F>)],
+    max_len: usize,
+) -> Result<(Vec<AssignedValue<'v, F>>, AssignedValue<'v, F>), RsaCircuitError> {
+    let (start, end) = fields
+        .get(QrField::Name.position())
+        .ok_or(RsaCircuitError::Parse(QrField::Name))?;
+    Ok(extract_region(range, ctx, qr_bytes, start, end, max_len))
+}
+
+/// Extracts the last 4 digits of the Aadhaar number, which lead the reference id, from the fields located by [`split_fields`].
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * fields - the field boundaries returned by [`split_fields`] for the same payload.
+///
+/// # Return values
+/// Returns the digits as an assigned integer of a single [`LAST4_BITS`]-bit limb in `0..=9999`.
+/// The reference id is constrained to [`REFERENCE_ID_LEN`] bytes and each of the 4 bytes to a decimal digit.
+/// Returns [`RsaCircuitError::Parse`] if `fields` does not reach the reference id field.
+pub fn extract_last4<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    fields: &[(AssignedValue<'v, F>, AssignedValue<'v, F>)],
+) -> Result<AssignedBigUint<'v, F, Fresh>, RsaCircuitError> {
+    let (start, end) = fields
+        .get(QrField::ReferenceId.position())
+        .ok_or(RsaCircuitError::Parse(QrField::ReferenceId))?;
+    let (reference_id, len) = extract_region(range, ctx, qr_bytes, start, end, REFERENCE_ID_LEN);
+    range
+        .gate()
+        .assert_is_const(ctx, &len, F::from(REFERENCE_ID_LEN as u64));
+    let last4 = ascii_digits_to_int(range, ctx, &reference_id[..4]);
+    let value = last4.value().map(|v| fe_to_biguint(v));
+    let int = OverflowInteger::construct(vec![last4], LAST4_BITS);
+    Ok(AssignedBigUint::new(int, value))
+}
+
+/// Extracts the state of the holder from the fields located by [`split_fields`] and commits to it.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * fields - the field boundaries returned by [`split_fields`] for the same payload.
+/// * max_len - the maximum byte length of the state.
+///
+/// # Return values
+/// Returns the [`ExtractedRegion`] of the state. Residence in a state is proven by comparing its commitment with [`region_commitment_native`] of the expected state.
+/// Returns [`RsaCircuitError::Parse`] if `fields` does not reach the state field.
+pub fn extract_state<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    poseidon: &StandardPoseidonChip<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    fields: &[(AssignedValue<'v, F>, AssignedValue<'v, F>)],
+    max_len: usize,
+) -> Result<ExtractedRegion<'v, F>, RsaCircuitError> {
+    extract_fields_region(
+        range,
+        poseidon,
+        ctx,
+        qr_bytes,
+        fields,
+        (QrField::State, QrField::State),
+        max_len,
+    )
+}
+
+/// Extracts the full address of the holder from the fields located by [`split_fields`] and commits to it.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+/// * fields - the field boundaries returned by [`split_fields`] for the same payload.
+/// * max_len - the maximum byte length of the address.
+///
+/// # Return values
+/// Returns the [`ExtractedRegion`] spanning the fields from care-of to VTC, delimiters included, as [`crate::address::extract_address`] does for format v2.
+/// Returns [`RsaCircuitError::Parse`] if `fields` does not reach the VTC field.
+pub fn extract_address<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    poseidon: &StandardPoseidonChip<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    fields: &[(AssignedValue<'v, F>, AssignedValue<'v, F>)],
+    max_len: usize,
+) -> Result<ExtractedRegion<'v, F>, RsaCircuitError> {
+    extract_fields_region(
+        range,
+        poseidon,
+        ctx,
+        qr_bytes,
+        fields,
+        QrFormat::V2.address_fields(),
+        max_len,
+    )
+}
+
+/// Extracts and commits to the region from the start of `first` to the end of `last`.
+fn extract_fields_region<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    poseidon: &StandardPoseidonChip<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+    fields: &[(AssignedValue<'v, F>, AssignedValue<'v, F>)],
+    (first, last): (QrField, QrField),
+    max_len: usize,
+) -> Result<ExtractedRegion<'v, F>, RsaCircuitError> {
+    let (start, _) = fields
+        .get(first.position())
+        .ok_or(RsaCircuitError::Parse(first))?;
+    let (_, end) = fields
+        .get(last.position())
+        .ok_or(RsaCircuitError::Parse(last))?;
+    let (bytes, len) = extract_region(range, ctx, qr_bytes, start, end, max_len);
+    Ok(commit_region(range.gate(), poseidon, ctx, bytes, len))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::address::region_commitment_native;
+    use crate::poseidon::{R_F, R_P};
+    use crate::qr_spec::field_range;
+    use crate::test_utils::{
+        load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR, SAMPLE_QR_FIELDS,
+    };
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    fn expected_boundaries(fields: &[&[u8]]) -> Vec<Fr> {
+        let mut boundaries = vec![];
+        let mut start = 0;
+        for field in fields {
+            let end = start + field.len();
+            boundaries.extend([Fr::from(start as u64), Fr::from(end as u64)]);
+            start = end + 1;
         }
+        boundaries
+    }
 
-        let n255_filter = meta.advice_column();
-        let n_delimited_data = meta.advice_column();
+    fn split_fields_prover(data: Vec<u8>, num_fields: usize, expected: Vec<Fr>) -> bool {
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len() + 8);
+                Ok(split_fields(range, ctx, &qr_bytes, num_fields)
+                    .into_iter()
+                    .flat_map(|(start, end)| [start, end])
+                    .collect())
+            },
+            expected,
+        );
+        prover.verify().is_ok()
+    }
 
-        QRDataExtractorConfig {
-            q_enable,
+    #[test]
+    fn test_split_fields() {
+        let fields: [&[u8]; 5] = [b"V2", b"", b"abc", b"", b"\x00\x01"];
+        let mut data = fields.join(&DELIMITER);
+        data.push(DELIMITER);
+        data.extend_from_slice(b"photo");
+        assert!(split_fields_prover(
+            data.clone(),
+            fields.len(),
+            expected_boundaries(&fields)
+        ));
+        // There are only 5 delimiters, so no witness locates a sixth field.
+        let mut expected = expected_boundaries(&fields);
+        expected.extend([Fr::from(data.len() as u64 - 5), Fr::zero()]);
+        assert!(!split_fields_prover(data, fields.len() + 1, expected));
+
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        assert!(split_fields_prover(
             data,
-            qr_data_padded_length,
-            delimiter_indices,
-            timestamp,
-            age_above_18,
-            gender,
-            state,
-            pin_code,
-            photo,
-            n255_filter,
-            n_delimited_data,
-        }
+            SAMPLE_QR_FIELDS.len(),
+            expected_boundaries(&SAMPLE_QR_FIELDS)
+        ));
     }
 
-    fn synthesize(
-        &self,
-        config: QRDataExtractorConfig,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "QR data extraction",
-            |mut region| {
-                let offset = 0;
-
-                config.q_enable.enable(&mut region, offset)?;
-
-                for (i, &data) in self.data.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("data_{}", i),
-                        config.data,
-                        offset + i,
-                        || data.ok_or(Error::SynthesisError),
-                    )?;
-                }
+    #[test]
+    fn test_extract_name() {
+        const NAME_MAX_LEN: usize = 32;
+        let name = "Zoë Ñandú दास".as_bytes();
+        assert!(name.iter().any(|byte| !byte.is_ascii()));
+        let mut fields = SAMPLE_QR_FIELDS;
+        fields[QrField::Name.position()] = name;
+        let data = sample_qr_payload(&fields, 32);
+        let mut expected = name
+            .iter()
+            .map(|byte| Fr::from(*byte as u64))
+            .collect::<Vec<_>>();
+        expected.resize(NAME_MAX_LEN, Fr::zero());
+        expected.push(Fr::from(name.len() as u64));
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let fields = split_fields(range, ctx, &qr_bytes, QrField::Name.position());
+                assert!(matches!(
+                    extract_name(range, ctx, &qr_bytes, &fields, NAME_MAX_LEN),
+                    Err(RsaCircuitError::Parse(QrField::Name))
+                ));
+                let fields = split_fields(range, ctx, &qr_bytes, QrField::Name.position() + 1);
+                let (mut bytes, len) = extract_name(range, ctx, &qr_bytes, &fields, NAME_MAX_LEN)?;
+                bytes.push(len);
+                Ok(bytes)
+            },
+            expected,
+        );
+        prover.assert_satisfied();
+    }
 
-                let qr_data_padded_length = region.assign_advice(
-                    || "qr_data_padded_length",
-                    config.qr_data_padded_length,
-                    offset,
-                    || self.qr_data_padded_length.ok_or(Error::SynthesisError),
-                )?;
-
-                for (i, &delimiter) in self.delimiter_indices.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("delimiter_indices_{}", i),
-                        config.delimiter_indices,
-                        offset + i,
-                        || delimiter.ok_or(Error::SynthesisError),
-                    )?;
-                }
+    fn last4_prover(reference_id: &'static [u8], expected: u64) -> bool {
+        let mut fields = SAMPLE_QR_FIELDS;
+        fields[QrField::ReferenceId.position()] = reference_id;
+        let data = sample_qr_payload(&fields, 32);
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let fields =
+                    split_fields(range, ctx, &qr_bytes, QrField::ReferenceId.position() + 1);
+                let last4 = extract_last4(range, ctx, &qr_bytes, &fields)?;
+                assert_eq!(last4.num_limbs(), 1);
+                Ok(last4.limbs().to_vec())
+            },
+            vec![Fr::from(expected)],
+        );
+        prover.verify().is_ok()
+    }
 
-                // Create `nDelimitedData`
-                let max_data_length = self.data.len();
-                let mut n255_filter = vec![Value::zero(); max_data_length + 1];
-                let mut n_delimited_data = vec![Value::zero(); max_data_length];
-
-                for i in 0..max_data_length {
-                    let is_255 = self.data[i] == Value::known(F::from(255u64));
-                    let index_before_photo = i < self.delimiter_indices[photoPosition() - 1].get().unwrap() as usize + 1;
-                    let is_255_and_index_before_photo = is_255 * Value::known(F::from(index_before_photo as u64));
-
-                    n255_filter[i + 1] = is_255_and_index_before_photo * Value::known(F::from(255u64)) + n255_filter[i];
-                    n_delimited_data[i] = is_255_and_index_before_photo * n255_filter[i] + self.data[i];
-
-                    region.assign_advice(
-                        || format!("n255_filter_{}", i),
-                        config.n255_filter,
-                        offset + i,
-                        || n255_filter[i].ok_or(Error::SynthesisError),
-                    )?;
-
-                    region.assign_advice(
-                        || format!("n_delimited_data_{}", i),
-                        config.n_delimited_data,
-                        offset + i,
-                        || n_delimited_data[i].ok_or(Error::SynthesisError),
-                    )?;
-                }
+    #[test]
+    fn test_extract_last4() {
+        assert!(last4_prover(b"269720240718124553876", 2697));
+        assert!(last4_prover(b"000120240718124553876", 1));
+        assert!(last4_prover(b"999920240718124553876", 9999));
+        // Not a digit.
+        assert!(!last4_prover(b"26a720240718124553876", 2697));
+        // Too short.
+        assert!(!last4_prover(b"26972024071812455387", 2697));
+    }
 
-                // Extract timestamp
-                let timestamp_extractor = TimestampExtractor::new(max_data_length);
-                let timestamp = timestamp_extractor.extract(&mut region, &n_delimited_data)?;
-
-                // Assign timestamp output
-                region.assign_advice(
-                    || "timestamp",
-                    config.timestamp,
-                    offset,
-                    || timestamp.ok_or(Error::SynthesisError),
-                )?;
-
-                // Extract age and calculate if above 18
-                let age_extractor = AgeExtractor::new(max_data_length);
-                let age_data = age_extractor.extract(&mut region, &n_delimited_data, &self.delimiter_indices, &timestamp)?;
-
-                // Assign age output
-                region.assign_advice(
-                    || "age_above_18",
-                    config.age_above_18,
-                    offset,
-                    || age_data.age.ok_or(Error::SynthesisError),
-                )?;
-
-                let age_above_18 = age_data.age.map(|age| age > Value::known(F::from(18u64)));
-                region.assign_advice(
-                    || "age_above_18_checker",
-                    config.age_above_18,
-                    offset,
-                    || age_above_18.ok_or(Error::SynthesisError),
-                )?;
-
-                // Extract gender
-                let gender_extractor = GenderExtractor::new(max_data_length);
-                let gender = gender_extractor.extract(&mut region, &age_data.n_delimited_data_shifted_to_dob)?;
-
-                // Assign gender output
-                region.assign_advice(
-                    || "gender",
-                    config.gender,
-                    offset,
-                    || gender.ok_or(Error::SynthesisError),
-                )?;
-
-                // Extract PIN code
-                let pin_code_extractor = PinCodeExtractor::new(max_data_length);
-                let pin_code = pin_code_extractor.extract(&mut region, &n_delimited_data, &self.delimiter_indices)?;
-
-                // Assign pin code output
-                region.assign_advice(
-                    || "pin_code",
-                    config.pin_code,
-                    offset,
-                    || pin_code.ok_or(Error::SynthesisError),
-                )?;
-
-                // Extract state
-                let state_extractor = ExtractAndPackAsInt::new(max_data_length, statePosition());
-                let state = state_extractor.extract(&mut region, &n_delimited_data, &self.delimiter_indices)?;
-
-                // Assign state output
-                region.assign_advice(
-                    || "state",
-                    config.state,
-                    offset,
-                    || state.ok_or(Error::SynthesisError),
-                )?;
-
-                // Extract photo
-                let photo_extractor = PhotoExtractor::new(max_data_length);
-                let photo = photo_extractor.extract(&mut region, &n_delimited_data, self.delimiter_indices[photoPosition() - 1], self.qr_data_padded_length)?;
-
-                for (i, &photo_part) in photo.iter().enumerate() {
-                    region.assign_advice(
-                        || format!("photo_{}", i),
-                        config.photo[i],
-                        offset,
-                        || photo_part.ok_or(Error::SynthesisError),
-                    )?;
+    #[test]
+    fn test_extract_state_and_address() {
+        const STATE_MAX_LEN: usize = 32;
+        const ADDRESS_MAX_LEN: usize = 160;
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        let state = SAMPLE_QR_FIELDS[QrField::State.position()];
+        assert_eq!(state, b"Delhi");
+        let start = field_range(&data, QrField::CareOf).unwrap().start;
+        let end = field_range(&data, QrField::Vtc).unwrap().end;
+        let address = data[start..end].to_vec();
+        let mut expected = vec![
+            Fr::from(state.len() as u64),
+            region_commitment_native(state, STATE_MAX_LEN).unwrap(),
+        ];
+        expected.extend([
+            Fr::from(address.len() as u64),
+            region_commitment_native(&address, ADDRESS_MAX_LEN).unwrap(),
+        ]);
+        // Another state yields another commitment.
+        assert_ne!(
+            expected[1],
+            region_commitment_native::<Fr>(b"Karnataka", STATE_MAX_LEN).unwrap()
+        );
+        let prover = mock_prove(
+            move |ctx, range| {
+                let poseidon = StandardPoseidonChip::new(R_F, R_P);
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let fields = split_fields(range, ctx, &qr_bytes, SAMPLE_QR_FIELDS.len());
+                let state =
+                    extract_state(range, &poseidon, ctx, &qr_bytes, &fields, STATE_MAX_LEN)?;
+                let address =
+                    extract_address(range, &poseidon, ctx, &qr_bytes, &fields, ADDRESS_MAX_LEN)?;
+                for (byte, expected) in state.bytes.iter().zip(b"Delhi") {
+                    range
+                        .gate
+                        .assert_is_const(ctx, byte, Fr::from(*expected as u64));
                 }
+                Ok(vec![
+                    state.len,
+                    state.commitment,
+                    address.len,
+                    address.commitment,
+                ])
+            },
+            expected,
+        );
+        prover.assert_satisfied();
+    }
 
-                Ok(())
+    fn decimal_encoding_prover(digits: Vec<u8>, bytes: Vec<u8>) -> bool {
+        let prover = mock_prove(
+            move |ctx, range| {
+                let digits = load_bytes(ctx, range, &digits, digits.len());
+                let bytes = load_bytes(ctx, range, &bytes, bytes.len());
+                assert_decimal_encoding(range, ctx, &digits, &bytes)?;
+                Ok(vec![])
             },
-        )
+            vec![],
+        );
+        prover.verify().is_ok()
     }
-}
 
-impl<F: FieldExt> QRDataExtractorCircuit<F> {
-    pub fn new(
-        data: Vec<Value<F>>,
-        qr_data_padded_length: Value<F>,
-        delimiter_indices: Vec<Value<F>>,
-    ) -> Self {
-        Self {
-            data,
-            qr_data_padded_length,
-            delimiter_indices,
+    #[test]
+    fn test_decode_qr_bignum() {
+        assert_eq!(decode_qr_bignum("255"), Some(vec![0xff]));
+        assert_eq!(decode_qr_bignum(" 256\n"), Some(vec![1, 0]));
+        assert_eq!(
+            decode_qr_bignum("18446744073709551616"),
+            Some(vec![1, 0, 0, 0, 0, 0, 0, 0, 0])
+        );
+        for invalid in ["", " ", "-1", "12a4", "1 2"] {
+            assert_eq!(decode_qr_bignum(invalid), None);
         }
+
+        let payload = sample_qr_payload(&SAMPLE_QR_FIELDS, 16);
+        let decimal = BigUint::from_bytes_be(&payload).to_str_radix(10);
+        assert_eq!(decode_qr_bignum(&decimal), Some(payload));
+    }
+
+    #[test]
+    fn test_inflate() {
+        use flate2::write::{DeflateEncoder, GzEncoder, ZlibEncoder};
+        use flate2::Compression;
+        use std::io::Write;
+
+        let payload = sample_qr_payload(&SAMPLE_QR_FIELDS, 64);
+        let mut gzip = GzEncoder::new(vec![], Compression::default());
+        gzip.write_all(&payload).unwrap();
+        let mut zlib = ZlibEncoder::new(vec![], Compression::default());
+        zlib.write_all(&payload).unwrap();
+        let mut deflate = DeflateEncoder::new(vec![], Compression::default());
+        deflate.write_all(&payload).unwrap();
+        for compressed in [
+            gzip.finish().unwrap(),
+            zlib.finish().unwrap(),
+            deflate.finish().unwrap(),
+        ] {
+            assert_eq!(inflate(&compressed), Some(payload.clone()));
+            // The decimal digits of the QR code decode to the compressed bytes.
+            let decimal = BigUint::from_bytes_be(&compressed).to_str_radix(10);
+            assert_eq!(
+                decode_qr_bignum(&decimal).and_then(|bytes| inflate(&bytes)),
+                Some(payload.clone())
+            );
+        }
+        assert_eq!(inflate(&payload), None);
+        assert_eq!(inflate(&[0x1f, 0x8b, 0x08]), None);
+    }
+
+    #[test]
+    fn test_assert_decimal_encoding() {
+        // The digits of the sample QR code and the compressed bytes they encode.
+        let digits = SAMPLE_QR
+            .trim()
+            .bytes()
+            .map(|digit| digit - b'0')
+            .collect::<Vec<_>>();
+        let compressed = decode_qr_bignum(SAMPLE_QR).unwrap();
+        assert!(decimal_encoding_prover(digits.clone(), compressed.clone()));
+
+        // Leading zeros do not change the integer.
+        let mut padded_digits = vec![0; 5];
+        padded_digits.extend_from_slice(&digits);
+        let mut padded_bytes = vec![0; 3];
+        padded_bytes.extend_from_slice(&compressed);
+        assert!(decimal_encoding_prover(padded_digits, padded_bytes));
+        assert!(decimal_encoding_prover(vec![2, 5, 6], vec![1, 0]));
+
+        let mut tampered = digits.clone();
+        tampered[40] = (tampered[40] + 1) % 10;
+        assert!(!decimal_encoding_prover(tampered, compressed.clone()));
+        // 2 * 100 + 4 * 10 + 16 = 256
+        assert!(!decimal_encoding_prover(vec![2, 4, 16], vec![1, 0]));
+        // The integer overflows the bytes.
+        assert!(!decimal_encoding_prover(digits, compressed[1..].to_vec()));
     }
 }
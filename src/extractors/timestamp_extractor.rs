@@ -0,0 +1,100 @@
+//! Extraction of the signing time embedded in the reference id of the QR payload.
+//!
+//! The reference id consists of the last 4 digits of the Aadhaar number followed by the signing time `YYYYMMDDHHMMSSsss` in IST.
+
+use super::extractor::extract_fixed_len_field;
+use crate::qr_spec::QrField;
+use crate::timestamp::{to_unix_timestamp, TimeZone, TimestampParts};
+use crate::utils::ascii_digits_to_int;
+use crate::RsaCircuitError;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
+};
+
+/// The byte length of the reference id.
+pub const REFERENCE_ID_LEN: usize = 21;
+
+/// Extracts the calendar fields of the signing time.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the [`TimestampParts`] of the signing time in IST. The milliseconds are ignored.
+/// The reference id is constrained to [`REFERENCE_ID_LEN`] bytes and every parsed byte to a decimal digit.
+pub fn extract_timestamp_parts<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<TimestampParts<'v, F>, RsaCircuitError> {
+    let reference_id =
+        extract_fixed_len_field(range, ctx, qr_bytes, QrField::ReferenceId, REFERENCE_ID_LEN)?;
+    Ok(TimestampParts {
+        year: ascii_digits_to_int(range, ctx, &reference_id[4..8]),
+        month: ascii_digits_to_int(range, ctx, &reference_id[8..10]),
+        day: ascii_digits_to_int(range, ctx, &reference_id[10..12]),
+        hour: ascii_digits_to_int(range, ctx, &reference_id[12..14]),
+        minute: ascii_digits_to_int(range, ctx, &reference_id[14..16]),
+        second: ascii_digits_to_int(range, ctx, &reference_id[16..18]),
+    })
+}
+
+/// Extracts the signing time as a UNIX timestamp.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * qr_bytes - assigned bytes of the payload.
+///
+/// # Return values
+/// Returns the assigned UNIX timestamp, i.e. the signing time converted from IST to UTC.
+pub fn extract_timestamp<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    qr_bytes: &[AssignedValue<'v, F>],
+) -> Result<AssignedValue<'v, F>, RsaCircuitError> {
+    let parts = extract_timestamp_parts(range, ctx, qr_bytes)?;
+    let local = to_unix_timestamp(range, ctx, &parts);
+    let timestamp = range.gate().sub(
+        ctx,
+        QuantumCell::Existing(&local),
+        QuantumCell::Constant(F::from(TimeZone::Ist.offset_seconds())),
+    );
+    range.range_check(ctx, &timestamp, 32);
+    Ok(timestamp)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{load_bytes, mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use crate::timestamp::unix_timestamp_native;
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    #[test]
+    fn test_extract_timestamp() {
+        let data = sample_qr_payload(&SAMPLE_QR_FIELDS, 32);
+        // 2024-07-18 12:45:53 IST.
+        let expected =
+            unix_timestamp_native(2024, 7, 18, 12, 45, 53) - TimeZone::Ist.offset_seconds();
+        let prover = mock_prove(
+            move |ctx, range| {
+                let qr_bytes = load_bytes(ctx, range, &data, data.len());
+                let parts = extract_timestamp_parts(range, ctx, &qr_bytes)?;
+                let timestamp = extract_timestamp(range, ctx, &qr_bytes)?;
+                Ok(vec![parts.year, parts.month, parts.day, timestamp])
+            },
+            vec![
+                Fr::from(2024),
+                Fr::from(7),
+                Fr::from(18),
+                Fr::from(expected),
+            ],
+        );
+        prover.assert_satisfied();
+    }
+}
@@ -0,0 +1,70 @@
+//! Hash algorithms of pkcs1v15 signatures and their DigestInfo prefixes ([RFC 8017, Section 9.2](https://www.rfc-editor.org/rfc/rfc8017#section-9.2)).
+
+/// A hash algorithm used by pkcs1v15 signatures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RsaHashAlgo {
+    /// SHA-1, used by legacy Aadhaar QR signatures.
+    Sha1,
+    /// SHA-256.
+    Sha256,
+    /// SHA-384.
+    Sha384,
+    /// SHA-512.
+    Sha512,
+}
+
+impl RsaHashAlgo {
+    /// Returns the byte length of the hash.
+    pub fn output_len(self) -> usize {
+        match self {
+            Self::Sha1 => 20,
+            Self::Sha256 => 32,
+            Self::Sha384 => 48,
+            Self::Sha512 => 64,
+        }
+    }
+
+    /// Returns the DER encoding of the DigestInfo preceding the hash in the encoded message.
+    pub fn digest_info_prefix(self) -> &'static [u8] {
+        match self {
+            Self::Sha1 => &[
+                0x30, 0x21, 0x30, 0x09, 0x06, 0x05, 0x2b, 0x0e, 0x03, 0x02, 0x1a, 0x05, 0x00, 0x04,
+                0x14,
+            ],
+            Self::Sha256 => &[
+                0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x01, 0x05, 0x00, 0x04, 0x20,
+            ],
+            Self::Sha384 => &[
+                0x30, 0x41, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x02, 0x05, 0x00, 0x04, 0x30,
+            ],
+            Self::Sha512 => &[
+                0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02,
+                0x03, 0x05, 0x00, 0x04, 0x40,
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rsa::sha2::{Sha256, Sha384, Sha512};
+    use rsa::Pkcs1v15Sign;
+
+    #[test]
+    fn test_digest_info_prefix() {
+        for (algo, scheme) in [
+            (RsaHashAlgo::Sha256, Pkcs1v15Sign::new::<Sha256>()),
+            (RsaHashAlgo::Sha384, Pkcs1v15Sign::new::<Sha384>()),
+            (RsaHashAlgo::Sha512, Pkcs1v15Sign::new::<Sha512>()),
+        ] {
+            assert_eq!(algo.digest_info_prefix(), &scheme.prefix[..]);
+            assert_eq!(Some(algo.output_len()), scheme.hash_len);
+        }
+        let sha1_prefix = RsaHashAlgo::Sha1.digest_info_prefix();
+        // The last byte is the length of the hash.
+        assert_eq!(sha1_prefix[sha1_prefix.len() - 1], 20);
+    }
+}
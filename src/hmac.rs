@@ -0,0 +1,178 @@
+//! HMAC-SHA256 ([RFC 2104](https://www.rfc-editor.org/rfc/rfc2104)) on top of [`Sha256DynamicConfig`].
+//!
+//! `HMAC(K, m) = SHA256((K ^ opad) || SHA256((K ^ ipad) || m))`, where `K` is zero-padded to the block length.
+
+use crate::utils::assigned_bytes_to_vec;
+use halo2_base::halo2_proofs::plonk::Error;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
+};
+use halo2_dynamic_sha256::{AssignedHashResult, Sha256DynamicConfig};
+use sha2::{Digest, Sha256};
+
+/// The block length of SHA256 in bytes, which is also the maximum key length.
+pub const HMAC_BLOCK_LEN: usize = 64;
+/// The byte length of a HMAC-SHA256 tag.
+pub const HMAC_LEN: usize = 32;
+const IPAD: u8 = 0x36;
+const OPAD: u8 = 0x5c;
+
+/// Computes HMAC-SHA256 of `msg` under `key`.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * sha256 - a [`Sha256DynamicConfig`] with two free slots, for messages of `64 + msg.len()` bytes and `96` bytes in this order.
+/// * ctx - a region context.
+/// * key - assigned key bytes, at most [`HMAC_BLOCK_LEN`] bytes. Each byte is constrained to 8 bits.
+/// * msg - message bytes.
+///
+/// # Return values
+/// Returns the assigned bytes of the HMAC tag.
+/// Returns [`Error::Synthesis`] if `key` is longer than [`HMAC_BLOCK_LEN`].
+pub fn hmac_sha256<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    sha256: &mut Sha256DynamicConfig<F>,
+    ctx: &mut Context<'v, F>,
+    key: &[AssignedValue<'v, F>],
+    msg: &[u8],
+) -> Result<Vec<AssignedValue<'v, F>>, Error> {
+    if key.len() > HMAC_BLOCK_LEN {
+        return Err(Error::Synthesis);
+    }
+    let gate = range.gate();
+    let mut padded_key = key.to_vec();
+    padded_key.resize(HMAC_BLOCK_LEN, gate.load_zero(ctx));
+    let key_bits = padded_key
+        .iter()
+        .map(|byte| gate.num_to_bits(ctx, byte, 8))
+        .collect::<Vec<_>>();
+
+    let inner_key = xor_const(gate, ctx, &padded_key, &key_bits, IPAD);
+    let inner = digest_with_prefix(gate, sha256, ctx, &inner_key, msg)?;
+    let outer_key = xor_const(gate, ctx, &padded_key, &key_bits, OPAD);
+    let inner_hash = assigned_bytes_to_vec(&inner.output_bytes);
+    let outer = digest_with_prefix(gate, sha256, ctx, &outer_key, &inner_hash)?;
+    // The outer message must be the inner hash.
+    for (hashed, byte) in outer.input_bytes[HMAC_BLOCK_LEN..]
+        .iter()
+        .zip(inner.output_bytes.iter())
+    {
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(hashed),
+            QuantumCell::Existing(byte),
+        );
+    }
+    Ok(outer.output_bytes)
+}
+
+/// Computes HMAC-SHA256 of `msg` under `key` off-circuit.
+///
+/// # Arguments
+/// * key - key bytes, at most [`HMAC_BLOCK_LEN`] bytes.
+/// * msg - message bytes.
+///
+/// # Return values
+/// Returns the HMAC tag, which equals the output of [`hmac_sha256`] for the same inputs.
+pub fn hmac_sha256_native(key: &[u8], msg: &[u8]) -> [u8; HMAC_LEN] {
+    assert!(key.len() <= HMAC_BLOCK_LEN);
+    let mut padded_key = key.to_vec();
+    padded_key.resize(HMAC_BLOCK_LEN, 0);
+    let pad = |c: u8| padded_key.iter().map(|byte| byte ^ c).collect::<Vec<u8>>();
+    let inner = Sha256::digest([pad(IPAD), msg.to_vec()].concat());
+    Sha256::digest([pad(OPAD), inner.to_vec()].concat()).into()
+}
+
+/// Computes `byte ^ c` for a constant `c` as `byte + c - 2 * (byte & c)` from the bits of `byte`.
+fn xor_const<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    bytes: &[AssignedValue<'v, F>],
+    bits: &[Vec<AssignedValue<'v, F>>],
+    c: u8,
+) -> Vec<AssignedValue<'v, F>> {
+    bytes
+        .iter()
+        .zip(bits.iter())
+        .map(|(byte, bits)| {
+            let and_times_two = gate.inner_product(
+                ctx,
+                bits.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                (0..8)
+                    .map(|j| QuantumCell::Constant(F::from(2 * (c as u64 & (1 << j)))))
+                    .collect::<Vec<_>>(),
+            );
+            let sum = gate.add(
+                ctx,
+                QuantumCell::Existing(byte),
+                QuantumCell::Constant(F::from(c as u64)),
+            );
+            gate.sub(
+                ctx,
+                QuantumCell::Existing(&sum),
+                QuantumCell::Existing(&and_times_two),
+            )
+        })
+        .collect()
+}
+
+/// Hashes `prefix || suffix` and constrains the hashed prefix to the assigned `prefix`.
+fn digest_with_prefix<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    sha256: &mut Sha256DynamicConfig<F>,
+    ctx: &mut Context<'v, F>,
+    prefix: &[AssignedValue<'v, F>],
+    suffix: &[u8],
+) -> Result<AssignedHashResult<'v, F>, Error> {
+    let mut input = assigned_bytes_to_vec(prefix);
+    input.extend_from_slice(suffix);
+    let result = sha256.digest(ctx, &input, None)?;
+    for (hashed, byte) in result.input_bytes.iter().zip(prefix.iter()) {
+        gate.assert_equal(
+            ctx,
+            QuantumCell::Existing(hashed),
+            QuantumCell::Existing(byte),
+        );
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::mock_prove_with_sha256;
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+
+    #[test]
+    fn test_hmac_sha256_matches_native() {
+        // RFC 4231, test case 2.
+        assert_eq!(
+            hmac_sha256_native(b"Jefe", b"what do ya want for nothing?")[..4],
+            [0x5b, 0xdc, 0xc1, 0x46]
+        );
+        for key in [&b"Jefe"[..], &[0xaa; HMAC_BLOCK_LEN][..]] {
+            let msg = b"what do ya want for nothing?";
+            let expected = hmac_sha256_native(key, msg)
+                .iter()
+                .map(|byte| Fr::from(*byte as u64))
+                .collect::<Vec<_>>();
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let key = key
+                        .iter()
+                        .map(|byte| {
+                            range
+                                .gate
+                                .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                        })
+                        .collect::<Vec<_>>();
+                    hmac_sha256(range, sha256, ctx, &key, msg)
+                },
+                expected,
+            );
+            prover.assert_satisfied();
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::{
     AssignedBigUint, AssignedRSAPublicKey, AssignedRSASignature, Fresh, RSAPublicKey, RSASignature,
+    RsaCircuitError, RsaHashAlgo,
 };
 use halo2_base::halo2_proofs::plonk::Error;
 use halo2_base::{utils::PrimeField, AssignedValue, Context};
@@ -10,14 +11,14 @@ pub trait RSAInstructions<F: PrimeField> {
         &self,
         ctx: &mut Context<'v, F>,
         public_key: RSAPublicKey<F>,
-    ) -> Result<AssignedRSAPublicKey<'v, F>, Error>;
+    ) -> Result<AssignedRSAPublicKey<'v, F>, RsaCircuitError>;
 
     /// Assigns a [`AssignedRSASignature`].
     fn assign_signature<'v>(
         &self,
         ctx: &mut Context<'v, F>,
         signature: RSASignature<F>,
-    ) -> Result<AssignedRSASignature<'v, F>, Error>;
+    ) -> Result<AssignedRSASignature<'v, F>, RsaCircuitError>;
 
     /// Given a base `x`, a RSA public key (e,n), performs the modular power `x^e mod n`.
     fn modpow_public_key<'v>(
@@ -35,4 +36,14 @@ pub trait RSAInstructions<F: PrimeField> {
         hashed_msg: &[AssignedValue<'v, F>],
         signature: &AssignedRSASignature<'v, F>,
     ) -> Result<AssignedValue<'v, F>, Error>;
+
+    /// Given a RSA public key, a message hashed with `hash_algo`, and a pkcs1v15 signature, verifies the signature with the public key and the hashed messaged.
+    fn verify_pkcs1v15_signature_with_hash<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+        hash_algo: RsaHashAlgo,
+        hashed_bytes: &[AssignedValue<'v, F>],
+        signature: &AssignedRSASignature<'v, F>,
+    ) -> Result<AssignedValue<'v, F>, Error>;
 }
@@ -0,0 +1,342 @@
+//! Generation of proving and verifying keys with an on-disk cache.
+//!
+//! A key file starts with the hash returned by [`config_hash`], followed by the key in [`SerdeFormat::RawBytes`].
+//! The hash tags the key with the verifying key and the KZG parameters it was generated for:
+//! a key whose hash differs from the one of the current circuit is considered stale, i.e. not read by [`read_pk`] and [`read_vk`], and regenerated by [`setup`].
+//! The verifying key covers the whole circuit, i.e. its gates, fixed columns, constants, selectors and permutation,
+//! so a change made only during synthesis also invalidates the cache.
+
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+#[cfg(feature = "sha256")]
+use crate::RSASignatureVerifier;
+
+use halo2_base::halo2_proofs::{
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        group::{ff::PrimeField, GroupEncoding},
+    },
+    plonk::{keygen_pk, keygen_vk, Circuit, Error, ProvingKey, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+    SerdeFormat,
+};
+use sha2::{Digest, Sha256};
+
+/// The byte length of the hash returned by [`config_hash`].
+pub const CONFIG_HASH_LEN: usize = 32;
+
+/// Computes a hash identifying a verifying key together with the KZG parameters it was generated with.
+///
+/// # Arguments
+/// * params - the KZG parameters.
+/// * vk - the verifying key.
+///
+/// # Return values
+/// Returns the SHA256 hash of the degree and the G2 points of `params`, which identify its toxic waste, and [`VerifyingKey::transcript_repr`] of `vk`.
+pub fn config_hash(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+) -> [u8; CONFIG_HASH_LEN] {
+    let mut hasher = Sha256::new();
+    hasher.update(params.k().to_le_bytes());
+    hasher.update(params.g2().to_bytes());
+    hasher.update(params.s_g2().to_bytes());
+    hasher.update(vk.transcript_repr().to_repr());
+    hasher.finalize().into()
+}
+
+/// Returns the proving and verifying keys of `circuit`, loading the proving key from `path` if it is cached there.
+///
+/// The verifying key is always generated, since the cache is tagged with it.
+///
+/// # Arguments
+/// * circuit - a circuit whose keys are generated.
+/// * params - the KZG parameters.
+/// * path - the path of the cache file. It is created or overwritten if the cached key is missing or stale.
+///
+/// # Return values
+/// Returns the proving key and the verifying key.
+/// Returns [`Error::Transcript`] if the cache file cannot be read or written.
+pub fn setup<C: Circuit<Fr>>(
+    circuit: &C,
+    params: &ParamsKZG<Bn256>,
+    path: impl AsRef<Path>,
+) -> Result<(ProvingKey<G1Affine>, VerifyingKey<G1Affine>), Error> {
+    let path = path.as_ref();
+    let vk = keygen_vk(params, circuit)?;
+    if let Some(mut reader) = open_tagged(path, &config_hash(params, &vk))? {
+        let pk = ProvingKey::read::<_, C>(&mut reader, SerdeFormat::RawBytes)
+            .map_err(Error::Transcript)?;
+        return Ok((pk, vk));
+    }
+    let pk = keygen_pk(params, vk.clone(), circuit)?;
+    write_pk(path, params, &pk)?;
+    Ok((pk, vk))
+}
+
+/// Generates the proving and verifying keys of `circuit`.
+///
+/// # Arguments
+/// * circuit - a circuit whose keys are generated.
+/// * params - the KZG parameters.
+///
+/// # Return values
+/// Returns the proving key and the verifying key.
+pub fn keygen<C: Circuit<Fr>>(
+    circuit: &C,
+    params: &ParamsKZG<Bn256>,
+) -> Result<(ProvingKey<G1Affine>, VerifyingKey<G1Affine>), Error> {
+    let vk = keygen_vk(params, circuit)?;
+    let pk = keygen_pk(params, vk.clone(), circuit)?;
+    Ok((pk, vk))
+}
+
+#[cfg(feature = "sha256")]
+impl RSASignatureVerifier<Fr> {
+    /// Generates the proving and verifying keys of a circuit built on [`RSASignatureVerifier`], see [`keygen`].
+    /// Store them with [`write_pk`] and [`write_vk`] to reuse them across runs.
+    ///
+    /// The verifier is a chip whose columns are configured by the circuit, so the keys are those of `circuit`.
+    ///
+    /// # Arguments
+    /// * circuit - a circuit whose keys are generated.
+    /// * params - the KZG parameters.
+    ///
+    /// # Return values
+    /// Returns the proving key and the verifying key.
+    pub fn keygen<C: Circuit<Fr>>(
+        circuit: &C,
+        params: &ParamsKZG<Bn256>,
+    ) -> Result<(ProvingKey<G1Affine>, VerifyingKey<G1Affine>), Error> {
+        keygen(circuit, params)
+    }
+}
+
+/// Reads a proving key of `circuit` written by [`write_pk`].
+///
+/// # Arguments
+/// * path - the path of the key file.
+/// * circuit - the circuit of the key. Its verifying key is generated to check the tag of the file.
+/// * params - the KZG parameters.
+///
+/// # Return values
+/// Returns the proving key, or `None` if the file is missing or the key is stale.
+/// Returns [`Error::Transcript`] if the file cannot be read.
+pub fn read_pk<C: Circuit<Fr>>(
+    path: impl AsRef<Path>,
+    circuit: &C,
+    params: &ParamsKZG<Bn256>,
+) -> Result<Option<ProvingKey<G1Affine>>, Error> {
+    let vk = keygen_vk(params, circuit)?;
+    let Some(mut reader) = open_tagged(path.as_ref(), &config_hash(params, &vk))? else {
+        return Ok(None);
+    };
+    let pk =
+        ProvingKey::read::<_, C>(&mut reader, SerdeFormat::RawBytes).map_err(Error::Transcript)?;
+    Ok(Some(pk))
+}
+
+/// Reads a verifying key of `circuit` written by [`write_vk`].
+///
+/// # Arguments
+/// * path - the path of the key file.
+/// * circuit - the circuit of the key. Its verifying key is generated to check the tag of the file.
+/// * params - the KZG parameters.
+///
+/// # Return values
+/// Returns the verifying key, or `None` if the file is missing or the key is stale.
+/// Returns [`Error::Transcript`] if the file cannot be read.
+pub fn read_vk<C: Circuit<Fr>>(
+    path: impl AsRef<Path>,
+    circuit: &C,
+    params: &ParamsKZG<Bn256>,
+) -> Result<Option<VerifyingKey<G1Affine>>, Error> {
+    let vk = keygen_vk(params, circuit)?;
+    let Some(mut reader) = open_tagged(path.as_ref(), &config_hash(params, &vk))? else {
+        return Ok(None);
+    };
+    let vk = VerifyingKey::read::<_, C>(&mut reader, SerdeFormat::RawBytes)
+        .map_err(Error::Transcript)?;
+    Ok(Some(vk))
+}
+
+/// Writes a proving key tagged with the [`config_hash`] of its verifying key.
+///
+/// # Arguments
+/// * path - the path of the key file. It is created or overwritten.
+/// * params - the KZG parameters the key was generated with.
+/// * pk - the proving key.
+///
+/// # Return values
+/// Returns [`Error::Transcript`] if the file cannot be written.
+pub fn write_pk(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+) -> Result<(), Error> {
+    let mut writer = create_tagged(path.as_ref(), &config_hash(params, pk.get_vk()))?;
+    pk.write(&mut writer, SerdeFormat::RawBytes)
+        .map_err(Error::Transcript)?;
+    writer.flush().map_err(Error::Transcript)
+}
+
+/// Writes a verifying key tagged with its [`config_hash`].
+///
+/// # Arguments
+/// * path - the path of the key file. It is created or overwritten.
+/// * params - the KZG parameters the key was generated with.
+/// * vk - the verifying key.
+///
+/// # Return values
+/// Returns [`Error::Transcript`] if the file cannot be written.
+pub fn write_vk(
+    path: impl AsRef<Path>,
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+) -> Result<(), Error> {
+    let mut writer = create_tagged(path.as_ref(), &config_hash(params, vk))?;
+    vk.write(&mut writer, SerdeFormat::RawBytes)
+        .map_err(Error::Transcript)?;
+    writer.flush().map_err(Error::Transcript)
+}
+
+/// Opens a key file and reads its tag, returning `None` if the file is missing or the tag is not `expected_hash`.
+fn open_tagged(
+    path: &Path,
+    expected_hash: &[u8; CONFIG_HASH_LEN],
+) -> Result<Option<BufReader<File>>, Error> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(Error::Transcript(e)),
+    };
+    let mut reader = BufReader::new(file);
+    let mut cached_hash = [0u8; CONFIG_HASH_LEN];
+    match reader.read_exact(&mut cached_hash) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(Error::Transcript(e)),
+    }
+    if &cached_hash != expected_hash {
+        return Ok(None);
+    }
+    Ok(Some(reader))
+}
+
+/// Creates a key file starting with `hash`.
+fn create_tagged(path: &Path, hash: &[u8; CONFIG_HASH_LEN]) -> Result<BufWriter<File>, Error> {
+    let mut writer = BufWriter::new(File::create(path).map_err(Error::Transcript)?);
+    writer.write_all(hash).map_err(Error::Transcript)?;
+    Ok(writer)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::nullifier::{compute_nullifier_native, NullifierCircuit};
+    use halo2_base::halo2_proofs::{
+        plonk::{create_proof, verify_proof},
+        poly::kzg::{
+            commitment::KZGCommitmentScheme,
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+        transcript::{
+            Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+        },
+    };
+    use rand::rngs::OsRng;
+
+    #[test]
+    fn test_setup_caches_keys() {
+        let circuit = NullifierCircuit::new(Fr::from(1u64), vec![0; 32]);
+        let params = ParamsKZG::<Bn256>::setup(NullifierCircuit::<Fr>::K as u32, OsRng);
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("nullifier.pk");
+
+        let (_, vk) = setup(&circuit, &params, &path).unwrap();
+        assert!(path.exists());
+        let (cached_pk, cached_vk) = setup(&circuit, &params, &path).unwrap();
+        assert_eq!(
+            vk.to_bytes(SerdeFormat::RawBytes),
+            cached_vk.to_bytes(SerdeFormat::RawBytes)
+        );
+        assert_eq!(
+            vk.to_bytes(SerdeFormat::RawBytes),
+            cached_pk.get_vk().to_bytes(SerdeFormat::RawBytes)
+        );
+
+        // A cache written for another configuration is stale and overwritten.
+        let mut stale = [0u8; CONFIG_HASH_LEN].to_vec();
+        stale.extend_from_slice(b"stale");
+        std::fs::write(&path, stale).unwrap();
+        let (_, regenerated_vk) = setup(&circuit, &params, &path).unwrap();
+        assert_eq!(
+            vk.to_bytes(SerdeFormat::RawBytes),
+            regenerated_vk.to_bytes(SerdeFormat::RawBytes)
+        );
+        let mut cached_hash = [0u8; CONFIG_HASH_LEN];
+        File::open(&path)
+            .unwrap()
+            .read_exact(&mut cached_hash)
+            .unwrap();
+        assert_eq!(cached_hash, config_hash(&params, &vk));
+    }
+
+    #[test]
+    fn test_cached_keys_prove_and_verify() {
+        let photo = vec![0; 32];
+        let app_id = Fr::from(12345678u64);
+        let instances = [app_id, compute_nullifier_native(app_id, &photo).unwrap()];
+        let circuit = NullifierCircuit::new(app_id, photo);
+        let k = NullifierCircuit::<Fr>::K as u32;
+        let params = ParamsKZG::<Bn256>::setup(k, OsRng);
+        let dir = tempfile::tempdir().unwrap();
+        let pk_path = dir.path().join("nullifier.pk");
+        let vk_path = dir.path().join("nullifier.vk");
+
+        let (pk, vk) = keygen(&circuit, &params).unwrap();
+        write_pk(&pk_path, &params, &pk).unwrap();
+        write_vk(&vk_path, &params, &vk).unwrap();
+        let cached_pk = read_pk(&pk_path, &circuit, &params).unwrap().unwrap();
+        let cached_vk = read_vk(&vk_path, &circuit, &params).unwrap().unwrap();
+
+        // Keys generated with another SRS of the same degree are stale.
+        let other_params = ParamsKZG::<Bn256>::setup(k, OsRng);
+        assert!(read_vk(&vk_path, &circuit, &other_params)
+            .unwrap()
+            .is_none());
+        // So are keys of a circuit differing only in its synthesis, here in the number of hashed photo bytes.
+        let longer_photo_circuit = NullifierCircuit::new(app_id, vec![0; 64]);
+        assert!(read_pk(&pk_path, &longer_photo_circuit, &params)
+            .unwrap()
+            .is_none());
+        std::fs::remove_file(&pk_path).unwrap();
+        assert!(read_pk(&pk_path, &circuit, &params).unwrap().is_none());
+
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+            &params,
+            &cached_pk,
+            &[circuit],
+            &[&[&instances]],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        let proof = transcript.finalize();
+        let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+        assert!(
+            verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+                &params,
+                &cached_vk,
+                SingleStrategy::new(&params),
+                &[&[&instances]],
+                &mut transcript,
+            )
+            .is_ok()
+        );
+    }
+}
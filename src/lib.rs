@@ -6,6 +6,7 @@
 //!
 //! In addition to [`RSAConfig`], this library also provides a high-level circuit implementation to verify pkcs1v15 signatures, [`RSASignatureVerifier`].  
 //! The verification function in [`RSAConfig`] requires as input a hashed message, whereas the function in [`RSASignatureVerifier`] computes a SHA256 hash of the given message and verifies the given signature for that hash.
+//! Callers hashing off-circuit can use [`RSAHashlessVerifier`] instead, which is available without the `sha256` feature.
 
 #![feature(more_qualified_paths)]
 
@@ -20,60 +21,89 @@ use halo2_base::halo2_proofs::{
     plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
 };
 
-use halo2_base::{gates::range::RangeStrategy::Vertical, QuantumCell, SKIP_FIRST_PASS};
-use halo2_base::{
-    gates::{range::RangeConfig, GateInstructions},
-    utils::PrimeField,
-    AssignedValue, Context,
-};
+use halo2_base::{gates::GateInstructions, utils::PrimeField, AssignedValue, Context};
+use halo2_base::{QuantumCell, SKIP_FIRST_PASS};
 use num_bigint::BigUint;
 
 use rsa::{
     pkcs1v15::SigningKey,
+    pkcs8::DecodePublicKey,
     signature::{SignatureEncoding, Signer},
     traits::PublicKeyParts,
     RsaPublicKey,
 };
 
+pub mod poseidon;
 mod qr_data_extractor;
-//pub mod poseidon;
 //mod aadhaar_verifier_circuit;
-pub mod timestamp;
-//pub mod nullifier;
+#[cfg(feature = "sha256")]
+pub mod aadhaar_verifier;
+pub mod address;
+#[cfg(feature = "sha256")]
+pub mod claims;
 pub mod conditional_secrets;
+#[cfg(feature = "sha256")]
+pub mod contact_hash;
+#[cfg(feature = "evm")]
+pub mod evm;
+pub mod extractors;
+#[cfg(feature = "sha256")]
+pub mod hmac;
+pub mod keygen;
+#[cfg(feature = "pem")]
+pub mod keys;
+pub mod nullifier;
+pub mod qr_spec;
+#[cfg(feature = "serde")]
+mod serde_utils;
 pub mod signal;
-/*mod extractors{
-    pub mod extractor;
-    pub mod timstamp_extractor;
-    pub mod age_extractor;
-    pub mod gender_extractor;
-    pub mod pincode_extractor;
-    pub mod photo_extractor;
-    pub mod qrdata_extractor;
-}*/
+#[cfg(test)]
+mod test_utils;
+pub mod timestamp;
+pub mod utils;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 use crate::conditional_secrets::IdentityCircuit;
 use crate::signal::SquareCircuit;
 use crate::timestamp::TimestampCircuit;
 use poseidon::Poseidon;
 
+mod builder;
 mod chip;
+mod error;
+mod hash_algo;
 mod instructions;
+pub use builder::*;
 pub use chip::*;
+pub use error::*;
+pub use extractors::{
+    age_extractor, gender_extractor, photo_extractor, pincode_extractor, qrdata_extractor,
+};
+#[cfg(feature = "sha256")]
+use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
 #[cfg(feature = "sha256")]
 pub use halo2_dynamic_sha256;
 #[cfg(feature = "sha256")]
 use halo2_dynamic_sha256::Sha256DynamicConfig;
+pub use hash_algo::*;
 pub use instructions::*;
 #[cfg(feature = "sha256")]
+use poseidon::StandardPoseidonChip;
+#[cfg(feature = "sha256")]
+use sha2::Digest;
 
 /// A parameter `e` in the RSA public key that is about to be assigned.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RSAPubE {
     /// A variable parameter `e`.
-    Var(Value<BigUint>),
+    Var(
+        #[cfg_attr(feature = "serde", serde(with = "serde_utils::value_biguint_bytes"))]
+        Value<BigUint>,
+    ),
     /// A fixed parameter `e`.
-    Fix(BigUint),
+    Fix(#[cfg_attr(feature = "serde", serde(with = "serde_utils::biguint_bytes"))] BigUint),
 }
 
 /// A parameter `e` in the assigned RSA public key.
@@ -87,11 +117,14 @@ pub enum AssignedRSAPubE<'v, F: PrimeField> {
 
 /// RSA public key that is about to be assigned.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RSAPublicKey<F: PrimeField> {
     /// a modulus parameter
+    #[cfg_attr(feature = "serde", serde(with = "serde_utils::value_biguint_bytes"))]
     pub n: Value<BigUint>,
     /// an exponent parameter
     pub e: RSAPubE,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _f: PhantomData<F>,
 }
 
@@ -112,6 +145,46 @@ impl<F: PrimeField> RSAPublicKey<F> {
         }
     }
 
+    /// Creates new [`RSAPublicKey`] from a DER-encoded SubjectPublicKeyInfo, e.g. of the UIDAI signing certificate.
+    ///
+    /// # Arguments
+    /// * bytes - the DER encoding of a SubjectPublicKeyInfo structure of a RSA public key.
+    ///
+    /// # Return values
+    /// Returns new [`RSAPublicKey`] whose `e` is [`RSAPubE::Fix`] if it equals `65537`, and [`RSAPubE::Var`] otherwise.
+    /// Returns [`RsaCircuitError::InvalidKey`] if `bytes` is not a valid encoding.
+    pub fn from_der(bytes: &[u8]) -> Result<Self, RsaCircuitError> {
+        let public_key =
+            RsaPublicKey::from_public_key_der(bytes).map_err(|_| RsaCircuitError::InvalidKey)?;
+        Ok(Self::from_rsa_public_key(&public_key))
+    }
+
+    /// Creates new [`RSAPublicKey`] from a PEM-encoded SubjectPublicKeyInfo, i.e. a `-----BEGIN PUBLIC KEY-----` block.
+    ///
+    /// # Arguments
+    /// * pem - the PEM encoding of a SubjectPublicKeyInfo structure of a RSA public key.
+    ///
+    /// # Return values
+    /// Returns new [`RSAPublicKey`] as [`RSAPublicKey::from_der`].
+    /// Returns [`RsaCircuitError::InvalidKey`] if `pem` is not a valid encoding.
+    #[cfg(feature = "pem")]
+    pub fn from_pem(pem: &str) -> Result<Self, RsaCircuitError> {
+        let public_key =
+            RsaPublicKey::from_public_key_pem(pem).map_err(|_| RsaCircuitError::InvalidKey)?;
+        Ok(Self::from_rsa_public_key(&public_key))
+    }
+
+    fn from_rsa_public_key(public_key: &RsaPublicKey) -> Self {
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let e = BigUint::from_bytes_be(&public_key.e().to_bytes_be());
+        let e = if e == BigUint::from(65537u64) {
+            RSAPubE::Fix(e)
+        } else {
+            RSAPubE::Var(Value::known(e))
+        };
+        Self::new(Value::known(n), e)
+    }
+
     pub fn without_witness(fix_e: BigUint) -> Self {
         let n = Value::unknown();
         let e = RSAPubE::Fix(fix_e);
@@ -144,13 +217,75 @@ impl<'v, F: PrimeField> AssignedRSAPublicKey<'v, F> {
     pub fn new(n: AssignedBigUint<'v, F, Fresh>, e: AssignedRSAPubE<'v, F>) -> Self {
         Self { n, e }
     }
+
+    /// Returns the cells of the public key, which outlive the region it is assigned in.
+    pub fn cells(&self) -> RSAPublicKeyCells {
+        let e = match &self.e {
+            AssignedRSAPubE::Var(e) => Some(e.cell()),
+            AssignedRSAPubE::Fix(_) => None,
+        };
+        RSAPublicKeyCells {
+            n: self.n.limbs().iter().map(|limb| limb.cell()).collect(),
+            e,
+        }
+    }
+}
+
+/// Cells of an assigned RSA public key to be exposed by [`RSAConfig::expose_public_key_instance`].
+#[derive(Clone, Debug)]
+pub struct RSAPublicKeyCells {
+    /// the limbs of the modulus parameter
+    pub n: Vec<Cell>,
+    /// a variable exponent parameter. It is `None` for a fixed `e`, which is a constant of the circuit and thus already bound by the verifying key.
+    pub e: Option<Cell>,
+}
+
+/// An intermediate state of the right-to-left modular power `x^e mod n` computed by [`RSAConfig::modpow_ladder_steps`].
+///
+/// After the `num_bits` least significant bits of `e` are processed, `acc = x^(e mod 2^num_bits) mod n` and `squared = x^(2^num_bits) mod n`.
+/// The state can be exposed by [`RSAConfig::expose_modpow_state_instance`] and re-imported by [`RSAConfig::assign_modpow_state`] in a follow-up circuit continuing the ladder.
+#[derive(Clone, Debug)]
+pub struct AssignedModPowState<'v, F: PrimeField> {
+    /// the product of the processed powers, i.e. `x^(e mod 2^num_bits) mod n`.
+    pub acc: AssignedBigUint<'v, F, Fresh>,
+    /// the next power to multiply, i.e. `x^(2^num_bits) mod n`.
+    pub squared: AssignedBigUint<'v, F, Fresh>,
+    /// the number of processed bits of `e`.
+    pub num_bits: usize,
+}
+
+impl<'v, F: PrimeField> AssignedModPowState<'v, F> {
+    /// Returns the cells of the state, which outlive the region it is assigned in.
+    pub fn cells(&self) -> ModPowStateCells {
+        ModPowStateCells {
+            acc: self.acc.limbs().iter().map(|limb| limb.cell()).collect(),
+            squared: self
+                .squared
+                .limbs()
+                .iter()
+                .map(|limb| limb.cell())
+                .collect(),
+        }
+    }
+}
+
+/// Cells of an [`AssignedModPowState`] to be exposed by [`RSAConfig::expose_modpow_state_instance`].
+#[derive(Clone, Debug)]
+pub struct ModPowStateCells {
+    /// the limbs of `acc`
+    pub acc: Vec<Cell>,
+    /// the limbs of `squared`
+    pub squared: Vec<Cell>,
 }
 
 /// RSA signature that is about to be assigned.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RSASignature<F: PrimeField> {
     /// an integer of the signature.
+    #[cfg_attr(feature = "serde", serde(with = "serde_utils::value_biguint_bytes"))]
     pub c: Value<BigUint>,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _f: PhantomData<F>,
 }
 
@@ -172,6 +307,102 @@ impl<F: PrimeField> RSASignature<F> {
     }
 }
 
+/// Wipes the signature witness.
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> zeroize::Zeroize for RSASignature<F> {
+    fn zeroize(&mut self) {
+        utils::zeroize_value_biguint(&mut self.c);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Drop for RSASignature<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
+/// The CRT components of a RSA private key that are about to be assigned, for [`RSAConfig::verify_pkcs1v15_signature_crt`].
+///
+/// The private exponents `dp` and `dq` are only used to sign, so verifying with the public exponent needs the primes and `qinv` alone.
+#[derive(Clone, Debug)]
+pub struct RSACrtComponents<F: PrimeField> {
+    /// the first prime factor of the modulus.
+    pub p: Value<BigUint>,
+    /// the second prime factor of the modulus.
+    pub q: Value<BigUint>,
+    /// the CRT coefficient `q^{-1} mod p`.
+    pub qinv: Value<BigUint>,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> RSACrtComponents<F> {
+    /// Creates new [`RSACrtComponents`] from the prime factors and the CRT coefficient.
+    ///
+    /// # Arguments
+    /// * p - the first prime factor of the modulus.
+    /// * q - the second prime factor of the modulus.
+    /// * qinv - the CRT coefficient `q^{-1} mod p`.
+    ///
+    /// # Return values
+    /// Returns new [`RSACrtComponents`].
+    pub fn new(p: Value<BigUint>, q: Value<BigUint>, qinv: Value<BigUint>) -> Self {
+        Self {
+            p,
+            q,
+            qinv,
+            _f: PhantomData,
+        }
+    }
+
+    /// Creates new [`RSACrtComponents`] from a two-prime RSA private key.
+    ///
+    /// # Arguments
+    /// * private_key - a RSA private key.
+    ///
+    /// # Return values
+    /// Returns new [`RSACrtComponents`].
+    /// Returns [`RsaCircuitError::InvalidKey`] if the key does not consist of two distinct primes.
+    pub fn from_private_key(private_key: &RsaPrivateKey) -> Result<Self, RsaCircuitError> {
+        let primes = private_key
+            .primes()
+            .iter()
+            .map(|prime| BigUint::from_bytes_be(&prime.to_bytes_be()))
+            .collect::<Vec<_>>();
+        if primes.len() != 2 {
+            return Err(RsaCircuitError::InvalidKey);
+        }
+        let (p, q) = (primes[0].clone(), primes[1].clone());
+        let qinv = q.modinv(&p).ok_or(RsaCircuitError::InvalidKey)?;
+        Ok(Self::new(
+            Value::known(p),
+            Value::known(q),
+            Value::known(qinv),
+        ))
+    }
+
+    pub fn without_witness() -> Self {
+        Self::new(Value::unknown(), Value::unknown(), Value::unknown())
+    }
+}
+
+/// Wipes the prime factors and the CRT coefficient, which reveal the private key.
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> zeroize::Zeroize for RSACrtComponents<F> {
+    fn zeroize(&mut self) {
+        for value in [&mut self.p, &mut self.q, &mut self.qinv] {
+            utils::zeroize_value_biguint(value);
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<F: PrimeField> Drop for RSACrtComponents<F> {
+    fn drop(&mut self) {
+        zeroize::Zeroize::zeroize(self);
+    }
+}
+
 /// An assigned RSA signature.
 #[derive(Clone, Debug)]
 pub struct AssignedRSASignature<'v, F: PrimeField> {
@@ -192,12 +423,131 @@ impl<'v, F: PrimeField> AssignedRSASignature<'v, F> {
     }
 }
 
+/// The byte length of a SHA256 hash.
+#[cfg(feature = "sha256")]
+pub const SHA256_HASH_LEN: usize = 32;
+
+/// Returns the byte length of a `msg_len`-byte message after SHA256 padding.
+///
+/// [`Sha256DynamicConfig`] computes every block up to the maximum byte size of a slot and selects the digest at the real message length.
+/// Sizing a slot with this function thus hashes the message with the fewest blocks, e.g. a QR payload without a photo takes much fewer blocks than one with a photo.
+#[cfg(feature = "sha256")]
+pub const fn sha256_padded_len(msg_len: usize) -> usize {
+    // 0x80 and the 64-bit message length follow the message.
+    (msg_len + 9 + 63) / 64 * 64
+}
+
+/// Computes the commitment of [`RSASignatureVerifier::verify_pkcs1v15_signature_with_signal`] off-circuit.
+///
+/// # Arguments
+/// * signal - a signal hash.
+/// * msg - signed message bytes.
+///
+/// # Return values
+/// Returns `Poseidon(signal, packed SHA256 hash bytes of msg...)`, or an error under the conditions of [`poseidon::poseidon_hash`].
+#[cfg(feature = "sha256")]
+pub fn signal_commitment_native<F: PrimeField>(signal: F, msg: &[u8]) -> Result<F, Error> {
+    let hash = sha2::Sha256::digest(msg);
+    let mut inputs = vec![signal];
+    inputs.extend(utils::pack_bytes_native::<F>(&hash));
+    poseidon::poseidon_hash(&inputs)
+}
+
+/// A circuit implementation to verify pkcs1v15 signatures of messages hashed with SHA256 off-circuit.
+///
+/// Unlike [`RSASignatureVerifier`], it does not depend on the `sha256` feature and thus on `halo2_dynamic_sha256`.
+/// The caller is responsible for binding the hash to the message, e.g. by exposing it as a public input.
+#[derive(Clone, Debug)]
+pub struct RSAHashlessVerifier<F: PrimeField> {
+    rsa_config: RSAConfig<F>,
+}
+
+impl<F: PrimeField> RSAHashlessVerifier<F> {
+    /// Creates new [`RSAHashlessVerifier`].
+    ///
+    /// # Arguments
+    /// * rsa_config - a [`RSAConfig`].
+    ///
+    /// # Return values
+    /// Returns new [`RSAHashlessVerifier`].
+    pub fn new(rsa_config: RSAConfig<F>) -> Self {
+        Self { rsa_config }
+    }
+
+    /// Assigns a SHA256 hash computed off-circuit as limbs for [`RSAHashlessVerifier::verify_pkcs1v15_signature`].
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * hash - the SHA256 hash of the signed message in its digest order.
+    ///
+    /// # Return values
+    /// Returns the range checked limbs of the hash in the limb bits of the [`RSAConfig`], least significant first.
+    /// Returns [`Error::Synthesis`] if `hash` is not 32 bytes long.
+    pub fn assign_hashed_msg<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        hash: &[u8],
+    ) -> Result<Vec<AssignedValue<'v, F>>, Error> {
+        let hash_bits = RsaHashAlgo::Sha256.output_len() * 8;
+        if hash.len() * 8 != hash_bits {
+            return Err(Error::Synthesis);
+        }
+        let biguint_config = self.rsa_config.biguint_config();
+        let limb_bits = biguint_config.limb_bits();
+        let hashed_int = biguint_config.assign_integer(
+            ctx,
+            Value::known(BigUint::from_bytes_be(hash)),
+            (hash_bits + limb_bits - 1) / limb_bits * limb_bits,
+        )?;
+        Ok(hashed_int.limbs().to_vec())
+    }
+
+    /// Given a RSA public key, a message hashed with SHA256, and a pkcs1v15 signature, verifies the signature with [`RSAConfig::verify_pkcs1v15_signature`].
+    ///
+    /// # Arguments
+    /// * ctx - a region context.
+    /// * public_key - an assigned public key used for the verification.
+    /// * hashed_msg - the limbs of the hash, least significant first, e.g. as returned by [`RSAHashlessVerifier::assign_hashed_msg`].
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>`.
+    /// If `signature` is valid for `public_key` and `hashed_msg`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    pub fn verify_pkcs1v15_signature<'v>(
+        &self,
+        ctx: &mut Context<'v, F>,
+        public_key: &AssignedRSAPublicKey<'v, F>,
+        hashed_msg: &[AssignedValue<'v, F>],
+        signature: &AssignedRSASignature<'v, F>,
+    ) -> Result<AssignedValue<'v, F>, Error> {
+        self.rsa_config
+            .verify_pkcs1v15_signature(ctx, public_key, hashed_msg, signature)
+    }
+}
+
+/// Computes the commitment of [`RSASignatureVerifier::commit_public_key`] off-circuit.
+///
+/// # Arguments
+/// * public_key - a RSA public key.
+///
+/// # Return values
+/// Returns the packed SHA256 hash bytes of the modulus, encoded in big-endian on `public_key.size()` bytes.
+#[cfg(feature = "sha256")]
+pub fn public_key_commitment_native<F: PrimeField>(public_key: &RsaPublicKey) -> Vec<F> {
+    let n = public_key.n().to_bytes_be();
+    let mut n_bytes = vec![0u8; public_key.size() - n.len()];
+    n_bytes.extend_from_slice(&n);
+    utils::pack_bytes_native::<F>(&sha2::Sha256::digest(&n_bytes))
+}
+
 #[cfg(feature = "sha256")]
 /// A circuit implementation to verify pkcs1v15 signatures.
 #[derive(Clone, Debug)]
 pub struct RSASignatureVerifier<F: PrimeField> {
     rsa_config: RSAConfig<F>,
-    sha256_config: Sha256DynamicConfig<F>,
+    /// The SHA256 configs in ascending order of their maximum byte size.
+    sha256_configs: Vec<Sha256DynamicConfig<F>>,
 }
 
 #[cfg(feature = "sha256")]
@@ -211,60 +561,610 @@ impl<F: PrimeField> RSASignatureVerifier<F> {
     /// # Return values
     /// Returns new [`RSASignatureVerifier`].
     pub fn new(rsa_config: RSAConfig<F>, sha256_config: Sha256DynamicConfig<F>) -> Self {
+        Self::with_buckets(rsa_config, vec![sha256_config])
+    }
+
+    /// Creates new [`RSASignatureVerifier`] hashing each message with the smallest of several SHA256 configs, i.e. buckets, that fits it.
+    ///
+    /// A SHA256 config computes every block up to its maximum byte size, so a short message hashed in a small bucket takes fewer rows than in a bucket sized for the longest message.
+    /// Each bucket is configured with its own maximum byte sizes, e.g. `vec![sha256_padded_len(300)]` and `vec![sha256_padded_len(3000)]`, and loaded by the circuit.
+    /// Since the bucket used by a message is part of the circuit layout, the proving and verifying keys are specific to it:
+    /// keys generated for a message of one bucket serve every message of the same bucket.
+    ///
+    /// # Arguments
+    /// * rsa_config - a [`RSAConfig`].
+    /// * sha256_configs - the [`Sha256DynamicConfig`] of each bucket, in any order.
+    ///
+    /// # Return values
+    /// Returns new [`RSASignatureVerifier`].
+    pub fn with_buckets(
+        rsa_config: RSAConfig<F>,
+        mut sha256_configs: Vec<Sha256DynamicConfig<F>>,
+    ) -> Self {
+        sha256_configs.sort_by_key(Self::max_byte_size);
         Self {
             rsa_config,
-            sha256_config,
+            sha256_configs,
         }
     }
 
+    /// Returns the index of the bucket hashing a message of `msg_len` bytes, see [`RSASignatureVerifier::with_buckets`].
+    ///
+    /// # Arguments
+    /// * msg_len - the byte length of a message.
+    ///
+    /// # Return values
+    /// Returns the index of the smallest bucket fitting the message after SHA256 padding in ascending order of the bucket sizes, or `None` if the message fits no bucket.
+    pub fn sha256_bucket(&self, msg_len: usize) -> Option<usize> {
+        let padded_len = sha256_padded_len(msg_len);
+        self.sha256_configs
+            .iter()
+            .position(|config| Self::max_byte_size(config) >= padded_len)
+    }
+
     /// Given a RSA public key, signed message bytes, and a pkcs1v15 signature, verifies the signature with SHA256 hash function.
     ///
     /// # Arguments
     /// * public_key - an assigned public key used for the verification.
-    /// * msg - signed message bytes.
+    /// * msg - signed message bytes, hashed in the next slot of the smallest SHA256 bucket fitting it. The slot should be sized by [`sha256_padded_len`].
     /// * signature - a pkcs1v15 signature to be verified.
     ///
     /// # Return values
     /// Returns the assigned bit as `AssignedValue<F>`.
     /// If `signature` is valid for `public_key` and `msg`, the bit is equivalent to one.
     /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`RsaCircuitError::BadLength`] with the largest bucket size if `msg` does not fit any bucket after padding, see [`RSASignatureVerifier::sha256_bucket`].
     pub fn verify_pkcs1v15_signature<'a, 'b: 'a>(
         &'a mut self,
         ctx: &mut Context<'b, F>,
         public_key: &AssignedRSAPublicKey<'b, F>,
         msg: &'a [u8],
         signature: &AssignedRSASignature<'b, F>,
-    ) -> Result<(AssignedValue<'b, F>, Vec<AssignedValue<'b, F>>), Error> {
-        let sha256 = &mut self.sha256_config;
+    ) -> Result<(AssignedValue<'b, F>, Vec<AssignedValue<'b, F>>), RsaCircuitError> {
+        let bucket = self.bucket_for(msg.len())?;
+        let result = self.sha256_configs[bucket].digest(ctx, msg, None)?;
+        Ok(self.verify_hashed_bytes(ctx, public_key, result.output_bytes, signature)?)
+    }
+
+    /// Given a RSA public key, signed message bytes, associated data, and a pkcs1v15 signature, verifies the signature of `SHA256(msg || associated_data)`.
+    ///
+    /// The associated data, e.g. an application context, is appended to the message without a separator or a length prefix,
+    /// i.e. the digest input is the bytes of `msg` followed by the bytes of `associated_data`, as the signer hashes them.
+    /// The boundary between both is thus fixed by the circuit layout rather than by the signed bytes.
+    ///
+    /// # Arguments
+    /// * public_key - an assigned public key used for the verification.
+    /// * msg - signed message bytes.
+    /// * associated_data - the bytes signed after `msg`.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>`, the assigned bytes of the hash, and the assigned bytes of `associated_data` in the digest input, e.g. to be exposed as public inputs.
+    /// If `signature` is valid for `public_key` and `msg || associated_data`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`RsaCircuitError::BadLength`] with the largest bucket size if `msg || associated_data` does not fit any bucket after padding, see [`RSASignatureVerifier::sha256_bucket`].
+    pub fn verify_pkcs1v15_signature_with_associated_data<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        msg: &'a [u8],
+        associated_data: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<
+        (
+            AssignedValue<'b, F>,
+            Vec<AssignedValue<'b, F>>,
+            Vec<AssignedValue<'b, F>>,
+        ),
+        RsaCircuitError,
+    > {
+        let input = [msg, associated_data].concat();
+        let bucket = self.bucket_for(input.len())?;
+        let result = self.sha256_configs[bucket].digest(ctx, &input, None)?;
+        let associated_bytes = result.input_bytes[msg.len()..input.len()].to_vec();
+        let (is_valid, hashed_bytes) =
+            self.verify_hashed_bytes(ctx, public_key, result.output_bytes, signature)?;
+        Ok((is_valid, hashed_bytes, associated_bytes))
+    }
+
+    /// Given a RSA public key, signed message bytes, a pkcs1v15 signature, and a signal, verifies the signature with SHA256 hash function and binds the signal to the hash.
+    ///
+    /// The returned commitment is `Poseidon(signal, packed hash bytes...)`.
+    /// Once the caller exposes both the signal and the commitment as public inputs, a proof is only valid for the signal it was generated with,
+    /// i.e. changing the signal invalidates a previously generated proof and the proof cannot be replayed in another application context.
+    ///
+    /// # Arguments
+    /// * poseidon - a [`StandardPoseidonChip`].
+    /// * public_key - an assigned public key used for the verification.
+    /// * msg - signed message bytes, hashed in the next slot of the smallest SHA256 bucket fitting it.
+    /// * signature - a pkcs1v15 signature to be verified.
+    /// * signal - an assigned signal hash, e.g. of the application context. It should be exposed as a public input by the caller.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>` and the assigned commitment, which equals [`signal_commitment_native`] for the same signal and message.
+    /// If `signature` is valid for `public_key` and `msg`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    pub fn verify_pkcs1v15_signature_with_signal<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        poseidon: &StandardPoseidonChip<F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        msg: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+        signal: &AssignedValue<'b, F>,
+    ) -> Result<(AssignedValue<'b, F>, AssignedValue<'b, F>), RsaCircuitError> {
+        let (is_valid, hashed_bytes) =
+            self.verify_pkcs1v15_signature(ctx, public_key, msg, signature)?;
+        let gate = self.rsa_config.gate();
+        let mut inputs = vec![signal.clone()];
+        inputs.extend(utils::pack_bytes(gate, ctx, &hashed_bytes));
+        let commitment = poseidon.hash(gate, ctx, &inputs);
+        Ok((is_valid, commitment))
+    }
+
+    /// Commits to the modulus of a RSA public key with its SHA256 hash, to be exposed as a public input instead of the limbs of the modulus.
+    ///
+    /// Exposing a 2048-bit modulus in 64-bit limbs takes 32 instance values, each of which the verifier reads and folds into the instance column evaluation,
+    /// e.g. 32 words of calldata for the contract of [`evm::gen_evm_verifier`], whereas the packed hash takes 2 of them.
+    /// In exchange, the modulus is decomposed into `bits_len / 8` range checked bytes and hashed in a slot of `sha256_padded_len(bits_len / 8)` bytes,
+    /// i.e. 5 more SHA256 blocks for a 2048-bit modulus.
+    /// `cargo bench --bench rsa_signature_with_hash` prints the cells of both ways for comparison.
+    ///
+    /// # Arguments
+    /// * public_key - an assigned public key whose modulus is committed to.
+    ///
+    /// # Return values
+    /// Returns the SHA256 hash of the big-endian bytes of `n` packed by [`utils::pack_bytes`], which equals [`public_key_commitment_native`] for the same key.
+    /// A variable `e` is not committed to and should be exposed separately by the caller.
+    /// Returns [`RsaCircuitError::BadLength`] with the largest bucket size if the bytes of `n` do not fit any bucket after padding, see [`RSASignatureVerifier::sha256_bucket`].
+    pub fn commit_public_key<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+    ) -> Result<Vec<AssignedValue<'b, F>>, RsaCircuitError> {
+        let n_bytes =
+            self.rsa_config
+                .biguint_config()
+                .to_assigned_bytes(ctx, &public_key.n, Endianness::Big);
+        let bucket = self.bucket_for(n_bytes.len())?;
+        let hashed = self.sha256_configs[bucket].digest(
+            ctx,
+            &utils::assigned_bytes_to_vec(&n_bytes),
+            None,
+        )?;
+        let gate = self.rsa_config.gate();
+        gate.assert_is_const(ctx, &hashed.input_len, F::from(n_bytes.len() as u64));
+        for (hashed, byte) in hashed.input_bytes.iter().zip(n_bytes.iter()) {
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(hashed),
+                QuantumCell::Existing(byte),
+            );
+        }
+        Ok(utils::pack_bytes(gate, ctx, &hashed.output_bytes))
+    }
+
+    /// Given a RSA public key and pairs of signed message bytes and pkcs1v15 signatures, verifies every signature with SHA256 hash function.
+    ///
+    /// This is a convenience loop over [`RSASignatureVerifier::verify_pkcs1v15_signature`] and saves no constraints:
+    /// every signature still costs its own SHA256 digest and modular power, exactly as separate calls would.
+    ///
+    /// # Arguments
+    /// * public_key - an assigned public key used for every verification.
+    /// * signed_msgs - pairs of signed message bytes and pkcs1v15 signatures.
+    ///   Each message is hashed in the smallest SHA256 bucket fitting it after padding, see [`RSASignatureVerifier::sha256_bucket`],
+    ///   and takes the next free slot of that bucket, so messages of similar lengths share a bucket and its slots.
+    ///
+    /// # Return values
+    /// Returns the assigned bits as `AssignedValue<F>`, one per pair in order.
+    /// If the signature of a pair is valid for `public_key` and its message, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`RsaCircuitError::BadLength`] with the largest bucket size if a message does not fit any bucket after padding.
+    pub fn verify_pkcs1v15_signatures<'b>(
+        &mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        signed_msgs: &[(&[u8], AssignedRSASignature<'b, F>)],
+    ) -> Result<Vec<AssignedValue<'b, F>>, RsaCircuitError> {
+        let mut is_valids = vec![];
+        for (msg, signature) in signed_msgs.iter() {
+            let (is_valid, _) = self.verify_pkcs1v15_signature(ctx, public_key, msg, signature)?;
+            is_valids.push(is_valid);
+        }
+        Ok(is_valids)
+    }
+
+    /// Given candidate RSA public keys, signed message bytes, and a pkcs1v15 signature, verifies the signature under any of the keys with SHA256 hash function.
+    ///
+    /// UIDAI has rotated its signing certificates, so a QR code may be signed by any key of a small set.
+    /// The message is hashed once, and the signature is verified under every key, each costing a modular power.
+    ///
+    /// # Arguments
+    /// * keys - assigned candidate public keys, e.g. exposed by [`RSAConfig::expose_public_key_instance`] or fixed by the circuit.
+    /// * msg - signed message bytes, hashed in the next slot of the smallest SHA256 bucket fitting it.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>`, the assigned index of the matching key, and the assigned bytes of the hash of `msg`.
+    /// If `signature` is valid for `msg` under at least one of `keys`, the bit is equivalent to one and the index is that of the first such key.
+    /// Otherwise, the bit and the index are equivalent to zero.
+    /// Returns [`RsaCircuitError::Halo2`] if `keys` is empty, and [`RsaCircuitError::BadLength`] if `msg` does not fit any bucket after padding.
+    pub fn verify_against_keys<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        keys: &[AssignedRSAPublicKey<'b, F>],
+        msg: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<
+        (
+            AssignedValue<'b, F>,
+            AssignedValue<'b, F>,
+            Vec<AssignedValue<'b, F>>,
+        ),
+        RsaCircuitError,
+    > {
+        if keys.is_empty() {
+            return Err(Error::Synthesis.into());
+        }
+        let bucket = self.bucket_for(msg.len())?;
+        let hashed_bytes = self.sha256_configs[bucket]
+            .digest(ctx, msg, None)?
+            .output_bytes;
+        let gate = self.rsa_config.gate();
+        let mut is_found = gate.load_zero(ctx);
+        let mut index = gate.load_zero(ctx);
+        for (i, public_key) in keys.iter().enumerate() {
+            let (is_valid, _) =
+                self.verify_hashed_bytes(ctx, public_key, hashed_bytes.clone(), signature)?;
+            // The index is only updated at the first valid key.
+            let is_not_found = gate.not(ctx, QuantumCell::Existing(&is_found));
+            let is_first = gate.and(
+                ctx,
+                QuantumCell::Existing(&is_valid),
+                QuantumCell::Existing(&is_not_found),
+            );
+            index = gate.select(
+                ctx,
+                QuantumCell::Constant(F::from(i as u64)),
+                QuantumCell::Existing(&index),
+                QuantumCell::Existing(&is_first),
+            );
+            is_found = gate.or(
+                ctx,
+                QuantumCell::Existing(&is_found),
+                QuantumCell::Existing(&is_valid),
+            );
+        }
+        Ok((is_found, index, hashed_bytes))
+    }
+
+    /// Given a RSA public key, a HMAC key, signed message bytes, and a pkcs1v15 signature, verifies the signature for the HMAC-SHA256 tag of the message.
+    ///
+    /// # Arguments
+    /// * public_key - an assigned public key used for the verification.
+    /// * hmac_key - assigned bytes of the HMAC key shared with the signer, at most [`hmac::HMAC_BLOCK_LEN`] bytes.
+    /// * msg - signed message bytes.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>` and the assigned bytes of the HMAC tag.
+    /// If `signature` is valid for `public_key` and the HMAC tag of `msg`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    pub fn verify_pkcs1v15_hmac_signature<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        hmac_key: &[AssignedValue<'b, F>],
+        msg: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<(AssignedValue<'b, F>, Vec<AssignedValue<'b, F>>), RsaCircuitError> {
+        let bucket = self.bucket_for(hmac::HMAC_BLOCK_LEN + msg.len())?;
+        let tag = hmac::hmac_sha256(
+            self.rsa_config.range(),
+            &mut self.sha256_configs[bucket],
+            ctx,
+            hmac_key,
+            msg,
+        )?;
+        Ok(self.verify_hashed_bytes(ctx, public_key, tag, signature)?)
+    }
+
+    /// Given a RSA public key, signed message bytes, and a pkcs1v15 signature, verifies the signature with the hash function `hash_algo`.
+    ///
+    /// Only [`RsaHashAlgo::Sha256`] has an in-circuit digest chip.
+    /// For the other algorithms, hash the message with a dedicated chip and pass the hash to [`RSAInstructions::verify_pkcs1v15_signature_with_hash`].
+    ///
+    /// # Arguments
+    /// * public_key - an assigned public key used for the verification.
+    /// * hash_algo - the hash algorithm.
+    /// * msg - signed message bytes.
+    /// * signature - a pkcs1v15 signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>` and the assigned bytes of the hash of `msg`.
+    /// If `signature` is valid for `public_key` and `msg`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`RsaCircuitError::UnsupportedHash`] if `hash_algo` has no in-circuit digest chip.
+    pub fn verify_pkcs1v15_signature_with_hash<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        hash_algo: RsaHashAlgo,
+        msg: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<(AssignedValue<'b, F>, Vec<AssignedValue<'b, F>>), RsaCircuitError> {
+        let hashed_bytes = match hash_algo {
+            RsaHashAlgo::Sha256 => {
+                let bucket = self.bucket_for(msg.len())?;
+                self.sha256_configs[bucket]
+                    .digest(ctx, msg, None)?
+                    .output_bytes
+            }
+            RsaHashAlgo::Sha1 | RsaHashAlgo::Sha384 | RsaHashAlgo::Sha512 => {
+                return Err(RsaCircuitError::UnsupportedHash(hash_algo))
+            }
+        };
+        let is_sign_valid = self.rsa_config.verify_pkcs1v15_signature_with_hash(
+            ctx,
+            public_key,
+            hash_algo,
+            &hashed_bytes,
+            signature,
+        )?;
+        Ok((is_sign_valid, hashed_bytes))
+    }
+
+    /// Given a RSA public key, signed message bytes, and a RSASSA-PSS signature, verifies the signature with SHA256 hash function and MGF1 with SHA256.
+    ///
+    /// The salt length is [`RSAConfig::pss_salt_len`].
+    /// The modulus is assumed to have exactly [`RSAConfig::default_bits`] bits, a multiple of 8, so the encoded message has `default_bits / 8` bytes.
+    /// Besides the slot for `msg`, the SHA256 config must have free slots for `ceil((em_len - 33) / 32)` MGF1 blocks of 36 bytes and for `40 + salt_len` bytes in this order,
+    /// where `em_len` is the byte length of the encoded message.
+    ///
+    /// # Arguments
+    /// * public_key - an assigned public key used for the verification.
+    /// * msg - signed message bytes.
+    /// * signature - a RSASSA-PSS signature to be verified.
+    ///
+    /// # Return values
+    /// Returns the assigned bit as `AssignedValue<F>` and the assigned bytes of the SHA256 hash of `msg`.
+    /// If `signature` is valid for `public_key` and `msg`, the bit is equivalent to one.
+    /// Otherwise, the bit is equivalent to zero.
+    /// Returns [`RsaCircuitError::Halo2`] if the encoded message is too short for the salt length, and [`RsaCircuitError::BadLength`] if a hash input does not fit any bucket after padding.
+    pub fn verify_pss_signature<'a, 'b: 'a>(
+        &'a mut self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        msg: &'a [u8],
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<(AssignedValue<'b, F>, Vec<AssignedValue<'b, F>>), RsaCircuitError> {
         let rsa = self.rsa_config.clone();
-        let biguint = &rsa.biguint_config();
-        let result = sha256.digest(ctx, msg, None)?;
-        let mut hashed_bytes = result.output_bytes;
-        hashed_bytes.reverse();
-        let bytes_bits = hashed_bytes.len() * 8;
-        let limb_bits = biguint.limb_bits();
-        let limb_bytes = limb_bits / 8;
-        let mut hashed_u64s = vec![];
-        let bases = (0..limb_bytes)
-            .map(|i| F::from((1u64 << (8 * i)) as u64))
-            .map(QuantumCell::Constant)
-            .collect::<Vec<QuantumCell<F>>>();
-        for i in 0..(bytes_bits / limb_bits) {
-            let left = hashed_bytes[limb_bytes * i..limb_bytes * (i + 1)]
+        let gate = rsa.gate();
+        let salt_len = rsa.pss_salt_len();
+        let em_len = rsa.default_bits() / 8;
+        if em_len < SHA256_HASH_LEN + salt_len + 2 {
+            return Err(Error::Synthesis.into());
+        }
+        let db_len = em_len - SHA256_HASH_LEN - 1;
+        let bucket = self.bucket_for(msg.len())?;
+        let m_hash = self.sha256_configs[bucket]
+            .digest(ctx, msg, None)?
+            .output_bytes;
+
+        // EM = maskedDB || H || 0xbc, as bytes in big-endian order whose bits are in little-endian order.
+        let powed = rsa.modpow_public_key(ctx, &signature.c, public_key)?;
+        let limb_bits = rsa.biguint_config().limb_bits();
+        let mut le_bits = vec![];
+        for limb in powed.limbs().iter() {
+            le_bits.extend(gate.num_to_bits(ctx, limb, limb_bits));
+        }
+        let em_bits = le_bits.chunks(8).rev().collect::<Vec<_>>();
+        let last_byte = utils::bits_to_num(gate, ctx, em_bits[em_len - 1]);
+        let mut is_eq = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(&last_byte),
+            QuantumCell::Constant(F::from(0xbc)),
+        );
+        // The leftmost bit of EM is zero since emBits = 8 * em_len - 1.
+        let is_top_bit_zero = gate.not(ctx, QuantumCell::Existing(&em_bits[0][7]));
+        is_eq = gate.and(
+            ctx,
+            QuantumCell::Existing(&is_eq),
+            QuantumCell::Existing(&is_top_bit_zero),
+        );
+        let h = em_bits[db_len..em_len - 1]
+            .iter()
+            .map(|bits| utils::bits_to_num(gate, ctx, bits))
+            .collect::<Vec<_>>();
+
+        // dbMask = MGF1(H, db_len)
+        let h_values = utils::assigned_bytes_to_vec(&h);
+        let mut db_mask = vec![];
+        for counter in 0..(db_len + SHA256_HASH_LEN - 1) / SHA256_HASH_LEN {
+            let counter_bytes = (counter as u32).to_be_bytes();
+            let input = [&h_values[..], &counter_bytes[..]].concat();
+            let bucket = self.bucket_for(input.len())?;
+            let result = self.sha256_configs[bucket].digest(ctx, &input, None)?;
+            gate.assert_is_const(ctx, &result.input_len, F::from(input.len() as u64));
+            for (hashed, byte) in result.input_bytes.iter().zip(h.iter()) {
+                gate.assert_equal(
+                    ctx,
+                    QuantumCell::Existing(hashed),
+                    QuantumCell::Existing(byte),
+                );
+            }
+            for (hashed, byte) in result.input_bytes[SHA256_HASH_LEN..]
                 .iter()
-                .map(QuantumCell::Existing)
-                .collect::<Vec<QuantumCell<F>>>();
-            let sum = biguint.gate().inner_product(ctx, left, bases.clone());
-            hashed_u64s.push(sum);
+                .zip(counter_bytes)
+            {
+                gate.assert_is_const(ctx, hashed, F::from(byte as u64));
+            }
+            db_mask.extend(result.output_bytes);
         }
-        let is_sign_valid =
-            rsa.verify_pkcs1v15_signature(ctx, public_key, &hashed_u64s, signature)?;
 
-        hashed_bytes.reverse();
+        // DB = maskedDB ^ dbMask with its leftmost bit cleared, which must be PS || 0x01 || salt.
+        let mut db = vec![];
+        for (i, (masked_bits, mask)) in em_bits[..db_len].iter().zip(db_mask.iter()).enumerate() {
+            let mask_bits = gate.num_to_bits(ctx, mask, 8);
+            let num_bits = if i == 0 { 7 } else { 8 };
+            let bits = masked_bits[..num_bits]
+                .iter()
+                .zip(mask_bits.iter())
+                .map(|(a, b)| utils::xor_bits(gate, ctx, a, b))
+                .collect::<Vec<_>>();
+            db.push(utils::bits_to_num(gate, ctx, &bits));
+        }
+        let ps_len = db_len - salt_len - 1;
+        for byte in db[..ps_len].iter() {
+            let is_zero = gate.is_zero(ctx, byte);
+            is_eq = gate.and(
+                ctx,
+                QuantumCell::Existing(&is_eq),
+                QuantumCell::Existing(&is_zero),
+            );
+        }
+        let is_separator = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(&db[ps_len]),
+            QuantumCell::Constant(F::one()),
+        );
+        is_eq = gate.and(
+            ctx,
+            QuantumCell::Existing(&is_eq),
+            QuantumCell::Existing(&is_separator),
+        );
+        let salt = &db[ps_len + 1..];
+
+        // H = SHA256(0x00 * 8 || mHash || salt)
+        let mut input = vec![0u8; 8];
+        input.extend(utils::assigned_bytes_to_vec(&m_hash));
+        input.extend(utils::assigned_bytes_to_vec(salt));
+        let bucket = self.bucket_for(input.len())?;
+        let result = self.sha256_configs[bucket].digest(ctx, &input, None)?;
+        gate.assert_is_const(ctx, &result.input_len, F::from(input.len() as u64));
+        for hashed in result.input_bytes[..8].iter() {
+            gate.assert_is_const(ctx, hashed, F::zero());
+        }
+        for (hashed, byte) in result.input_bytes[8..]
+            .iter()
+            .zip(m_hash.iter().chain(salt.iter()))
+        {
+            gate.assert_equal(
+                ctx,
+                QuantumCell::Existing(hashed),
+                QuantumCell::Existing(byte),
+            );
+        }
+        for (computed, expected) in result.output_bytes.iter().zip(h.iter()) {
+            let is_byte_eq = gate.is_equal(
+                ctx,
+                QuantumCell::Existing(computed),
+                QuantumCell::Existing(expected),
+            );
+            is_eq = gate.and(
+                ctx,
+                QuantumCell::Existing(&is_eq),
+                QuantumCell::Existing(&is_byte_eq),
+            );
+        }
+        Ok((is_eq, m_hash))
+    }
+
+    fn verify_hashed_bytes<'b>(
+        &self,
+        ctx: &mut Context<'b, F>,
+        public_key: &AssignedRSAPublicKey<'b, F>,
+        hashed_bytes: Vec<AssignedValue<'b, F>>,
+        signature: &AssignedRSASignature<'b, F>,
+    ) -> Result<(AssignedValue<'b, F>, Vec<AssignedValue<'b, F>>), Error> {
+        let rsa = self.rsa_config.clone();
+        if rsa.biguint_config().limb_bits() != 64 {
+            // Only 64-bit limbs have a limb-wise comparison, so the bytes are compared directly.
+            let is_sign_valid = rsa.verify_pkcs1v15_signature_with_hash(
+                ctx,
+                public_key,
+                RsaHashAlgo::Sha256,
+                &hashed_bytes,
+                signature,
+            )?;
+            return Ok((is_sign_valid, hashed_bytes));
+        }
+        // The hash is big-endian, while the limbs are little-endian.
+        let hashed_int =
+            rsa.biguint_config()
+                .from_assigned_bytes(ctx, &hashed_bytes, Endianness::Big);
+        let is_sign_valid =
+            rsa.verify_pkcs1v15_signature(ctx, public_key, hashed_int.limbs(), signature)?;
         Ok((is_sign_valid, hashed_bytes))
     }
 }
 
+#[cfg(feature = "sha256")]
+impl<F: PrimeField> RSASignatureVerifier<F> {
+    /// Returns the index of the bucket hashing a message of `msg_len` bytes,
+    /// or [`RsaCircuitError::BadLength`] with the largest bucket size if it fits none.
+    /// The digest chip would otherwise fail on an oversized message with a less helpful error.
+    fn bucket_for(&self, msg_len: usize) -> Result<usize, RsaCircuitError> {
+        self.sha256_bucket(msg_len)
+            .ok_or_else(|| RsaCircuitError::BadLength {
+                len: msg_len,
+                max_len: self.sha256_configs.last().map(Self::max_byte_size),
+            })
+    }
+
+    fn max_byte_size(config: &Sha256DynamicConfig<F>) -> usize {
+        config
+            .max_variable_byte_sizes
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl RSASignatureVerifier<Fr> {
+    /// Estimates the minimal degree `k` of a circuit built on [`RSASignatureVerifier`], see [`RSAConfigBuilder::estimate_k`].
+    ///
+    /// Integrators can synthesize their circuit once at a generous `k`, read [`CircuitUsage::from_context`] at the end of the region,
+    /// and size the circuit to the estimate instead of shrinking `k` until the prover runs out of rows.
+    ///
+    /// # Arguments
+    /// * params - the parameters the circuit is configured with.
+    /// * usage - the cells assigned by one synthesis pass.
+    ///
+    /// # Return values
+    /// Returns the minimal `k`, or the [`RSAConfigBuilderError`] of incompatible parameters.
+    pub fn estimate_k(
+        params: &RSAConfigBuilder,
+        usage: &CircuitUsage,
+    ) -> Result<u32, RSAConfigBuilderError> {
+        params.estimate_k::<Fr>(usage)
+    }
+}
+
+/// Computes the instance columns of a circuit exposing the modulus with [`RSAConfig::expose_public_key_instance`]
+/// and the hash returned by [`RSASignatureVerifier::verify_pkcs1v15_signature`], e.g. `TestRSASignatureWithHashCircuit1`.
+///
+/// # Arguments
+/// * public_key - the RSA public key of the signer.
+/// * msg - the signed message.
+///
+/// # Return values
+/// Returns the 64 bits limbs of the modulus, least significant first, and the bytes of the SHA256 hash of `msg` in their digest order.
+#[cfg(feature = "sha256")]
+pub fn compute_public_inputs<F: PrimeField>(public_key: &RsaPublicKey, msg: &[u8]) -> Vec<Vec<F>> {
+    const LIMB_BITS: usize = 64;
+    let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+    let num_limbs = public_key.size() * 8 / LIMB_BITS;
+    let hash = sha2::Sha256::digest(msg)
+        .iter()
+        .map(|byte| F::from(*byte as u64))
+        .collect();
+    vec![decompose_biguint::<F>(&n, num_limbs, LIMB_BITS), hash]
+}
+
+#[cfg(feature = "sha256")]
 #[derive(Debug, Clone)]
 struct TestRSASignatureWithHashConfig1<F: PrimeField> {
     rsa_config: RSAConfig<F>,
@@ -272,67 +1172,97 @@ struct TestRSASignatureWithHashConfig1<F: PrimeField> {
     n_instance: Column<Instance>,
     hash_instance: Column<Instance>,
 }
+/// A test circuit verifying a 2048-bit pkcs1v15 signature at degree `K` with the given column counts.
+#[cfg(feature = "sha256")]
 #[derive(Debug, Clone)]
-struct TestRSASignatureWithHashCircuit1<F: PrimeField> {
-    private_key: RsaPrivateKey,
-    public_key: RsaPublicKey,
+struct TestRSASignatureWithHashCircuit<
+    F: PrimeField,
+    const K: usize,
+    const NUM_ADVICE: usize,
+    const NUM_LOOKUP_ADVICE: usize,
+> {
+    signature: Value<BigUint>,
+    n: Value<BigUint>,
     msg: Vec<u8>,
+    /// The usage of the last synthesis pass, e.g. for [`RSASignatureVerifier::estimate_k`].
+    usage: std::rc::Rc<std::cell::RefCell<CircuitUsage>>,
     _f: PhantomData<F>,
 }
 
-impl<F: PrimeField> TestRSASignatureWithHashCircuit1<F> {
+#[cfg(feature = "sha256")]
+type TestRSASignatureWithHashCircuit1<F> = TestRSASignatureWithHashCircuit<F, 15, 80, 16>;
+
+#[cfg(feature = "sha256")]
+impl<F: PrimeField, const K: usize, const NUM_ADVICE: usize, const NUM_LOOKUP_ADVICE: usize>
+    TestRSASignatureWithHashCircuit<F, K, NUM_ADVICE, NUM_LOOKUP_ADVICE>
+{
     const BITS_LEN: usize = 2048;
-    const MSG_LEN: usize = 1024;
+    /// The maximum byte length of a signed message.
+    /// The signed data of a real QR payload includes its photo and usually exceeds it, see [`claims::MAX_SIGNED_DATA_LEN`].
+    const MAX_MSG_LEN: usize = 1015;
+    /// The SHA256 slot size fitting [`Self::MAX_MSG_LEN`].
+    const MSG_LEN: usize = sha256_padded_len(Self::MAX_MSG_LEN);
     const EXP_LIMB_BITS: usize = 5;
     const DEFAULT_E: u128 = 65537;
-    const NUM_ADVICE: usize = 80;
     const NUM_FIXED: usize = 1;
-    const NUM_LOOKUP_ADVICE: usize = 16;
     const LOOKUP_BITS: usize = 12;
     const SHA256_LOOKUP_BITS: usize = 8;
     const SHA256_LOOKUP_ADVICE: usize = 8;
-}
 
-impl<F: PrimeField> TestRSASignatureWithHashCircuit1<F> {
+    /// Creates a circuit verifying the signature of `msg` by `private_key`.
     pub fn new(private_key: RsaPrivateKey, public_key: RsaPublicKey, msg: Vec<u8>) -> Self {
+        let signing_key = SigningKey::<rsa::sha2::Sha256>::new(private_key);
+        let signature = BigUint::from_bytes_be(&signing_key.sign(&msg).to_vec());
         Self {
-            private_key,
-            public_key,
+            signature: Value::known(signature),
+            n: Value::known(BigUint::from_bytes_be(&public_key.n().to_bytes_be())),
             msg,
+            usage: Default::default(),
             _f: PhantomData,
         }
     }
 }
 
-impl<F: PrimeField> Circuit<F> for TestRSASignatureWithHashCircuit1<F> {
+#[cfg(all(feature = "sha256", feature = "zeroize"))]
+impl<F: PrimeField, const K: usize, const NUM_ADVICE: usize, const NUM_LOOKUP_ADVICE: usize> Drop
+    for TestRSASignatureWithHashCircuit<F, K, NUM_ADVICE, NUM_LOOKUP_ADVICE>
+{
+    fn drop(&mut self) {
+        utils::zeroize_value_biguint(&mut self.signature);
+    }
+}
+
+#[cfg(feature = "sha256")]
+impl<F: PrimeField, const K: usize, const NUM_ADVICE: usize, const NUM_LOOKUP_ADVICE: usize>
+    Circuit<F> for TestRSASignatureWithHashCircuit<F, K, NUM_ADVICE, NUM_LOOKUP_ADVICE>
+{
     type Config = TestRSASignatureWithHashConfig1<F>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        unimplemented!();
+        Self {
+            signature: Value::unknown(),
+            n: Value::unknown(),
+            msg: vec![0; self.msg.len()],
+            usage: self.usage.clone(),
+            _f: PhantomData,
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let range_config = RangeConfig::configure(
-            meta,
-            Vertical,
-            &[Self::NUM_ADVICE],
-            &[Self::NUM_LOOKUP_ADVICE],
-            Self::NUM_FIXED,
-            Self::LOOKUP_BITS,
-            0,
-            15,
-        );
-        let bigint_config = BigUintConfig::construct(range_config.clone(), 64);
-        let rsa_config = RSAConfig::construct(bigint_config, Self::BITS_LEN, Self::EXP_LIMB_BITS);
-        let sha256_config = Sha256DynamicConfig::configure(
-            meta,
-            vec![Self::MSG_LEN],
-            range_config,
-            Self::SHA256_LOOKUP_BITS,
-            Self::SHA256_LOOKUP_ADVICE,
-            true,
-        );
+        let (rsa_config, sha256_config) = RSAConfigBuilder::new()
+            .k(K)
+            .bits_len(Self::BITS_LEN)
+            .exp_limb_bits(Self::EXP_LIMB_BITS)
+            .num_advice(NUM_ADVICE)
+            .num_lookup_advice(NUM_LOOKUP_ADVICE)
+            .num_fixed(Self::NUM_FIXED)
+            .lookup_bits(Self::LOOKUP_BITS)
+            .sha256_max_byte_sizes(vec![Self::MSG_LEN])
+            .sha256_lookup_bits(Self::SHA256_LOOKUP_BITS)
+            .sha256_lookup_advice(Self::SHA256_LOOKUP_ADVICE)
+            .build(meta)
+            .unwrap_or_else(|e| panic!("invalid configuration: {e}"));
         let n_instance = meta.instance_column();
         let hash_instance = meta.instance_column();
         meta.enable_equality(n_instance);
@@ -359,24 +1289,18 @@ impl<F: PrimeField> Circuit<F> for TestRSASignatureWithHashCircuit1<F> {
             |region| {
                 if first_pass {
                     first_pass = false;
-                    return Ok((vec![], vec![]));
+                    return Ok((None, vec![]));
                 }
 
                 let mut aux = biguint_config.new_context(region);
                 let ctx = &mut aux;
-                let signing_key = SigningKey::<rsa::sha2::Sha256>::new(self.private_key.clone());
-                let sign = signing_key.sign(&self.msg).to_vec();
-                let sign_big = BigUint::from_bytes_be(&sign);
                 let sign = config
                     .rsa_config
-                    .assign_signature(ctx, RSASignature::new(Value::known(sign_big)))?;
-                let n_big =
-                    BigUint::from_radix_le(&self.public_key.n().clone().to_radix_le(16), 16)
-                        .unwrap();
+                    .assign_signature(ctx, RSASignature::new(self.signature.clone()))?;
                 let e_fix = RSAPubE::Fix(BigUint::from(Self::DEFAULT_E));
                 let public_key = config
                     .rsa_config
-                    .assign_public_key(ctx, RSAPublicKey::new(Value::known(n_big), e_fix))?;
+                    .assign_public_key(ctx, RSAPublicKey::new(self.n.clone(), e_fix))?;
                 let mut verifier = RSASignatureVerifier::new(
                     config.rsa_config.clone(),
                     config.sha256_config.clone(),
@@ -387,27 +1311,22 @@ impl<F: PrimeField> Circuit<F> for TestRSASignatureWithHashCircuit1<F> {
                     .gate()
                     .assert_is_const(ctx, &is_valid, F::one());
                 biguint_config.range().finalize(ctx);
-                {
-                    println!("total advice cells: {}", ctx.total_advice);
-                    let const_rows = ctx.total_fixed + 1;
-                    println!("maximum rows used by a fixed column: {const_rows}");
-                    println!("lookup cells used: {}", ctx.cells_to_lookup.len());
-                }
-                let public_key_cells = public_key
-                    .n
-                    .limbs()
-                    .into_iter()
-                    .map(|v| v.cell())
-                    .collect::<Vec<Cell>>();
+                *self.usage.borrow_mut() = CircuitUsage::from_context(ctx);
+                let public_key_cells = public_key.cells();
                 let hashed_msg_cells = hashed_msg
                     .into_iter()
                     .map(|v| v.cell())
                     .collect::<Vec<Cell>>();
-                Ok((public_key_cells, hashed_msg_cells))
+                Ok((Some(public_key_cells), hashed_msg_cells))
             },
         )?;
-        for (i, cell) in public_key_cells.into_iter().enumerate() {
-            layouter.constrain_instance(cell, config.n_instance, i)?;
+        if let Some(public_key_cells) = public_key_cells {
+            config.rsa_config.expose_public_key_instance(
+                &mut layouter,
+                &public_key_cells,
+                config.n_instance,
+                0,
+            )?;
         }
         for (i, cell) in hashed_msg_cells.into_iter().enumerate() {
             layouter.constrain_instance(cell, config.hash_instance, i)?;
@@ -421,12 +1340,119 @@ impl<F: PrimeField> Circuit<F> for TestRSASignatureWithHashCircuit1<F> {
 mod test {
     use super::*;
     use crate::big_uint::decompose_biguint;
+    use crate::test_utils::{
+        mock_prove, mock_prove_with_sha256, sign_hash_with_test_key, sign_with_test_key,
+        test_modulus, test_private_key, SHA256_MAX_BYTE_SIZES,
+    };
+    use crate::timestamp::{unix_timestamp_native, TimeZone};
+    use halo2_base::gates::range::{RangeConfig, RangeStrategy::Vertical};
     use halo2_base::halo2_proofs::halo2curves::pasta::Fp;
     use halo2_base::halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
+    use halo2_base::halo2_proofs::{
+        halo2curves::bn256::{Bn256, G1Affine},
+        plonk::{create_proof, keygen_vk, verify_proof},
+        poly::kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy,
+        },
+        transcript::{
+            Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+        },
+        SerdeFormat,
+    };
     use halo2curves::bn256::Fr as FR;
-    use rand::{thread_rng, Rng};
+    use rand::{rngs::OsRng, thread_rng, Rng};
+    use rsa::signature::RandomizedSigner;
     use rsa::{traits::PublicKeyParts, RsaPrivateKey, RsaPublicKey};
-    use sha2::{Digest, Sha256};
+    use sha2::{Digest, Sha256, Sha512};
+    use std::{cell::RefCell, rc::Rc};
+
+    #[derive(Debug, Clone)]
+    struct TestSha256CostConfig<F: PrimeField> {
+        range_config: RangeConfig<F>,
+        sha256_config: Sha256DynamicConfig<F>,
+        hash_instance: Column<Instance>,
+    }
+
+    /// A circuit hashing `msg` in a single slot of `MAX_BYTE_SIZE` bytes and recording the advice cells it assigns.
+    #[derive(Debug, Clone)]
+    struct TestSha256CostCircuit<F: PrimeField, const MAX_BYTE_SIZE: usize> {
+        msg: Vec<u8>,
+        advice_cells: Rc<RefCell<usize>>,
+        _f: PhantomData<F>,
+    }
+
+    impl<F: PrimeField, const MAX_BYTE_SIZE: usize> Circuit<F>
+        for TestSha256CostCircuit<F, MAX_BYTE_SIZE>
+    {
+        type Config = TestSha256CostConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let range_config = RangeConfig::configure(meta, Vertical, &[8], &[1], 1, 12, 0, 15);
+            let sha256_config = Sha256DynamicConfig::configure(
+                meta,
+                vec![MAX_BYTE_SIZE],
+                range_config.clone(),
+                8,
+                1,
+                true,
+            );
+            let hash_instance = meta.instance_column();
+            meta.enable_equality(hash_instance);
+            Self::Config {
+                range_config,
+                sha256_config,
+                hash_instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            config.sha256_config.load(&mut layouter)?;
+            config.range_config.load_lookup_table(&mut layouter)?;
+            let mut first_pass = SKIP_FIRST_PASS;
+            let hash_cells = layouter.assign_region(
+                || "sha256 cost",
+                |region| {
+                    if first_pass {
+                        first_pass = false;
+                        return Ok(vec![]);
+                    }
+                    let mut aux = Context::new(
+                        region,
+                        halo2_base::ContextParams {
+                            max_rows: config.range_config.gate.max_rows,
+                            num_context_ids: 1,
+                            fixed_columns: config.range_config.gate.constants.clone(),
+                        },
+                    );
+                    let ctx = &mut aux;
+                    let mut sha256 = config.sha256_config.clone();
+                    let result = sha256.digest(ctx, &self.msg, None)?;
+                    config.range_config.finalize(ctx);
+                    *self.advice_cells.borrow_mut() = ctx.total_advice;
+                    Ok(result
+                        .output_bytes
+                        .into_iter()
+                        .map(|v| v.cell())
+                        .collect::<Vec<Cell>>())
+                },
+            )?;
+            for (i, cell) in hash_cells.into_iter().enumerate() {
+                layouter.constrain_instance(cell, config.hash_instance, i)?;
+            }
+            Ok(())
+        }
+    }
 
     #[test]
     fn test_rsa_signature_with_hash_circuit1() {
@@ -442,12 +1468,8 @@ mod test {
                 msg[i] = rng.gen();
             }
             let hashed_msg = Sha256::digest(&msg);
-            let circuit = TestRSASignatureWithHashCircuit1::<F> {
-                private_key,
-                public_key,
-                msg: msg.to_vec(),
-                _f: PhantomData,
-            };
+            let circuit =
+                TestRSASignatureWithHashCircuit1::<F>::new(private_key, public_key, msg.to_vec());
             let num_limbs = 2048 / 64;
             let limb_bits = 64;
             let n_fes = decompose_biguint::<F>(&n, num_limbs, limb_bits);
@@ -457,15 +1479,410 @@ mod test {
                 .collect::<Vec<F>>();
             let public_inputs = vec![n_fes, hash_fes];
             let k = 15;
-            let prover = match MockProver::run(k, &circuit, public_inputs) {
+            let prover = match MockProver::run(k, &circuit, public_inputs.clone()) {
                 Ok(prover) => prover,
                 Err(e) => panic!("{:#?}", e),
             };
             prover.verify().unwrap();
+
+            // Tampering with a limb of `n` or a byte of the hash must fail.
+            for (column, row) in [(0, 0), (0, num_limbs - 1), (1, 0)] {
+                let mut tampered = public_inputs.clone();
+                tampered[column][row] += F::one();
+                let prover = MockProver::run(k, &circuit, tampered).unwrap();
+                assert!(prover.verify().is_err());
+            }
         }
         run::<Fr>();
     }
 
+    #[test]
+    fn test_rsa_signature_with_hash_circuit_with_half_columns() {
+        // Half of the columns of `TestRSASignatureWithHashCircuit1` at twice the rows.
+        type HalfColumnsCircuit = TestRSASignatureWithHashCircuit<Fr, 16, 40, 8>;
+        let mut rng = thread_rng();
+        let private_key = RsaPrivateKey::new(&mut rng, HalfColumnsCircuit::BITS_LEN)
+            .expect("failed to generate a key");
+        let public_key = RsaPublicKey::from(&private_key);
+        let msg = (0..128).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let public_inputs = compute_public_inputs::<Fr>(&public_key, &msg);
+        let circuit = HalfColumnsCircuit::new(private_key, public_key, msg);
+        let prover = match MockProver::run(16, &circuit, public_inputs) {
+            Ok(prover) => prover,
+            Err(e) => panic!("{:#?}", e),
+        };
+        prover.verify().unwrap();
+    }
+
+    #[test]
+    fn test_estimate_k() {
+        fn prove<const K: usize>(private_key: RsaPrivateKey, msg: Vec<u8>) {
+            let public_key = RsaPublicKey::from(&private_key);
+            let public_inputs = compute_public_inputs::<Fr>(&public_key, &msg);
+            let circuit =
+                TestRSASignatureWithHashCircuit::<Fr, K, 80, 16>::new(private_key, public_key, msg);
+            MockProver::run(K as u32, &circuit, public_inputs)
+                .unwrap()
+                .assert_satisfied();
+        }
+        let mut rng = thread_rng();
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let msg = (0..128).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let public_inputs = compute_public_inputs::<Fr>(&public_key, &msg);
+        let circuit = TestRSASignatureWithHashCircuit1::<Fr>::new(
+            private_key.clone(),
+            public_key,
+            msg.clone(),
+        );
+        MockProver::run(15, &circuit, public_inputs)
+            .unwrap()
+            .assert_satisfied();
+        let usage = *circuit.usage.borrow();
+        assert!(usage.advice_cells > 0 && usage.lookup_cells > 0);
+
+        // The default parameters are those of `TestRSASignatureWithHashCircuit1`, which fits at `k = 15`.
+        let k = RSASignatureVerifier::estimate_k(&RSAConfigBuilder::new(), &usage).unwrap();
+        assert!(k <= 15);
+        match k {
+            13 => prove::<13>(private_key, msg),
+            14 => prove::<14>(private_key, msg),
+            15 => prove::<15>(private_key, msg),
+            _ => panic!("estimated k = {k} is below the lookup table"),
+        }
+
+        // Halving the advice columns needs more rows.
+        let half = RSAConfigBuilder::new().num_advice(40).num_lookup_advice(8);
+        assert!(RSASignatureVerifier::estimate_k(&half, &usage).unwrap() >= k);
+    }
+
+    #[test]
+    fn test_verify_pkcs1v15_signature_with_associated_data() {
+        let n = test_modulus();
+        let (msg, associated_data) = (b"signed message".to_vec(), b"app:login".to_vec());
+        let sign = sign_with_test_key(&[&msg[..], &associated_data[..]].concat());
+
+        for (associated_data, is_valid) in [
+            (associated_data.clone(), true),
+            (b"app:logout".to_vec(), false),
+            (vec![], false),
+        ] {
+            let (n, msg, sign) = (n.clone(), msg.clone(), sign.clone());
+            let mut expected = vec![Fr::from(is_valid as u64)];
+            expected.extend(associated_data.iter().map(|byte| Fr::from(*byte as u64)));
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                    let (is_valid, _, associated_bytes) = verifier
+                        .verify_pkcs1v15_signature_with_associated_data(
+                            ctx,
+                            &public_key,
+                            &msg,
+                            &associated_data,
+                            &sign,
+                        )?;
+                    Ok([vec![is_valid], associated_bytes].concat())
+                },
+                expected,
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_verify_pkcs1v15_signature_rejects_oversized_msg() {
+        let prover = mock_prove_with_sha256(
+            |ctx, range, sha256| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                let public_key = rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(
+                        Value::known(BigUint::from(3u64)),
+                        RSAPubE::Fix(BigUint::from(65537u64)),
+                    ),
+                )?;
+                let sign = rsa_config
+                    .assign_signature(ctx, RSASignature::new(Value::known(BigUint::from(1u64))))?;
+                let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                // The padding of a message filling a slot takes an extra block.
+                let msg = vec![0u8; SHA256_MAX_BYTE_SIZES[0]];
+                assert!(matches!(
+                    verifier.verify_pkcs1v15_signature(ctx, &public_key, &msg, &sign),
+                    Err(RsaCircuitError::BadLength { len, max_len: Some(max_len) })
+                        if len == msg.len() && max_len == SHA256_MAX_BYTE_SIZES[0]
+                ));
+                Ok(vec![])
+            },
+            vec![],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_compute_public_inputs() {
+        let mut rng = thread_rng();
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let msg = (0..128).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let public_inputs = compute_public_inputs::<Fr>(&public_key, &msg);
+        assert_eq!(public_inputs[0], decompose_biguint::<Fr>(&n, 2048 / 64, 64));
+        assert_eq!(public_inputs[1].len(), 32);
+
+        let circuit = TestRSASignatureWithHashCircuit1::<Fr>::new(private_key, public_key, msg);
+        let prover = MockProver::run(15, &circuit, public_inputs.clone()).unwrap();
+        prover.verify().unwrap();
+        // The circuit expects the least significant limb first.
+        let mut reversed = public_inputs;
+        reversed[0].reverse();
+        let prover = MockProver::run(15, &circuit, reversed).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    #[test]
+    fn test_commit_public_key() {
+        // The bytes of a 512-bit modulus fit a test slot once padded.
+        let mut rng = thread_rng();
+        let private_key = RsaPrivateKey::new(&mut rng, 512).expect("failed to generate a key");
+        let public_key = RsaPublicKey::from(&private_key);
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let commitment = public_key_commitment_native::<Fr>(&public_key);
+        assert_eq!(commitment.len(), 2);
+
+        for (assigned_n, is_ok) in [(n.clone(), true), (n + 2u64, false)] {
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 512, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(assigned_n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                    Ok(verifier.commit_public_key(ctx, &public_key)?)
+                },
+                commitment.clone(),
+            );
+            assert_eq!(prover.verify().is_ok(), is_ok);
+        }
+    }
+
+    #[test]
+    fn test_rsa_signature_with_hash_circuit_real_proof() {
+        let mut rng = thread_rng();
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let msg = (0..128).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let [n_fes, hash_fes]: [Vec<Fr>; 2] =
+            compute_public_inputs(&public_key, &msg).try_into().unwrap();
+        let circuit = TestRSASignatureWithHashCircuit1::<Fr>::new(private_key, public_key, msg);
+
+        let params = ParamsKZG::<Bn256>::setup(15, OsRng);
+        let (pk, vk) = RSASignatureVerifier::keygen(&circuit, &params).unwrap();
+        // Prove and verify with the keys read back from their cache files.
+        let dir = tempfile::tempdir().unwrap();
+        let (pk_path, vk_path) = (dir.path().join("rsa.pk"), dir.path().join("rsa.vk"));
+        keygen::write_pk(&pk_path, &params, &pk).unwrap();
+        keygen::write_vk(&vk_path, &params, &vk).unwrap();
+        let pk = keygen::read_pk(&pk_path, &circuit, &params)
+            .unwrap()
+            .unwrap();
+        let vk = keygen::read_vk(&vk_path, &circuit, &params)
+            .unwrap()
+            .unwrap();
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+            &params,
+            &pk,
+            &[circuit],
+            &[&[&n_fes, &hash_fes]],
+            OsRng,
+            &mut transcript,
+        )
+        .unwrap();
+        let proof = transcript.finalize();
+
+        let verify = |hash_fes: &[Fr]| {
+            let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
+            verify_proof::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>, _, _, _>(
+                &params,
+                &vk,
+                SingleStrategy::new(&params),
+                &[&[&n_fes, hash_fes]],
+                &mut transcript,
+            )
+            .is_ok()
+        };
+        assert!(verify(&hash_fes));
+        let mut tampered = hash_fes;
+        tampered[0] += Fr::one();
+        assert!(!verify(&tampered));
+    }
+
+    #[test]
+    fn test_rsa_signature_with_hash_circuit_keygen_without_witnesses() {
+        let mut rng = thread_rng();
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let msg = (0..128).map(|_| rng.gen()).collect::<Vec<u8>>();
+        let circuit = TestRSASignatureWithHashCircuit1::<Fr>::new(private_key, public_key, msg);
+        let params = ParamsKZG::<Bn256>::setup(15, OsRng);
+        let vk = keygen_vk(&params, &circuit.without_witnesses()).unwrap();
+        // The keys do not depend on the witnesses.
+        assert_eq!(
+            vk.to_bytes(SerdeFormat::RawBytes),
+            keygen_vk(&params, &circuit)
+                .unwrap()
+                .to_bytes(SerdeFormat::RawBytes)
+        );
+    }
+
+    thread_local! {
+        /// The parameters of the next [`TestColumnsCircuit`], since `Circuit::configure` takes none.
+        static COLUMNS_PARAMS: RefCell<RSAConfigBuilder> = RefCell::new(RSAConfigBuilder::new());
+    }
+
+    /// A circuit verifying a pkcs1v15 signature of a SHA256 hash with the parameters of [`COLUMNS_PARAMS`] and recording its usage.
+    #[derive(Debug, Clone)]
+    struct TestColumnsCircuit<F: PrimeField> {
+        signature: BigUint,
+        n: BigUint,
+        hashed: Vec<u8>,
+        usage: Rc<RefCell<CircuitUsage>>,
+        _f: PhantomData<F>,
+    }
+
+    impl<F: PrimeField> Circuit<F> for TestColumnsCircuit<F> {
+        type Config = RSAConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            COLUMNS_PARAMS
+                .with(|params| params.borrow().build_rsa(meta))
+                .unwrap_or_else(|e| panic!("invalid configuration: {e}"))
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let biguint_config = config.biguint_config();
+            biguint_config.range().load_lookup_table(&mut layouter)?;
+            let mut first_pass = SKIP_FIRST_PASS;
+            layouter.assign_region(
+                || "signature with suggested columns",
+                |region| {
+                    if first_pass {
+                        first_pass = false;
+                        return Ok(());
+                    }
+                    let mut aux = biguint_config.new_context(region);
+                    let ctx = &mut aux;
+                    let sign = config.assign_signature(
+                        ctx,
+                        RSASignature::new(Value::known(self.signature.clone())),
+                    )?;
+                    let public_key = config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(self.n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let hashed = self
+                        .hashed
+                        .iter()
+                        .map(|byte| {
+                            biguint_config
+                                .gate()
+                                .load_witness(ctx, Value::known(F::from(*byte as u64)))
+                        })
+                        .collect::<Vec<_>>();
+                    let is_valid = config.verify_pkcs1v15_signature_with_hash(
+                        ctx,
+                        &public_key,
+                        RsaHashAlgo::Sha256,
+                        &hashed,
+                        &sign,
+                    )?;
+                    biguint_config
+                        .gate()
+                        .assert_is_const(ctx, &is_valid, F::one());
+                    biguint_config.range().finalize(ctx);
+                    *self.usage.borrow_mut() = CircuitUsage::from_context(ctx);
+                    Ok(())
+                },
+            )
+        }
+    }
+
+    #[test]
+    fn test_suggest_num_advice() {
+        fn prove(params: RSAConfigBuilder, k: u32, circuit: &TestColumnsCircuit<Fr>) {
+            COLUMNS_PARAMS.with(|columns_params| *columns_params.borrow_mut() = params);
+            MockProver::run(k, circuit, vec![])
+                .unwrap()
+                .assert_satisfied();
+        }
+        let mut rng = thread_rng();
+        let msg = b"signed with the suggested columns";
+        for bits_len in [2048, 4096] {
+            let private_key =
+                RsaPrivateKey::new(&mut rng, bits_len).expect("failed to generate a key");
+            let n = BigUint::from_bytes_be(&private_key.n().to_bytes_be());
+            let signing_key = SigningKey::<rsa::sha2::Sha256>::new(private_key);
+            let circuit = TestColumnsCircuit {
+                signature: BigUint::from_bytes_be(&signing_key.sign(msg).to_vec()),
+                n,
+                hashed: Sha256::digest(msg).to_vec(),
+                usage: Default::default(),
+                _f: PhantomData,
+            };
+            // The usage is measured with the default 80 advice and 16 lookup advice columns at k = 15.
+            let defaults = RSAConfigBuilder::new().bits_len(bits_len);
+            prove(defaults.clone(), 15, &circuit);
+            let usage = *circuit.usage.borrow();
+
+            let mut suggested = vec![];
+            for k in [15, 16] {
+                let params = defaults.clone().k(k);
+                let (num_advice, num_lookup_advice) =
+                    params.suggest_num_advice::<Fr>(&usage).unwrap();
+                prove(
+                    params
+                        .num_advice(num_advice)
+                        .num_lookup_advice(num_lookup_advice),
+                    k as u32,
+                    &circuit,
+                );
+                suggested.push(num_advice);
+            }
+            assert!(suggested[0] <= 80, "bits_len = {bits_len}");
+            // Doubling the rows roughly halves the columns.
+            assert!(suggested[1] < suggested[0], "bits_len = {bits_len}");
+        }
+    }
+
     #[test]
     fn test_rsa_signature_with_hash_circuit2() {
         fn run<F: PrimeField>() {
@@ -578,12 +1995,8 @@ mod test {
                 byte_vec2.push(var_name[i].parse::<u8>().unwrap());
             }
             let hashed_msg2 = Sha256::digest(&byte_vec2);
-            let circuit = TestRSASignatureWithHashCircuit1::<F> {
-                private_key,
-                public_key,
-                msg: byte_vec,
-                _f: PhantomData,
-            };
+            let circuit =
+                TestRSASignatureWithHashCircuit1::<F>::new(private_key, public_key, byte_vec);
             let num_limbs = 2048 / 64;
             let limb_bits = 64;
             let n_fes = decompose_biguint::<F>(&n, num_limbs, limb_bits);
@@ -604,12 +2017,8 @@ mod test {
                     .expect("failed to generate a key");
             let public_key2 = RsaPublicKey::from(&private_key2);
             let n2 = BigUint::from_radix_le(&public_key2.n().to_radix_le(16), 16).unwrap();
-            let circuit2 = TestRSASignatureWithHashCircuit1::<F> {
-                private_key: private_key2,
-                public_key: public_key2,
-                msg: byte_vec2,
-                _f: PhantomData,
-            };
+            let circuit2 =
+                TestRSASignatureWithHashCircuit1::<F>::new(private_key2, public_key2, byte_vec2);
             let hash_fes2 = hashed_msg2
                 .iter()
                 .map(|byte| F::from(*byte as u64))
@@ -661,6 +2070,1155 @@ mod test {
         18
     }
 
+    #[test]
+    fn test_rsa_modulus_with_leading_zero_byte() {
+        fn run<F: PrimeField>() {
+            let mut rng = thread_rng();
+            // A 2040-bit modulus has a zero most-significant byte in the 2048-bit encoding.
+            let private_key = RsaPrivateKey::new(&mut rng, 2040).expect("failed to generate a key");
+            let public_key = RsaPublicKey::from(&private_key);
+            let n_bytes = public_key.n().to_bytes_be();
+            assert_eq!(n_bytes.len(), 255);
+            let n = BigUint::from_bytes_be(&n_bytes);
+            let e = BigUint::from(65537u64);
+            let sign = n.clone() - BigUint::from(2u64);
+            let powed = sign.modpow(&e, &n);
+            let out_of_range = n.clone() + BigUint::from(1u64);
+
+            let mut public_inputs = decompose_biguint::<F>(&n, 2048 / 64, 64);
+            public_inputs.extend([F::one(), F::zero()]);
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config.clone(), 2048, 5);
+                    // `assign_public_key` rejects a modulus without the top bit, so it is assigned as an integer.
+                    let public_key = AssignedRSAPublicKey::new(
+                        biguint_config.assign_integer(ctx, Value::known(n.clone()), 2048)?,
+                        AssignedRSAPubE::Fix(e.clone()),
+                    );
+                    assert_eq!(public_key.n.num_limbs(), 2048 / 64);
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let powed_expected =
+                        biguint_config.assign_integer(ctx, Value::known(powed.clone()), 2048)?;
+                    let powed = rsa_config.modpow_public_key(ctx, &sign.c, &public_key)?;
+                    biguint_config.assert_equal_fresh(ctx, &powed, &powed_expected)?;
+                    let is_sign_in_field =
+                        biguint_config.is_in_field(ctx, &sign.c, &public_key.n)?;
+                    let out_of_range = biguint_config.assign_integer(
+                        ctx,
+                        Value::known(out_of_range.clone()),
+                        2048,
+                    )?;
+                    let is_out_of_range_in_field =
+                        biguint_config.is_in_field(ctx, &out_of_range, &public_key.n)?;
+                    let mut public_values = public_key.n.limbs().to_vec();
+                    public_values.extend([is_sign_in_field, is_out_of_range_in_field]);
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+        run::<Fr>();
+    }
+
+    #[test]
+    fn test_rsa_signature_with_signal() {
+        let n = test_modulus();
+        let msg = b"bound to a signal";
+        let sign = sign_with_test_key(msg);
+        let signal = Fr::from(42u64);
+        let commitment = signal_commitment_native(signal, msg).unwrap();
+        assert_ne!(
+            commitment,
+            signal_commitment_native(Fr::from(43u64), msg).unwrap()
+        );
+
+        let prove = |signal: Fr, public_inputs: Vec<Fr>| {
+            let n = n.clone();
+            let sign = sign.clone();
+            mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let signal = range.gate.load_witness(ctx, Value::known(signal));
+                    let poseidon = StandardPoseidonChip::new(poseidon::R_F, poseidon::R_P);
+                    let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                    let (is_valid, commitment) = verifier.verify_pkcs1v15_signature_with_signal(
+                        ctx,
+                        &poseidon,
+                        &public_key,
+                        msg,
+                        &sign,
+                        &signal,
+                    )?;
+                    Ok(vec![signal, commitment, is_valid])
+                },
+                public_inputs,
+            )
+        };
+        prove(signal, vec![signal, commitment, Fr::one()]).assert_satisfied();
+        // Replaying the commitment with another signal fails.
+        let other_signal = Fr::from(43u64);
+        assert!(
+            prove(other_signal, vec![other_signal, commitment, Fr::one()])
+                .verify()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_rsa_signature_with_32_bit_limbs() {
+        let n = test_modulus();
+        let msg = b"verified with 32-bit limbs";
+        let sign = sign_with_test_key(msg);
+        let hashed = Sha256::digest(msg).to_vec();
+        let mut public_inputs = decompose_biguint::<Fr>(&n, 2048 / 32, 32);
+        public_inputs.extend([Fr::one(), Fr::one()]);
+
+        let prover = mock_prove_with_sha256(
+            move |ctx, range, sha256| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 32);
+                let rsa_config = RSAConfig::construct(biguint_config.clone(), 2048, 5);
+                assert_eq!(rsa_config.num_limbs(), 2048 / 32);
+                let public_key = rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(
+                        Value::known(n.clone()),
+                        RSAPubE::Fix(BigUint::from(65537u64)),
+                    ),
+                )?;
+                let sign = rsa_config
+                    .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                // The hash given as 32-bit limbs to the chip.
+                let hashed_int = biguint_config.assign_integer(
+                    ctx,
+                    Value::known(BigUint::from_bytes_be(&hashed)),
+                    256,
+                )?;
+                let is_chip_valid = rsa_config.verify_pkcs1v15_signature(
+                    ctx,
+                    &public_key,
+                    hashed_int.limbs(),
+                    &sign,
+                )?;
+                let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                let (is_valid, _) =
+                    verifier.verify_pkcs1v15_signature(ctx, &public_key, msg, &sign)?;
+                let mut outputs = public_key.n.limbs().to_vec();
+                outputs.extend([is_chip_valid, is_valid]);
+                Ok(outputs)
+            },
+            public_inputs,
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_rsa_signature_with_bits_len() {
+        fn run<F: PrimeField>(bits_len: usize) {
+            let mut rng = thread_rng();
+            let private_key =
+                RsaPrivateKey::new(&mut rng, bits_len).expect("failed to generate a key");
+            let public_key = RsaPublicKey::from(&private_key);
+            let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+            let msg = b"signed with a key of another size";
+            let sign = sign_with_test_key(msg);
+            let num_limbs = bits_len / 64;
+            let mut public_inputs = decompose_biguint::<F>(&n, num_limbs, 64);
+            public_inputs.push(F::one());
+
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, bits_len, 5);
+                    assert_eq!(rsa_config.num_limbs(), num_limbs);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                    let (is_valid, _) =
+                        verifier.verify_pkcs1v15_signature(ctx, &public_key, msg, &sign)?;
+                    let mut public_values = public_key.n.limbs().to_vec();
+                    public_values.push(is_valid);
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+        for bits_len in [1024, 3072, 4096] {
+            run::<Fr>(bits_len);
+        }
+    }
+
+    #[test]
+    fn test_rsa_public_key_from_der() {
+        use rsa::pkcs8::EncodePublicKey;
+        let private_key = test_private_key();
+        let public_key = RsaPublicKey::from(&private_key);
+        let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+        let der = public_key.to_public_key_der().unwrap();
+        let parsed = RSAPublicKey::<Fr>::from_der(der.as_bytes()).unwrap();
+        parsed.n.assert_if_known(|parsed_n| parsed_n == &n);
+        assert!(matches!(parsed.e, RSAPubE::Fix(e) if e == BigUint::from(65537u64)));
+        assert!(RSAPublicKey::<Fr>::from_der(&der.as_bytes()[1..]).is_err());
+
+        #[cfg(feature = "pem")]
+        {
+            let pem = public_key
+                .to_public_key_pem(rsa::pkcs8::LineEnding::LF)
+                .unwrap();
+            let parsed = RSAPublicKey::<Fr>::from_pem(&pem).unwrap();
+            parsed.n.assert_if_known(|parsed_n| parsed_n == &n);
+            assert!(RSAPublicKey::<Fr>::from_pem("not a key").is_err());
+        }
+
+        let other_e = RsaPublicKey::new(public_key.n().clone(), rsa::BigUint::from(3u64)).unwrap();
+        let der = other_e.to_public_key_der().unwrap();
+        let parsed = RSAPublicKey::<Fr>::from_der(der.as_bytes()).unwrap();
+        assert!(matches!(parsed.e, RSAPubE::Var(_)));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_rsa_witness_serde_round_trip() {
+        let n = (BigUint::from(1u64) << 2047) + BigUint::from(3233u64);
+        let sign = BigUint::from(0xdead_beefu64);
+        for e in [
+            RSAPubE::Fix(BigUint::from(65537u64)),
+            RSAPubE::Var(Value::known(BigUint::from(3u64))),
+        ] {
+            let public_key = RSAPublicKey::<Fr>::new(Value::known(n.clone()), e);
+            let json = serde_json::to_string(&public_key).unwrap();
+            let parsed: RSAPublicKey<Fr> = serde_json::from_str(&json).unwrap();
+            parsed.n.assert_if_known(|parsed_n| parsed_n == &n);
+            match (&public_key.e, &parsed.e) {
+                (RSAPubE::Fix(e), RSAPubE::Fix(parsed_e)) => assert_eq!(e, parsed_e),
+                (RSAPubE::Var(e), RSAPubE::Var(parsed_e)) => e
+                    .as_ref()
+                    .zip(parsed_e.as_ref())
+                    .assert_if_known(|(e, parsed_e)| e == parsed_e),
+                _ => panic!("the variant of e changed"),
+            }
+            assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+        }
+
+        let signature = RSASignature::<Fr>::new(Value::known(sign.clone()));
+        let json = serde_json::to_string(&signature).unwrap();
+        let parsed: RSASignature<Fr> = serde_json::from_str(&json).unwrap();
+        parsed.c.assert_if_known(|parsed_c| parsed_c == &sign);
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+        // Unknown values are represented explicitly and stay unknown.
+        let json = serde_json::to_string(&RSASignature::<Fr>::without_witness()).unwrap();
+        assert_eq!(json, r#"{"c":null}"#);
+        let parsed: RSASignature<Fr> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+        let json = serde_json::to_string(&RSAPublicKey::<Fr>::without_witness(BigUint::from(
+            65537u64,
+        )))
+        .unwrap();
+        let parsed: RSAPublicKey<Fr> = serde_json::from_str(&json).unwrap();
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+        assert!(json.starts_with(r#"{"n":null"#));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_secret_witnesses() {
+        use zeroize::Zeroize;
+        let mut rng = thread_rng();
+        let private_key = RsaPrivateKey::new(&mut rng, 1024).expect("failed to generate a key");
+        let mut crt = RSACrtComponents::<Fr>::from_private_key(&private_key).unwrap();
+        crt.p.assert_if_known(|p| p > &BigUint::from(1u64));
+        crt.zeroize();
+        for value in [&crt.p, &crt.q, &crt.qinv] {
+            value.assert_if_known(|v| v.iter_u32_digits().all(|digit| digit == 0));
+        }
+
+        let mut sign = RSASignature::<Fr>::new(Value::known(BigUint::from(u128::MAX)));
+        sign.zeroize();
+        sign.c.assert_if_known(|c| c == &BigUint::from(0u64));
+    }
+
+    #[test]
+    fn test_rsa_signature_crt() {
+        let private_key = test_private_key();
+        let n = test_modulus();
+        let msg = b"verified modulo each prime";
+        let sign = sign_with_test_key(msg);
+        let hashed = Sha256::digest(msg).to_vec();
+        let crt = RSACrtComponents::<Fr>::from_private_key(&private_key).unwrap();
+
+        let prove = |crt: RSACrtComponents<Fr>, hashed: Vec<u8>, expected: Fr| {
+            let n = n.clone();
+            let sign = sign.clone();
+            mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let hashed_bytes = hashed
+                        .iter()
+                        .map(|byte| {
+                            range
+                                .gate
+                                .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                        })
+                        .collect::<Vec<_>>();
+                    let is_valid = rsa_config.verify_pkcs1v15_signature_crt(
+                        ctx,
+                        &public_key,
+                        RsaHashAlgo::Sha256,
+                        &hashed_bytes,
+                        &sign,
+                        crt.clone(),
+                    )?;
+                    Ok(vec![is_valid])
+                },
+                vec![expected],
+            )
+        };
+        prove(crt.clone(), hashed.clone(), Fr::one()).assert_satisfied();
+        // A signature of another message is invalid.
+        let mut other_hashed = hashed.clone();
+        other_hashed[0] ^= 1;
+        prove(crt.clone(), other_hashed, Fr::zero()).assert_satisfied();
+
+        // A wrong CRT witness is rejected.
+        let wrong_qinv = RSACrtComponents::new(
+            crt.p.clone(),
+            crt.q.clone(),
+            crt.qinv.clone().map(|qinv| qinv + 1u64),
+        );
+        assert!(prove(wrong_qinv, hashed.clone(), Fr::one())
+            .verify()
+            .is_err());
+        let wrong_p = RSACrtComponents::new(
+            crt.p.clone().map(|p| p + 2u64),
+            crt.q.clone(),
+            crt.qinv.clone(),
+        );
+        assert!(prove(wrong_p, hashed, Fr::one()).verify().is_err());
+    }
+
+    #[test]
+    fn test_rsa_degenerate_public_key() {
+        let n = (BigUint::from(1u64) << 2047) + BigUint::from(3233u64);
+        let assign = |n: BigUint, e: RSAPubE| {
+            mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(Value::known(n.clone()), e.clone()),
+                    )?;
+                    Ok(vec![])
+                },
+                vec![],
+            )
+        };
+        assign(n.clone(), RSAPubE::Var(Value::known(BigUint::from(3u64)))).assert_satisfied();
+        // e = 1 makes the padded message a valid signature.
+        assert!(
+            assign(n.clone(), RSAPubE::Var(Value::known(BigUint::from(1u64))))
+                .verify()
+                .is_err()
+        );
+        assert!(
+            assign(n.clone(), RSAPubE::Var(Value::known(BigUint::from(0u64))))
+                .verify()
+                .is_err()
+        );
+        // An even modulus.
+        assert!(assign(
+            &n + BigUint::from(1u64),
+            RSAPubE::Fix(BigUint::from(65537u64))
+        )
+        .verify()
+        .is_err());
+        // A modulus shorter than the default bit length.
+        assert!(assign(
+            BigUint::from(3233u64),
+            RSAPubE::Fix(BigUint::from(65537u64))
+        )
+        .verify()
+        .is_err());
+
+        let prover = mock_prove(
+            move |ctx, range| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                let public_key =
+                    RSAPublicKey::new(Value::known(n.clone()), RSAPubE::Fix(BigUint::from(1u64)));
+                assert!(rsa_config.assign_public_key(ctx, public_key).is_err());
+                Ok(vec![])
+            },
+            vec![],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_public_key_cells() {
+        let prover = mock_prove(
+            |ctx, range| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                let n = Value::known((BigUint::from(1u64) << 2047) + BigUint::from(3233u64));
+                for (e, num_cells) in [
+                    (RSAPubE::Fix(BigUint::from(65537u64)), 32),
+                    (RSAPubE::Var(Value::known(BigUint::from(17u64))), 33),
+                ] {
+                    let public_key =
+                        rsa_config.assign_public_key(ctx, RSAPublicKey::new(n.clone(), e))?;
+                    let cells = public_key.cells();
+                    assert_eq!(cells.n.len(), 32);
+                    assert_eq!(cells.n.len() + cells.e.iter().count(), num_cells);
+                }
+                Ok(vec![])
+            },
+            vec![],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_verify_against_keys() {
+        let mut rng = thread_rng();
+        let keys = (0..2)
+            .map(|_| RsaPrivateKey::new(&mut rng, 2048).expect("failed to generate a key"))
+            .collect::<Vec<_>>();
+        let ns = keys
+            .iter()
+            .map(|key| BigUint::from_bytes_be(&key.n().to_bytes_be()))
+            .collect::<Vec<_>>();
+        let msg = b"signed by the rotated key".to_vec();
+        let hashed = Sha256::digest(&msg);
+        let sign = keys[1]
+            .sign(rsa::Pkcs1v15Sign::new::<Sha256>(), &hashed)
+            .unwrap();
+        let sign = BigUint::from_bytes_be(&sign);
+
+        // The signature matches the second key only.
+        for (ns, expected) in [
+            (ns.clone(), [1u64, 1]),
+            (vec![ns[1].clone(), ns[0].clone()], [1, 0]),
+            (vec![ns[0].clone(), ns[0].clone()], [0, 0]),
+        ] {
+            let (msg, sign) = (msg.clone(), sign.clone());
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let mut verifier =
+                        RSASignatureVerifier::new(rsa_config.clone(), sha256.clone());
+                    let mut keys = vec![];
+                    for n in ns.iter() {
+                        keys.push(rsa_config.assign_public_key(
+                            ctx,
+                            RSAPublicKey::new(
+                                Value::known(n.clone()),
+                                RSAPubE::Fix(BigUint::from(65537u64)),
+                            ),
+                        )?);
+                    }
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let (is_valid, index, _) =
+                        verifier.verify_against_keys(ctx, &keys, &msg, &sign)?;
+                    Ok(vec![is_valid, index])
+                },
+                expected.iter().map(|v| Fr::from(*v)).collect(),
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_rsa_batch_signatures() {
+        let n = test_modulus();
+        let mut signed_msgs = [&b"name"[..], b"date of birth", b"gender"]
+            .iter()
+            .map(|msg| (msg.to_vec(), sign_with_test_key(msg)))
+            .collect::<Vec<_>>();
+        // A signature over another message is rejected without affecting the others.
+        signed_msgs[2].0 = b"pincode".to_vec();
+
+        let prover = mock_prove_with_sha256(
+            move |ctx, range, sha256| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                let mut verifier = RSASignatureVerifier::new(rsa_config.clone(), sha256.clone());
+                let public_key = rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(
+                        Value::known(n.clone()),
+                        RSAPubE::Fix(BigUint::from(65537u64)),
+                    ),
+                )?;
+                let mut assigned = vec![];
+                for (msg, sign) in signed_msgs.iter() {
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    assigned.push((&msg[..], sign));
+                }
+                Ok(verifier.verify_pkcs1v15_signatures(ctx, &public_key, &assigned)?)
+            },
+            vec![Fr::one(), Fr::one(), Fr::zero()],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_rsa_signature_with_hmac() {
+        fn run<F: PrimeField>(hmac_key: &'static [u8], expected: bool) {
+            let n = test_modulus();
+            let msg = b"HMAC-signed payload";
+            let tag = hmac::hmac_sha256_native(b"shared secret", msg);
+            let sign = sign_hash_with_test_key(&tag);
+
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let hmac_key = hmac_key
+                        .iter()
+                        .map(|byte| {
+                            range
+                                .gate
+                                .load_witness(ctx, Value::known(F::from(*byte as u64)))
+                        })
+                        .collect::<Vec<_>>();
+                    let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                    let (is_valid, _) = verifier.verify_pkcs1v15_hmac_signature(
+                        ctx,
+                        &public_key,
+                        &hmac_key,
+                        msg,
+                        &sign,
+                    )?;
+                    Ok(vec![is_valid])
+                },
+                vec![F::from(expected as u64)],
+            );
+            prover.assert_satisfied();
+        }
+        run::<Fr>(b"shared secret", true);
+        run::<Fr>(b"another secret", false);
+    }
+
+    #[test]
+    fn test_rsa_pss_signature() {
+        fn run<F: PrimeField>(salt_len: usize, tampered: bool) {
+            let mut rng = thread_rng();
+            let private_key = test_private_key();
+            let public_key = RsaPublicKey::from(&private_key);
+            let n = BigUint::from_bytes_be(&public_key.n().to_bytes_be());
+            let msg = b"PSS-signed payload";
+            let signing_key =
+                rsa::pss::SigningKey::<rsa::sha2::Sha256>::new_with_salt_len(private_key, salt_len);
+            let mut sign = signing_key.sign_with_rng(&mut rng, msg).to_vec();
+            if tampered {
+                sign[128] ^= 1;
+            }
+            let sign = BigUint::from_bytes_be(&sign) % &n;
+            let mut public_inputs = vec![F::from(!tampered as u64)];
+            public_inputs.extend(Sha256::digest(msg).iter().map(|byte| F::from(*byte as u64)));
+
+            let prover = mock_prove_with_sha256(
+                move |ctx, range, sha256| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config =
+                        RSAConfig::construct_with_pss_salt_len(biguint_config, 2048, 5, salt_len);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let mut verifier = RSASignatureVerifier::new(rsa_config, sha256.clone());
+                    let (is_valid, hashed_msg) =
+                        verifier.verify_pss_signature(ctx, &public_key, msg, &sign)?;
+                    let mut public_values = vec![is_valid];
+                    public_values.extend(hashed_msg);
+                    Ok(public_values)
+                },
+                public_inputs,
+            );
+            prover.assert_satisfied();
+        }
+        run::<Fr>(32, false);
+        run::<Fr>(20, false);
+        run::<Fr>(32, true);
+    }
+
+    #[test]
+    fn test_rsa_signature_with_hash_algo() {
+        fn run<F: PrimeField>(hash_algo: RsaHashAlgo) {
+            let private_key = test_private_key();
+            let n = test_modulus();
+            // Any bytes of the hash length serve as a hashed message.
+            let hashed = Sha512::digest(b"hashed message")[..hash_algo.output_len()].to_vec();
+            let digest_info = [hash_algo.digest_info_prefix(), &hashed[..]].concat();
+            let sign = private_key
+                .sign(rsa::Pkcs1v15Sign::new_unprefixed(), &digest_info)
+                .unwrap();
+            let sign = BigUint::from_bytes_be(&sign);
+            let mut other_hashed = hashed.clone();
+            other_hashed[0] ^= 1;
+
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let mut public_values = vec![];
+                    for hashed in [&hashed, &other_hashed] {
+                        let hashed_bytes = hashed
+                            .iter()
+                            .map(|byte| {
+                                range
+                                    .gate
+                                    .load_witness(ctx, Value::known(F::from(*byte as u64)))
+                            })
+                            .collect::<Vec<_>>();
+                        public_values.push(rsa_config.verify_pkcs1v15_signature_with_hash(
+                            ctx,
+                            &public_key,
+                            hash_algo,
+                            &hashed_bytes,
+                            &sign,
+                        )?);
+                    }
+                    Ok(public_values)
+                },
+                vec![F::one(), F::zero()],
+            );
+            prover.assert_satisfied();
+        }
+        for hash_algo in [
+            RsaHashAlgo::Sha1,
+            RsaHashAlgo::Sha256,
+            RsaHashAlgo::Sha384,
+            RsaHashAlgo::Sha512,
+        ] {
+            run::<Fr>(hash_algo);
+        }
+    }
+
+    #[test]
+    fn test_rsa_recover_encoded_message() {
+        let private_key = test_private_key();
+        let n = test_modulus();
+        // Application-specific bytes follow the DigestInfo.
+        let hashed = Sha256::digest(b"hashed message");
+        let payload = [
+            RsaHashAlgo::Sha256.digest_info_prefix(),
+            &hashed[..],
+            b"app-specific",
+        ]
+        .concat();
+        let sign = private_key
+            .sign(rsa::Pkcs1v15Sign::new_unprefixed(), &payload)
+            .unwrap();
+        let sign = BigUint::from_bytes_be(&sign);
+        let mut em = vec![0x00, 0x01];
+        em.resize(256 - payload.len() - 1, 0xff);
+        em.push(0x00);
+        em.extend_from_slice(&payload);
+        let expected = em.iter().map(|byte| Fr::from(*byte as u64)).collect();
+
+        let prover = mock_prove(
+            move |ctx, range| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                let public_key = rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(
+                        Value::known(n.clone()),
+                        RSAPubE::Fix(BigUint::from(65537u64)),
+                    ),
+                )?;
+                let sign = rsa_config
+                    .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                let em = rsa_config.recover_encoded_message(ctx, &public_key, &sign)?;
+                rsa_config.encoded_message_bytes(ctx, &em)
+            },
+            expected,
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_exp_limb_bits() {
+        let n = test_modulus();
+        let hashed = Sha256::digest(b"hashed message").to_vec();
+        let sign = sign_hash_with_test_key(&hashed);
+        // 65537 has 17 bits.
+        for exp_limb_bits in [17, 32] {
+            let (n, hashed, sign) = (n.clone(), hashed.clone(), sign.clone());
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    // Without the bound, a too narrow config only rejects `e` at assignment.
+                    let narrow_config = RSAConfig::construct(biguint_config.clone(), 2048, 8);
+                    assert!(matches!(
+                        narrow_config.assign_public_key(
+                            ctx,
+                            RSAPublicKey::new(
+                                Value::known(n.clone()),
+                                RSAPubE::Var(Value::known(BigUint::from(65537u64))),
+                            ),
+                        ),
+                        Err(RsaCircuitError::InvalidKey)
+                    ));
+                    let rsa_config = RSAConfig::construct_for_exponent(
+                        biguint_config,
+                        2048,
+                        exp_limb_bits,
+                        &BigUint::from(65537u64),
+                    );
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Var(Value::known(BigUint::from(65537u64))),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let hashed_bytes = hashed
+                        .iter()
+                        .map(|byte| {
+                            range
+                                .gate
+                                .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                        })
+                        .collect::<Vec<_>>();
+                    let is_valid = rsa_config.verify_pkcs1v15_signature_with_hash(
+                        ctx,
+                        &public_key,
+                        RsaHashAlgo::Sha256,
+                        &hashed_bytes,
+                        &sign,
+                    )?;
+                    Ok(vec![is_valid])
+                },
+                vec![Fr::one()],
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "exp_bits (8) cannot represent the exponent 65537 of 17 bits")]
+    fn test_exp_limb_bits_too_narrow() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let biguint_config = BigUintConfig::configure(&mut meta, 1, 1, 1, 12, 15, 64);
+        RSAConfig::construct_for_exponent(biguint_config, 2048, 8, &BigUint::from(65537u64));
+    }
+
+    #[test]
+    #[should_panic(expected = "exp_bits (1) must be at least 2")]
+    fn test_exp_limb_bits_too_short() {
+        let mut meta = ConstraintSystem::<Fr>::default();
+        let biguint_config = BigUintConfig::configure(&mut meta, 1, 1, 1, 12, 15, 64);
+        RSAConfig::construct(biguint_config, 2048, 1);
+    }
+
+    #[test]
+    fn test_rsa_encoded_message_equals_constant() {
+        let n = test_modulus();
+        let hashed = Sha256::digest(b"hashed message");
+        let sign = sign_hash_with_test_key(&hashed);
+        let payload = [RsaHashAlgo::Sha256.digest_info_prefix(), &hashed[..]].concat();
+        let mut em = vec![0x00, 0x01];
+        em.resize(256 - payload.len() - 1, 0xff);
+        em.push(0x00);
+        em.extend_from_slice(&payload);
+        let em = BigUint::from_bytes_be(&em);
+
+        for (expected, is_ok) in [(em.clone(), true), (em ^ BigUint::from(1u64 << 40), false)] {
+            let (n, sign) = (n.clone(), sign.clone());
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                    let rsa_config = RSAConfig::construct(biguint_config.clone(), 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let sign = rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                    let em = rsa_config.recover_encoded_message(ctx, &public_key, &sign)?;
+                    biguint_config.assert_equal_const(ctx, &em, &expected)?;
+                    Ok(vec![])
+                },
+                vec![],
+            );
+            assert_eq!(prover.verify().is_ok(), is_ok);
+        }
+    }
+
+    #[test]
+    fn test_modpow_ladder_split() {
+        const SPLIT_BITS: usize = 9;
+        let n = test_modulus();
+        let e = BigUint::from(65537u64);
+        let hashed = Sha256::digest(b"hashed message").to_vec();
+        let sign = sign_hash_with_test_key(&hashed);
+        let mut other_hashed = hashed.clone();
+        other_hashed[0] ^= 1;
+
+        // The first circuit exposes the state after the `SPLIT_BITS` least significant bits of `e`.
+        let acc = sign.modpow(&(&e % (1u64 << SPLIT_BITS)), &n);
+        let squared = sign.modpow(&BigUint::from(1u64 << SPLIT_BITS), &n);
+        let state_instance = [&acc, &squared]
+            .iter()
+            .flat_map(|v| decompose_biguint::<Fr>(v, 2048 / 64, 64))
+            .collect::<Vec<Fr>>();
+        let (first_n, first_e, first_sign) = (n.clone(), e.clone(), sign.clone());
+        let prover = mock_prove(
+            move |ctx, range| {
+                let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                let public_key = rsa_config.assign_public_key(
+                    ctx,
+                    RSAPublicKey::new(Value::known(first_n.clone()), RSAPubE::Fix(first_e.clone())),
+                )?;
+                let sign = rsa_config
+                    .assign_signature(ctx, RSASignature::new(Value::known(first_sign.clone())))?;
+                let state = rsa_config.modpow_ladder_start(ctx, &sign.c, &public_key)?;
+                let state = rsa_config.modpow_ladder_steps(ctx, state, &public_key, SPLIT_BITS)?;
+                assert!(rsa_config
+                    .modpow_ladder_finish(state.clone(), &public_key)
+                    .is_err());
+                Ok([state.acc.limbs(), state.squared.limbs()].concat())
+            },
+            state_instance,
+        );
+        prover.assert_satisfied();
+
+        // The second circuit finishes the ladder and returns the same bit as the monolithic verification.
+        for hashed in [hashed, other_hashed] {
+            let run = |split: bool| {
+                let (n, e, sign, hashed) = (n.clone(), e.clone(), sign.clone(), hashed.clone());
+                let (acc, squared) = (acc.clone(), squared.clone());
+                let is_valid = Sha256::digest(b"hashed message")[..] == hashed[..];
+                mock_prove(
+                    move |ctx, range| {
+                        let biguint_config = BigUintConfig::construct(range.clone(), 64);
+                        let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                        let public_key = rsa_config.assign_public_key(
+                            ctx,
+                            RSAPublicKey::new(Value::known(n.clone()), RSAPubE::Fix(e.clone())),
+                        )?;
+                        let hashed_bytes = hashed
+                            .iter()
+                            .map(|byte| {
+                                range
+                                    .gate
+                                    .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                            })
+                            .collect::<Vec<_>>();
+                        let is_valid = if split {
+                            let state = rsa_config.assign_modpow_state(
+                                ctx,
+                                Value::known(acc.clone()),
+                                Value::known(squared.clone()),
+                                SPLIT_BITS,
+                                &public_key.n,
+                            )?;
+                            let state = rsa_config.modpow_ladder_steps(
+                                ctx,
+                                state,
+                                &public_key,
+                                17 - SPLIT_BITS,
+                            )?;
+                            let em = rsa_config.modpow_ladder_finish(state, &public_key)?;
+                            rsa_config.is_pkcs1v15_encoded_message(
+                                ctx,
+                                &em,
+                                RsaHashAlgo::Sha256,
+                                &hashed_bytes,
+                            )?
+                        } else {
+                            let sign = rsa_config.assign_signature(
+                                ctx,
+                                RSASignature::new(Value::known(sign.clone())),
+                            )?;
+                            rsa_config.verify_pkcs1v15_signature_with_hash(
+                                ctx,
+                                &public_key,
+                                RsaHashAlgo::Sha256,
+                                &hashed_bytes,
+                                &sign,
+                            )?
+                        };
+                        Ok(vec![is_valid])
+                    },
+                    vec![Fr::from(is_valid as u64)],
+                )
+            };
+            run(true).assert_satisfied();
+            run(false).assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_sha256_padded_len_boundaries() {
+        fn run<const MAX_BYTE_SIZE: usize>(msg_len: usize) {
+            let msg = (0..msg_len).map(|i| i as u8).collect::<Vec<u8>>();
+            let circuit = TestSha256CostCircuit::<Fr, MAX_BYTE_SIZE> {
+                msg: msg.clone(),
+                advice_cells: Default::default(),
+                _f: PhantomData,
+            };
+            let hash_fes = Sha256::digest(&msg)
+                .iter()
+                .map(|byte| Fr::from(*byte as u64))
+                .collect::<Vec<Fr>>();
+            MockProver::run(15, &circuit, vec![hash_fes])
+                .unwrap()
+                .assert_satisfied();
+        }
+        // 55 bytes leave room for 0x80 and the 8-byte length in one block, 56 and 64 bytes do not.
+        assert_eq!(sha256_padded_len(55), 64);
+        assert_eq!(sha256_padded_len(56), 128);
+        assert_eq!(sha256_padded_len(64), 128);
+        run::<{ sha256_padded_len(55) }>(55);
+        run::<{ sha256_padded_len(56) }>(56);
+        run::<{ sha256_padded_len(64) }>(64);
+    }
+
+    #[test]
+    fn test_sha256_cost_follows_photo_length() {
+        fn run<const MAX_BYTE_SIZE: usize>(msg: &[u8]) -> usize {
+            assert_eq!(sha256_padded_len(msg.len()), MAX_BYTE_SIZE);
+            let advice_cells = Rc::new(RefCell::new(0));
+            let circuit = TestSha256CostCircuit::<Fr, MAX_BYTE_SIZE> {
+                msg: msg.to_vec(),
+                advice_cells: advice_cells.clone(),
+                _f: PhantomData,
+            };
+            let hash_fes = Sha256::digest(msg)
+                .iter()
+                .map(|byte| Fr::from(*byte as u64))
+                .collect::<Vec<Fr>>();
+            let prover = MockProver::run(15, &circuit, vec![hash_fes]).unwrap();
+            prover.verify().unwrap();
+            let cells = *advice_cells.borrow();
+            cells
+        }
+        let delimiter = [qr_spec::DELIMITER];
+        let mut photoless = [
+            &b"V2"[..],
+            b"0",
+            b"269720240718124553876",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+        ]
+        .join(&delimiter[..]);
+        photoless.push(qr_spec::DELIMITER);
+        let mut with_photo = photoless.clone();
+        with_photo.extend((0..400u32).map(|i| (i * 7 % 256) as u8));
+        let photoless_cells = run::<64>(&photoless);
+        let with_photo_cells = run::<512>(&with_photo);
+        assert!(photoless_cells < with_photo_cells);
+    }
+
+    #[derive(Debug, Clone)]
+    struct TestSha256BucketsConfig<F: PrimeField> {
+        rsa_config: RSAConfig<F>,
+        sha256_configs: Vec<Sha256DynamicConfig<F>>,
+        hash_instance: Column<Instance>,
+    }
+
+    /// A circuit verifying a signature of `msg` with the buckets of [`SHA256_BUCKETS`], recording the bucket used and the advice cells it assigns.
+    #[derive(Debug, Clone)]
+    struct TestSha256BucketsCircuit<F: PrimeField> {
+        private_key: RsaPrivateKey,
+        msg: Vec<u8>,
+        bucket: Rc<RefCell<Option<usize>>>,
+        advice_cells: Rc<RefCell<usize>>,
+        _f: PhantomData<F>,
+    }
+
+    /// Listed in descending order to check that the verifier sorts its buckets.
+    const SHA256_BUCKETS: [usize; 2] = [256, 128];
+
+    impl<F: PrimeField> Circuit<F> for TestSha256BucketsCircuit<F> {
+        type Config = TestSha256BucketsConfig<F>;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let bigint_config = BigUintConfig::configure(meta, 80, 16, 1, 12, 15, 64);
+            let range_config = bigint_config.range().clone();
+            let rsa_config = RSAConfig::construct(bigint_config, 2048, 5);
+            let sha256_configs = SHA256_BUCKETS
+                .iter()
+                .map(|max_byte_size| {
+                    Sha256DynamicConfig::configure(
+                        meta,
+                        vec![*max_byte_size],
+                        range_config.clone(),
+                        8,
+                        8,
+                        true,
+                    )
+                })
+                .collect();
+            let hash_instance = meta.instance_column();
+            meta.enable_equality(hash_instance);
+            Self::Config {
+                rsa_config,
+                sha256_configs,
+                hash_instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let biguint_config = config.rsa_config.biguint_config();
+            for sha256_config in config.sha256_configs.iter() {
+                sha256_config.load(&mut layouter)?;
+            }
+            biguint_config.range().load_lookup_table(&mut layouter)?;
+            let mut first_pass = SKIP_FIRST_PASS;
+            let hash_cells = layouter.assign_region(
+                || "sha256 buckets",
+                |region| {
+                    if first_pass {
+                        first_pass = false;
+                        return Ok(vec![]);
+                    }
+                    let mut aux = biguint_config.new_context(region);
+                    let ctx = &mut aux;
+                    let signing_key =
+                        SigningKey::<rsa::sha2::Sha256>::new(self.private_key.clone());
+                    let sign = BigUint::from_bytes_be(&signing_key.sign(&self.msg).to_vec());
+                    let sign = config
+                        .rsa_config
+                        .assign_signature(ctx, RSASignature::new(Value::known(sign)))?;
+                    let n = BigUint::from_bytes_be(&self.private_key.n().to_bytes_be());
+                    let public_key = config.rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(Value::known(n), RSAPubE::Fix(BigUint::from(65537u64))),
+                    )?;
+                    let mut verifier = RSASignatureVerifier::with_buckets(
+                        config.rsa_config.clone(),
+                        config.sha256_configs.clone(),
+                    );
+                    *self.bucket.borrow_mut() = verifier.sha256_bucket(self.msg.len());
+                    let (is_valid, hashed_msg) =
+                        verifier.verify_pkcs1v15_signature(ctx, &public_key, &self.msg, &sign)?;
+                    biguint_config
+                        .gate()
+                        .assert_is_const(ctx, &is_valid, F::one());
+                    biguint_config.range().finalize(ctx);
+                    *self.advice_cells.borrow_mut() = ctx.total_advice;
+                    Ok(hashed_msg
+                        .into_iter()
+                        .map(|v| v.cell())
+                        .collect::<Vec<Cell>>())
+                },
+            )?;
+            for (i, cell) in hash_cells.into_iter().enumerate() {
+                layouter.constrain_instance(cell, config.hash_instance, i)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_rsa_signature_verifier_sha256_buckets() {
+        let mut rng = thread_rng();
+        let private_key = test_private_key();
+        let mut run = |msg_len: usize| {
+            let msg = (0..msg_len).map(|_| rng.gen()).collect::<Vec<u8>>();
+            let circuit = TestSha256BucketsCircuit::<Fr> {
+                private_key: private_key.clone(),
+                msg: msg.clone(),
+                bucket: Rc::new(RefCell::new(None)),
+                advice_cells: Rc::new(RefCell::new(0)),
+                _f: PhantomData,
+            };
+            let hash_fes = Sha256::digest(&msg)
+                .iter()
+                .map(|byte| Fr::from(*byte as u64))
+                .collect::<Vec<Fr>>();
+            let prover = MockProver::run(15, &circuit, vec![hash_fes]).unwrap();
+            prover.verify().unwrap();
+            let bucket = *circuit.bucket.borrow();
+            let cells = *circuit.advice_cells.borrow();
+            (bucket, cells)
+        };
+        // The bucket of 128 bytes fits messages up to 119 bytes.
+        let (short_bucket, short_cells) = run(100);
+        let (long_bucket, long_cells) = run(200);
+        assert_eq!(short_bucket, Some(0));
+        assert_eq!(long_bucket, Some(1));
+        assert!(short_cells < long_cells);
+    }
+
     #[test]
     fn test_aadhaar_qr_verifier_circuit() {
         fn run<F: PrimeField>() {
@@ -936,6 +3494,7 @@ mod test {
                 Some(F::from(hour_data)),
                 Some(F::from(00u64)),
                 Some(F::from(00u64)),
+                TimeZone::Utc,
             );
 
             // Signal Hash Subcircuit
@@ -996,7 +3555,9 @@ mod test {
             );
 
             // Verifying the timestamp subcircuit
-            let public_inputs = vec![];
+            let public_inputs = vec![vec![F::from(unix_timestamp_native(
+                year_data, month_data, day_data, hour_data, 0, 0,
+            ))]];
             let prover = MockProver::run(k, &timestamp_circuit.clone(), public_inputs).unwrap();
 
             let timestamp_proof_generation_duration = start_time.elapsed();
@@ -1063,3 +3624,57 @@ mod test {
         run::<Fr>();
     }
 }
+
+#[cfg(test)]
+mod hashless_test {
+    use super::*;
+    use crate::test_utils::{mock_prove, sign_hash_with_test_key, test_modulus};
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
+
+    #[test]
+    fn test_rsa_hashless_verifier() {
+        // SHA256("abc") from FIPS 180-2.
+        const ABC_HASH: [u8; 32] = [
+            0xba, 0x78, 0x16, 0xbf, 0x8f, 0x01, 0xcf, 0xea, 0x41, 0x41, 0x40, 0xde, 0x5d, 0xae,
+            0x22, 0x23, 0xb0, 0x03, 0x61, 0xa3, 0x96, 0x17, 0x7a, 0x9c, 0xb4, 0x10, 0xff, 0x61,
+            0xf2, 0x00, 0x15, 0xad,
+        ];
+        let n = test_modulus();
+        let sign = sign_hash_with_test_key(&ABC_HASH);
+        let mut other_hash = ABC_HASH;
+        other_hash[31] ^= 1;
+
+        for limb_bits in [64, 32] {
+            for (hash, is_valid) in [(ABC_HASH, Fr::one()), (other_hash, Fr::zero())] {
+                let (n, sign) = (n.clone(), sign.clone());
+                let prover = mock_prove(
+                    move |ctx, range| {
+                        let biguint_config = BigUintConfig::construct(range.clone(), limb_bits);
+                        let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                        let public_key = rsa_config.assign_public_key(
+                            ctx,
+                            RSAPublicKey::new(
+                                Value::known(n.clone()),
+                                RSAPubE::Fix(BigUint::from(65537u64)),
+                            ),
+                        )?;
+                        let sign = rsa_config
+                            .assign_signature(ctx, RSASignature::new(Value::known(sign.clone())))?;
+                        let verifier = RSAHashlessVerifier::new(rsa_config);
+                        let hashed_msg = verifier.assign_hashed_msg(ctx, &hash)?;
+                        assert_eq!(hashed_msg.len(), 256 / limb_bits);
+                        let is_valid = verifier.verify_pkcs1v15_signature(
+                            ctx,
+                            &public_key,
+                            &hashed_msg,
+                            &sign,
+                        )?;
+                        Ok(vec![is_valid])
+                    },
+                    vec![is_valid],
+                );
+                prover.assert_satisfied();
+            }
+        }
+    }
+}
@@ -1,42 +1,257 @@
+use crate::big_uint::decompose_biguint;
+use crate::extractors::photo_extractor::extract_photo_commitment;
+use crate::poseidon::{poseidon_hash, StandardPoseidonChip, R_F, R_P};
+use crate::utils::{pack_bytes, pack_bytes_native};
+use crate::AssignedRSAPublicKey;
 use halo2_base::halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner}, halo2curves::pasta::pallas, 
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Instance, Selector, Error}
+    circuit::{Cell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
 };
+use halo2_base::{
+    gates::{
+        range::{RangeConfig, RangeStrategy::Vertical},
+        GateInstructions, RangeInstructions,
+    },
+    utils::PrimeField,
+    AssignedValue, Context, ContextParams, QuantumCell, SKIP_FIRST_PASS,
+};
+use num_bigint::BigUint;
+
+/// Returns the number of `limb_bits`-bit limbs packed into a single Poseidon input by [`pubkey_nullifier`], e.g. three 64-bit limbs.
+fn pubkey_limbs_per_element<F: PrimeField>(limb_bits: usize) -> usize {
+    F::CAPACITY as usize / limb_bits
+}
+
+/// Computes a nullifier scoped to `external_nullifier`, i.e. `Poseidon(external_nullifier, secret...)`.
+///
+/// # Arguments
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * external_nullifier - an assigned external nullifier (app ID). It should be exposed as a public input by the caller.
+/// * secret - assigned values identifying the holder.
+///
+/// # Return values
+/// Returns the assigned nullifier.
+pub fn compute_nullifier<'v, F: PrimeField>(
+    poseidon: &StandardPoseidonChip<F>,
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    external_nullifier: &AssignedValue<'v, F>,
+    secret: &[AssignedValue<'v, F>],
+) -> AssignedValue<'v, F> {
+    let mut inputs = vec![external_nullifier.clone()];
+    inputs.extend_from_slice(secret);
+    poseidon.hash(gate, ctx, &inputs)
+}
+
+/// Computes the nullifier of [`NullifierCircuit`] off-circuit.
+///
+/// # Arguments
+/// * external_nullifier - an external nullifier (app ID).
+/// * photo - photo bytes.
+///
+/// # Return values
+/// Returns the nullifier exposed by [`NullifierCircuit`] for the same inputs, or an error under the conditions of [`poseidon_hash`].
+pub fn compute_nullifier_native<F: PrimeField>(
+    external_nullifier: F,
+    photo: &[u8],
+) -> Result<F, Error> {
+    let mut inputs = vec![external_nullifier];
+    inputs.extend(pack_bytes_native::<F>(photo));
+    poseidon_hash(&inputs)
+}
+
+/// Computes a nullifier of the holder scoped to an application, i.e. the [`compute_nullifier`] `Poseidon(app_namespace, photo_commitment)`.
+///
+/// The photo commitment is the same for every application, while nullifiers under different namespaces cannot be linked to each other
+/// without knowing the commitment. [`NamespacedNullifierCircuit`] exposes the nullifier on its instance column.
+///
+/// # Arguments
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * photo_commitment - an assigned photo commitment, e.g. returned by [`crate::extractors::photo_extractor::extract_photo_commitment`]. It must not be exposed.
+/// * app_namespace - an assigned namespace of the application. It should be exposed as a public input by the caller together with the nullifier.
+///
+/// # Return values
+/// Returns the assigned nullifier, which equals [`namespaced_nullifier_native`] for the same inputs.
+pub fn namespaced_nullifier<'v, F: PrimeField>(
+    poseidon: &StandardPoseidonChip<F>,
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    photo_commitment: &AssignedValue<'v, F>,
+    app_namespace: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    compute_nullifier(
+        poseidon,
+        gate,
+        ctx,
+        app_namespace,
+        std::slice::from_ref(photo_commitment),
+    )
+}
+
+/// Computes the nullifier of [`namespaced_nullifier`] off-circuit.
+///
+/// # Arguments
+/// * photo_commitment - a photo commitment, e.g. returned by [`crate::extractors::photo_extractor::photo_commitment_native`].
+/// * app_namespace - a namespace of the application.
+///
+/// # Return values
+/// Returns the nullifier, or an error under the conditions of [`poseidon_hash`].
+pub fn namespaced_nullifier_native<F: PrimeField>(
+    photo_commitment: F,
+    app_namespace: F,
+) -> Result<F, Error> {
+    poseidon_hash(&[app_namespace, photo_commitment])
+}
 
-//use halo2_ecc::halo2_base::poseidon;
-use poseidon::Poseidon;
+/// Computes a nullifier of the issuer from its public key, i.e. `Poseidon(packed limbs of n...)`.
+///
+/// The limbs are packed into as few inputs as fit the field, least significant first, e.g. three 64-bit limbs per input,
+/// and hashed by the t=3 [`StandardPoseidonChip`], so the nullifier is stable per issuer without exposing the modulus.
+///
+/// # Arguments
+/// * poseidon - a [`StandardPoseidonChip`].
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * public_key - an assigned public key. Its limb width is that of the [`crate::BigUintConfig`] assigning it.
+///
+/// # Return values
+/// Returns the assigned nullifier, which equals [`compute_pubkey_nullifier`] for the same modulus and limb width.
+pub fn pubkey_nullifier<'v, F: PrimeField>(
+    poseidon: &StandardPoseidonChip<F>,
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    public_key: &AssignedRSAPublicKey<'v, F>,
+) -> AssignedValue<'v, F> {
+    let limb_bits = public_key.n.limb_bits();
+    let packed = public_key
+        .n
+        .limbs()
+        .chunks(pubkey_limbs_per_element::<F>(limb_bits))
+        .map(|chunk| {
+            let bases = (0..chunk.len())
+                .map(|i| QuantumCell::Constant(gate.pow_of_two()[limb_bits * i]))
+                .collect::<Vec<_>>();
+            gate.inner_product(
+                ctx,
+                chunk.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                bases,
+            )
+        })
+        .collect::<Vec<_>>();
+    poseidon.hash(gate, ctx, &packed)
+}
+
+/// Computes the nullifier of [`pubkey_nullifier`] off-circuit.
+///
+/// # Arguments
+/// * n - the modulus of the public key. Its limbs are counted from its bit length, so the most significant limb must be non-zero,
+///   as is the case for a modulus generated at the bit length of the circuit.
+/// * limb_bits - the limb width of the [`crate::BigUintConfig`] assigning the public key in the circuit.
+///
+/// # Return values
+/// Returns the nullifier, or an error under the conditions of [`poseidon_hash`].
+pub fn compute_pubkey_nullifier<F: PrimeField>(n: &BigUint, limb_bits: usize) -> Result<F, Error> {
+    let num_limbs = (n.bits() as usize + limb_bits - 1) / limb_bits;
+    let limbs = decompose_biguint::<F>(n, num_limbs, limb_bits);
+    let base = F::from(2u64).pow_vartime([limb_bits as u64]);
+    let packed = limbs
+        .chunks(pubkey_limbs_per_element::<F>(limb_bits))
+        .map(|chunk| {
+            chunk
+                .iter()
+                .rev()
+                .fold(F::zero(), |acc, limb| acc * base + limb)
+        })
+        .collect::<Vec<_>>();
+    poseidon_hash(&packed)
+}
 
-#[derive(Default, Clone)]
-struct PoseidonCircuit {
-    nullifier_seed: u64,          // Nullifier seed as u64
-    photo: Vec<u64>,              // Photo as a vector of u64
+/// Returns a bit that is one iff two photo commitments differ, i.e. the two QRs belong to different people.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * commitment_a - an assigned photo commitment of the first QR.
+/// * commitment_b - an assigned photo commitment of the second QR.
+///
+/// # Return values
+/// Returns the assigned bit as `AssignedValue<F>`.
+pub fn is_distinct_photo<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    commitment_a: &AssignedValue<'v, F>,
+    commitment_b: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    let is_eq = gate.is_equal(
+        ctx,
+        QuantumCell::Existing(commitment_a),
+        QuantumCell::Existing(commitment_b),
+    );
+    gate.not(ctx, QuantumCell::Existing(&is_eq))
 }
 
-#[derive(Default, Clone)]
-struct PoseidonConfig {
-    poseidon_selector: Selector,
-    advice: Column<Advice>,
+#[derive(Debug, Clone)]
+pub struct NullifierConfig<F: PrimeField> {
+    range: RangeConfig<F>,
+    poseidon: StandardPoseidonChip<F>,
     instance: Column<Instance>,
 }
 
-impl Circuit<pallas::Scalar> for PoseidonCircuit {
-    type Config = PoseidonConfig;
-    type FloorPlanner = SimpleFloorPlanner;
+/// A circuit exposing `[external_nullifier, nullifier]` on its instance column,
+/// where the nullifier is derived from the photo bytes and the external nullifier.
+#[derive(Debug, Clone)]
+pub struct NullifierCircuit<F: PrimeField> {
+    external_nullifier: Value<F>,
+    photo: Vec<u8>,
+}
 
-    fn configure(meta: &mut ConstraintSystem<pallas::Scalar>) -> Self::Config {
-        let poseidon_selector = meta.selector();
-        let advice = meta.advice_column();
-        let instance = meta.instance_column();
+impl<F: PrimeField> NullifierCircuit<F> {
+    pub const K: usize = 11;
+    const NUM_ADVICE: usize = 8;
+    const NUM_FIXED: usize = 1;
+    const NUM_LOOKUP_ADVICE: usize = 1;
+    const LOOKUP_BITS: usize = 8;
 
-        meta.create_gate("Poseidon Hash", |v| {
-            // Example constraint for Poseidon hash
-            let s = poseidon_selector;
-            vec![s.clone() * (v[0] - v[1])] // Placeholder for actual constraints
-        });
+    pub fn new(external_nullifier: F, photo: Vec<u8>) -> Self {
+        Self {
+            external_nullifier: Value::known(external_nullifier),
+            photo,
+        }
+    }
+}
 
-        PoseidonConfig {
-            poseidon_selector,
-            advice,
+impl<F: PrimeField> Circuit<F> for NullifierCircuit<F> {
+    type Config = NullifierConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            external_nullifier: Value::unknown(),
+            photo: vec![0; self.photo.len()],
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let range = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[Self::NUM_ADVICE],
+            &[Self::NUM_LOOKUP_ADVICE],
+            Self::NUM_FIXED,
+            Self::LOOKUP_BITS,
+            0,
+            Self::K,
+        );
+        let poseidon = StandardPoseidonChip::new(R_F, R_P);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        NullifierConfig {
+            range,
+            poseidon,
             instance,
         }
     }
@@ -44,112 +259,131 @@ impl Circuit<pallas::Scalar> for PoseidonCircuit {
     fn synthesize(
         &self,
         config: Self::Config,
-        mut layouter: impl Layouter<pallas::Scalar>,
+        mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "Poseidon Hash",
-            |mut region| {
-                let mut poseidon = Poseidon::new();
-
-                // Assign nullifier seed
-                let nullifier_seed = self.nullifier_seed;
-
-                // Compute the Poseidon hash
-                let hash = poseidon.hash(&[nullifier_seed], &self.photo);
-
-                // Assign the hash output to the circuit
-                region.assign_advice(
-                    || "hash output",
-                    config.advice,
-                    0,
-                    || Ok(hash.into()),
-                )?;
-
-                Ok(())
+        config.range.load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let public_cells = layouter.assign_region(
+            || "nullifier",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+                let mut aux = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.range.gate.constants.clone(),
+                    },
+                );
+                let ctx = &mut aux;
+                let gate = config.range.gate();
+                let external_nullifier = gate.load_witness(ctx, self.external_nullifier);
+                let photo = self
+                    .photo
+                    .iter()
+                    .map(|byte| {
+                        let byte = gate.load_witness(ctx, Value::known(F::from(*byte as u64)));
+                        config.range.range_check(ctx, &byte, 8);
+                        byte
+                    })
+                    .collect::<Vec<_>>();
+                let packed_photo = pack_bytes(gate, ctx, &photo);
+                let nullifier = compute_nullifier(
+                    &config.poseidon,
+                    gate,
+                    ctx,
+                    &external_nullifier,
+                    &packed_photo,
+                );
+                config.range.finalize(ctx);
+                Ok(vec![external_nullifier.cell(), nullifier.cell()])
             },
-        )
-    }
-    
-    fn without_witnesses(&self) -> Self {
-        unimplemented!()
+        )?;
+        for (i, cell) in public_cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.instance, i)?;
+        }
+        Ok(())
     }
 }
 
-// Test function for the circuit
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use halo2_base::halo2_proofs::dev::MockProver;
-
-    #[test]
-    fn test_poseidon_circuit() {
-        let nullifier_seed = 12345678u64; // Example nullifier seed
-        let photo = vec![1u64, 2, 3, 4];     // Example photo data
-
-        let circuit = PoseidonCircuit {
-            nullifier_seed,
-            photo,
-        };
-
-        // Create a mock prover with the circuit
-        let k = 4; // Number of rows in the circuit
-        let prover = MockProver::run(k, &circuit, vec![]).unwrap();
-
-        // Verify the proof
-        assert!(prover.verify().is_ok());
-
-        // Get the computed hash from the instance column
-        let hash = prover.instance(0, 0).unwrap();
-        println!("Computed hash: {:?}", hash);
-    }
+#[derive(Debug, Clone)]
+pub struct NamespacedNullifierConfig<F: PrimeField> {
+    range: RangeConfig<F>,
+    poseidon: StandardPoseidonChip<F>,
+    instance: Column<Instance>,
 }
 
-/*use halo2_base::halo2_proofs::{
-    arithmetic::FieldExt, circuit::{Layouter, SimpleFloorPlanner}, 
-    halo2curves::pasta::Fp, 
-    plonk::{Advice, Circuit, ConstraintSystem, Error, Instance, Column},
-};
-
-use crate::halo2_ecc::halo2_base::{
-    poseidon::{
-        hasher::{PoseidonChip, PoseidonInstructions, range_chip},
-        spec::OptimizedPoseidonSpec,
-    },
-    safe_types::FixLenBytes,
-    gates:: RangeChip,
-};
-
-const PHOTO_SIZE: usize = 32;
-#[derive(Default)]
-struct NullifierCircuit {
-    nullifier_seed: Option<Fp>,
-    photo: [Option<Fp>; PHOTO_SIZE],
-    out: Option<Fp>,
+/// A circuit exposing `[app_namespace, nullifier]` on its instance column,
+/// where the nullifier is the [`namespaced_nullifier`] of the photo commitment extracted from a payload by [`extract_photo_commitment`].
+///
+/// The payload is not authenticated here: a verifier of real QR codes derives the nullifier from the `photo_commitment`
+/// of [`crate::aadhaar_verifier::AadhaarOutputs`], whose payload is bound to a verified signature.
+#[derive(Debug, Clone)]
+pub struct NamespacedNullifierCircuit<F: PrimeField> {
+    app_namespace: Value<F>,
+    signed_data: Vec<u8>,
+    padded_len: usize,
 }
-#[derive(Clone)]
-pub struct NullifierConfig {
-    pub nullifier_seed: Column<Advice>,
-    pub photo: [Column<Advice>; PHOTO_SIZE],
-    pub out: Column<Instance>,
+
+impl<F: PrimeField> NamespacedNullifierCircuit<F> {
+    pub const K: usize = 14;
+    const NUM_ADVICE: usize = 8;
+    const NUM_FIXED: usize = 1;
+    const NUM_LOOKUP_ADVICE: usize = 1;
+    const LOOKUP_BITS: usize = 8;
+
+    /// Creates new [`NamespacedNullifierCircuit`].
+    ///
+    /// # Arguments
+    /// * app_namespace - a namespace of the application.
+    /// * signed_data - the signed payload.
+    /// * padded_len - the number of assigned payload bytes, at least the length of `signed_data`.
+    ///
+    /// # Return values
+    /// Returns new [`NamespacedNullifierCircuit`].
+    pub fn new(app_namespace: F, signed_data: Vec<u8>, padded_len: usize) -> Self {
+        Self {
+            app_namespace: Value::known(app_namespace),
+            signed_data,
+            padded_len,
+        }
+    }
 }
 
-impl<F: FieldExt> Circuit<F> for NullifierCircuit {
-    //type Config = NullifierConfig;
-    type Config = PoseidonChip<F, 3, 2>;
+impl<F: PrimeField> Circuit<F> for NamespacedNullifierCircuit<F> {
+    type Config = NamespacedNullifierConfig<F>;
     type FloorPlanner = SimpleFloorPlanner;
-    
+
     fn without_witnesses(&self) -> Self {
         Self {
-            nullifier_seed: None,
-            photo: [None; PHOTO_SIZE],
-            out: None,
+            app_namespace: Value::unknown(),
+            signed_data: vec![],
+            padded_len: self.padded_len,
         }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let range_chip = RangeChip::default();
-        let poseidon_spec = OptimizedPoseidonSpec::new::<3, 2, 3>(); // Example Poseidon spec, adjust as needed
-        PoseidonChip::<F, 3, 2>::new(meta, poseidon_spec, &range_chip)
+        let range = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[Self::NUM_ADVICE],
+            &[Self::NUM_LOOKUP_ADVICE],
+            Self::NUM_FIXED,
+            Self::LOOKUP_BITS,
+            0,
+            Self::K,
+        );
+        let poseidon = StandardPoseidonChip::new(R_F, R_P);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        NamespacedNullifierConfig {
+            range,
+            poseidon,
+            instance,
+        }
     }
 
     fn synthesize(
@@ -157,95 +391,200 @@ impl<F: FieldExt> Circuit<F> for NullifierCircuit {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        layouter.assign_region(
-            || "Nullifier calculation",
-            |mut region| {
-                let poseidon_spec = OptimizedPoseidonSpec::new::<3, 2, 3>();
-                let poseidon_chip = PoseidonChip::<F, 3, 2>::new(config, poseidon_spec);
-                let mut hasher = PoseidonInstructions::new(poseidon_chip);
-
-                let first16_hash = hasher.hash_fix_len_bytes(config, &FixLenBytes::new(&self.photo[0..16]))?;
-                let last16_hash = hasher.hash_fix_len_bytes(config, &FixLenBytes::new(&self.photo[16..32]))?;
-
-                let out = poseidon_chip.hash_fix_len_bytes(config, &FixLenBytes::new(&[self.nullifier_seed, first16_hash, last16_hash]))?;
-                
-                // Output the final hash
-                region.assign_advice(
-                    || "output",
-                    config.output,
-                    0,
-                    || out,
+        config.range.load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let public_cells = layouter.assign_region(
+            || "namespaced nullifier",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+                let mut aux = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.range.gate.constants.clone(),
+                    },
+                );
+                let ctx = &mut aux;
+                let gate = config.range.gate();
+                let app_namespace = gate.load_witness(ctx, self.app_namespace);
+                let qr_bytes = (0..self.padded_len)
+                    .map(|i| {
+                        let byte = self.signed_data.get(i).copied().unwrap_or(0);
+                        let byte = gate.load_witness(ctx, Value::known(F::from(byte as u64)));
+                        config.range.range_check(ctx, &byte, 8);
+                        byte
+                    })
+                    .collect::<Vec<_>>();
+                let data_len =
+                    gate.load_witness(ctx, Value::known(F::from(self.signed_data.len() as u64)));
+                let photo_commitment = extract_photo_commitment(
+                    &config.range,
+                    &config.poseidon,
+                    ctx,
+                    &qr_bytes,
+                    &data_len,
                 )?;
-
-                Ok(())
+                let nullifier = namespaced_nullifier(
+                    &config.poseidon,
+                    gate,
+                    ctx,
+                    &photo_commitment,
+                    &app_namespace,
+                );
+                config.range.finalize(ctx);
+                Ok(vec![app_namespace.cell(), nullifier.cell()])
             },
-        )
+        )?;
+        for (i, cell) in public_cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.instance, i)?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(test)]
-mod tests{
+mod tests {
     use super::*;
-    use halo2_base::halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+    use crate::extractors::photo_extractor::photo_commitment_native;
+    use crate::test_utils::{mock_prove, sample_qr_payload, SAMPLE_QR_FIELDS};
+    use crate::{BigUintConfig, RSAConfig, RSAInstructions, RSAPubE, RSAPublicKey};
+    use halo2_base::halo2_proofs::{dev::MockProver, halo2curves::bn256::Fr};
 
     #[test]
-    fn nullifier_test() {
-        let circuit = NullifierCircuit {
-            nullifier_seed: Some(Fp::from(12345)), // Replace with actual nullifier seed
-            photo: [Some(Fp::from(1)); PHOTO_SIZE], // Example initialization, replace with actual photo data
-            out: None,
-        };
-
-        let public_inputs = vec![];
-    
-        let prover = MockProver::<Fp>::run(10, &circuit, public_inputs).unwrap();
-        assert_eq!(prover.verify(), Ok(()));
+    fn test_is_distinct_photo() {
+        let commitment = |photo: &[u8]| poseidon_hash(&pack_bytes_native::<Fr>(photo)).unwrap();
+        let photo_a = commitment(&[1, 2, 3, 4]);
+        let photo_b = commitment(&[4, 3, 2, 1]);
+        for (a, b, expected) in [(photo_a, photo_a, false), (photo_a, photo_b, true)] {
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let a = range.gate.load_witness(ctx, Value::known(a));
+                    let b = range.gate.load_witness(ctx, Value::known(b));
+                    Ok(vec![is_distinct_photo(&range.gate, ctx, &a, &b)])
+                },
+                vec![Fr::from(expected as u64)],
+            );
+            prover.assert_satisfied();
+        }
     }
-}
 
-    /*fn synthesize(
-        &self,
-        config: Self::Config,
-        mut layouter: impl Layouter<F>,
-    ) -> Result<(), Error> {
-        // Poseidon template supports only 16 inputs - so we do in two chunks (photo is 32 chunks)
-        let first_16_hash = {
-            let mut sponge = PoseidonSponge::<PrimeField>::new(config.clone());
-            for i in 0..16 {
-                sponge.absorb(layouter.namespace(|| format!("absorb_first_{}", i)), self.photo[i])?;
-            }
-            sponge.squeeze(layouter.namespace(|| "squeeze_first"))
-        }?;
-
-        let last_16_hash = {
-            let mut sponge = PoseidonSponge::<PrimeField>::new(config.clone());
-            for i in 0..16 {
-                sponge.absorb(layouter.namespace(|| format!("absorb_last_{}", i)), self.photo[i + 16])?;
-            }
-            sponge.squeeze(layouter.namespace(|| "squeeze_last"))
-        }?;
-
-        let nullifier = {
-            let mut sponge = PoseidonSponge::<PrimeField>::new(config.clone());
-            sponge.absorb(layouter.namespace(|| "absorb_seed"), self.nullifier_seed)?;
-            sponge.absorb(layouter.namespace(|| "absorb_first_16_hash"), first_16_hash)?;
-            sponge.absorb(layouter.namespace(|| "absorb_last_16_hash"), last_16_hash)?;
-            sponge.squeeze(layouter.namespace(|| "squeeze_nullifier"))
-        }?;
-
-        layouter.constrain_instance(nullifier.cell(), 0)?;
+    #[test]
+    fn test_namespaced_nullifier() {
+        let photo_commitment = poseidon_hash(&pack_bytes_native::<Fr>(&[1, 2, 3, 4])).unwrap();
+        let namespaces = [Fr::from(12345678u64), Fr::from(87654321u64)];
+        let nullifiers = namespaces
+            .map(|namespace| namespaced_nullifier_native(photo_commitment, namespace).unwrap());
+        assert_ne!(nullifiers[0], nullifiers[1]);
+        for (namespace, nullifier) in namespaces.into_iter().zip(nullifiers) {
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let photo_commitment =
+                        range.gate.load_witness(ctx, Value::known(photo_commitment));
+                    let namespace = range.gate.load_witness(ctx, Value::known(namespace));
+                    let poseidon = StandardPoseidonChip::new(R_F, R_P);
+                    let nullifier = namespaced_nullifier(
+                        &poseidon,
+                        &range.gate,
+                        ctx,
+                        &photo_commitment,
+                        &namespace,
+                    );
+                    Ok(vec![namespace, nullifier])
+                },
+                vec![namespace, nullifier],
+            );
+            prover.assert_satisfied();
+        }
+    }
 
-        Ok(())
+    #[test]
+    fn test_namespaced_nullifier_circuit() {
+        let k = NamespacedNullifierCircuit::<Fr>::K as u32;
+        let padded_len = 320;
+        let signed_data = sample_qr_payload(&SAMPLE_QR_FIELDS, 64);
+        let photo_commitment = photo_commitment_native::<Fr>(&signed_data, padded_len).unwrap();
+
+        let namespaces = [Fr::from(12345678u64), Fr::from(87654321u64)];
+        let nullifiers = namespaces
+            .map(|namespace| namespaced_nullifier_native(photo_commitment, namespace).unwrap());
+        assert_ne!(nullifiers[0], nullifiers[1]);
+        for (namespace, nullifier) in namespaces.into_iter().zip(nullifiers) {
+            let circuit =
+                NamespacedNullifierCircuit::new(namespace, signed_data.clone(), padded_len);
+            let prover = MockProver::run(k, &circuit, vec![vec![namespace, nullifier]]).unwrap();
+            assert_eq!(prover.verify(), Ok(()));
+        }
+
+        // The nullifier is bound to the namespace.
+        let circuit = NamespacedNullifierCircuit::new(namespaces[0], signed_data, padded_len);
+        let prover =
+            MockProver::run(k, &circuit, vec![vec![namespaces[0], nullifiers[1]]]).unwrap();
+        assert!(prover.verify().is_err());
     }
-}
 
-fn main() {
-    // Instantiate the circuit with the desired parameters and values.
-    let circuit = NullifierCircuit {
-        nullifier_seed: Value::known(PrimeField::from(/* your nullifier seed here */)),
-        photo: vec![/* your photo data here */],
-    };
+    #[test]
+    fn test_pubkey_nullifier() {
+        let n = (BigUint::from(1u64) << 2047) + BigUint::from(0x1234_5678_9abc_def1u64);
+        let other_n = &n + BigUint::from(2u64);
+        // The limb width follows the config, e.g. five 32-bit limbs or three 64-bit limbs per input.
+        for limb_bits in [32, 64] {
+            let expected = compute_pubkey_nullifier::<Fr>(&n, limb_bits).unwrap();
+            assert_ne!(
+                expected,
+                compute_pubkey_nullifier::<Fr>(&other_n, limb_bits).unwrap()
+            );
+            let n = n.clone();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let biguint_config = BigUintConfig::construct(range.clone(), limb_bits);
+                    let rsa_config = RSAConfig::construct(biguint_config, 2048, 5);
+                    let public_key = rsa_config.assign_public_key(
+                        ctx,
+                        RSAPublicKey::new(
+                            Value::known(n.clone()),
+                            RSAPubE::Fix(BigUint::from(65537u64)),
+                        ),
+                    )?;
+                    let poseidon = StandardPoseidonChip::new(R_F, R_P);
+                    Ok(vec![pubkey_nullifier(
+                        &poseidon,
+                        &range.gate,
+                        ctx,
+                        &public_key,
+                    )])
+                },
+                vec![expected],
+            );
+            prover.assert_satisfied();
+        }
+    }
 
-    // Create a prover and verify the circuit.
-    // Here you can use the actual proving and verification code.
-}*/*/ 
+    #[test]
+    fn test_nullifier_circuit_external_nullifier() {
+        let photo = (0..64u8).collect::<Vec<u8>>();
+        let k = NullifierCircuit::<Fr>::K as u32;
+
+        let app_id = Fr::from(12345678u64);
+        let nullifier = compute_nullifier_native(app_id, &photo).unwrap();
+        let circuit = NullifierCircuit::new(app_id, photo.clone());
+        let prover = MockProver::run(k, &circuit, vec![vec![app_id, nullifier]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The nullifier differs for another app.
+        let other_app_id = Fr::from(87654321u64);
+        let other_nullifier = compute_nullifier_native(other_app_id, &photo).unwrap();
+        assert_ne!(nullifier, other_nullifier);
+        let circuit = NullifierCircuit::new(other_app_id, photo.clone());
+        let prover =
+            MockProver::run(k, &circuit, vec![vec![other_app_id, other_nullifier]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        // The external nullifier is bound to the public input.
+        let prover = MockProver::run(k, &circuit, vec![vec![app_id, other_nullifier]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+}
@@ -0,0 +1,309 @@
+//! An in-circuit Poseidon sponge built from [`GateInstructions`].
+//!
+//! The round constants and MDS matrices are taken from the [`poseidon`] crate over the BN256 scalar field,
+//! so a hash computed by [`PoseidonChip::hash`] equals the one computed off-circuit by [`poseidon_hash`].
+
+use halo2_base::halo2_proofs::plonk::Error;
+use halo2_base::{
+    gates::GateInstructions,
+    utils::{biguint_to_fe, fe_to_biguint, PrimeField},
+    AssignedValue, Context, QuantumCell,
+};
+use halo2curves::bn256::Fr as Bn256Fr;
+use halo2curves::ff::PrimeField as _;
+use num_bigint::BigUint;
+use poseidon::{Poseidon, Spec};
+
+/// The number of full rounds.
+pub const R_F: usize = 8;
+/// The number of partial rounds.
+pub const R_P: usize = 57;
+/// The state width of the standard BN256 configuration.
+pub const POSEIDON_T: usize = 3;
+/// The rate of the standard BN256 configuration.
+pub const POSEIDON_RATE: usize = 2;
+
+/// A Poseidon chip with the standard BN256 `t = 3` parameters.
+pub type StandardPoseidonChip<F> = PoseidonChip<F, POSEIDON_T, POSEIDON_RATE>;
+
+/// A chip that computes Poseidon hashes with the optimized (sparse MDS) round structure.
+#[derive(Debug, Clone)]
+pub struct PoseidonChip<F: PrimeField, const T: usize, const RATE: usize> {
+    r_f: usize,
+    start: Vec<[F; T]>,
+    partial: Vec<F>,
+    end: Vec<[F; T]>,
+    mds: [[F; T]; T],
+    pre_sparse_mds: [[F; T]; T],
+    sparse_matrices: Vec<([F; T], [F; RATE])>,
+}
+
+impl<F: PrimeField, const T: usize, const RATE: usize> PoseidonChip<F, T, RATE> {
+    /// Creates new [`PoseidonChip`] from the numbers of rounds.
+    ///
+    /// # Arguments
+    /// * r_f - the number of full rounds.
+    /// * r_p - the number of partial rounds.
+    ///
+    /// # Return values
+    /// Returns new [`PoseidonChip`].
+    pub fn new(r_f: usize, r_p: usize) -> Self {
+        let spec = Spec::<Bn256Fr, T, RATE>::new(r_f, r_p);
+        let constants = spec.constants();
+        let matrices = spec.mds_matrices();
+        Self {
+            r_f,
+            start: constants.start().iter().map(from_bn256_row).collect(),
+            partial: constants.partial().iter().map(from_bn256).collect(),
+            end: constants.end().iter().map(from_bn256_row).collect(),
+            mds: matrices.mds().rows().map(|row| from_bn256_row(&row)),
+            pre_sparse_mds: matrices
+                .pre_sparse_mds()
+                .rows()
+                .map(|row| from_bn256_row(&row)),
+            sparse_matrices: matrices
+                .sparse_matrices()
+                .iter()
+                .map(|matrix| {
+                    (
+                        from_bn256_row(matrix.row()),
+                        matrix.col_hat().map(|e| from_bn256(&e)),
+                    )
+                })
+                .collect(),
+        }
+    }
+
+    /// Hashes the given assigned values.
+    ///
+    /// # Arguments
+    /// * gate - a gate chip.
+    /// * ctx - a region context.
+    /// * inputs - assigned values to be absorbed.
+    ///
+    /// # Return values
+    /// Returns the assigned hash value.
+    pub fn hash<'v>(
+        &self,
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        inputs: &[AssignedValue<'v, F>],
+    ) -> AssignedValue<'v, F> {
+        let mut state = Vec::with_capacity(T);
+        state.push(gate.load_constant(ctx, F::from_u128(1 << 64)));
+        for _ in 1..T {
+            state.push(gate.load_zero(ctx));
+        }
+        let mut chunks = inputs.chunks_exact(RATE);
+        for chunk in chunks.by_ref() {
+            Self::absorb(
+                gate,
+                ctx,
+                &mut state,
+                chunk.iter().map(QuantumCell::Existing),
+            );
+            self.permute(gate, ctx, &mut state);
+        }
+        // The remaining inputs are padded with a single one.
+        let last_chunk = chunks
+            .remainder()
+            .iter()
+            .map(QuantumCell::Existing)
+            .chain([QuantumCell::Constant(F::one())]);
+        Self::absorb(gate, ctx, &mut state, last_chunk);
+        self.permute(gate, ctx, &mut state);
+        state.swap_remove(1)
+    }
+
+    fn absorb<'a, 'v: 'a>(
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        state: &mut [AssignedValue<'v, F>],
+        chunk: impl Iterator<Item = QuantumCell<'a, 'v, F>>,
+    ) {
+        for (word, input) in state.iter_mut().skip(1).zip(chunk) {
+            *word = gate.add(ctx, QuantumCell::Existing(word), input);
+        }
+    }
+
+    fn permute<'v>(
+        &self,
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        state: &mut Vec<AssignedValue<'v, F>>,
+    ) {
+        let r_f = self.r_f / 2;
+        Self::add_constants(gate, ctx, state, &self.start[0]);
+        for constants in self.start.iter().skip(1).take(r_f - 1) {
+            Self::sbox_full(gate, ctx, state);
+            Self::add_constants(gate, ctx, state, constants);
+            Self::apply_mds(gate, ctx, state, &self.mds);
+        }
+        Self::sbox_full(gate, ctx, state);
+        Self::add_constants(gate, ctx, state, self.start.last().unwrap());
+        Self::apply_mds(gate, ctx, state, &self.pre_sparse_mds);
+
+        for (constant, (row, col_hat)) in self.partial.iter().zip(self.sparse_matrices.iter()) {
+            state[0] = Self::sbox(gate, ctx, &state[0]);
+            state[0] = gate.add(
+                ctx,
+                QuantumCell::Existing(&state[0]),
+                QuantumCell::Constant(*constant),
+            );
+            let first = gate.inner_product(
+                ctx,
+                state.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                row.iter()
+                    .map(|e| QuantumCell::Constant(*e))
+                    .collect::<Vec<_>>(),
+            );
+            for i in 1..T {
+                state[i] = gate.mul_add(
+                    ctx,
+                    QuantumCell::Constant(col_hat[i - 1]),
+                    QuantumCell::Existing(&state[0]),
+                    QuantumCell::Existing(&state[i]),
+                );
+            }
+            state[0] = first;
+        }
+
+        for constants in self.end.iter() {
+            Self::sbox_full(gate, ctx, state);
+            Self::add_constants(gate, ctx, state, constants);
+            Self::apply_mds(gate, ctx, state, &self.mds);
+        }
+        Self::sbox_full(gate, ctx, state);
+        Self::apply_mds(gate, ctx, state, &self.mds);
+    }
+
+    fn sbox<'v>(
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        x: &AssignedValue<'v, F>,
+    ) -> AssignedValue<'v, F> {
+        let x2 = gate.mul(ctx, QuantumCell::Existing(x), QuantumCell::Existing(x));
+        let x4 = gate.mul(ctx, QuantumCell::Existing(&x2), QuantumCell::Existing(&x2));
+        gate.mul(ctx, QuantumCell::Existing(&x4), QuantumCell::Existing(x))
+    }
+
+    fn sbox_full<'v>(
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        state: &mut [AssignedValue<'v, F>],
+    ) {
+        for word in state.iter_mut() {
+            *word = Self::sbox(gate, ctx, word);
+        }
+    }
+
+    fn add_constants<'v>(
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        state: &mut [AssignedValue<'v, F>],
+        constants: &[F; T],
+    ) {
+        for (word, constant) in state.iter_mut().zip(constants.iter()) {
+            *word = gate.add(
+                ctx,
+                QuantumCell::Existing(word),
+                QuantumCell::Constant(*constant),
+            );
+        }
+    }
+
+    fn apply_mds<'v>(
+        gate: &impl GateInstructions<F>,
+        ctx: &mut Context<'v, F>,
+        state: &mut Vec<AssignedValue<'v, F>>,
+        mds: &[[F; T]; T],
+    ) {
+        let new_state = mds
+            .iter()
+            .map(|row| {
+                gate.inner_product(
+                    ctx,
+                    state.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                    row.iter()
+                        .map(|e| QuantumCell::Constant(*e))
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+        *state = new_state;
+    }
+}
+
+/// Computes the Poseidon hash of `inputs` off-circuit with the standard BN256 `t = 3` parameters.
+///
+/// # Arguments
+/// * inputs - field elements to be hashed.
+///
+/// # Return values
+/// Returns the hash value, which equals the output of [`StandardPoseidonChip::hash`] for the same inputs.
+/// Returns [`Error::Synthesis`] if an input is not a canonical element of the BN256 scalar field, e.g. for a larger `F`.
+pub fn poseidon_hash<F: PrimeField>(inputs: &[F]) -> Result<F, Error> {
+    let mut poseidon = Poseidon::<Bn256Fr, POSEIDON_T, POSEIDON_RATE>::new(R_F, R_P);
+    poseidon.update(&inputs.iter().map(to_bn256).collect::<Result<Vec<_>, _>>()?);
+    Ok(from_bn256(&poseidon.squeeze()))
+}
+
+fn from_bn256<F: PrimeField>(value: &Bn256Fr) -> F {
+    biguint_to_fe(&BigUint::from_bytes_le(value.to_repr().as_ref()))
+}
+
+fn from_bn256_row<F: PrimeField, const T: usize>(row: &[Bn256Fr; T]) -> [F; T] {
+    row.map(|e| from_bn256(&e))
+}
+
+fn to_bn256<F: PrimeField>(value: &F) -> Result<Bn256Fr, Error> {
+    let mut repr = [0u8; 32];
+    let bytes = fe_to_biguint(value).to_bytes_le();
+    if bytes.len() > repr.len() {
+        return Err(Error::Synthesis);
+    }
+    repr[..bytes.len()].copy_from_slice(&bytes);
+    Option::from(Bn256Fr::from_repr(repr)).ok_or(Error::Synthesis)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::mock_prove;
+    use halo2_base::halo2_proofs::{
+        circuit::Value,
+        halo2curves::bn256::{Fq, Fr},
+    };
+
+    #[test]
+    fn test_poseidon_matches_off_circuit() {
+        for num_inputs in [0usize, 1, 2, 5] {
+            let inputs = (0..num_inputs)
+                .map(|i| Fr::from(i as u64 + 1))
+                .collect::<Vec<_>>();
+            let expected = poseidon_hash(&inputs).unwrap();
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let chip = StandardPoseidonChip::<Fr>::new(R_F, R_P);
+                    let assigned = inputs
+                        .iter()
+                        .map(|input| range.gate.load_witness(ctx, Value::known(*input)))
+                        .collect::<Vec<_>>();
+                    Ok(vec![chip.hash(&range.gate, ctx, &assigned)])
+                },
+                vec![expected],
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    #[test]
+    fn test_poseidon_rejects_non_canonical_inputs() {
+        // The base field of BN256 is larger than its scalar field.
+        assert!(poseidon_hash(&[Fq::from(1)]).is_ok());
+        assert!(matches!(
+            poseidon_hash(&[-Fq::one()]),
+            Err(Error::Synthesis)
+        ));
+    }
+}
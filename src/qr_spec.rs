@@ -0,0 +1,574 @@
+//! Field layout of the UIDAI secure QR code, format v2.
+//!
+//! The decoded payload starts with delimited fields separated by [`DELIMITER`], followed by the photo,
+//! the optional email and mobile hashes, and the RSA signature:
+//!
+//! ```text
+//! V2 | indicator | reference id | name | ... | last 4 mobile digits | photo [email hash] [mobile hash] signature
+//! ```
+//!
+//! Extractors resolve field offsets through [`QrField::spec`] and [`field_range`] rather than hard-coding positions.
+//!
+//! Older payloads ([`QrFormat::V1`]) have no version field and pack the whole address into the location field,
+//! see [`QR_V1_FIELDS`].
+
+use std::ops::Range;
+
+/// The byte separating delimited fields.
+pub const DELIMITER: u8 = 255;
+/// The number of delimiters before the photo in format v2.
+pub const NUM_DELIMITERS: usize = 18;
+/// The number of delimiters before the photo in format v1.
+pub const NUM_V1_DELIMITERS: usize = 8;
+/// The byte length of an email or mobile hash.
+pub const CONTACT_HASH_LEN: usize = 32;
+/// The byte length of the RSA-2048 signature at the end of the payload.
+pub const SIGNATURE_LEN: usize = 256;
+
+/// How a field is laid out in the payload.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldLayout {
+    /// Terminated by [`DELIMITER`].
+    Delimited,
+    /// Runs from the last delimiter up to the trailing fixed-length fields.
+    Variable,
+    /// A fixed number of bytes located relative to the end of the payload.
+    Fixed(usize),
+}
+
+/// How the bytes of a field are interpreted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    /// Free-form ASCII text.
+    Text,
+    /// ASCII decimal digits.
+    Digits,
+    /// A date of the form `DD-MM-YYYY`.
+    Date,
+    /// A single ASCII character, `M`, `F` or `T`.
+    Gender,
+    /// Raw bytes.
+    Binary,
+}
+
+/// Every field of the format v2 payload, in order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrField {
+    Version,
+    EmailMobilePresent,
+    ReferenceId,
+    Name,
+    DateOfBirth,
+    Gender,
+    CareOf,
+    District,
+    Landmark,
+    House,
+    Location,
+    Pincode,
+    PostOffice,
+    State,
+    Street,
+    SubDistrict,
+    Vtc,
+    MobileLast4,
+    Photo,
+    EmailHash,
+    MobileHash,
+    Signature,
+}
+
+/// Description of a single field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldSpec {
+    /// The field.
+    pub field: QrField,
+    /// The index of the field in the payload.
+    /// For delimited fields, it equals the number of delimiters preceding the field.
+    pub position: usize,
+    /// The layout of the field.
+    pub layout: FieldLayout,
+    /// The type of the field.
+    pub ty: FieldType,
+}
+
+const fn spec(field: QrField, position: usize, layout: FieldLayout, ty: FieldType) -> FieldSpec {
+    FieldSpec {
+        field,
+        position,
+        layout,
+        ty,
+    }
+}
+
+/// The fields of the format v2 payload, in order.
+pub const QR_V2_FIELDS: [FieldSpec; 22] = [
+    spec(QrField::Version, 0, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::EmailMobilePresent,
+        1,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(
+        QrField::ReferenceId,
+        2,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(QrField::Name, 3, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::DateOfBirth,
+        4,
+        FieldLayout::Delimited,
+        FieldType::Date,
+    ),
+    spec(
+        QrField::Gender,
+        5,
+        FieldLayout::Delimited,
+        FieldType::Gender,
+    ),
+    spec(QrField::CareOf, 6, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::District,
+        7,
+        FieldLayout::Delimited,
+        FieldType::Text,
+    ),
+    spec(
+        QrField::Landmark,
+        8,
+        FieldLayout::Delimited,
+        FieldType::Text,
+    ),
+    spec(QrField::House, 9, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::Location,
+        10,
+        FieldLayout::Delimited,
+        FieldType::Text,
+    ),
+    spec(
+        QrField::Pincode,
+        11,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(
+        QrField::PostOffice,
+        12,
+        FieldLayout::Delimited,
+        FieldType::Text,
+    ),
+    spec(QrField::State, 13, FieldLayout::Delimited, FieldType::Text),
+    spec(QrField::Street, 14, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::SubDistrict,
+        15,
+        FieldLayout::Delimited,
+        FieldType::Text,
+    ),
+    spec(QrField::Vtc, 16, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::MobileLast4,
+        17,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(QrField::Photo, 18, FieldLayout::Variable, FieldType::Binary),
+    spec(
+        QrField::EmailHash,
+        19,
+        FieldLayout::Fixed(CONTACT_HASH_LEN),
+        FieldType::Binary,
+    ),
+    spec(
+        QrField::MobileHash,
+        20,
+        FieldLayout::Fixed(CONTACT_HASH_LEN),
+        FieldType::Binary,
+    ),
+    spec(
+        QrField::Signature,
+        21,
+        FieldLayout::Fixed(SIGNATURE_LEN),
+        FieldType::Binary,
+    ),
+];
+
+/// The fields of the format v1 payload, in order.
+/// The location field holds the full address.
+pub const QR_V1_FIELDS: [FieldSpec; 12] = [
+    spec(
+        QrField::EmailMobilePresent,
+        0,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(
+        QrField::ReferenceId,
+        1,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(QrField::Name, 2, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::DateOfBirth,
+        3,
+        FieldLayout::Delimited,
+        FieldType::Date,
+    ),
+    spec(
+        QrField::Gender,
+        4,
+        FieldLayout::Delimited,
+        FieldType::Gender,
+    ),
+    spec(QrField::CareOf, 5, FieldLayout::Delimited, FieldType::Text),
+    spec(
+        QrField::Location,
+        6,
+        FieldLayout::Delimited,
+        FieldType::Text,
+    ),
+    spec(
+        QrField::MobileLast4,
+        7,
+        FieldLayout::Delimited,
+        FieldType::Digits,
+    ),
+    spec(QrField::Photo, 8, FieldLayout::Variable, FieldType::Binary),
+    spec(
+        QrField::EmailHash,
+        9,
+        FieldLayout::Fixed(CONTACT_HASH_LEN),
+        FieldType::Binary,
+    ),
+    spec(
+        QrField::MobileHash,
+        10,
+        FieldLayout::Fixed(CONTACT_HASH_LEN),
+        FieldType::Binary,
+    ),
+    spec(
+        QrField::Signature,
+        11,
+        FieldLayout::Fixed(SIGNATURE_LEN),
+        FieldType::Binary,
+    ),
+];
+
+/// The version of the payload layout.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QrFormat {
+    /// The older layout without a version field, whose address is a single location field.
+    V1,
+    /// The layout starting with the `V2` version field, whose address is split into components.
+    V2,
+}
+
+impl QrFormat {
+    /// Detects the format of `data` from its version field.
+    pub fn detect(data: &[u8]) -> Self {
+        if data.starts_with(b"V2") {
+            Self::V2
+        } else {
+            Self::V1
+        }
+    }
+
+    /// Returns the fields of the format, in order.
+    pub fn fields(self) -> &'static [FieldSpec] {
+        match self {
+            Self::V1 => &QR_V1_FIELDS,
+            Self::V2 => &QR_V2_FIELDS,
+        }
+    }
+
+    /// Returns the number of delimiters before the photo.
+    pub fn num_delimiters(self) -> usize {
+        match self {
+            Self::V1 => NUM_V1_DELIMITERS,
+            Self::V2 => NUM_DELIMITERS,
+        }
+    }
+
+    /// Returns the [`FieldSpec`] of `field`, or `None` if the format does not have the field.
+    pub fn spec(self, field: QrField) -> Option<&'static FieldSpec> {
+        self.fields().iter().find(|spec| spec.field == field)
+    }
+
+    /// Returns the first and the last delimited fields making up the full address.
+    /// The address spans the bytes from the start of the first field to the end of the last one, including delimiters.
+    pub fn address_fields(self) -> (QrField, QrField) {
+        match self {
+            Self::V1 => (QrField::Location, QrField::Location),
+            Self::V2 => (QrField::CareOf, QrField::Vtc),
+        }
+    }
+}
+
+impl QrField {
+    /// Returns the [`FieldSpec`] of the field in format v2.
+    pub fn spec(self) -> &'static FieldSpec {
+        QR_V2_FIELDS
+            .iter()
+            .find(|spec| spec.field == self)
+            .expect("every field has a spec")
+    }
+
+    /// Returns the index of the field in the format v2 payload.
+    pub fn position(self) -> usize {
+        self.spec().position
+    }
+}
+
+/// Returns the indices of the delimiters before the photo in `data`, whose format is detected by [`QrFormat::detect`].
+/// Delimiter bytes inside the photo are not counted.
+pub fn delimiter_indices(data: &[u8]) -> Vec<usize> {
+    data.iter()
+        .enumerate()
+        .filter(|(_, byte)| **byte == DELIMITER)
+        .map(|(idx, _)| idx)
+        .take(QrFormat::detect(data).num_delimiters())
+        .collect()
+}
+
+/// Returns the byte range of `field` in `data`, whose format is detected by [`QrFormat::detect`].
+///
+/// # Arguments
+/// * data - the decoded QR payload, including the trailing signature.
+/// * field - a field to be located.
+///
+/// # Return values
+/// Returns the range of the field, or `None` if `data` is malformed or its format does not have the field.
+/// The photo range excludes the email and mobile hashes, whose presence is read from [`QrField::EmailMobilePresent`].
+pub fn field_range(data: &[u8], field: QrField) -> Option<Range<usize>> {
+    let format = QrFormat::detect(data);
+    let num_delimiters = format.num_delimiters();
+    let delimiters = delimiter_indices(data);
+    if delimiters.len() != num_delimiters {
+        return None;
+    }
+    let spec = format.spec(field)?;
+    match spec.layout {
+        FieldLayout::Delimited => {
+            let start = match spec.position {
+                0 => 0,
+                position => delimiters[position - 1] + 1,
+            };
+            Some(start..delimiters[spec.position])
+        }
+        FieldLayout::Variable => {
+            let start = delimiters[num_delimiters - 1] + 1;
+            let end = data
+                .len()
+                .checked_sub(SIGNATURE_LEN + trailing_hashes_len(data)?)?;
+            (start <= end).then_some(start..end)
+        }
+        FieldLayout::Fixed(len) => {
+            let (email_present, mobile_present) = contact_hashes_present(data)?;
+            let signature_start = data.len().checked_sub(SIGNATURE_LEN)?;
+            let mobile_start =
+                signature_start.checked_sub(CONTACT_HASH_LEN * mobile_present as usize)?;
+            let start = match field {
+                QrField::Signature => signature_start,
+                QrField::MobileHash if mobile_present => mobile_start,
+                QrField::EmailHash if email_present => {
+                    mobile_start.checked_sub(CONTACT_HASH_LEN)?
+                }
+                _ => return None,
+            };
+            Some(start..start + len)
+        }
+    }
+}
+
+/// Returns whether the email and mobile hashes are present, read from the indicator field.
+///
+/// The indicator is `0` without hashes, `1` for the email hash only, `2` for the mobile hash only and `3` for both.
+/// Returns `None` for any other indicator.
+pub fn contact_hashes_present(data: &[u8]) -> Option<(bool, bool)> {
+    let range = field_range(data, QrField::EmailMobilePresent)?;
+    let indicator = std::str::from_utf8(&data[range]).ok()?.parse::<u8>().ok()?;
+    if indicator > 3 {
+        return None;
+    }
+    Some((indicator & 1 != 0, indicator & 2 != 0))
+}
+
+/// Returns the length of the email and mobile hashes at the end of `data`, see [`contact_hashes_present`].
+pub(crate) fn trailing_hashes_len(data: &[u8]) -> Option<usize> {
+    let (email_present, mobile_present) = contact_hashes_present(data)?;
+    Some(CONTACT_HASH_LEN * (email_present as usize + mobile_present as usize))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample_qr() -> Vec<u8> {
+        let fields: [&[u8]; NUM_DELIMITERS] = [
+            b"V2",
+            b"3",
+            b"269720240718124553876",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+            b"C/O Ishwar Chand",
+            b"East Delhi",
+            b"",
+            b"B-31, 3rd Floor",
+            b"",
+            b"110051",
+            b"Krishna Nagar",
+            b"Delhi",
+            b"Radhey Shyam Park Extension",
+            b"Gandhi Nagar",
+            b"Krishna Nagar",
+            b"1234",
+        ];
+        let mut data = vec![];
+        for field in fields {
+            data.extend_from_slice(field);
+            data.push(DELIMITER);
+        }
+        // The photo starts with the JPEG 2000 markers 0xff4f 0xff51.
+        data.extend_from_slice(&[255, 79, 255, 81, 0, 47, 0, 0]);
+        data.extend_from_slice(&[0xe0; CONTACT_HASH_LEN]);
+        data.extend_from_slice(&[0x0b; CONTACT_HASH_LEN]);
+        data.extend_from_slice(&[0x51; SIGNATURE_LEN]);
+        data
+    }
+
+    const V1_LOCATION: &[u8] =
+        b"B-31, 3rd Floor, Radhey Shyam Park Extension, Gandhi Nagar, East Delhi, Delhi 110051";
+
+    fn sample_qr_v1() -> Vec<u8> {
+        let fields: [&[u8]; NUM_V1_DELIMITERS] = [
+            b"2",
+            b"269720190612104534087",
+            b"Sumit Kumar",
+            b"01-01-1984",
+            b"M",
+            b"C/O Ishwar Chand",
+            V1_LOCATION,
+            b"1234",
+        ];
+        let mut data = vec![];
+        for field in fields {
+            data.extend_from_slice(field);
+            data.push(DELIMITER);
+        }
+        data.extend_from_slice(&[255, 79, 255, 81, 0, 47, 0, 0]);
+        data.extend_from_slice(&[0x0b; CONTACT_HASH_LEN]);
+        data.extend_from_slice(&[0x51; SIGNATURE_LEN]);
+        data
+    }
+
+    #[test]
+    fn test_qr_v2_spec_ordering() {
+        assert_eq!(QR_V2_FIELDS.len(), 22);
+        for (idx, spec) in QR_V2_FIELDS.iter().enumerate() {
+            assert_eq!(spec.position, idx);
+            assert_eq!(spec.field.position(), idx);
+        }
+        let num_delimited = QR_V2_FIELDS
+            .iter()
+            .filter(|spec| spec.layout == FieldLayout::Delimited)
+            .count();
+        assert_eq!(num_delimited, NUM_DELIMITERS);
+    }
+
+    #[test]
+    fn test_qr_v2_spec_matches_sample() {
+        let data = sample_qr();
+        assert_eq!(
+            delimiter_indices(&data),
+            vec![2, 4, 26, 38, 49, 51, 68, 79, 80, 96, 97, 104, 118, 124, 152, 165, 179, 184]
+        );
+        let field = |field: QrField| &data[field_range(&data, field).unwrap()];
+        assert_eq!(field(QrField::Version), b"V2");
+        assert_eq!(field(QrField::ReferenceId), b"269720240718124553876");
+        assert_eq!(field(QrField::Name), b"Sumit Kumar");
+        assert_eq!(field(QrField::DateOfBirth), b"01-01-1984");
+        assert_eq!(field(QrField::Gender), b"M");
+        assert_eq!(field(QrField::Landmark), b"");
+        assert_eq!(field(QrField::Pincode), b"110051");
+        assert_eq!(field(QrField::State), b"Delhi");
+        assert_eq!(field(QrField::MobileLast4), b"1234");
+        assert_eq!(field(QrField::Photo), &[255, 79, 255, 81, 0, 47, 0, 0]);
+        assert_eq!(field(QrField::EmailHash), &[0xe0; CONTACT_HASH_LEN]);
+        assert_eq!(field(QrField::MobileHash), &[0x0b; CONTACT_HASH_LEN]);
+        assert_eq!(field(QrField::Signature), &[0x51; SIGNATURE_LEN]);
+    }
+
+    #[test]
+    fn test_contact_hashes_present() {
+        let sample = sample_qr();
+        let photo_end = sample.len() - SIGNATURE_LEN - 2 * CONTACT_HASH_LEN;
+        for (indicator, email_present, mobile_present) in [
+            (b'0', false, false),
+            (b'1', true, false),
+            (b'2', false, true),
+            (b'3', true, true),
+        ] {
+            let mut data = sample[..photo_end].to_vec();
+            data[3] = indicator;
+            if email_present {
+                data.extend_from_slice(&[0xe0; CONTACT_HASH_LEN]);
+            }
+            if mobile_present {
+                data.extend_from_slice(&[0x0b; CONTACT_HASH_LEN]);
+            }
+            data.extend_from_slice(&[0x51; SIGNATURE_LEN]);
+            assert_eq!(
+                contact_hashes_present(&data),
+                Some((email_present, mobile_present))
+            );
+            let field = |field: QrField| field_range(&data, field).map(|range| &data[range]);
+            assert_eq!(
+                field(QrField::Photo),
+                Some(&[255, 79, 255, 81, 0, 47, 0, 0][..])
+            );
+            assert_eq!(
+                field(QrField::EmailHash),
+                email_present.then_some(&[0xe0; CONTACT_HASH_LEN][..])
+            );
+            assert_eq!(
+                field(QrField::MobileHash),
+                mobile_present.then_some(&[0x0b; CONTACT_HASH_LEN][..])
+            );
+        }
+
+        let mut data = sample;
+        data[3] = b'4';
+        assert_eq!(contact_hashes_present(&data), None);
+    }
+
+    #[test]
+    fn test_qr_v1_spec_matches_sample() {
+        for (idx, spec) in QR_V1_FIELDS.iter().enumerate() {
+            assert_eq!(spec.position, idx);
+        }
+        let data = sample_qr_v1();
+        assert_eq!(QrFormat::detect(&data), QrFormat::V1);
+        assert_eq!(QrFormat::detect(&sample_qr()), QrFormat::V2);
+        let field = |field: QrField| field_range(&data, field).map(|range| &data[range]);
+        assert_eq!(field(QrField::Name), Some(&b"Sumit Kumar"[..]));
+        assert_eq!(field(QrField::Location), Some(V1_LOCATION));
+        assert_eq!(field(QrField::MobileLast4), Some(&b"1234"[..]));
+        assert_eq!(
+            field(QrField::Photo),
+            Some(&[255, 79, 255, 81, 0, 47, 0, 0][..])
+        );
+        assert_eq!(field(QrField::EmailHash), None);
+        assert_eq!(
+            field(QrField::MobileHash),
+            Some(&[0x0b; CONTACT_HASH_LEN][..])
+        );
+        assert_eq!(field(QrField::Pincode), None);
+        assert_eq!(field(QrField::Version), None);
+    }
+}
@@ -0,0 +1,51 @@
+//! Serde helpers for witness values, enabled by the `serde` feature.
+//!
+//! Integers are serialized as their big-endian bytes.
+//! A [`Value`] is serialized as an optional integer, where `None` represents [`Value::unknown`].
+
+use halo2_base::halo2_proofs::circuit::Value;
+use num_bigint::BigUint;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Serializes a [`BigUint`] as its big-endian bytes.
+pub(crate) mod biguint_bytes {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &BigUint,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        value.to_bytes_be().serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<BigUint, D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        Ok(BigUint::from_bytes_be(&bytes))
+    }
+}
+
+/// Serializes a [`Value<BigUint>`] as the optional big-endian bytes of the known integer.
+pub(crate) mod value_biguint_bytes {
+    use super::*;
+
+    pub(crate) fn serialize<S: Serializer>(
+        value: &Value<BigUint>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = None;
+        value.as_ref().map(|v| bytes = Some(v.to_bytes_be()));
+        bytes.serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Value<BigUint>, D::Error> {
+        let bytes = Option::<Vec<u8>>::deserialize(deserializer)?;
+        Ok(match bytes {
+            Some(bytes) => Value::known(BigUint::from_bytes_be(&bytes)),
+            None => Value::unknown(),
+        })
+    }
+}
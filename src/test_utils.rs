@@ -0,0 +1,341 @@
+//! A small harness for testing gadgets built on top of [`RangeConfig`] with the mock prover.
+
+use std::marker::PhantomData;
+
+use crate::qr_spec::{DELIMITER, NUM_DELIMITERS};
+use halo2_base::halo2_proofs::{
+    circuit::{Cell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use halo2_base::{
+    gates::{
+        range::{RangeConfig, RangeStrategy::Vertical},
+        GateInstructions,
+    },
+    utils::PrimeField,
+    AssignedValue, Context, ContextParams, SKIP_FIRST_PASS,
+};
+#[cfg(feature = "sha256")]
+use halo2_dynamic_sha256::Sha256DynamicConfig;
+use num_bigint::BigUint;
+use rsa::{pkcs8::DecodePrivateKey, traits::PublicKeyParts, Pkcs1v15Sign, RsaPrivateKey};
+#[cfg(feature = "sha256")]
+use sha2::Digest;
+use sha2::Sha256;
+
+pub(crate) const K: u32 = 15;
+const NUM_ADVICE: usize = 80;
+const NUM_FIXED: usize = 1;
+const NUM_LOOKUP_ADVICE: usize = 16;
+const LOOKUP_BITS: usize = 12;
+#[cfg(feature = "sha256")]
+pub(crate) const SHA256_MAX_BYTE_SIZES: [usize; 10] = [128; 10];
+#[cfg(feature = "sha256")]
+const SHA256_LOOKUP_BITS: usize = 8;
+#[cfg(feature = "sha256")]
+const SHA256_LOOKUP_ADVICE: usize = 8;
+
+#[derive(Debug, Clone)]
+pub(crate) struct GadgetTestConfig<F: PrimeField> {
+    range: RangeConfig<F>,
+    instance: Column<Instance>,
+}
+
+/// A circuit that runs `synthesize` inside a single region and constrains the returned cells to its instance column.
+#[derive(Clone)]
+pub(crate) struct GadgetTestCircuit<F: PrimeField, S> {
+    synthesize: S,
+    _f: PhantomData<F>,
+}
+
+impl<F, S> Circuit<F> for GadgetTestCircuit<F, S>
+where
+    F: PrimeField,
+    S: Clone
+        + for<'v> Fn(&mut Context<'v, F>, &RangeConfig<F>) -> Result<Vec<AssignedValue<'v, F>>, Error>,
+{
+    type Config = GadgetTestConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let range = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[NUM_ADVICE],
+            &[NUM_LOOKUP_ADVICE],
+            NUM_FIXED,
+            LOOKUP_BITS,
+            0,
+            K as usize,
+        );
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        GadgetTestConfig { range, instance }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.range.load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let public_cells = layouter.assign_region(
+            || "gadget test",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+                let mut aux = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.range.gate.constants.clone(),
+                    },
+                );
+                let ctx = &mut aux;
+                let public_values = (self.synthesize)(ctx, &config.range)?;
+                config.range.finalize(ctx);
+                Ok(public_values
+                    .into_iter()
+                    .map(|v| v.cell())
+                    .collect::<Vec<Cell>>())
+            },
+        )?;
+        for (i, cell) in public_cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.instance, i)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the mock prover on a [`GadgetTestCircuit`] built from `synthesize`.
+pub(crate) fn mock_prove<F, S>(synthesize: S, public_inputs: Vec<F>) -> MockProver<F>
+where
+    F: PrimeField,
+    S: Clone
+        + for<'v> Fn(&mut Context<'v, F>, &RangeConfig<F>) -> Result<Vec<AssignedValue<'v, F>>, Error>,
+{
+    let circuit = GadgetTestCircuit {
+        synthesize,
+        _f: PhantomData,
+    };
+    match MockProver::run(K, &circuit, vec![public_inputs]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    }
+}
+
+/// The decimal QR string of a synthetic payload signed by `test_private_key.pem`, see `tests/fixtures/README.md`.
+pub(crate) const SAMPLE_QR: &str = include_str!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/fixtures/sample_qr.txt"
+));
+
+/// The RSA-2048 test key with `e = 65537` of `test_private_key.pem`, see `tests/fixtures/README.md`.
+pub(crate) fn test_private_key() -> RsaPrivateKey {
+    RsaPrivateKey::from_pkcs8_pem(include_str!(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/test_private_key.pem"
+    )))
+    .expect("invalid test key")
+}
+
+/// Returns the modulus of [`test_private_key`].
+pub(crate) fn test_modulus() -> BigUint {
+    BigUint::from_bytes_be(&test_private_key().n().to_bytes_be())
+}
+
+/// Signs the SHA256 hash of `msg` with [`test_private_key`] and returns the pkcs1v15 signature as an integer.
+#[cfg(feature = "sha256")]
+pub(crate) fn sign_with_test_key(msg: &[u8]) -> BigUint {
+    sign_hash_with_test_key(&Sha256::digest(msg))
+}
+
+/// Signs a SHA256 hash with [`test_private_key`] and returns the pkcs1v15 signature as an integer.
+pub(crate) fn sign_hash_with_test_key(hashed: &[u8]) -> BigUint {
+    let sign = test_private_key()
+        .sign(Pkcs1v15Sign::new::<Sha256>(), hashed)
+        .expect("failed to sign");
+    BigUint::from_bytes_be(&sign)
+}
+
+/// The delimited fields of a sample format v2 payload.
+pub(crate) const SAMPLE_QR_FIELDS: [&[u8]; NUM_DELIMITERS] = [
+    b"V2",
+    b"0",
+    b"269720240718124553876",
+    b"Sumit Kumar",
+    b"01-01-1984",
+    b"M",
+    b"C/O Ishwar Chand",
+    b"East Delhi",
+    b"",
+    b"B-31, 3rd Floor",
+    b"",
+    b"110051",
+    b"Krishna Nagar",
+    b"Delhi",
+    b"Radhey Shyam Park Extension",
+    b"Gandhi Nagar",
+    b"Krishna Nagar",
+    b"1234",
+];
+
+/// Builds a signed payload, i.e. without its signature, from delimited fields followed by a photo of `photo_len` bytes.
+pub(crate) fn sample_qr_payload(fields: &[&[u8]], photo_len: usize) -> Vec<u8> {
+    let mut data = fields.join(&DELIMITER);
+    data.push(DELIMITER);
+    data.extend((0..photo_len as u32).map(|i| (i * 7 % 251) as u8));
+    data
+}
+
+/// Assigns `bytes` zero-padded to `padded_len`.
+pub(crate) fn load_bytes<'v, F: PrimeField>(
+    ctx: &mut Context<'v, F>,
+    range: &RangeConfig<F>,
+    bytes: &[u8],
+    padded_len: usize,
+) -> Vec<AssignedValue<'v, F>> {
+    let mut padded = bytes.to_vec();
+    padded.resize(padded_len, 0);
+    padded
+        .iter()
+        .map(|byte| {
+            range
+                .gate
+                .load_witness(ctx, Value::known(F::from(*byte as u64)))
+        })
+        .collect()
+}
+
+#[cfg(feature = "sha256")]
+#[derive(Debug, Clone)]
+pub(crate) struct Sha256GadgetTestConfig<F: PrimeField> {
+    range: RangeConfig<F>,
+    sha256: Sha256DynamicConfig<F>,
+    instance: Column<Instance>,
+}
+
+/// Same as [`GadgetTestCircuit`] but also passes a [`Sha256DynamicConfig`] configured with [`SHA256_MAX_BYTE_SIZES`].
+#[cfg(feature = "sha256")]
+#[derive(Clone)]
+pub(crate) struct Sha256GadgetTestCircuit<F: PrimeField, S> {
+    synthesize: S,
+    _f: PhantomData<F>,
+}
+
+#[cfg(feature = "sha256")]
+impl<F, S> Circuit<F> for Sha256GadgetTestCircuit<F, S>
+where
+    F: PrimeField,
+    S: Clone
+        + for<'v> Fn(
+            &mut Context<'v, F>,
+            &RangeConfig<F>,
+            &mut Sha256DynamicConfig<F>,
+        ) -> Result<Vec<AssignedValue<'v, F>>, Error>,
+{
+    type Config = Sha256GadgetTestConfig<F>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let range = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[NUM_ADVICE],
+            &[NUM_LOOKUP_ADVICE],
+            NUM_FIXED,
+            LOOKUP_BITS,
+            0,
+            K as usize,
+        );
+        let sha256 = Sha256DynamicConfig::configure(
+            meta,
+            SHA256_MAX_BYTE_SIZES.to_vec(),
+            range.clone(),
+            SHA256_LOOKUP_BITS,
+            SHA256_LOOKUP_ADVICE,
+            true,
+        );
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Sha256GadgetTestConfig {
+            range,
+            sha256,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.sha256.load(&mut layouter)?;
+        config.range.load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let public_cells = layouter.assign_region(
+            || "sha256 gadget test",
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(vec![]);
+                }
+                let mut aux = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.range.gate.constants.clone(),
+                    },
+                );
+                let ctx = &mut aux;
+                let mut sha256 = config.sha256.clone();
+                let public_values = (self.synthesize)(ctx, &config.range, &mut sha256)?;
+                config.range.finalize(ctx);
+                Ok(public_values
+                    .into_iter()
+                    .map(|v| v.cell())
+                    .collect::<Vec<Cell>>())
+            },
+        )?;
+        for (i, cell) in public_cells.into_iter().enumerate() {
+            layouter.constrain_instance(cell, config.instance, i)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs the mock prover on a [`Sha256GadgetTestCircuit`] built from `synthesize`.
+#[cfg(feature = "sha256")]
+pub(crate) fn mock_prove_with_sha256<F, S>(synthesize: S, public_inputs: Vec<F>) -> MockProver<F>
+where
+    F: PrimeField,
+    S: Clone
+        + for<'v> Fn(
+            &mut Context<'v, F>,
+            &RangeConfig<F>,
+            &mut Sha256DynamicConfig<F>,
+        ) -> Result<Vec<AssignedValue<'v, F>>, Error>,
+{
+    let circuit = Sha256GadgetTestCircuit {
+        synthesize,
+        _f: PhantomData,
+    };
+    match MockProver::run(K, &circuit, vec![public_inputs]) {
+        Ok(prover) => prover,
+        Err(e) => panic!("{:#?}", e),
+    }
+}
@@ -1,11 +1,48 @@
 use halo2_base::halo2_proofs::{
     circuit::{Layouter, SimpleFloorPlanner, Value},
-    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
 };
 //use std::sync::Arc;
 
-use halo2_base::utils::PrimeField;
+use crate::utils::ascii_digits_to_int;
+use halo2_base::{
+    gates::{
+        range::{RangeConfig, RangeStrategy::Vertical},
+        GateInstructions, RangeInstructions,
+    },
+    utils::PrimeField,
+    AssignedValue, Context, ContextParams, QuantumCell, SKIP_FIRST_PASS,
+};
+
+/// The time zone of the date and time fields of a [`TimestampCircuit`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TimeZone {
+    /// Coordinated Universal Time.
+    #[default]
+    Utc,
+    /// Indian Standard Time (UTC+5:30), in which Aadhaar QR timestamps are given.
+    Ist,
+}
+
+impl TimeZone {
+    /// Returns the offset of the time zone from UTC in seconds.
+    pub fn offset_seconds(self) -> u64 {
+        match self {
+            Self::Utc => 0,
+            Self::Ist => 5 * 3600 + 30 * 60,
+        }
+    }
+}
+
+/// The byte length of a timestamp `YYYYMMDDHHMMSSsss` in an Aadhaar QR payload.
+pub const QR_TIMESTAMP_LEN: usize = 17;
 
+/// A circuit exposing the UNIX timestamp of a date and time on its instance column.
+///
+/// The fields are given in the [`TimeZone`] passed to [`TimestampCircuit::new`], and the exposed timestamp is always in UTC.
+///
+/// The fields are constrained by [`to_unix_timestamp`]: `1970 <= year <= 2099`, `1 <= month <= 12`, `1 <= day <= days_in_month` with leap years,
+/// `hour <= 23`, `minute <= 59` and `second <= 59`. Fields out of these ranges fail verification.
 #[derive(Debug, Clone, Default)]
 pub struct TimestampCircuit<F: PrimeField> {
     year: Option<F>,
@@ -14,21 +51,22 @@ pub struct TimestampCircuit<F: PrimeField> {
     hour: Option<F>,
     minute: Option<F>,
     second: Option<F>,
+    time_zone: TimeZone,
 }
 
 #[derive(Debug, Clone)]
-pub struct TimestampConfig {
-    sel: Selector,
-    year: Column<Advice>,
-    month: Column<Advice>,
-    day: Column<Advice>,
-    hour: Column<Advice>,
-    minute: Column<Advice>,
-    second: Column<Advice>,
-    timestamp: Column<Advice>,
+pub struct TimestampConfig<F: PrimeField> {
+    range: RangeConfig<F>,
+    instance: Column<Instance>,
 }
 
 impl<F: PrimeField> TimestampCircuit<F> {
+    pub const K: usize = 10;
+    const NUM_ADVICE: usize = 2;
+    const NUM_FIXED: usize = 1;
+    const NUM_LOOKUP_ADVICE: usize = 1;
+    const LOOKUP_BITS: usize = 8;
+
     pub fn new(
         year: Option<F>,
         month: Option<F>,
@@ -36,6 +74,7 @@ impl<F: PrimeField> TimestampCircuit<F> {
         hour: Option<F>,
         minute: Option<F>,
         second: Option<F>,
+        time_zone: TimeZone,
     ) -> Self {
         Self {
             year,
@@ -44,98 +83,98 @@ impl<F: PrimeField> TimestampCircuit<F> {
             hour,
             minute,
             second,
+            time_zone,
         }
     }
+
+    /// Parses a timestamp of an Aadhaar QR payload into the field witnesses of a circuit.
+    ///
+    /// # Arguments
+    /// * bytes - ASCII digits `YYYYMMDDHHMMSSsss` of length [`QR_TIMESTAMP_LEN`]. The milliseconds `sss` are ignored.
+    ///
+    /// # Return values
+    /// Returns a circuit with the parsed fields in [`TimeZone::Ist`], the time zone of QR timestamps.
+    /// Returns [`Error::Synthesis`] if `bytes` is not [`QR_TIMESTAMP_LEN`] bytes long or contains a non-digit.
+    pub fn from_qr_timestamp(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != QR_TIMESTAMP_LEN || !bytes.iter().all(u8::is_ascii_digit) {
+            return Err(Error::Synthesis);
+        }
+        let parse = |start: usize, end: usize| {
+            let value = bytes[start..end]
+                .iter()
+                .fold(0u64, |acc, byte| acc * 10 + (byte - b'0') as u64);
+            Some(F::from(value))
+        };
+        Ok(Self::new(
+            parse(0, 4),
+            parse(4, 6),
+            parse(6, 8),
+            parse(8, 10),
+            parse(10, 12),
+            parse(12, 14),
+            TimeZone::Ist,
+        ))
+    }
+
+    /// Returns the field witnesses in the order of [`assign_timestamp`] and the UTC timestamp computed from them.
+    ///
+    /// If every field is `None`, as in [`Circuit::without_witnesses`], all of them are unknown.
+    /// Returns [`Error::Synthesis`] if only some of the fields are `None`, the fields are rejected by [`is_valid_date_time`], or the UTC timestamp would be before the epoch.
+    fn witnesses(&self) -> Result<([Value<F>; 6], Value<F>), Error> {
+        let fields = [
+            self.year,
+            self.month,
+            self.day,
+            self.hour,
+            self.minute,
+            self.second,
+        ];
+        if fields.iter().all(Option::is_none) {
+            return Ok(([Value::unknown(); 6], Value::unknown()));
+        }
+        let mut values = [0u64; 6];
+        for (value, field) in values.iter_mut().zip(fields.iter()) {
+            *value = field.ok_or(Error::Synthesis)?.get_lower_32() as u64;
+        }
+        let [year, month, day, hour, minute, second] = values;
+        if !is_valid_date_time(year, month, day, hour, minute, second) {
+            return Err(Error::Synthesis);
+        }
+        let total_seconds = unix_timestamp_native(year, month, day, hour, minute, second)
+            .checked_sub(self.time_zone.offset_seconds())
+            .ok_or(Error::Synthesis)?;
+        Ok((
+            values.map(|value| Value::known(F::from(value))),
+            Value::known(F::from(total_seconds)),
+        ))
+    }
 }
 
 impl<F: PrimeField> Circuit<F> for TimestampCircuit<F> {
-    type Config = TimestampConfig;
+    type Config = TimestampConfig<F>;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
-        unimplemented!();
+        Self {
+            time_zone: self.time_zone,
+            ..Self::default()
+        }
     }
 
     fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let sel = meta.selector();
-
-        let year = meta.advice_column();
-        let month = meta.advice_column();
-        let day = meta.advice_column();
-        let hour = meta.advice_column();
-        let minute = meta.advice_column();
-        let second = meta.advice_column();
-        let timestamp = meta.advice_column();
-
-        // Constraints to ensure the inputs are within valid ranges
-        /*meta.create_gate("year range", |meta| {
-            let sel = meta.query_selector(sel);
-            let year = meta.query_advice(year, Rotation::cur());
-
-            vec![
-                sel.clone() * (year.clone() - Constant(F::from(1970))),
-                sel.clone() * (Constant(F::from(2100)) - year.clone()), // assuming max year as 2100
-            ]
-        });*/
-
-        /*meta.create_gate("month range", |meta| {
-            let sel = meta.query_selector(sel);
-            let month = meta.query_advice(month, Rotation::cur());
-            let min_month = F::one();
-            //let max_month = F::from(12u64);
-
-            vec![
-                sel.clone() * (month.clone() - Expression::Constant(min_month.clone())),
-                //sel.clone() * (Constant(max_month) - month.clone()),
-            ]
-        });*/
-
-        /*meta.create_gate("day range", |meta| {
-            let sel = meta.query_selector(sel);
-            let day = meta.query_advice(day, Rotation::cur());
-
-            vec![
-                sel * (day.clone() - Constant(F::from(1))) * (Constant(F::from(31)) - day),
-            ]
-        });
-
-        meta.create_gate("hour range", |meta| {
-            let sel = meta.query_selector(sel);
-            let hour = meta.query_advice(hour, Rotation::cur());
-
-            vec![
-                sel * (hour.clone() - Constant(F::from(0))) * (Constant(F::from(23)) - hour),
-            ]
-        });
-
-        meta.create_gate("minute range", |meta| {
-            let sel = meta.query_selector(sel);
-            let minute = meta.query_advice(minute, Rotation::cur());
-
-            vec![
-                sel * (minute.clone() - Constant(F::from(0))) * (Constant(F::from(59)) - minute),
-            ]
-        });
-
-        meta.create_gate("second range", |meta| {
-            let sel = meta.query_selector(sel);
-            let second = meta.query_advice(second, Rotation::cur());
-
-            vec![
-                sel * (second.clone() - Constant(F::from(0))) * (Constant(F::from(59)) - second),
-            ]
-        });*/
-
-        TimestampConfig {
-            sel,
-            year,
-            month,
-            day,
-            hour,
-            minute,
-            second,
-            timestamp,
-        }
+        let range = RangeConfig::configure(
+            meta,
+            Vertical,
+            &[Self::NUM_ADVICE],
+            &[Self::NUM_LOOKUP_ADVICE],
+            Self::NUM_FIXED,
+            Self::LOOKUP_BITS,
+            0,
+            Self::K,
+        );
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        TimestampConfig { range, instance }
     }
 
     fn synthesize(
@@ -143,135 +182,739 @@ impl<F: PrimeField> Circuit<F> for TimestampCircuit<F> {
         config: Self::Config,
         mut layouter: impl Layouter<F>,
     ) -> Result<(), Error> {
-        layouter.assign_region(
+        let (fields, total_seconds) = self.witnesses()?;
+        config.range.load_lookup_table(&mut layouter)?;
+        let mut first_pass = SKIP_FIRST_PASS;
+        let timestamp_cell = layouter.assign_region(
             || "timestamp calculation",
-            |mut region| {
-                config.sel.enable(&mut region, 0)?;
-
-                region.assign_advice(
-                    || "year",
-                    config.year,
-                    0,
-                    || Value::known(self.year.ok_or(Error::Synthesis).unwrap()),
-                )?;
-                region.assign_advice(
-                    || "month",
-                    config.month,
-                    0,
-                    || Value::known(self.month.ok_or(Error::Synthesis).unwrap()),
-                )?;
-                region.assign_advice(
-                    || "day",
-                    config.day,
-                    0,
-                    || Value::known(self.day.ok_or(Error::Synthesis).unwrap()),
-                )?;
-                region.assign_advice(
-                    || "hour",
-                    config.hour,
-                    0,
-                    || Value::known(self.hour.ok_or(Error::Synthesis).unwrap()),
-                )?;
-                region.assign_advice(
-                    || "minute",
-                    config.minute,
-                    0,
-                    || Value::known(self.minute.ok_or(Error::Synthesis).unwrap()),
-                )?;
-                region.assign_advice(
-                    || "second",
-                    config.second,
-                    0,
-                    || Value::known(self.second.ok_or(Error::Synthesis).unwrap()),
-                )?;
-
-                // Days in each month
-                let days_till_previous_month: [F; 12] = [
-                    F::from(0u64),
-                    F::from(31u64),
-                    F::from(59u64),
-                    F::from(90u64),
-                    F::from(120u64),
-                    F::from(151u64),
-                    F::from(181u64),
-                    F::from(212u64),
-                    F::from(243u64),
-                    F::from(273u64),
-                    F::from(304u64),
-                    F::from(334u64),
-                ];
-
-                // Calculate leap years
-                let leap_years_before = |year: u64| -> u64 {
-                    (year - 1969) / 4 - (year - 1901) / 100 + (year - 1601) / 400
-                };
-
-                let year_val = self
-                    .year
-                    .map(|year| year.get_lower_32() as u64)
-                    .unwrap_or(0);
-                let month_val = self
-                    .month
-                    .map(|month| month.get_lower_32() as u64)
-                    .unwrap_or(0);
-                let day_val = self.day.map(|day| day.get_lower_32() as u64).unwrap_or(0);
-                let hour_val = self
-                    .hour
-                    .map(|hour| hour.get_lower_32() as u64)
-                    .unwrap_or(0);
-                let minute_val = self
-                    .minute
-                    .map(|minute| minute.get_lower_32() as u64)
-                    .unwrap_or(0);
-                let second_val = self
-                    .second
-                    .map(|second| second.get_lower_32() as u64)
-                    .unwrap_or(0);
-
-                let days_passed = Value::known(F::from(
-                    (year_val - 1970) * 365 + leap_years_before(year_val),
-                ))
-                .and_then(|days| {
-                    Value::known(days + F::from(days_till_previous_month[(month_val - 1) as usize]))
-                })
-                .and_then(|days| Value::known(days + F::from(day_val - 1)));
-
-                // Convert days to seconds and add hours, minutes, and seconds
-                let total_seconds = days_passed
-                    .map(|d| d * F::from(86400u64))
-                    .and_then(|t| Value::known(t) + Value::known(F::from(hour_val * 3600)))
-                    .and_then(|t| Value::known(t) + Value::known(F::from(minute_val * 60)))
-                    .and_then(|t| Value::known(t) + Value::known(F::from(second_val)));
-
-                // Expose the total seconds as a public output
-                region.assign_advice(|| "timestamp", config.timestamp, 0, || total_seconds)?;
-
-                Ok(())
+            |region| {
+                if first_pass {
+                    first_pass = false;
+                    return Ok(None);
+                }
+                let mut aux = Context::new(
+                    region,
+                    ContextParams {
+                        max_rows: config.range.gate.max_rows,
+                        num_context_ids: 1,
+                        fixed_columns: config.range.gate.constants.clone(),
+                    },
+                );
+                let ctx = &mut aux;
+                let timestamp =
+                    assign_timestamp(&config.range, ctx, fields, self.time_zone, total_seconds);
+                config.range.finalize(ctx);
+                Ok(Some(timestamp.cell()))
             },
-        )
+        )?;
+        if let Some(cell) = timestamp_cell {
+            layouter.constrain_instance(cell, config.instance, 0)?;
+        }
+        Ok(())
+    }
+}
+
+/// Assigns calendar fields and a claimed timestamp, and constrains the timestamp to the fields with [`to_unix_timestamp`].
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * fields - the year, month, day, hour, minute and second in this order.
+/// * time_zone - the time zone of `fields`. Its offset is subtracted from the timestamp of `fields`, and the difference is range-checked so that it does not underflow.
+/// * timestamp - the claimed UNIX timestamp in UTC.
+///
+/// # Return values
+/// Returns the assigned timestamp.
+fn assign_timestamp<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    fields: [Value<F>; 6],
+    time_zone: TimeZone,
+    timestamp: Value<F>,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let [year, month, day, hour, minute, second] =
+        fields.map(|field| gate.load_witness(ctx, field));
+    let parts = TimestampParts {
+        year,
+        month,
+        day,
+        hour,
+        minute,
+        second,
+    };
+    let mut computed = to_unix_timestamp(range, ctx, &parts);
+    if time_zone != TimeZone::Utc {
+        computed = gate.sub(
+            ctx,
+            QuantumCell::Existing(&computed),
+            QuantumCell::Constant(F::from(time_zone.offset_seconds())),
+        );
+        // Every timestamp before 2100 fits in 32 bits, while an underflow wraps around the field.
+        range.range_check(ctx, &computed, 32);
     }
+    let timestamp = gate.load_witness(ctx, timestamp);
+    gate.assert_equal(
+        ctx,
+        QuantumCell::Existing(&timestamp),
+        QuantumCell::Existing(&computed),
+    );
+    timestamp
+}
+
+/// Assigned calendar fields of a UTC timestamp.
+#[derive(Debug, Clone)]
+pub struct TimestampParts<'v, F: PrimeField> {
+    pub year: AssignedValue<'v, F>,
+    pub month: AssignedValue<'v, F>,
+    pub day: AssignedValue<'v, F>,
+    pub hour: AssignedValue<'v, F>,
+    pub minute: AssignedValue<'v, F>,
+    pub second: AssignedValue<'v, F>,
+}
+
+/// Length of an ISO 8601 timestamp in the `YYYY-MM-DDTHH:MM:SSZ` form.
+pub const ISO_TIMESTAMP_LEN: usize = 20;
+
+pub(crate) const DAYS_TILL_PREVIOUS_MONTH: [u64; 12] =
+    [0, 31, 59, 90, 120, 151, 181, 212, 243, 273, 304, 334];
+const DAYS_IN_MONTH: [u64; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+
+/// The first year supported by the timestamp computations, i.e. the year of the UNIX epoch.
+pub const MIN_TIMESTAMP_YEAR: u64 = 1970;
+/// The last year supported by the timestamp computations.
+pub const MAX_TIMESTAMP_YEAR: u64 = 2099;
+
+/// Returns whether `year` is a leap year in the Gregorian calendar.
+fn is_leap_year(year: u64) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+/// Returns whether the fields form a valid date and time in the supported range, i.e. the fields accepted by [`to_unix_timestamp`].
+///
+/// # Arguments
+/// * year - the year.
+/// * month - the month.
+/// * day - the day of the month.
+/// * hour - the hour.
+/// * minute - the minute.
+/// * second - the second.
+///
+/// # Return values
+/// Returns `true` iff `MIN_TIMESTAMP_YEAR <= year <= MAX_TIMESTAMP_YEAR`, `1 <= month <= 12`, `1 <= day <= days_in_month`, `hour <= 23`, `minute <= 59` and `second <= 59`.
+pub fn is_valid_date_time(
+    year: u64,
+    month: u64,
+    day: u64,
+    hour: u64,
+    minute: u64,
+    second: u64,
+) -> bool {
+    if !(MIN_TIMESTAMP_YEAR..=MAX_TIMESTAMP_YEAR).contains(&year) || !(1..=12).contains(&month) {
+        return false;
+    }
+    let days_in_month =
+        DAYS_IN_MONTH[(month - 1) as usize] + (is_leap_year(year) && month == 2) as u64;
+    (1..=days_in_month).contains(&day) && hour < 24 && minute < 60 && second < 60
+}
+
+/// Converts a UTC date and time into seconds since the UNIX epoch off-circuit.
+///
+/// # Arguments
+/// * year - the year, at least [`MIN_TIMESTAMP_YEAR`].
+/// * month - the month in `1..=12`.
+/// * day - the day of the month, at least 1.
+/// * hour - the hour.
+/// * minute - the minute.
+/// * second - the second.
+///
+/// # Return values
+/// Returns the UNIX timestamp, which equals [`to_unix_timestamp`] for the same fields.
+///
+/// # Panics
+/// Panics on an underflow if `year`, `month` or `day` is below its minimum. Check the fields with [`is_valid_date_time`] first.
+pub fn unix_timestamp_native(
+    year: u64,
+    month: u64,
+    day: u64,
+    hour: u64,
+    minute: u64,
+    second: u64,
+) -> u64 {
+    // Leap years in [1970, year).
+    let leap_years_before = (year - 1969) / 4 - (year - 1901) / 100 + (year - 1601) / 400;
+    // The leap day of the current year has only passed after February.
+    let leap_day = (is_leap_year(year) && month > 2) as u64;
+    let days_passed = (year - 1970) * 365
+        + leap_years_before
+        + DAYS_TILL_PREVIOUS_MONTH[(month - 1) as usize]
+        + leap_day
+        + (day - 1);
+    days_passed * 86400 + hour * 3600 + minute * 60 + second
+}
+
+/// Converts seconds since the UNIX epoch into a UTC date off-circuit.
+///
+/// # Arguments
+/// * timestamp - the UNIX timestamp.
+///
+/// # Return values
+/// Returns the year, the month and the day of the month, i.e. the inverse of [`unix_timestamp_native`] at midnight.
+pub fn unix_timestamp_to_date_native(timestamp: u64) -> (u64, u64, u64) {
+    let mut days = timestamp / 86400;
+    let mut year = MIN_TIMESTAMP_YEAR;
+    while days >= 365 + is_leap_year(year) as u64 {
+        days -= 365 + is_leap_year(year) as u64;
+        year += 1;
+    }
+    let mut month = 1;
+    loop {
+        let days_in_month =
+            DAYS_IN_MONTH[(month - 1) as usize] + (is_leap_year(year) && month == 2) as u64;
+        if days < days_in_month {
+            break;
+        }
+        days -= days_in_month;
+        month += 1;
+    }
+    (year, month, days + 1)
+}
+
+/// Parses an ISO 8601 timestamp `YYYY-MM-DDTHH:MM:SSZ` into [`TimestampParts`].
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * bytes - assigned ASCII bytes of the timestamp.
+///
+/// # Return values
+/// Returns the parsed [`TimestampParts`].
+/// The separators `-`, `-`, `T`, `:`, `:`, `Z` are constrained to their positions and every other byte must be a decimal digit.
+pub fn parse_iso_timestamp<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    bytes: &[AssignedValue<'v, F>],
+) -> Result<TimestampParts<'v, F>, Error> {
+    if bytes.len() != ISO_TIMESTAMP_LEN {
+        return Err(Error::Synthesis);
+    }
+    let gate = range.gate();
+    for (idx, separator) in [
+        (4, b'-'),
+        (7, b'-'),
+        (10, b'T'),
+        (13, b':'),
+        (16, b':'),
+        (19, b'Z'),
+    ] {
+        gate.assert_is_const(ctx, &bytes[idx], F::from(separator as u64));
+    }
+    Ok(TimestampParts {
+        year: ascii_digits_to_int(range, ctx, &bytes[0..4]),
+        month: ascii_digits_to_int(range, ctx, &bytes[5..7]),
+        day: ascii_digits_to_int(range, ctx, &bytes[8..10]),
+        hour: ascii_digits_to_int(range, ctx, &bytes[11..13]),
+        minute: ascii_digits_to_int(range, ctx, &bytes[14..16]),
+        second: ascii_digits_to_int(range, ctx, &bytes[17..19]),
+    })
+}
+
+/// Converts [`TimestampParts`] into seconds since the UNIX epoch.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * parts - assigned calendar fields.
+///
+/// # Return values
+/// Returns the assigned UNIX timestamp.
+/// The year is constrained to `MIN_TIMESTAMP_YEAR..=MAX_TIMESTAMP_YEAR` and the remaining fields to valid calendar ranges, taking leap years into account.
+pub fn to_unix_timestamp<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    parts: &TimestampParts<'v, F>,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let years_since_epoch = gate.sub(
+        ctx,
+        QuantumCell::Existing(&parts.year),
+        QuantumCell::Constant(F::from(MIN_TIMESTAMP_YEAR)),
+    );
+    range.check_less_than_safe(
+        ctx,
+        &years_since_epoch,
+        MAX_TIMESTAMP_YEAR - MIN_TIMESTAMP_YEAR + 1,
+    );
+    let month_idx = gate.sub(
+        ctx,
+        QuantumCell::Existing(&parts.month),
+        QuantumCell::Constant(F::one()),
+    );
+    range.check_less_than_safe(ctx, &month_idx, 12);
+    range.check_less_than_safe(ctx, &parts.hour, 24);
+    range.check_less_than_safe(ctx, &parts.minute, 60);
+    range.check_less_than_safe(ctx, &parts.second, 60);
+
+    let leap_years_before = count_leap_years(range, ctx, &parts.year, MIN_TIMESTAMP_YEAR);
+    let is_leap_year = assign_is_leap_year(range, ctx, &parts.year);
+
+    let days_till_previous_month = gate.select_from_idx(
+        ctx,
+        DAYS_TILL_PREVIOUS_MONTH
+            .iter()
+            .map(|days| QuantumCell::Constant(F::from(*days)))
+            .collect::<Vec<_>>(),
+        QuantumCell::Existing(&month_idx),
+    );
+    let is_after_february = range.is_less_than(
+        ctx,
+        QuantumCell::Constant(F::from(2u64)),
+        QuantumCell::Existing(&parts.month),
+        4,
+    );
+    let leap_day = gate.and(
+        ctx,
+        QuantumCell::Existing(&is_leap_year),
+        QuantumCell::Existing(&is_after_february),
+    );
+
+    // The day must be in `1..=days_in_month`.
+    let day_idx = gate.sub(
+        ctx,
+        QuantumCell::Existing(&parts.day),
+        QuantumCell::Constant(F::one()),
+    );
+    let is_february = gate.is_equal(
+        ctx,
+        QuantumCell::Existing(&parts.month),
+        QuantumCell::Constant(F::from(2u64)),
+    );
+    let is_leap_february = gate.and(
+        ctx,
+        QuantumCell::Existing(&is_leap_year),
+        QuantumCell::Existing(&is_february),
+    );
+    let days_in_month = gate.select_from_idx(
+        ctx,
+        DAYS_IN_MONTH
+            .iter()
+            .map(|days| QuantumCell::Constant(F::from(*days)))
+            .collect::<Vec<_>>(),
+        QuantumCell::Existing(&month_idx),
+    );
+    let days_in_month = gate.add(
+        ctx,
+        QuantumCell::Existing(&days_in_month),
+        QuantumCell::Existing(&is_leap_february),
+    );
+    range.check_less_than_safe(ctx, &day_idx, 31);
+    range.check_less_than(
+        ctx,
+        QuantumCell::Existing(&day_idx),
+        QuantumCell::Existing(&days_in_month),
+        5,
+    );
+
+    let days_passed = gate.sum(
+        ctx,
+        vec![
+            QuantumCell::Existing(&leap_years_before),
+            QuantumCell::Existing(&days_till_previous_month),
+            QuantumCell::Existing(&leap_day),
+            QuantumCell::Existing(&day_idx),
+        ],
+    );
+    let days_passed = gate.mul_add(
+        ctx,
+        QuantumCell::Existing(&years_since_epoch),
+        QuantumCell::Constant(F::from(365u64)),
+        QuantumCell::Existing(&days_passed),
+    );
+    gate.inner_product(
+        ctx,
+        vec![
+            QuantumCell::Existing(&days_passed),
+            QuantumCell::Existing(&parts.hour),
+            QuantumCell::Existing(&parts.minute),
+            QuantumCell::Existing(&parts.second),
+        ],
+        [86400u64, 3600, 60, 1]
+            .into_iter()
+            .map(|base| QuantumCell::Constant(F::from(base)))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Counts the leap years in `[first_year, year)`.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * year - an assigned year of at most 12 bits, not before `first_year`.
+/// * first_year - the first year counted.
+///
+/// # Return values
+/// Returns the assigned number of leap years.
+pub(crate) fn count_leap_years<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    year: &AssignedValue<'v, F>,
+    first_year: u64,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    // `(year - offset) / divisor` counts the multiples of `divisor` in `[first_year, year)`, `offset - 1` being the last one before `first_year`.
+    let mut counts = vec![];
+    for divisor in [4u64, 100, 400] {
+        let offset = (first_year - 1) / divisor * divisor + 1;
+        let shifted = gate.sub(
+            ctx,
+            QuantumCell::Existing(year),
+            QuantumCell::Constant(F::from(offset)),
+        );
+        let (quotient, _) = range.div_mod(ctx, QuantumCell::Existing(&shifted), divisor, 12);
+        counts.push(quotient);
+    }
+    let sum = gate.add(
+        ctx,
+        QuantumCell::Existing(&counts[0]),
+        QuantumCell::Existing(&counts[2]),
+    );
+    gate.sub(
+        ctx,
+        QuantumCell::Existing(&sum),
+        QuantumCell::Existing(&counts[1]),
+    )
+}
+
+/// Returns the assigned bit whether `year`, of at most 12 bits, is a leap year in the Gregorian calendar.
+pub(crate) fn assign_is_leap_year<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    year: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let mut is_divisible = vec![];
+    for divisor in [4u64, 100, 400] {
+        let (_, rem) = range.div_mod(ctx, QuantumCell::Existing(year), divisor, 12);
+        is_divisible.push(gate.is_zero(ctx, &rem));
+    }
+    let not_century = gate.not(ctx, QuantumCell::Existing(&is_divisible[1]));
+    let leap_century = gate.or(
+        ctx,
+        QuantumCell::Existing(&not_century),
+        QuantumCell::Existing(&is_divisible[2]),
+    );
+    gate.and(
+        ctx,
+        QuantumCell::Existing(&is_divisible[0]),
+        QuantumCell::Existing(&leap_century),
+    )
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use halo2_base::halo2_proofs::{dev::MockProver, halo2curves::pasta::Fp};
+    use crate::test_utils::mock_prove;
+    use halo2_base::halo2_proofs::dev::MockProver;
+    use halo2_base::halo2_proofs::halo2curves::bn256::Fr;
 
     #[test]
     fn test_timestamp_circuit() {
-        let k = 6;
+        let k = TimestampCircuit::<Fr>::K as u32;
         let circuit = TimestampCircuit {
-            year: Some(Fp::from(2023u64)),
-            month: Some(Fp::from(7u64)),
-            day: Some(Fp::from(8u64)),
-            hour: Some(Fp::from(12u64)),
-            minute: Some(Fp::from(34u64)),
-            second: Some(Fp::from(56u64)),
+            year: Some(Fr::from(2023u64)),
+            month: Some(Fr::from(7u64)),
+            day: Some(Fr::from(8u64)),
+            hour: Some(Fr::from(12u64)),
+            minute: Some(Fr::from(34u64)),
+            second: Some(Fr::from(56u64)),
+            time_zone: TimeZone::Utc,
         };
 
-        let public_inputs = vec![];
+        let public_inputs = vec![vec![Fr::from(1688819696u64)]];
 
+        let prover = MockProver::run(k, &circuit.clone(), public_inputs).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        // The exposed timestamp must be the computed one.
+        let public_inputs = vec![vec![Fr::from(1688819697u64)]];
         let prover = MockProver::run(k, &circuit, public_inputs).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    fn timestamp_fields_prover(fields: [u64; 6]) -> MockProver<Fr> {
+        mock_prove(
+            move |ctx, range| {
+                let [year, month, day, hour, minute, second] = fields.map(|field| {
+                    range
+                        .gate()
+                        .load_witness(ctx, Value::known(Fr::from(field)))
+                });
+                let parts = TimestampParts {
+                    year,
+                    month,
+                    day,
+                    hour,
+                    minute,
+                    second,
+                };
+                to_unix_timestamp(range, ctx, &parts);
+                Ok(vec![])
+            },
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_timestamp_fields_out_of_range() {
+        timestamp_fields_prover([2023, 7, 8, 12, 34, 56]).assert_satisfied();
+        timestamp_fields_prover([2024, 2, 29, 23, 59, 59]).assert_satisfied();
+        for fields in [
+            [1969, 12, 31, 0, 0, 0],
+            [2100, 1, 1, 0, 0, 0],
+            [2023, 0, 8, 12, 34, 56],
+            [2023, 13, 8, 12, 34, 56],
+            [2023, 7, 0, 12, 34, 56],
+            [2023, 7, 40, 12, 34, 56],
+            [2023, 7, 8, 24, 34, 56],
+            [2023, 7, 8, 12, 60, 56],
+            [2023, 7, 8, 12, 34, 99],
+            // Month-specific upper bounds of the day.
+            [2023, 2, 29, 0, 0, 0],
+            [2023, 4, 31, 0, 0, 0],
+        ] {
+            assert!(
+                timestamp_fields_prover(fields).verify().is_err(),
+                "{:?} must be rejected",
+                fields
+            );
+        }
+    }
+
+    #[test]
+    fn test_timestamp_circuit_without_witnesses() {
+        use halo2_base::halo2_proofs::{
+            halo2curves::bn256::Bn256,
+            plonk::{keygen_pk, keygen_vk},
+            poly::kzg::commitment::ParamsKZG,
+        };
+        use rand::rngs::OsRng;
+
+        let circuit = TimestampCircuit::new(
+            Some(Fr::from(2023u64)),
+            Some(Fr::from(7u64)),
+            Some(Fr::from(8u64)),
+            Some(Fr::from(12u64)),
+            Some(Fr::from(34u64)),
+            Some(Fr::from(56u64)),
+            TimeZone::Utc,
+        );
+        let params = ParamsKZG::<Bn256>::setup(TimestampCircuit::<Fr>::K as u32, OsRng);
+        let vk = keygen_vk(&params, &circuit.without_witnesses()).unwrap();
+        keygen_pk(&params, vk, &circuit.without_witnesses()).unwrap();
+
+        // A partially missing witness is an error rather than a panic.
+        let partial = TimestampCircuit {
+            second: None,
+            ..circuit
+        };
+        assert!(matches!(
+            MockProver::run(TimestampCircuit::<Fr>::K as u32, &partial, vec![vec![]]),
+            Err(Error::Synthesis)
+        ));
+    }
+
+    #[test]
+    fn test_timestamp_circuit_out_of_domain() {
+        let k = TimestampCircuit::<Fr>::K as u32;
+        for fields in [
+            [1969, 12, 31, 23, 59, 59],
+            [2100, 1, 1, 0, 0, 0],
+            [2023, 7, 0, 12, 34, 56],
+            [2023, 0, 8, 12, 34, 56],
+            [2023, 13, 8, 12, 34, 56],
+            [2023, 2, 29, 12, 34, 56],
+            [2023, 7, 8, 24, 0, 0],
+        ] {
+            assert!(!is_valid_date_time(
+                fields[0], fields[1], fields[2], fields[3], fields[4], fields[5]
+            ));
+            let [year, month, day, hour, minute, second] =
+                fields.map(|field| Some(Fr::from(field)));
+            let circuit =
+                TimestampCircuit::new(year, month, day, hour, minute, second, TimeZone::Utc);
+            assert!(
+                matches!(
+                    MockProver::run(k, &circuit, vec![vec![]]),
+                    Err(Error::Synthesis)
+                ),
+                "{:?} must be rejected",
+                fields
+            );
+        }
+        assert!(is_valid_date_time(2024, 2, 29, 23, 59, 59));
+        assert!(is_valid_date_time(2099, 12, 31, 23, 59, 59));
+    }
+
+    #[test]
+    fn test_from_qr_timestamp() {
+        let k = TimestampCircuit::<Fr>::K as u32;
+        let circuit = TimestampCircuit::<Fr>::from_qr_timestamp(b"20230708123456789").unwrap();
+        let expected = TimestampCircuit::new(
+            Some(Fr::from(2023)),
+            Some(Fr::from(7)),
+            Some(Fr::from(8)),
+            Some(Fr::from(12)),
+            Some(Fr::from(34)),
+            Some(Fr::from(56)),
+            TimeZone::Ist,
+        );
+        assert_eq!(format!("{:?}", circuit), format!("{:?}", expected));
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(1688799896)]]).unwrap();
+        prover.assert_satisfied();
+
+        for bytes in [
+            &b""[..],
+            &b"2023070812345678"[..],
+            &b"202307081234567890"[..],
+            &b"2023-07-081234567"[..],
+            &b"20230708 23456789"[..],
+        ] {
+            assert!(matches!(
+                TimestampCircuit::<Fr>::from_qr_timestamp(bytes),
+                Err(Error::Synthesis)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_tampered_timestamp() {
+        let fields = [2023u64, 7, 8, 12, 34, 56].map(|field| Value::known(Fr::from(field)));
+        let expected = 1688819696u64;
+        for (timestamp, is_valid) in [
+            (expected, true),
+            (expected + 1, false),
+            (expected + 86400, false),
+        ] {
+            let prover = mock_prove(
+                move |ctx, range| {
+                    Ok(vec![assign_timestamp(
+                        range,
+                        ctx,
+                        fields,
+                        TimeZone::Utc,
+                        Value::known(Fr::from(timestamp)),
+                    )])
+                },
+                vec![Fr::from(timestamp)],
+            );
+            assert_eq!(prover.verify().is_ok(), is_valid);
+        }
+    }
+
+    #[test]
+    fn test_timestamp_circuit_ist() {
+        let k = TimestampCircuit::<Fr>::K as u32;
+        let ist_circuit = |fields: [u64; 6]| {
+            let [year, month, day, hour, minute, second] =
+                fields.map(|field| Some(Fr::from(field)));
+            TimestampCircuit::new(year, month, day, hour, minute, second, TimeZone::Ist)
+        };
+        // 2023-07-08 12:34:56 IST is 2023-07-08 07:04:56 UTC.
+        let circuit = ist_circuit([2023, 7, 8, 12, 34, 56]);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(1688799896u64)]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        // The UTC timestamp must not be the IST one.
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(1688819696u64)]]).unwrap();
+        assert!(prover.verify().is_err());
+
+        // 1970-01-01 05:30:00 IST is the epoch, while anything earlier underflows.
+        let circuit = ist_circuit([1970, 1, 1, 5, 30, 0]);
+        let prover = MockProver::run(k, &circuit, vec![vec![Fr::zero()]]).unwrap();
         assert_eq!(prover.verify(), Ok(()));
+        let circuit = ist_circuit([1970, 1, 1, 5, 29, 59]);
+        assert!(matches!(
+            MockProver::run(k, &circuit, vec![vec![Fr::zero()]]),
+            Err(Error::Synthesis)
+        ));
+        // The underflow is also rejected by the constraints.
+        let fields = [1970u64, 1, 1, 5, 29, 59].map(|field| Value::known(Fr::from(field)));
+        let prover = mock_prove(
+            move |ctx, range| {
+                Ok(vec![assign_timestamp(
+                    range,
+                    ctx,
+                    fields,
+                    TimeZone::Ist,
+                    Value::known(-Fr::one()),
+                )])
+            },
+            vec![-Fr::one()],
+        );
+        assert!(prover.verify().is_err());
+    }
+
+    fn iso_timestamp_circuit_prover(iso: &'static [u8], expected: u64) -> MockProver<Fr> {
+        mock_prove(
+            move |ctx, range| {
+                let bytes = iso
+                    .iter()
+                    .map(|byte| {
+                        range
+                            .gate()
+                            .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                    })
+                    .collect::<Vec<_>>();
+                let parts = parse_iso_timestamp(range, ctx, &bytes)?;
+                Ok(vec![to_unix_timestamp(range, ctx, &parts)])
+            },
+            vec![Fr::from(expected)],
+        )
+    }
+
+    #[test]
+    fn test_iso_timestamp_parsing() {
+        iso_timestamp_circuit_prover(b"2023-07-08T12:34:56Z", 1688819696).assert_satisfied();
+        // 2024-03-01 follows a leap day.
+        iso_timestamp_circuit_prover(b"2024-03-01T00:00:00Z", 1709251200).assert_satisfied();
+    }
+
+    #[test]
+    fn test_unix_timestamp_native_around_leap_day() {
+        assert_eq!(unix_timestamp_native(1970, 1, 1, 0, 0, 0), 0);
+        assert_eq!(unix_timestamp_native(2024, 2, 28, 0, 0, 0), 1709078400);
+        assert_eq!(unix_timestamp_native(2024, 2, 29, 0, 0, 0), 1709164800);
+        assert_eq!(unix_timestamp_native(2024, 3, 1, 0, 0, 0), 1709251200);
+        // 2000 is a leap year as a multiple of 400.
+        assert_eq!(unix_timestamp_native(2000, 3, 1, 0, 0, 0), 951868800);
+        assert_eq!(unix_timestamp_native(2023, 7, 8, 12, 34, 56), 1688819696);
+    }
+
+    #[test]
+    fn test_unix_timestamp_to_date_native() {
+        for (year, month, day) in [
+            (1970, 1, 1),
+            (1999, 12, 31),
+            (2000, 2, 29),
+            (2023, 2, 28),
+            (2024, 2, 29),
+            (2024, 3, 1),
+            (2024, 12, 31),
+            (2099, 12, 31),
+        ] {
+            let midnight = unix_timestamp_native(year, month, day, 0, 0, 0);
+            assert_eq!(unix_timestamp_to_date_native(midnight), (year, month, day));
+            assert_eq!(
+                unix_timestamp_to_date_native(midnight + 86399),
+                (year, month, day)
+            );
+        }
+    }
+
+    #[test]
+    fn test_iso_timestamp_invalid() {
+        let prover = iso_timestamp_circuit_prover(b"2023-07-08 12:34:56Z", 1688819696);
+        assert!(prover.verify().is_err());
+        let prover = iso_timestamp_circuit_prover(b"2023-02-29T12:34:56Z", 1677674096);
+        assert!(prover.verify().is_err());
     }
 }
@@ -0,0 +1,470 @@
+//! Small gadgets shared by the extraction and verification circuits.
+
+#[cfg(feature = "zeroize")]
+use halo2_base::halo2_proofs::circuit::Value;
+use halo2_base::{
+    gates::{range::RangeConfig, GateInstructions, RangeInstructions},
+    utils::PrimeField,
+    AssignedValue, Context, QuantumCell,
+};
+#[cfg(feature = "zeroize")]
+use num_bigint::BigUint;
+
+/// Converts big-endian ASCII decimal digits into the integer they represent.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`] used to constrain each digit.
+/// * ctx - a region context.
+/// * digits - assigned ASCII bytes, most significant digit first.
+///
+/// # Return values
+/// Returns the assigned integer.
+/// Every byte in `digits` is constrained to be one of `b'0'..=b'9'`.
+pub fn ascii_digits_to_int<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    digits: &[AssignedValue<'v, F>],
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    let mut values = vec![];
+    for digit in digits.iter() {
+        let value = gate.sub(
+            ctx,
+            QuantumCell::Existing(digit),
+            QuantumCell::Constant(F::from(b'0' as u64)),
+        );
+        range.check_less_than_safe(ctx, &value, 10);
+        values.push(value);
+    }
+    let bases = (0..digits.len())
+        .rev()
+        .map(|i| QuantumCell::Constant(F::from(10u64.pow(i as u32))))
+        .collect::<Vec<QuantumCell<F>>>();
+    gate.inner_product(
+        ctx,
+        values.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+        bases,
+    )
+}
+
+/// Returns a bit indicating whether a variable-length field starts with `prefix`.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * field - assigned bytes of the field, padded to the maximum field length.
+/// * field_len - the assigned length of the field. It must be at most `field.len()`.
+/// * prefix - public prefix bytes.
+///
+/// # Return values
+/// Returns the assigned bit as `AssignedValue<F>`.
+/// If the first `prefix.len()` bytes of the field equal `prefix`, the bit is equivalent to one.
+/// A prefix longer than the field never matches.
+pub fn starts_with<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    field: &[AssignedValue<'v, F>],
+    field_len: &AssignedValue<'v, F>,
+    prefix: &[u8],
+) -> AssignedValue<'v, F> {
+    let gate = range.gate();
+    if prefix.len() > field.len() {
+        return gate.load_zero(ctx);
+    }
+    let num_bits = (usize::BITS - field.len().leading_zeros()) as usize;
+    let is_too_short = range.is_less_than(
+        ctx,
+        QuantumCell::Existing(field_len),
+        QuantumCell::Constant(F::from(prefix.len() as u64)),
+        num_bits,
+    );
+    let mut is_match = gate.not(ctx, QuantumCell::Existing(&is_too_short));
+    for (byte, expected) in field.iter().zip(prefix.iter()) {
+        let is_eq = gate.is_equal(
+            ctx,
+            QuantumCell::Existing(byte),
+            QuantumCell::Constant(F::from(*expected as u64)),
+        );
+        is_match = gate.and(
+            ctx,
+            QuantumCell::Existing(&is_match),
+            QuantumCell::Existing(&is_eq),
+        );
+    }
+    is_match
+}
+
+/// Sums assigned values.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * values - assigned values to be summed.
+///
+/// # Return values
+/// Returns the assigned sum.
+/// The sum is computed in the field, so it equals the integer sum only if it is less than the field modulus.
+/// For instance, summing up to `2^k` values of at most `b` bits each cannot overflow a 254-bit field if `k + b < 253`.
+/// Callers must range-check the summands accordingly.
+pub fn sum<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    values: &[AssignedValue<'v, F>],
+) -> AssignedValue<'v, F> {
+    if values.is_empty() {
+        return gate.load_zero(ctx);
+    }
+    gate.sum(
+        ctx,
+        values.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+    )
+}
+
+/// Constrains the bytes from index `len` onwards to be zero, so that no data can be hidden after the real content.
+///
+/// # Arguments
+/// * range - a [`RangeConfig`].
+/// * ctx - a region context.
+/// * bytes - assigned bytes, padded to the maximum message length.
+/// * len - the assigned length of the real content, e.g. the end of the last field. It is constrained to be at most `bytes.len()`.
+pub fn assert_zero_padding<'v, F: PrimeField>(
+    range: &RangeConfig<F>,
+    ctx: &mut Context<'v, F>,
+    bytes: &[AssignedValue<'v, F>],
+    len: &AssignedValue<'v, F>,
+) {
+    let gate = range.gate();
+    range.check_less_than_safe(ctx, len, bytes.len() as u64 + 1);
+    let num_bits = (usize::BITS - bytes.len().leading_zeros()) as usize;
+    for (i, byte) in bytes.iter().enumerate() {
+        let is_content = range.is_less_than(
+            ctx,
+            QuantumCell::Constant(F::from(i as u64)),
+            QuantumCell::Existing(len),
+            num_bits,
+        );
+        let is_padding = gate.not(ctx, QuantumCell::Existing(&is_content));
+        let masked = gate.mul(
+            ctx,
+            QuantumCell::Existing(&is_padding),
+            QuantumCell::Existing(byte),
+        );
+        gate.assert_is_const(ctx, &masked, F::zero());
+    }
+}
+
+/// Reverses the order of assigned bytes, e.g. to convert between big-endian and little-endian.
+///
+/// # Arguments
+/// * ctx - a region context.
+/// * bytes - assigned bytes.
+///
+/// # Return values
+/// Returns the same assigned cells in reverse order. No new cells or constraints are added.
+pub fn reverse_bytes<'v, F: PrimeField>(
+    _ctx: &mut Context<'v, F>,
+    bytes: &[AssignedValue<'v, F>],
+) -> Vec<AssignedValue<'v, F>> {
+    bytes.iter().rev().cloned().collect()
+}
+
+/// Recomposes little-endian bits into the integer they represent.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * bits - assigned bits, least significant bit first. They are assumed to be already constrained to be boolean.
+///
+/// # Return values
+/// Returns the assigned integer.
+pub fn bits_to_num<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    bits: &[AssignedValue<'v, F>],
+) -> AssignedValue<'v, F> {
+    gate.inner_product(
+        ctx,
+        bits.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+        (0..bits.len())
+            .map(|i| QuantumCell::Constant(gate.pow_of_two()[i]))
+            .collect::<Vec<_>>(),
+    )
+}
+
+/// Computes the XOR of two bits as `a + b - 2ab`.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * a - an assigned bit.
+/// * b - an assigned bit.
+///
+/// # Return values
+/// Returns the assigned bit `a ^ b`.
+pub fn xor_bits<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    a: &AssignedValue<'v, F>,
+    b: &AssignedValue<'v, F>,
+) -> AssignedValue<'v, F> {
+    let ab = gate.mul(ctx, QuantumCell::Existing(a), QuantumCell::Existing(b));
+    let sum = gate.add(ctx, QuantumCell::Existing(a), QuantumCell::Existing(b));
+    gate.mul_add(
+        ctx,
+        QuantumCell::Existing(&ab),
+        QuantumCell::Constant(-F::from(2u64)),
+        QuantumCell::Existing(&sum),
+    )
+}
+
+/// Computes the AND of bits with a balanced tree, e.g. to aggregate the validity bits of several extractors.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * bits - assigned bits. They are assumed to be already constrained to be boolean.
+///
+/// # Return values
+/// Returns the assigned bit, which is equivalent to one if every bit is one.
+/// The AND of no bits is one.
+pub fn and_all<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    bits: &[AssignedValue<'v, F>],
+) -> AssignedValue<'v, F> {
+    if bits.is_empty() {
+        return gate.load_constant(ctx, F::one());
+    }
+    let mut layer = bits.to_vec();
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => gate.and(ctx, QuantumCell::Existing(a), QuantumCell::Existing(b)),
+                [a] => a.clone(),
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    layer.pop().unwrap()
+}
+
+/// Reads the witness values of assigned bytes, e.g. to hash them again with the dynamic SHA256 chip.
+/// Unknown values are read as zero.
+pub fn assigned_bytes_to_vec<F: PrimeField>(bytes: &[AssignedValue<F>]) -> Vec<u8> {
+    bytes
+        .iter()
+        .map(|byte| {
+            let mut value = 0u8;
+            byte.value().map(|v| value = v.get_lower_32() as u8);
+            value
+        })
+        .collect()
+}
+
+/// The number of bytes packed into a single field element by [`pack_bytes`].
+pub const PACKED_BYTES_PER_ELEMENT: usize = 31;
+
+/// Packs assigned bytes into field elements, [`PACKED_BYTES_PER_ELEMENT`] little-endian bytes per element.
+///
+/// # Arguments
+/// * gate - a gate chip.
+/// * ctx - a region context.
+/// * bytes - assigned bytes. They are assumed to be already constrained to 8 bits.
+///
+/// # Return values
+/// Returns the packed elements.
+pub fn pack_bytes<'v, F: PrimeField>(
+    gate: &impl GateInstructions<F>,
+    ctx: &mut Context<'v, F>,
+    bytes: &[AssignedValue<'v, F>],
+) -> Vec<AssignedValue<'v, F>> {
+    bytes
+        .chunks(PACKED_BYTES_PER_ELEMENT)
+        .map(|chunk| {
+            let bases = (0..chunk.len())
+                .map(|i| QuantumCell::Constant(gate.pow_of_two()[8 * i]))
+                .collect::<Vec<_>>();
+            gate.inner_product(
+                ctx,
+                chunk.iter().map(QuantumCell::Existing).collect::<Vec<_>>(),
+                bases,
+            )
+        })
+        .collect()
+}
+
+/// Computes [`pack_bytes`] off-circuit.
+pub fn pack_bytes_native<F: PrimeField>(bytes: &[u8]) -> Vec<F> {
+    bytes
+        .chunks(PACKED_BYTES_PER_ELEMENT)
+        .map(|chunk| {
+            chunk.iter().rev().fold(F::zero(), |acc, byte| {
+                acc * F::from(256u64) + F::from(*byte as u64)
+            })
+        })
+        .collect()
+}
+
+/// Overwrites the digits of a secret integer with zeros, e.g. a prime factor or a signature witness about to be dropped.
+///
+/// [`BigUint`] does not expose its digit buffer, so the digits are overwritten in place through [`BigUint::assign_from_slice`] before the value is set to zero.
+/// Copies made before, e.g. by cloning or by assigning the integer into a circuit, are not wiped.
+///
+/// # Arguments
+/// * value - the integer to wipe. It equals zero afterwards.
+#[cfg(feature = "zeroize")]
+pub fn zeroize_biguint(value: &mut BigUint) {
+    let num_digits = value.iter_u32_digits().len();
+    value.assign_from_slice(&vec![0u32; num_digits]);
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Wipes a secret integer witness with [`zeroize_biguint`] if it is known.
+#[cfg(feature = "zeroize")]
+pub fn zeroize_value_biguint(value: &mut Value<BigUint>) {
+    value.as_mut().map(zeroize_biguint);
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::mock_prove;
+    use halo2_base::halo2_proofs::{circuit::Value, halo2curves::bn256::Fr};
+
+    const NAME_MAX_LEN: usize = 16;
+
+    fn name_prefix_is_match(name: &'static [u8], prefix: &'static [u8], expected: bool) {
+        let prover = mock_prove(
+            move |ctx, range| {
+                let gate = range.gate();
+                let mut padded = name.to_vec();
+                padded.resize(NAME_MAX_LEN, 0);
+                let field = padded
+                    .iter()
+                    .map(|byte| gate.load_witness(ctx, Value::known(Fr::from(*byte as u64))))
+                    .collect::<Vec<_>>();
+                let field_len = gate.load_witness(ctx, Value::known(Fr::from(name.len() as u64)));
+                Ok(vec![starts_with(range, ctx, &field, &field_len, prefix)])
+            },
+            vec![Fr::from(expected as u64)],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_sum() {
+        let values = [110051u64, 1984, 0, 18, u32::MAX as u64];
+        let expected = Fr::from(values.iter().sum::<u64>());
+        let prover = mock_prove(
+            move |ctx, range| {
+                let assigned = values
+                    .iter()
+                    .map(|v| range.gate.load_witness(ctx, Value::known(Fr::from(*v))))
+                    .collect::<Vec<_>>();
+                let empty_sum = sum(&range.gate, ctx, &[]);
+                range.gate.assert_is_const(ctx, &empty_sum, Fr::from(0u64));
+                Ok(vec![sum(&range.gate, ctx, &assigned)])
+            },
+            vec![expected],
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_and_all() {
+        for (bits, expected) in [
+            (vec![], 1u64),
+            (vec![1u64], 1),
+            (vec![1; 7], 1),
+            (vec![1, 1, 1, 1, 1, 0, 1], 0),
+            (vec![0, 1, 1, 1, 1, 1, 1, 1], 0),
+            (vec![1, 1, 1, 1, 1, 1, 1, 0], 0),
+        ] {
+            let prover = mock_prove(
+                move |ctx, range| {
+                    let assigned = bits
+                        .iter()
+                        .map(|v| range.gate.load_witness(ctx, Value::known(Fr::from(*v))))
+                        .collect::<Vec<_>>();
+                    Ok(vec![and_all(&range.gate, ctx, &assigned)])
+                },
+                vec![Fr::from(expected)],
+            );
+            prover.assert_satisfied();
+        }
+    }
+
+    fn zero_padding_is_satisfied(content: &'static [u8], trailing: &'static [u8], expected: bool) {
+        const MSG_LEN: usize = 32;
+        let prover = mock_prove(
+            move |ctx, range| {
+                let mut padded = content.to_vec();
+                padded.extend_from_slice(trailing);
+                padded.resize(MSG_LEN, 0);
+                let bytes = padded
+                    .iter()
+                    .map(|byte| {
+                        range
+                            .gate
+                            .load_witness(ctx, Value::known(Fr::from(*byte as u64)))
+                    })
+                    .collect::<Vec<_>>();
+                let len = range
+                    .gate
+                    .load_witness(ctx, Value::known(Fr::from(content.len() as u64)));
+                assert_zero_padding(range, ctx, &bytes, &len);
+                Ok(vec![])
+            },
+            vec![],
+        );
+        assert_eq!(prover.verify().is_ok(), expected);
+    }
+
+    #[test]
+    fn test_zero_padding() {
+        zero_padding_is_satisfied(b"V2\xff3\xff2697", b"", true);
+        zero_padding_is_satisfied(b"V2\xff3\xff2697", b"\0\0\0", true);
+        zero_padding_is_satisfied(b"V2\xff3\xff2697", b"\0hidden", false);
+        zero_padding_is_satisfied(b"V2\xff3\xff2697", b"\x01", false);
+    }
+
+    #[test]
+    fn test_reverse_bytes() {
+        let bytes = [0x01u64, 0x23, 0x45, 0x67, 0x89];
+        let prover = mock_prove(
+            move |ctx, range| {
+                let assigned = bytes
+                    .iter()
+                    .map(|v| range.gate.load_witness(ctx, Value::known(Fr::from(*v))))
+                    .collect::<Vec<_>>();
+                let num_advice = ctx.total_advice;
+                let reversed = reverse_bytes(ctx, &assigned);
+                assert_eq!(ctx.total_advice, num_advice);
+                for (byte, reversed) in assigned.iter().zip(reversed.iter().rev()) {
+                    assert_eq!(byte.cell(), reversed.cell());
+                }
+                Ok(reversed)
+            },
+            bytes.iter().rev().map(|v| Fr::from(*v)).collect(),
+        );
+        prover.assert_satisfied();
+    }
+
+    #[test]
+    fn test_name_prefix_match() {
+        name_prefix_is_match(b"Sumit Kumar", b"S", true);
+        name_prefix_is_match(b"Sumit Kumar", b"Sumit", true);
+        name_prefix_is_match(b"Sumit Kumar", b"Sumit Kumar", true);
+    }
+
+    #[test]
+    fn test_name_prefix_mismatch() {
+        name_prefix_is_match(b"Sumit Kumar", b"K", false);
+        name_prefix_is_match(b"Sumit Kumar", b"Sumt", false);
+        // Longer than the name but within the padded field.
+        name_prefix_is_match(b"Sumit Kumar", b"Sumit Kumar\0", false);
+        // Longer than the whole field.
+        name_prefix_is_match(b"Sumit Kumar", b"Sumit Kumar Singh", false);
+    }
+}
@@ -0,0 +1,199 @@
+//! JavaScript bindings of [`prove_aadhaar_claims`] and [`verify_aadhaar_claims`] for `wasm32-unknown-unknown`.
+//!
+//! Build with `wasm-pack build --target web --no-default-features --features wasm`.
+//!
+//! # Serialization format
+//! * params - the KZG parameters of degree [`AadhaarClaimsCircuit::K`] as written by `ParamsKZG::write`.
+//! * qr_bytes - the decoded format v2 QR payload, including the trailing signature.
+//! * pubkey_der - the RSA public key of the signer as a DER-encoded `SubjectPublicKeyInfo`.
+//! * proof - the SHPLONK proof in a Blake2b transcript, as returned by [`prove_aadhaar_claims`].
+//! * public inputs - the values of [`PublicInputs::to_instances`], each as its 32 bytes little-endian representation, concatenated in order.
+//!
+//! The proving and verifying keys are generated from `params` on every call, for the claims of the default [`ClaimsRequest`].
+
+use crate::claims::{
+    prove_aadhaar_claims, verify_aadhaar_claims, AadhaarClaimsCircuit, ClaimsRequest, PublicInputs,
+};
+use halo2_base::halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+use halo2_base::utils::PrimeField;
+use rsa::{pkcs8::DecodePublicKey, RsaPublicKey};
+use wasm_bindgen::prelude::*;
+
+/// The byte length of a serialized public input.
+pub const FIELD_BYTES: usize = 32;
+
+/// Proves that `qr_bytes` is signed by `pubkey_der`.
+///
+/// # Arguments
+/// * params - the serialized KZG parameters.
+/// * qr_bytes - the decoded QR payload, including the trailing signature.
+/// * pubkey_der - the DER-encoded RSA public key of the signer.
+///
+/// # Return values
+/// Returns the proof.
+/// Returns an error if an argument cannot be parsed or the payload is malformed.
+#[wasm_bindgen]
+pub fn prove_aadhaar(
+    params: &[u8],
+    qr_bytes: &[u8],
+    pubkey_der: &[u8],
+) -> Result<Vec<u8>, JsError> {
+    let params = read_params(params)?;
+    let public_key = read_public_key(pubkey_der)?;
+    let circuit = AadhaarClaimsCircuit::<Fr>::keygen_circuit();
+    let vk = keygen_vk(&params, &circuit).map_err(|e| JsError::new(&format!("{e:?}")))?;
+    let pk = keygen_pk(&params, vk, &circuit).map_err(|e| JsError::new(&format!("{e:?}")))?;
+    let (proof, _) = prove_aadhaar_claims(
+        &params,
+        &pk,
+        qr_bytes,
+        &public_key,
+        ClaimsRequest::default(),
+    )
+    .map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(proof)
+}
+
+/// Computes the serialized public inputs of the proof returned by [`prove_aadhaar`].
+///
+/// # Arguments
+/// * qr_bytes - the decoded QR payload, including the trailing signature.
+/// * pubkey_der - the DER-encoded RSA public key of the signer.
+///
+/// # Return values
+/// Returns the public inputs.
+/// Returns an error if an argument cannot be parsed or the payload is malformed.
+#[wasm_bindgen]
+pub fn aadhaar_public_inputs(qr_bytes: &[u8], pubkey_der: &[u8]) -> Result<Vec<u8>, JsError> {
+    let public_key = read_public_key(pubkey_der)?;
+    let public_inputs = PublicInputs::<Fr>::new(qr_bytes, &public_key, &ClaimsRequest::default())
+        .map_err(|e| JsError::new(&e.to_string()))?;
+    Ok(encode_public_inputs(&public_inputs))
+}
+
+/// Verifies a proof returned by [`prove_aadhaar`].
+///
+/// # Arguments
+/// * params - the serialized KZG parameters.
+/// * proof - the proof.
+/// * public_inputs - the serialized public inputs returned by [`aadhaar_public_inputs`].
+///
+/// # Return values
+/// Returns `true` if the proof is valid for `public_inputs`, and `false` otherwise, including when an argument cannot be parsed.
+#[wasm_bindgen]
+pub fn verify_aadhaar(params: &[u8], proof: &[u8], public_inputs: &[u8]) -> bool {
+    let (Ok(params), Some(public_inputs)) =
+        (read_params(params), decode_public_inputs(public_inputs))
+    else {
+        return false;
+    };
+    let circuit = AadhaarClaimsCircuit::<Fr>::keygen_circuit();
+    match keygen_vk(&params, &circuit) {
+        Ok(vk) => verify_aadhaar_claims(&params, &vk, proof, &public_inputs),
+        Err(_) => false,
+    }
+}
+
+fn read_params(bytes: &[u8]) -> Result<ParamsKZG<Bn256>, JsError> {
+    let params = ParamsKZG::<Bn256>::read(&mut &bytes[..])
+        .map_err(|e| JsError::new(&format!("invalid params: {e}")))?;
+    if params.k() as usize != AadhaarClaimsCircuit::<Fr>::K {
+        return Err(JsError::new("params of a wrong degree"));
+    }
+    Ok(params)
+}
+
+fn read_public_key(der: &[u8]) -> Result<RsaPublicKey, JsError> {
+    RsaPublicKey::from_public_key_der(der)
+        .map_err(|e| JsError::new(&format!("invalid public key: {e}")))
+}
+
+fn encode_public_inputs<F: PrimeField>(public_inputs: &PublicInputs<F>) -> Vec<u8> {
+    public_inputs
+        .to_instances()
+        .iter()
+        .flat_map(|value| value.to_repr().as_ref().to_vec())
+        .collect()
+}
+
+fn decode_public_inputs<F: PrimeField>(bytes: &[u8]) -> Option<PublicInputs<F>> {
+    if bytes.len() % FIELD_BYTES != 0 {
+        return None;
+    }
+    let instances = bytes
+        .chunks(FIELD_BYTES)
+        .map(|chunk| {
+            let mut repr = F::Repr::default();
+            repr.as_mut().copy_from_slice(chunk);
+            Option::from(F::from_repr(repr))
+        })
+        .collect::<Option<Vec<F>>>()?;
+    PublicInputs::from_instances(&instances)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::{sample_qr_payload, test_private_key, SAMPLE_QR_FIELDS};
+    use rand::rngs::OsRng;
+    use rsa::{
+        pkcs1v15::SigningKey,
+        pkcs8::EncodePublicKey,
+        signature::{SignatureEncoding, Signer},
+    };
+
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::{wasm_bindgen_test as test, wasm_bindgen_test_configure};
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[test]
+    fn test_public_inputs_round_trip() {
+        let public_inputs = PublicInputs::<Fr>::from_instances(
+            &(0..40).map(|i| -Fr::from(i as u64)).collect::<Vec<_>>(),
+        )
+        .unwrap();
+        let bytes = encode_public_inputs(&public_inputs);
+        assert_eq!(bytes.len(), 40 * FIELD_BYTES);
+        assert_eq!(decode_public_inputs::<Fr>(&bytes), Some(public_inputs));
+        assert_eq!(decode_public_inputs::<Fr>(&bytes[1..]), None);
+        assert_eq!(decode_public_inputs::<Fr>(&bytes[FIELD_BYTES..]), None);
+        // Values above the modulus are not canonical representations.
+        assert_eq!(decode_public_inputs::<Fr>(&[0xff; FIELD_BYTES * 40]), None);
+    }
+
+    /// Runs in a headless browser with `wasm-pack test --headless --chrome --no-default-features --features wasm`.
+    #[test]
+    fn test_prove_and_verify_aadhaar() {
+        let private_key = test_private_key();
+        let pubkey_der = private_key
+            .to_public_key()
+            .to_public_key_der()
+            .unwrap()
+            .into_vec();
+        let mut qr_bytes = sample_qr_payload(&SAMPLE_QR_FIELDS, 200);
+        let signature = SigningKey::<rsa::sha2::Sha256>::new(private_key)
+            .sign(&qr_bytes)
+            .to_vec();
+        qr_bytes.extend(signature);
+
+        let mut params = vec![];
+        ParamsKZG::<Bn256>::setup(AadhaarClaimsCircuit::<Fr>::K as u32, OsRng)
+            .write(&mut params)
+            .unwrap();
+        let proof = prove_aadhaar(&params, &qr_bytes, &pubkey_der)
+            .ok()
+            .expect("failed to prove");
+        let mut public_inputs = aadhaar_public_inputs(&qr_bytes, &pubkey_der)
+            .ok()
+            .expect("malformed payload");
+        assert!(verify_aadhaar(&params, &proof, &public_inputs));
+        // A different modulus fails the verification.
+        public_inputs[0] ^= 1;
+        assert!(!verify_aadhaar(&params, &proof, &public_inputs));
+    }
+}